@@ -0,0 +1,48 @@
+// Renders the glow of a light source. The glow texture only provides the shape of the glow via its alpha channel,
+// while the colour is taken from the vertex colours of the mesh. The alpha of the vertex colours is the flicker of the
+// light, i.e. the maximum relative reduction of its intensity, and the phase of the flickering is derived from the
+// position of the glow, so that all glows can share one material without flickering in sync. The glow is added to
+// whatever is behind it (see `GlowMaterial` in `lighting.rs`), so that it brightens the darkness overlay instead of
+// painting over it.
+#import bevy_sprite::{mesh2d_functions as mesh_functions, mesh2d_view_bindings::globals}
+
+@group(2) @binding(0) var<uniform> alpha: f32;
+@group(2) @binding(1) var<uniform> flicker_speed: f32;
+@group(2) @binding(2) var glow_texture: texture_2d<f32>;
+@group(2) @binding(3) var glow_sampler: sampler;
+
+struct Vertex {
+  @builtin(instance_index) instance_index: u32,
+  @location(0) position: vec3<f32>,
+  @location(2) uv: vec2<f32>,
+  @location(4) colour: vec4<f32>,
+};
+
+struct VertexOutput {
+  @builtin(position) position: vec4<f32>,
+  @location(0) uv: vec2<f32>,
+  @location(1) colour: vec3<f32>,
+  @location(2) flicker: f32,
+  @location(3) phase: f32,
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+  var out: VertexOutput;
+  let world_from_local = mesh_functions::get_world_from_local(vertex.instance_index);
+  let world_position = mesh_functions::mesh2d_position_local_to_world(world_from_local, vec4<f32>(vertex.position, 1.0));
+  out.position = mesh_functions::mesh2d_position_world_to_clip(world_position);
+  out.uv = vertex.uv;
+  out.colour = vertex.colour.rgb;
+  out.flicker = vertex.colour.a;
+  let center = world_from_local[3].xy;
+  out.phase = fract(sin(dot(center, vec2<f32>(12.9898, 78.233))) * 43758.5453) * 6.2831853;
+  return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+  let glow = textureSample(glow_texture, glow_sampler, in.uv);
+  let wave = 0.5 + 0.5 * sin(globals.time * flicker_speed + in.phase);
+  return vec4<f32>(in.colour, alpha * (1.0 - in.flicker * wave) * glow.a);
+}
@@ -0,0 +1,52 @@
+use bevy::prelude::{DetectChangesMut, World};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use procedural_generation_2::bench::{object_resources_from_files, GenerationResourcesCollection, SharedResource};
+
+/// The number of async tasks that are scheduled in a single stage, e.g. one per chunk when generating the 3x3 chunks
+/// around the current chunk.
+const TASK_COUNTS: [usize; 3] = [1, 9, 25];
+
+/// Compares cloning the `GenerationResourcesCollection` into every task, as the stages used to do, with sharing a copy
+/// through a `SharedResource`, both while the resource is unchanged and when it has changed since the last stage.
+fn scheduling_tasks(c: &mut Criterion) {
+  let mut world = World::new();
+  world.insert_resource(GenerationResourcesCollection {
+    objects: object_resources_from_files(),
+    ..Default::default()
+  });
+  let mut group = c.benchmark_group("scheduling_tasks");
+  for tasks in TASK_COUNTS {
+    group.bench_with_input(BenchmarkId::new("clone_per_task", tasks), &tasks, |b, &tasks| {
+      let resources = world.resource::<GenerationResourcesCollection>();
+      b.iter(|| {
+        for _ in 0..tasks {
+          black_box(resources.clone());
+        }
+      })
+    });
+    group.bench_with_input(BenchmarkId::new("shared_resource", tasks), &tasks, |b, &tasks| {
+      let resources = world.resource_ref::<GenerationResourcesCollection>();
+      let mut shared = SharedResource::default();
+      b.iter(|| {
+        for _ in 0..tasks {
+          black_box(shared.get(&resources));
+        }
+      })
+    });
+    group.bench_with_input(BenchmarkId::new("shared_resource_changed", tasks), &tasks, |b, &tasks| {
+      let mut shared = SharedResource::default();
+      b.iter(|| {
+        world.increment_change_tick();
+        world.resource_mut::<GenerationResourcesCollection>().set_changed();
+        let resources = world.resource_ref::<GenerationResourcesCollection>();
+        for _ in 0..tasks {
+          black_box(shared.get(&resources));
+        }
+      })
+    });
+  }
+  group.finish();
+}
+
+criterion_group!(benches, scheduling_tasks);
+criterion_main!(benches);
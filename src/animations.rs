@@ -1,12 +1,21 @@
-use crate::components::AnimationComponent;
+use crate::components::{AnimationComponent, FadeInComponent, FadeOutComponent, PendingDespawnComponent};
+use crate::constants::FADE_IN_DURATION;
+use crate::generation::lib::{ObjectComponent, TileComponent};
+use crate::generation::resources::despawn_chunk;
+use crate::resources::Settings;
 use bevy::app::{App, Plugin};
-use bevy::prelude::{Query, Res, Sprite, Time, Update};
+use bevy::prelude::{
+  Alpha, Children, Commands, Entity, HierarchyQueryExt, OnAdd, Query, Res, Sprite, Time, Timer, TimerMode, Trigger, Update,
+};
 
 pub struct AnimationsPlugin;
 
 impl Plugin for AnimationsPlugin {
   fn build(&self, app: &mut App) {
-    app.add_systems(Update, sprite_animation_system);
+    app
+      .add_observer(on_add_tile_component_trigger)
+      .add_observer(on_add_object_component_trigger)
+      .add_systems(Update, (sprite_animation_system, fade_in_system, fade_out_system));
   }
 }
 
@@ -24,3 +33,84 @@ fn sprite_animation_system(time: Res<Time>, mut query: Query<(&mut AnimationComp
     }
   }
 }
+
+fn on_add_tile_component_trigger(
+  trigger: Trigger<OnAdd, TileComponent>,
+  commands: Commands,
+  query: Query<&mut Sprite>,
+  settings: Res<Settings>,
+) {
+  attach_fade_in_component(trigger.entity(), commands, query, &settings);
+}
+
+fn on_add_object_component_trigger(
+  trigger: Trigger<OnAdd, ObjectComponent>,
+  commands: Commands,
+  query: Query<&mut Sprite>,
+  settings: Res<Settings>,
+) {
+  attach_fade_in_component(trigger.entity(), commands, query, &settings);
+}
+
+/// Makes the sprite of the given entity fully transparent and attaches a `FadeInComponent` which restores its original
+/// alpha over time. Done as soon as the component is added, so that the sprite is never rendered fully opaque first.
+fn attach_fade_in_component(entity: Entity, mut commands: Commands, mut query: Query<&mut Sprite>, settings: &Settings) {
+  if !settings.general.enable_fade_in {
+    return;
+  }
+  if let Ok(mut sprite) = query.get_mut(entity) {
+    let target_alpha = sprite.color.alpha();
+    sprite.color.set_alpha(0.);
+    commands.entity(entity).insert(FadeInComponent {
+      timer: Timer::from_seconds(FADE_IN_DURATION, TimerMode::Once),
+      target_alpha,
+    });
+  }
+}
+
+fn fade_in_system(mut commands: Commands, time: Res<Time>, mut query: Query<(Entity, &mut FadeInComponent, &mut Sprite)>) {
+  for (entity, mut fade_in, mut sprite) in &mut query {
+    fade_in.timer.tick(time.delta());
+    sprite.color.set_alpha(fade_in.target_alpha * fade_in.timer.fraction());
+    if fade_in.timer.finished() {
+      commands.entity(entity).remove::<FadeInComponent>();
+    }
+  }
+}
+
+/// Fades out the sprites of every chunk with a `PendingDespawnComponent` and despawns the chunk once its timer has
+/// finished. The alpha of each sprite is set to its alpha at the start of the fade out, scaled by the ratio of the
+/// remaining time, so that sprites with a lower alpha (e.g. light glows) fade out proportionally. Any fade in is
+/// stopped and other systems leave sprites with a `FadeOutComponent` alone, so they cannot undo the fade out.
+fn fade_out_system(
+  mut commands: Commands,
+  time: Res<Time>,
+  mut chunks: Query<(Entity, &mut PendingDespawnComponent)>,
+  children: Query<&Children>,
+  mut sprites: Query<(&mut Sprite, Option<&FadeOutComponent>)>,
+) {
+  for (chunk, mut pending_despawn) in &mut chunks {
+    pending_despawn.timer.tick(time.delta());
+    if pending_despawn.timer.finished() {
+      despawn_chunk(&mut commands, chunk);
+      continue;
+    }
+    let remaining = 1. - pending_despawn.timer.fraction();
+    for entity in children.iter_descendants(chunk) {
+      if let Ok((mut sprite, fade_out)) = sprites.get_mut(entity) {
+        let start_alpha = match fade_out {
+          Some(fade_out) => fade_out.start_alpha,
+          None => {
+            let start_alpha = sprite.color.alpha();
+            commands
+              .entity(entity)
+              .remove::<FadeInComponent>()
+              .insert(FadeOutComponent { start_alpha });
+            start_alpha
+          }
+        };
+        sprite.color.set_alpha(start_alpha * remaining);
+      }
+    }
+  }
+}
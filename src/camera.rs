@@ -1,9 +1,21 @@
-use crate::constants::WATER_BLUE;
+use crate::constants::*;
+use crate::coords::point::{ChunkGrid, World};
+use crate::coords::projection::projection;
+use crate::coords::tile_size::{chunk_height, chunk_width, tile_scale};
+use crate::coords::Point;
+use crate::events::UpdateWorldEvent;
+use crate::generation::resources::ChunkComponentIndex;
+use crate::resources::{CurrentChunk, Settings, SpawnPoint, VisibleChunks};
+use crate::states::AppState;
 use bevy::app::{App, Plugin, Startup};
 use bevy::core_pipeline::bloom::Bloom;
 use bevy::prelude::*;
 use bevy::render::view::RenderLayers;
-use bevy_pancam::PanCam;
+use bevy::utils::HashSet;
+use bevy::window::PrimaryWindow;
+use bevy_pancam::{PanCam, PanCamSystemSet};
+use rand::prelude::StdRng;
+use rand::{Rng, SeedableRng};
 
 pub const WORLD_LAYER: RenderLayers = RenderLayers::layer(0);
 
@@ -13,14 +25,32 @@ impl Plugin for CameraPlugin {
   fn build(&self, app: &mut App) {
     app
       .add_systems(Startup, setup_camera_system)
-      .insert_resource(ClearColor(WATER_BLUE));
+      .add_systems(OnExit(AppState::Initialising), move_camera_to_spawn_point_system)
+      .add_systems(Update, demo_mode_system)
+      .add_systems(
+        Update,
+        (
+          zoom_system,
+          camera_speed_system,
+          visible_chunks_system.run_if(in_state(AppState::Running)),
+        )
+          .chain()
+          .after(PanCamSystemSet),
+      )
+      .insert_resource(ClearColor(WATER_BLUE))
+      .init_resource::<CameraSpeed>()
+      .init_resource::<DemoMode>()
+      .register_type::<DemoMode>();
   }
 }
 
 #[derive(Component)]
-struct WorldCamera;
+pub(crate) struct WorldCamera;
 
+/// Spawns the camera. Its scale, speed and zoom limits are multiplied by the tile scale so that the world looks the
+/// same on screen regardless of the configured tile size.
 fn setup_camera_system(mut commands: Commands) {
+  let scale = tile_scale();
   commands.spawn((
     Camera2d,
     Camera { order: 2, ..default() },
@@ -29,6 +59,7 @@ fn setup_camera_system(mut commands: Commands) {
     OrthographicProjection {
       near: -10000.0,
       far: 1000000.0,
+      scale,
       ..OrthographicProjection::default_3d()
     },
     WorldCamera,
@@ -38,11 +69,255 @@ fn setup_camera_system(mut commands: Commands) {
     SpatialListener::new(10.),
     PanCam {
       grab_buttons: vec![MouseButton::Right, MouseButton::Middle],
-      speed: 600.,
+      speed: CAMERA_SPEED * scale,
       zoom_to_cursor: false,
-      min_scale: 0.15,
-      max_scale: 5.,
+      min_scale: MIN_ZOOM_SCALE * scale,
+      max_scale: MAX_ZOOM_SCALE * scale,
       ..default()
     },
   ));
 }
+
+/// Centers the camera on the spawn point which may not be at the origin of the world, depending on the
+/// `SpawnPointCriterion` used.
+fn move_camera_to_spawn_point_system(spawn_point: Res<SpawnPoint>, mut camera: Query<&mut Transform, With<WorldCamera>>) {
+  let center = projection().project(spawn_point.get_center_world().to_vec2());
+  if let Ok(mut transform) = camera.get_single_mut() {
+    transform.translation.x = center.x;
+    transform.translation.y = center.y;
+  }
+}
+
+/// Applies the zoom settings to the camera. PanCam zooms by multiplying the scale of the projection, which would never
+/// leave a pixel-perfect level if the scale was snapped directly. Instead, every change PanCam makes is applied to the
+/// unsnapped scale which is tracked separately and only the result is snapped. All scales are relative to the tile
+/// scale, so that the same levels are pixel-perfect for any tile size.
+fn zoom_system(
+  settings: Res<Settings>,
+  mut camera: Query<(&mut OrthographicProjection, &mut PanCam), With<WorldCamera>>,
+  mut unsnapped_scale: Local<Option<(f32, f32)>>,
+) {
+  let Ok((mut projection, mut pan_cam)) = camera.get_single_mut() else {
+    return;
+  };
+  let tile_scale = tile_scale();
+  let (min, max) = (settings.general.min_zoom_scale, settings.general.max_zoom_scale);
+  pan_cam.min_scale = min * tile_scale;
+  pan_cam.max_scale = max * tile_scale;
+  let scale = match *unsnapped_scale {
+    Some((unsnapped, applied)) if applied != projection.scale => unsnapped * projection.scale / applied,
+    Some((unsnapped, _)) => unsnapped,
+    None => projection.scale,
+  };
+  let scale = (scale / tile_scale).clamp(min, max);
+  let snapped = if settings.general.enable_pixel_perfect_zoom {
+    snap_to_pixel_perfect_scale(scale, min, max)
+  } else {
+    scale
+  };
+  if projection.scale != snapped * tile_scale {
+    projection.scale = snapped * tile_scale;
+  }
+  *unsnapped_scale = Some((scale * tile_scale, projection.scale));
+}
+
+/// Returns the integer zoom level closest to `scale` that lies within `min` and `max`, i.e. `n` when zoomed out and
+/// `1/n` when zoomed in. Falls back to `scale` if there is no such level.
+fn snap_to_pixel_perfect_scale(scale: f32, min: f32, max: f32) -> f32 {
+  let to_level = |scale: f32, round: fn(f32) -> f32| {
+    if scale >= 1. {
+      round(scale).max(1.)
+    } else {
+      1. / round(1. / scale).max(1.)
+    }
+  };
+  let snapped = to_level(scale, f32::round);
+  if snapped < min {
+    let level = if min >= 1. {
+      to_level(min, f32::ceil)
+    } else {
+      to_level(min, f32::floor)
+    };
+    if level <= max {
+      level
+    } else {
+      scale
+    }
+  } else if snapped > max {
+    let level = if max >= 1. {
+      to_level(max, f32::floor)
+    } else {
+      to_level(max, f32::ceil)
+    };
+    if level >= min {
+      level
+    } else {
+      scale
+    }
+  } else {
+    snapped
+  }
+}
+
+/// The speed of the camera in px per second, as measured between the last two frames.
+#[derive(Resource, Default)]
+pub struct CameraSpeed {
+  speed: f32,
+  last_translation: Option<Vec2>,
+}
+
+impl CameraSpeed {
+  /// Returns `true` if the camera is moving faster than `CAMERA_FAST_MOVEMENT_THRESHOLD`, in which case no world updates
+  /// should be requested, so that chunks the camera merely passes through are never generated.
+  pub fn is_fast(&self) -> bool {
+    self.speed > CAMERA_FAST_MOVEMENT_THRESHOLD * tile_scale()
+  }
+}
+
+/// Measures the speed of the camera once per frame, after PanCam has moved it.
+pub(crate) fn camera_speed_system(
+  camera: Query<&Transform, With<WorldCamera>>,
+  time: Res<Time>,
+  mut camera_speed: ResMut<CameraSpeed>,
+) {
+  let Ok(transform) = camera.get_single() else {
+    return;
+  };
+  let translation = transform.translation.truncate();
+  let previous_translation = camera_speed.last_translation.replace(translation);
+  let delta = time.delta_secs();
+  camera_speed.speed = match previous_translation {
+    Some(previous_translation) if delta > 0. => translation.distance(previous_translation) / delta,
+    _ => 0.,
+  };
+}
+
+/// Updates the `VisibleChunks` to be the chunks that intersect the area visible through the camera, which depends on
+/// its position, its zoom level and the size of the window. Requests a world update whenever the visible chunks have
+/// changed and at least one of them doesn't exist yet. Like `camera_movement_system`, does nothing while the camera is
+/// moving faster than `CAMERA_FAST_MOVEMENT_THRESHOLD`, so that chunks the camera merely passes through are never
+/// generated.
+fn visible_chunks_system(
+  windows: Query<&Window, With<PrimaryWindow>>,
+  camera: Query<(&Transform, &OrthographicProjection), With<WorldCamera>>,
+  current_chunk: Res<CurrentChunk>,
+  chunk_index: Res<ChunkComponentIndex>,
+  camera_speed: Res<CameraSpeed>,
+  mut visible_chunks: ResMut<VisibleChunks>,
+  mut update_world_event: EventWriter<UpdateWorldEvent>,
+) {
+  let (Ok(window), Ok((transform, camera_projection))) = (windows.get_single(), camera.get_single()) else {
+    return;
+  };
+  if camera_speed.is_fast() {
+    return;
+  }
+  let translation = transform.translation.truncate();
+  let half_extent = Vec2::new(window.width(), window.height()) / 2. * camera_projection.scale;
+  let center = projection().unproject(translation);
+  let corners = [(-1., -1.), (-1., 1.), (1., -1.), (1., 1.)]
+    .map(|(x, y)| projection().unproject(translation + Vec2::new(x, y) * half_extent));
+  let chunk_size = Vec2::new(chunk_width() as f32, chunk_height() as f32);
+  let margin = chunk_size * VISIBLE_AREA_MARGIN_IN_CHUNKS;
+  let min = corners.iter().fold(Vec2::MAX, |min, corner| min.min(*corner)) - margin;
+  let max = corners.iter().fold(Vec2::MIN, |max, corner| max.max(*corner)) + margin;
+  let center_cg = chunk_grid_at(center, chunk_size);
+  let (min_cg, max_cg) = (chunk_grid_at(min, chunk_size), chunk_grid_at(max, chunk_size));
+  let (x_range, y_range) = (
+    min_cg.x.max(center_cg.x - MAX_VISIBLE_CHUNK_DISTANCE)..=max_cg.x.min(center_cg.x + MAX_VISIBLE_CHUNK_DISTANCE),
+    min_cg.y.max(center_cg.y - MAX_VISIBLE_CHUNK_DISTANCE)..=max_cg.y.min(center_cg.y + MAX_VISIBLE_CHUNK_DISTANCE),
+  );
+  let chunks = x_range
+    .flat_map(|x| y_range.clone().map(move |y| (x, y)))
+    .map(|(x, y)| Point::new_world(x * chunk_width() - chunk_width() / 2, y * chunk_height() + chunk_height() / 2))
+    .collect::<HashSet<Point<World>>>();
+  let radius = corners.iter().map(|corner| corner.distance(center)).fold(0., f32::max) + margin.length();
+  if !visible_chunks.update(chunks, radius) {
+    return;
+  }
+  let missing_chunk_count = visible_chunks.iter().filter(|w| chunk_index.get(w).is_none()).count();
+  if missing_chunk_count > 0 {
+    debug!(
+      "Visible area contains {} chunk(s) that don't exist yet, requesting world update",
+      missing_chunk_count
+    );
+    update_world_event.send(UpdateWorldEvent::forced(current_chunk.get_world()));
+  }
+}
+
+/// Returns the chunk that contains the given world coordinates. Chunks are offset by half their size, so that the
+/// origin of the world is at the center of the chunk at `(0, 0)`.
+fn chunk_grid_at(w: Vec2, chunk_size: Vec2) -> Point<ChunkGrid> {
+  Point::new_chunk_grid(
+    ((w.x + chunk_size.x / 2.) / chunk_size.x).floor() as i32,
+    ((w.y - chunk_size.y / 2.) / chunk_size.y).ceil() as i32,
+  )
+}
+
+/// Automatically pans the camera along a seeded route while enabled, which continuously exercises the world update and
+/// pruning logic. The camera moves at `speed` and turns towards a new random heading in regular intervals. The same
+/// seed always results in the same route.
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct DemoMode {
+  is_enabled: bool,
+  pub speed: f32,
+  heading: f32,
+  target_heading: f32,
+  time_until_turn: f32,
+  #[reflect(ignore)]
+  rng: Option<StdRng>,
+}
+
+impl Default for DemoMode {
+  fn default() -> Self {
+    Self {
+      is_enabled: false,
+      speed: DEMO_MODE_SPEED,
+      heading: 0.,
+      target_heading: 0.,
+      time_until_turn: 0.,
+      rng: None,
+    }
+  }
+}
+
+impl DemoMode {
+  pub fn toggle(&mut self, seed: u32) {
+    self.is_enabled = !self.is_enabled;
+    if self.is_enabled {
+      self.rng = Some(StdRng::seed_from_u64(seed as u64));
+      self.heading = 0.;
+      self.target_heading = 0.;
+      self.time_until_turn = 0.;
+    }
+  }
+
+  pub fn is_enabled(&self) -> bool {
+    self.is_enabled
+  }
+}
+
+fn demo_mode_system(time: Res<Time>, mut demo_mode: ResMut<DemoMode>, mut camera: Query<&mut Transform, With<WorldCamera>>) {
+  if !demo_mode.is_enabled {
+    return;
+  }
+  let delta = time.delta_secs();
+  demo_mode.time_until_turn -= delta;
+  if demo_mode.time_until_turn <= 0. {
+    if let Some(rng) = demo_mode.rng.as_mut() {
+      let turn = rng.gen_range(-DEMO_MODE_MAX_TURN_ANGLE..=DEMO_MODE_MAX_TURN_ANGLE);
+      let time_until_turn = rng.gen_range(DEMO_MODE_TURN_INTERVAL);
+      demo_mode.target_heading += turn;
+      demo_mode.time_until_turn = time_until_turn;
+    }
+  }
+  let max_turn = DEMO_MODE_TURN_SPEED * delta;
+  let heading_difference = (demo_mode.target_heading - demo_mode.heading).clamp(-max_turn, max_turn);
+  demo_mode.heading += heading_difference;
+  let direction = Vec2::from_angle(demo_mode.heading);
+  if let Ok(mut transform) = camera.get_single_mut() {
+    transform.translation.x += direction.x * demo_mode.speed * delta;
+    transform.translation.y += direction.y * demo_mode.speed * delta;
+  }
+}
@@ -9,3 +9,27 @@ pub struct AnimationComponent {
 
 #[derive(Component, Deref, DerefMut)]
 pub struct AnimationTimer(pub Timer);
+
+/// Attached to the glow of an object that emits light.
+#[derive(Component)]
+pub struct LightSourceComponent;
+
+#[derive(Component)]
+pub struct FadeInComponent {
+  pub(crate) timer: Timer,
+  pub(crate) target_alpha: f32,
+}
+
+/// Attached to every sprite of a chunk with a `PendingDespawnComponent` once it starts fading out. Holds the alpha of the
+/// sprite at that time, which is scaled by the remaining time of the chunk's timer.
+#[derive(Component)]
+pub struct FadeOutComponent {
+  pub(crate) start_alpha: f32,
+}
+
+/// Attached to a chunk that has been pruned. The sprites of the chunk are faded out until the timer finishes, after
+/// which the chunk is despawned.
+#[derive(Component)]
+pub struct PendingDespawnComponent {
+  pub(crate) timer: Timer,
+}
@@ -1,9 +1,10 @@
 #![allow(dead_code)]
 
-use crate::coords::point::{ChunkGrid, TileGrid, World};
+use crate::coords::point::{ChunkGrid, TileGrid};
 use crate::coords::Point;
+use crate::generation::lib::TerrainType;
+use crate::resources::{ClimateGradientDirection, SpawnPointCriterion};
 use bevy::color::Color;
-use bevy::math::UVec2;
 use std::ops::Range;
 
 // ------------------------------------------------------------------------------------------------------
@@ -11,21 +12,86 @@ use std::ops::Range;
 pub const DRAW_GIZMOS: bool = false;
 pub const GENERATE_NEIGHBOUR_CHUNKS: bool = true;
 pub const ENABLE_TILE_DEBUGGING: bool = true;
+pub const ENABLE_TERRAIN_BRUSH: bool = false;
+pub const TERRAIN_BRUSH_TYPE: TerrainType = TerrainType::Land1;
+pub const TERRAIN_BRUSH_RADIUS: i32 = 1;
+pub const ENABLE_REGENERATION_DIFF: bool = false;
 pub const DRAW_TERRAIN_SPRITES: bool = true;
 pub const ANIMATE_TERRAIN_SPRITES: bool = true;
-pub const SPAWN_UP_TO_LAYER: usize = 4;
+/// Names are only useful when inspecting entities, so they are omitted in release builds to avoid formatting a string
+/// for each of the many tile and object entities.
+pub const DEBUG_NAMES: bool = cfg!(debug_assertions);
+pub const SPAWN_UP_TO_LAYER: usize = 5;
 pub const SPAWN_FROM_LAYER: usize = 0;
 pub const ENABLE_WORLD_PRUNING: bool = true;
+pub const PRUNE_MARGIN_IN_CHUNKS: f32 = 0.5;
+pub const MIN_CHUNK_LIFETIME_SECS: f32 = 5.;
+pub const GENERATION_RADIUS: i32 = 1;
+pub const FREEZE_GENERATION: bool = false;
+pub const STEP_THROUGH_GENERATION: bool = false;
+pub const ENABLE_FADE_IN: bool = true;
+pub const ENABLE_FADE_OUT: bool = true;
+pub const ENABLE_CLIMATE_BLENDING: bool = false;
+pub const GENERATE_BEACHES: bool = true;
+pub const SPAWN_POINT_CRITERION: SpawnPointCriterion = SpawnPointCriterion::Origin;
+/// The maximum distance (in chunks) from the origin within which a spawn point matching the `SpawnPointCriterion` is
+/// searched for.
+pub const SPAWN_POINT_SEARCH_RADIUS: i32 = 10;
+pub const ENABLE_LIGHTING: bool = false;
+pub const DARKNESS: f32 = 0.5;
+pub const ENABLE_FRAME_TIME_GUARDRAIL: bool = true;
+pub const FRAME_TIME_BUDGET_MS: f32 = 20.;
+pub const OBJECT_SPAWN_BUDGET: usize = 500;
+/// The maximum number of consecutive frames for which the world generation may be paused by the frame time guardrail,
+/// which ensures that the world continues to be generated on machines that never manage to stay within the budget.
+pub const FRAME_TIME_GUARDRAIL_MAX_SKIPPED_FRAMES: u32 = 10;
+/// The number of times the async tasks of a world generation component are retried after panicking, before the
+/// component is given up on and despawned.
+pub const MAX_GENERATION_TASK_RETRIES: usize = 3;
+pub const ENABLE_PIXEL_PERFECT_ZOOM: bool = true;
+pub const MIN_ZOOM_SCALE: f32 = 0.15;
+pub const MAX_ZOOM_SCALE: f32 = 5.;
+/// The margin (as a fraction of the chunk size) by which the area visible through the camera is extended when
+/// determining the chunks that must be generated, so that chunks are ready shortly before they scroll into view.
+pub const VISIBLE_AREA_MARGIN_IN_CHUNKS: f32 = 0.5;
+/// The maximum distance (in chunks) from the chunk at the center of the camera up to which visible chunks are
+/// generated, which prevents zooming out far from queueing an excessive number of chunks.
+pub const MAX_VISIBLE_CHUNK_DISTANCE: i32 = 4;
+pub const REQUEUE_OUTDATED_CHUNKS: bool = true;
+pub const STUCK_COMPONENT_TIMEOUT_SECS: f32 = 30.;
+pub const FORCE_ADVANCE_STUCK_COMPONENTS: bool = false;
+/// The number of most recent durations of each generation stage that are kept for the stage timings shown in the
+/// generation debugger.
+pub const STAGE_TIMINGS_SAMPLE_SIZE: usize = 50;
+/// The interval at which tile and object sprites that are no longer part of a spawned chunk are looked for and
+/// despawned.
+pub const ORPHANED_SPRITE_SWEEP_INTERVAL_SECS: f32 = 5.;
+/// The z-coordinate of the placeholders shown for chunks that are still being generated, which keeps them behind the
+/// tiles that replace them.
+pub const CHUNK_PLACEHOLDER_Z: f32 = -1.;
+/// The maximum number of recycled entities that are kept for reuse per sprite category, i.e. tile and object sprites.
+pub const SPRITE_POOL_CAPACITY: usize = 20000;
 // ------------------------------------------------------------------------------------------------------
 // Settings: Metadata
-pub const METADATA_GRID_APOTHEM: i32 = 3;
+/// The number of rings of chunks around the current chunk for which metadata is generated, which covers the chunks up
+/// to `MAX_VISIBLE_CHUNK_DISTANCE` away as well as their neighbours.
+pub const METADATA_RADIUS: i32 = 5;
 pub const ELEVATION_CHUNK_STEP_SIZE: f64 = 0.2;
+pub const MIN_ELEVATION_CHUNK_STEP_SIZE: f64 = 0.001;
 pub const ELEVATION_OFFSET: f64 = 0.6;
 pub const BIOME_NOISE_FREQUENCY: f64 = 0.1;
+pub const CLIMATE_GRADIENT_STRENGTH: f64 = 0.;
+pub const CLIMATE_GRADIENT_DIRECTION: ClimateGradientDirection = ClimateGradientDirection::North;
+pub const CLIMATE_GRADIENT_DISTANCE: i32 = 20;
 pub const BIOME_IS_ROCKY_PROBABILITY: f64 = 0.3;
+/// The distance (in tiles) from a chunk border with a different climate within which tiles may take on the climate of
+/// the neighbouring chunk.
+pub const CLIMATE_BLEND_DISTANCE: i32 = 4;
 // ------------------------------------------------------------------------------------------------------
 // Settings: World
 pub const NOISE_SEED: u32 = 1;
+pub const TERRAIN_SEED: Option<u32> = None;
+pub const OBJECT_SEED: Option<u32> = None;
 pub const NOISE_STRENGTH: f64 = 0.75;
 pub const NOISE_OCTAVES: usize = 3;
 pub const NOISE_FREQUENCY: f64 = 0.07;
@@ -33,55 +99,81 @@ pub const NOISE_PERSISTENCE: f64 = 0.7;
 pub const NOISE_AMPLITUDE: f64 = 4.5;
 pub const FALLOFF_STRENGTH: f64 = 2.5;
 pub const FALLOFF_NOISE_STRENGTH: f64 = 0.5;
+/// The normalised noise value (which includes the elevation offset from the `ElevationMetadata`) above which terrain
+/// is covered in snow i.e. becomes `TerrainType::Land4`.
+pub const SNOW_LINE: f64 = 0.92;
+/// The base width (in tiles) of beaches along coastlines, per climate.
+pub const BEACH_WIDTH_DRY: i32 = 3;
+pub const BEACH_WIDTH_MODERATE: i32 = 2;
+pub const BEACH_WIDTH_HUMID: i32 = 1;
+/// The maximum number of tiles by which the width of a beach may deviate from its base width.
+pub const BEACH_WIDTH_VARIANCE: f64 = 1.5;
+pub const MAX_BEACH_WIDTH: i32 = 4;
+pub const BEACH_NOISE_FREQUENCY: f64 = 0.15;
 // ------------------------------------------------------------------------------------------------------
 // Settings: Objects
 pub const GENERATE_OBJECTS: bool = true;
 pub const ENABLE_COLOUR_VARIATIONS: bool = false;
+pub const ALIGN_OBJECTS_TO_GRID: bool = false;
+pub const OBJECT_SEED_OFFSET: u32 = 0;
+pub const SNAPSHOT_INTERVAL: i32 = 10;
+pub const MAX_RETRIES_PER_SNAPSHOT: usize = 1;
+pub const SHORT_CIRCUIT_AFTER_ITERATIONS: i32 = 0;
+pub const TREE_DENSITY: f32 = 1.;
+pub const ROCK_DENSITY: f32 = 1.;
+pub const FLORA_DENSITY: f32 = 1.;
+pub const DEBRIS_DENSITY: f32 = 1.;
+pub const PROP_DENSITY: f32 = 1.;
+pub const RARE_FEATURE_PROBABILITY: f32 = 0.05;
+/// The offset added to the object seed when seeding the random number generator that decides whether a chunk contains
+/// a rare feature, so that the decision is independent of the wave function collapse.
+pub const RARE_FEATURE_SEED_OFFSET: u32 = 7919;
+/// The number of random positions within a chunk at which placing a rare feature is attempted before giving up.
+pub const RARE_FEATURE_PLACEMENT_ATTEMPTS: usize = 10;
+pub const ROCKY_ROCK_DENSITY_MULTIPLIER: f32 = 2.5;
+pub const ROCKY_FEATURES_PER_CHUNK: usize = 2;
+/// The offset added to the object seed when seeding the random number generator that places the rocky features of a
+/// rocky chunk, so that their placement is independent of both the rare features and the wave function collapse.
+pub const ROCKY_FEATURE_SEED_OFFSET: u32 = 104_729;
+pub const WFC_VISUALISER_STEPS_PER_SECOND: f32 = 5.;
 // ------------------------------------------------------------------------------------------------------
 // Chunks and tiles
 /// The size of a buffer around a chunk that is generated but not rendered. Must be 1, always.
 pub const BUFFER_SIZE: i32 = 1;
-/// The size of a chunk, including a border that will not be rendered. This is to ensure that the
-/// `TileType`s of outermost tiles are known. Must not be modified directly. Change `CHUNK_SIZE` instead.
-pub const CHUNK_SIZE_PLUS_BUFFER: i32 = CHUNK_SIZE + 2 * BUFFER_SIZE;
-/// The size of a chunk that is rendered on the screen.
-pub const CHUNK_SIZE: i32 = 16;
+/// The width of a chunk, including a border that will not be rendered. This is to ensure that the `TileType`s of
+/// outermost tiles are known. Must not be modified directly. Change `CHUNK_WIDTH` instead.
+pub const CHUNK_WIDTH_PLUS_BUFFER: i32 = CHUNK_WIDTH + 2 * BUFFER_SIZE;
+/// The height of a chunk, including a border that will not be rendered. Must not be modified directly. Change
+/// `CHUNK_HEIGHT` instead.
+pub const CHUNK_HEIGHT_PLUS_BUFFER: i32 = CHUNK_HEIGHT + 2 * BUFFER_SIZE;
+pub use crate::coords::grid::{CHUNK_HEIGHT, CHUNK_WIDTH};
 pub const ORIGIN_CHUNK_GRID_SPAWN_POINT: Point<ChunkGrid> = Point::new_const(0, 0);
-pub const ORIGIN_WORLD_SPAWN_POINT: Point<World> =
-  Point::new_const(-(CHUNK_SIZE / 2) * TILE_SIZE as i32, (CHUNK_SIZE / 2) * TILE_SIZE as i32);
-pub const ORIGIN_TILE_GRID_SPAWN_POINT: Point<TileGrid> = Point::new_const(-(CHUNK_SIZE / 2), CHUNK_SIZE / 2);
-pub const DESPAWN_DISTANCE: f32 = CHUNK_SIZE as f32 * TILE_SIZE as f32 * 1.75;
+pub const ORIGIN_TILE_GRID_SPAWN_POINT: Point<TileGrid> = Point::new_const(-(CHUNK_WIDTH / 2), CHUNK_HEIGHT / 2);
+pub const DESPAWN_DISTANCE_IN_CHUNKS: f32 = 1.75;
 // ------------------------------------------------------------------------------------------------------
 // Tiles
-pub const TILE_SIZE: u32 = 32;
+/// The size of a tile in pixels unless configured otherwise via the `TileSize` resource. All other pixel values are
+/// expressed relative to this size.
+pub const DEFAULT_TILE_SIZE: u32 = 32;
 pub const WATER_LAYER: usize = 0;
 pub const SHORE_LAYER: usize = 1;
 pub const SAND_LAYER: usize = 2;
 pub const GRASS_LAYER: usize = 3;
 pub const FOREST_LAYER: usize = 4;
 // ------------------------------------------------------------------------------------------------------
-// Sprites: Placeholder tile set
-pub const TILE_SET_PLACEHOLDER_PATH: &str = "tilesets/default.png";
-pub const TILE_SET_PLACEHOLDER_COLUMNS: u32 = 5;
-pub const TILE_SET_PLACEHOLDER_ROWS: u32 = 1;
-// ------------------------------------------------------------------------------------------------------
-// Sprites: Detailed tile sets
-pub const TS_WATER_PATH: &str = "tilesets/water-deep.png";
-pub const TS_SHORE_PATH: &str = "tilesets/water-shallow.png";
-pub const TS_LAND_HUMID_L1_PATH: &str = "tilesets/land-humid-l1.png";
-pub const TS_LAND_HUMID_L2_PATH: &str = "tilesets/land-humid-l2.png";
-pub const TS_LAND_HUMID_L3_PATH: &str = "tilesets/land-humid-l3.png";
-pub const TS_LAND_MODERATE_L1_PATH: &str = "tilesets/land-moderate-l1.png";
-pub const TS_LAND_MODERATE_L2_PATH: &str = "tilesets/land-moderate-l2.png";
-pub const TS_LAND_MODERATE_L3_PATH: &str = "tilesets/land-moderate-l3.png";
-pub const TS_LAND_DRY_L1_PATH: &str = "tilesets/land-dry-l1.png";
-pub const TS_LAND_DRY_L2_PATH: &str = "tilesets/land-dry-l2.png";
-pub const TS_LAND_DRY_L3_PATH: &str = "tilesets/land-dry-l3.png";
-pub const TILE_SET_ROWS: u32 = 17;
-pub const DEFAULT_STATIC_TILE_SET_COLUMNS: u32 = 1;
-pub const DEFAULT_ANIMATED_TILE_SET_COLUMNS: u32 = 4;
+// Sprites: Art packs
+/// The folder that contains the `*.art-pack.ron` manifests, relative to the assets folder.
+pub const ART_PACKS_PATH: &str = "art-packs";
+/// The file in `ART_PACKS_PATH` that lists all art packs that can be selected.
+pub const ART_PACK_INDEX_FILE: &str = "all.index.ron";
+/// The name of the art pack that is selected on start up. Falls back to the first art pack found if it doesn't exist.
+pub const DEFAULT_ART_PACK_NAME: &str = "Default";
+// ------------------------------------------------------------------------------------------------------
+// Sprites: Animations
 pub const ANIMATION_LENGTH: usize = 4;
 pub const DEFAULT_ANIMATION_FRAME_DURATION: f32 = 0.5;
+pub const FADE_IN_DURATION: f32 = 0.3;
+pub const FADE_OUT_DURATION: f32 = 0.2;
 // ------------------------------------------------------------------------------------------------------
 // Sprites: Detailed tile set sprite indices
 pub const FILL: usize = 4;
@@ -102,34 +194,13 @@ pub const LEFT_FILL: usize = 5;
 pub const SINGLE: usize = 15;
 pub const ERROR: usize = 16;
 // ------------------------------------------------------------------------------------------------------
-// Objects
-pub const TREES_HUMID_OBJ_PATH: &str = "objects/trees-humid.png";
-pub const TREES_MODERATE_OBJ_PATH: &str = "objects/trees-moderate.png";
-pub const TREES_DRY_OBJ_PATH: &str = "objects/trees-dry.png";
-pub const TREES_OBJ_COLUMNS: u32 = 6;
-pub const TREES_OBJ_ROWS: u32 = 1;
-pub const TREES_OBJ_SIZE: UVec2 = UVec2::new(64, 128);
-pub const WATER_DEEP_OBJ_PATH: &str = "objects/objects-water-deep.png";
-pub const WATER_SHALLOW_OBJ_PATH: &str = "objects/objects-water-shallow.png";
-pub const OBJ_L1_DRY_PATH: &str = "objects/objects-l1-dry.png";
-pub const OBJ_L1_MODERATE_PATH: &str = "objects/objects-l1-moderate.png";
-pub const OBJ_L1_HUMID_PATH: &str = "objects/objects-l1-humid.png";
-pub const OBJ_L2_DRY_PATH: &str = "objects/objects-l2-dry.png";
-pub const OBJ_L2_MODERATE_PATH: &str = "objects/objects-l2-moderate.png";
-pub const OBJ_L2_HUMID_PATH: &str = "objects/objects-l2-humid.png";
-pub const OBJ_L3_DRY_PATH: &str = "objects/objects-l3-dry.png";
-pub const OBJ_L3_MODERATE_PATH: &str = "objects/objects-l3-moderate.png";
-pub const OBJ_L3_HUMID_PATH: &str = "objects/objects-l3-humid.png";
-pub const DEFAULT_OBJ_COLUMNS: u32 = 16;
-pub const DEFAULT_OBJ_ROWS: u32 = 2;
-pub const DEFAULT_OBJ_SIZE: UVec2 = UVec2::new(32, 32);
-// ------------------------------------------------------------------------------------------------------
 // Colours
 pub const RED: Color = Color::hsl(0.59, 0.32, 0.52);
 pub const PURPLE: Color = Color::srgb(0.706, 0.557, 0.678);
 pub const YELLOW: Color = Color::srgb(0.922, 0.796, 0.545);
 pub const ORANGE: Color = Color::srgb(0.816, 0.529, 0.439);
 pub const GREEN: Color = Color::srgb(0.639, 0.745, 0.549);
+pub const DARK_GREEN: Color = Color::srgb(0.420, 0.557, 0.396);
 pub const WATER_BLUE: Color = Color::srgb(0.305882, 0.611765, 0.74902);
 pub const DEEP_WATER_BLUE: Color = Color::srgb(0.259, 0.471, 0.565);
 pub const LIGHT: Color = Color::srgb(0.925, 0.937, 0.957);
@@ -139,10 +210,101 @@ pub const VERY_DARK: Color = Color::srgb(0.12, 0.14, 0.18);
 pub const RGB_COLOUR_VARIATION: f32 = 0.2;
 pub const DARKNESS_RANGE: Range<f32> = 0.0..0.2;
 pub const BRIGHTNESS_RANGE: Range<f32> = 0.0..0.4;
+pub const HOVERED_OBJECT_TINT: Color = Color::srgb(1.0, 0.9, 0.6);
+pub const SELECTED_OBJECT_TINT: Color = Color::srgb(1.0, 0.6, 0.4);
 // ------------------------------------------------------------------------------------------------------
 // Window
 pub const WINDOW_WIDTH: f32 = 1280.;
 pub const WINDOW_HEIGHT: f32 = 720.;
 // ------------------------------------------------------------------------------------------------------
+// Camera
+/// The camera speed (in px per second) above which no world updates are requested. Once the camera slows down again,
+/// a single update for the chunk the camera is in is requested instead of one for every chunk it passed through.
+pub const CAMERA_FAST_MOVEMENT_THRESHOLD: f32 = 2500.;
+pub const CAMERA_SPEED: f32 = 600.;
+// ------------------------------------------------------------------------------------------------------
+// Lighting
+/// The size (in px) of the procedurally generated texture that is scaled to the radius of each glow.
+pub const LIGHT_GLOW_TEXTURE_SIZE: u32 = 64;
+/// The shader used to render glows, which adds their colour to whatever is behind them.
+pub const LIGHT_GLOW_SHADER_PATH: &str = "shaders/glow.wgsl";
+pub const LIGHT_GLOW_ALPHA: f32 = 0.6;
+/// The rate in radians per second at which lights flicker.
+pub const LIGHT_FLICKER_SPEED: f32 = 9.;
+/// The z-offset of glows relative to the object that emits the light, which must place them above the
+/// darkness overlay.
+pub const LIGHT_GLOW_Z_OFFSET: f32 = 50000.;
+/// The absolute z-index of the darkness overlay, which must be above all terrain and object sprites.
+pub const DARKNESS_OVERLAY_Z: f32 = 40000.;
+/// The size (in px) of the darkness overlay, which must cover the entire viewport at the maximum zoom level.
+pub const DARKNESS_OVERLAY_SIZE: f32 = 20000.;
+// ------------------------------------------------------------------------------------------------------
+// Shadows
+pub const DRAW_OBJECT_SHADOWS: bool = true;
+/// The z-coordinate of the shadow mesh of each chunk, which must be above all terrain sprites and below all object
+/// sprites.
+pub const OBJECT_SHADOW_Z: f32 = 5000.;
+/// The number of vertices on the edge of each shadow ellipse.
+pub const OBJECT_SHADOW_SEGMENTS: u32 = 12;
+// ------------------------------------------------------------------------------------------------------
+// Regeneration diff
+/// The tint of a tile whose terrain has changed since the snapshot was taken.
+pub const REGENERATION_DIFF_TERRAIN_TINT: Color = Color::srgba(0.816, 0.529, 0.439, 0.6);
+/// The tint of a tile whose terrain is unchanged but whose object has changed since the snapshot was taken.
+pub const REGENERATION_DIFF_OBJECT_TINT: Color = Color::srgba(0.922, 0.796, 0.545, 0.6);
+/// The z-coordinate of the tints, which must be above all terrain and object sprites and below the darkness overlay.
+pub const REGENERATION_DIFF_OVERLAY_Z: f32 = 30000.;
+// ------------------------------------------------------------------------------------------------------
+// Occlusion
+pub const ENABLE_OCCLUSION_FADE: bool = true;
+/// The alpha of a tree or building while the cursor is behind it.
+pub const OCCLUSION_FADE_ALPHA: f32 = 0.5;
+/// The change in alpha per second while a tree or building fades out or back in.
+pub const OCCLUSION_FADE_SPEED: f32 = 4.;
+// ------------------------------------------------------------------------------------------------------
+// Layer visibility
+/// Whether each terrain layer and object group is shown by default.
+pub const LAYER_VISIBILITY: bool = true;
+// ------------------------------------------------------------------------------------------------------
+// Ocean
+pub const ENABLE_OCEAN_BACKDROP: bool = true;
+/// The z-coordinate of the ocean backdrop, which must be below all chunks and chunk placeholders.
+pub const OCEAN_BACKDROP_Z: f32 = -10.;
+/// The size (in px, at the default tile size) of the ocean backdrop, which must cover the entire viewport at the
+/// maximum zoom level.
+pub const OCEAN_BACKDROP_SIZE: f32 = 20000.;
+/// The size (in tiles) of the texture that is repeated across the ocean backdrop.
+pub const OCEAN_TEXTURE_SIZE_IN_TILES: u32 = 4;
+/// The value above which the wave function used to generate the ocean texture results in a lighter wave crest pixel.
+pub const OCEAN_WAVE_CREST_THRESHOLD: f32 = 0.92;
+/// The speed (in px per second, at the default tile size) at which the ocean backdrop scrolls.
+pub const OCEAN_SCROLL_SPEED: f32 = 4.;
+// ------------------------------------------------------------------------------------------------------
+// Demo mode
+pub const DEMO_MODE_SPEED: f32 = 300.;
+pub const DEMO_MODE_TURN_INTERVAL: Range<f32> = 2.0..8.0;
+pub const DEMO_MODE_MAX_TURN_ANGLE: f32 = std::f32::consts::FRAC_PI_2;
+/// The rate in radians per second at which the camera turns towards its next heading in demo mode.
+pub const DEMO_MODE_TURN_SPEED: f32 = 0.5;
+// ------------------------------------------------------------------------------------------------------
+// Recording
+pub const SESSION_RECORDING_PATH: &str = "session.recording.ron";
+// ------------------------------------------------------------------------------------------------------
+// Statistics
+pub const TERRAIN_STATISTICS_PATH: &str = "terrain.statistics.json";
+// ------------------------------------------------------------------------------------------------------
+// Stress test
+pub const STRESS_TEST_REPORT_PATH: &str = "stress-test.report.json";
+/// The number of entities that must exist before pruning is re-enabled.
+pub const STRESS_TEST_ENTITY_TARGET: usize = 30_000;
+/// The maximum number of chunks the camera is moved by before pruning is re-enabled, even if fewer entities exist.
+pub const STRESS_TEST_MAX_STEPS: u32 = 100;
+/// The minimum number of seconds between two camera moves, so that each move results in a world update.
+pub const STRESS_TEST_STEP_INTERVAL_SECS: f32 = 0.5;
+pub const STRESS_TEST_SAMPLE_FRAMES: usize = 300;
+pub const STRESS_TEST_SETTLE_SECS: f32 = 5.;
+/// The factor by which the steady state frame time may exceed the baseline frame time for the stress test to pass.
+pub const STRESS_TEST_TOLERANCE: f32 = 1.25;
+// ------------------------------------------------------------------------------------------------------
 // Common errors
 pub const TERRAIN_TYPE_ERROR: &'static str = "Invalid terrain type for drawing a terrain sprite";
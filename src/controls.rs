@@ -1,13 +1,30 @@
-use crate::constants::{CHUNK_SIZE, ORIGIN_TILE_GRID_SPAWN_POINT, TILE_SIZE};
-use crate::coords::Point;
-use crate::events::{MouseClickEvent, RefreshMetadata, ToggleDebugInfo, UpdateWorldEvent};
-use crate::resources::{CurrentChunk, GeneralGenerationSettings, ObjectGenerationSettings, Settings};
+use crate::camera::{camera_speed_system, CameraSpeed, DemoMode};
+use crate::coords::tile_size::{chunk_height, chunk_width};
+use crate::coords::{PickedTile, Point, TilePicker};
+use crate::events::{CrossedChunkBoundaryEvent, MouseClickEvent, RefreshMetadata, ToggleDebugInfo, UpdateWorldEvent};
+use crate::generation::lib::Direction;
+use crate::generation::resources::GenerationStepper;
+use crate::recording::SessionRecorder;
+use crate::resources::{CurrentChunk, GeneralGenerationSettings, ObjectGenerationSettings, Settings, SpawnPoint};
+use crate::stress_test::StressTest;
 use bevy::app::{App, Plugin};
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+use bevy::window::{MonitorSelection, PrimaryWindow, WindowMode};
 use bevy_inspector_egui::bevy_egui::EguiContexts;
 
 pub struct ControlPlugin;
 
+/// The keys that toggle the visibility of each terrain layer, starting with deep water.
+const TERRAIN_LAYER_VISIBILITY_KEYS: [KeyCode; 6] = [
+  KeyCode::Digit1,
+  KeyCode::Digit2,
+  KeyCode::Digit3,
+  KeyCode::Digit4,
+  KeyCode::Digit5,
+  KeyCode::Digit6,
+];
+
 impl Plugin for ControlPlugin {
   fn build(&self, app: &mut App) {
     app.add_systems(
@@ -15,26 +32,83 @@ impl Plugin for ControlPlugin {
       (
         event_control_system,
         settings_controls_system,
+        window_controls_system,
         left_mouse_click_system,
-        camera_movement_system,
+        camera_movement_system.after(camera_speed_system),
       ),
     );
   }
 }
 
+/// The events that regenerate or update the world when sent through the keyboard.
+#[derive(SystemParam)]
+struct WorldEventWriters<'w> {
+  refresh_metadata: EventWriter<'w, RefreshMetadata>,
+  update_world: EventWriter<'w, UpdateWorldEvent>,
+}
+
+/// The resources of the tools that can be started or toggled through the keyboard.
+#[derive(SystemParam)]
+struct ToolControls<'w> {
+  stepper: ResMut<'w, GenerationStepper>,
+  recorder: ResMut<'w, SessionRecorder>,
+  demo_mode: ResMut<'w, DemoMode>,
+  stress_test: ResMut<'w, StressTest>,
+}
+
 fn event_control_system(
   keyboard_input: Res<ButtonInput<KeyCode>>,
-  mut refresh_metadata_event: EventWriter<RefreshMetadata>,
+  mut world_events: WorldEventWriters,
+  mut tools: ToolControls,
+  settings: Res<Settings>,
   current_chunk: Res<CurrentChunk>,
+  spawn_point: Res<SpawnPoint>,
+  picker: TilePicker,
 ) {
   if keyboard_input.just_pressed(KeyCode::F5) | keyboard_input.just_pressed(KeyCode::KeyR) {
     info!("[F5]/[R] Triggered regeneration of the world");
-    let is_at_origin_spawn_point = current_chunk.get_tile_grid() == ORIGIN_TILE_GRID_SPAWN_POINT;
-    refresh_metadata_event.send(RefreshMetadata {
-      regenerate_world_after: is_at_origin_spawn_point,
-      prune_then_update_world_after: !is_at_origin_spawn_point,
+    let is_at_spawn_point = current_chunk.get_tile_grid() == spawn_point.tg;
+    world_events.refresh_metadata.send(RefreshMetadata {
+      regenerate_world_after: is_at_spawn_point,
+      prune_then_update_world_after: !is_at_spawn_point,
     });
   }
+
+  if keyboard_input.just_pressed(KeyCode::KeyG) {
+    if let Some(camera) = picker.camera() {
+      info!("[G] Triggered a dry run of updating the world at {}", camera.w);
+      world_events.update_world.send(UpdateWorldEvent::dry_run(camera.w));
+    }
+  }
+
+  if keyboard_input.just_pressed(KeyCode::KeyN) {
+    info!("[N] Requested the next world generation step");
+    tools.stepper.request_step();
+  }
+
+  if keyboard_input.just_pressed(KeyCode::F7) {
+    info!("[F7] Toggled recording the session");
+    tools.recorder.toggle_recording();
+  }
+
+  if keyboard_input.just_pressed(KeyCode::F8) {
+    info!("[F8] Requested replaying the last recorded session");
+    tools.recorder.request_replay();
+  }
+
+  if keyboard_input.just_pressed(KeyCode::KeyT) {
+    tools.demo_mode.toggle(settings.world.noise_seed);
+    info!("[T] Set demo mode to [{}]", tools.demo_mode.is_enabled());
+  }
+
+  if keyboard_input.just_pressed(KeyCode::KeyM) {
+    if tools.stress_test.is_running() {
+      warn!("[M] Ignored request to start the world pruning stress test because it is already running");
+    } else {
+      info!("[M] Requested starting the world pruning stress test");
+      tools.stress_test.request_start();
+    }
+  }
 }
 
 fn settings_controls_system(
@@ -89,55 +163,120 @@ fn settings_controls_system(
     object_settings.generate_objects = settings.object.generate_objects;
     info!("[F] Set object generation to [{}]", settings.object.generate_objects);
   }
+
+  if keyboard_input.just_pressed(KeyCode::KeyP) {
+    settings.general.freeze_generation = !settings.general.freeze_generation;
+    general_settings.freeze_generation = settings.general.freeze_generation;
+    info!("[P] Set freezing generation to [{}]", settings.general.freeze_generation);
+  }
+
+  for (layer, key) in TERRAIN_LAYER_VISIBILITY_KEYS.iter().enumerate() {
+    if keyboard_input.just_pressed(*key) {
+      if let Some(is_visible) = settings.general.layer_visibility.terrain_layer_mut(layer) {
+        *is_visible = !*is_visible;
+        info!(
+          "[{}] Set visibility of terrain layer {} to [{}]",
+          layer + 1,
+          layer,
+          is_visible
+        );
+      }
+      general_settings.layer_visibility = settings.general.layer_visibility;
+    }
+  }
+
+  if keyboard_input.just_pressed(KeyCode::Digit7) {
+    let layers = &mut settings.general.layer_visibility;
+    layers.decorations = !layers.decorations;
+    general_settings.layer_visibility = *layers;
+    info!("[7] Set visibility of decorations to [{}]", layers.decorations);
+  }
+
+  if keyboard_input.just_pressed(KeyCode::Digit8) {
+    let layers = &mut settings.general.layer_visibility;
+    layers.paths = !layers.paths;
+    general_settings.layer_visibility = *layers;
+    info!("[8] Set visibility of paths to [{}]", layers.paths);
+  }
+
+  if keyboard_input.just_pressed(KeyCode::Digit9) {
+    let layers = &mut settings.general.layer_visibility;
+    layers.buildings = !layers.buildings;
+    general_settings.layer_visibility = *layers;
+    info!("[9] Set visibility of buildings to [{}]", layers.buildings);
+  }
+}
+
+/// Toggles between windowed and borderless fullscreen mode. All systems that depend on the size of the window, such as
+/// the ones that determine the visible chunks, adapt to the new size automatically.
+fn window_controls_system(keyboard_input: Res<ButtonInput<KeyCode>>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+  if keyboard_input.just_pressed(KeyCode::F11) {
+    if let Ok(mut window) = windows.get_single_mut() {
+      window.mode = match window.mode {
+        WindowMode::Windowed => WindowMode::BorderlessFullscreen(MonitorSelection::Current),
+        _ => WindowMode::Windowed,
+      };
+      info!("[F11] Set window mode to [{:?}]", window.mode);
+    }
+  }
 }
 
 fn left_mouse_click_system(
   mouse_button_input: Res<ButtonInput<MouseButton>>,
-  camera: Query<(&Camera, &GlobalTransform)>,
-  windows: Query<&Window>,
+  picker: TilePicker,
   mut commands: Commands,
   mut egui_contexts: EguiContexts,
 ) {
   if mouse_button_input.just_pressed(MouseButton::Left) && !egui_contexts.ctx_mut().wants_pointer_input() {
-    let (camera, camera_transform) = camera.single();
-    if let Some(vec2) = windows
-      .single()
-      .cursor_position()
-      .and_then(|cursor| Some(camera.viewport_to_world(camera_transform, cursor)))
-      .map(|ray| ray.expect("Failed to find ray").origin.truncate())
-    {
-      let tg = Point::new_tile_grid_from_world_vec2(vec2);
-      let cg = Point::new_chunk_grid_from_world_vec2(vec2);
-      let tile_w = Point::new_world_from_tile_grid(tg);
-      debug!("[Left Mouse Button] Clicked on {} => {} {} {}", vec2.round(), tile_w, cg, tg);
-      commands.trigger(MouseClickEvent { tile_w, cg, tg });
+    if let Some(tile) = picker.cursor() {
+      debug!("[Left Mouse Button] Clicked on {} => {}", tile.translation.round(), tile);
+      commands.trigger(MouseClickEvent { tile });
     }
   }
 }
 
+/// Sends a `CrossedChunkBoundaryEvent` when the camera has left the `CurrentChunk`. While the camera is moving faster
+/// than `CAMERA_FAST_MOVEMENT_THRESHOLD`, no events are sent at all, so that chunks the camera merely passes through
+/// are never generated. The update for the chunk the camera ends up in is then sent once it slows down. The same
+/// applies while the generation is frozen, since the `CurrentChunk` isn't updated in the meantime.
 fn camera_movement_system(
   camera: Query<(&Camera, &GlobalTransform)>,
   current_chunk: Res<CurrentChunk>,
-  mut event: EventWriter<UpdateWorldEvent>,
+  recorder: Res<SessionRecorder>,
+  camera_speed: Res<CameraSpeed>,
+  settings: Res<Settings>,
+  mut event: EventWriter<CrossedChunkBoundaryEvent>,
 ) {
+  if recorder.is_replaying() || settings.general.freeze_generation {
+    return;
+  }
+  if camera_speed.is_fast() {
+    trace!("Camera is moving fast, deferring world updates until it slows down");
+    return;
+  }
   let translation = camera.single().1.translation();
-  let current_world = Point::new_world_from_world_vec2(translation.truncate());
+  let current_world = PickedTile::from_translation(translation.truncate()).w;
   let chunk_center_world = current_chunk.get_center_world();
   let distance_x = (current_world.x - chunk_center_world.x).abs();
   let distance_y = (current_world.y - chunk_center_world.y).abs();
-  let trigger_distance = ((CHUNK_SIZE * TILE_SIZE as i32) / 2) + 1;
+  let trigger_distance_x = (chunk_width() / 2) + 1;
+  let trigger_distance_y = (chunk_height() / 2) + 1;
   trace!(
-    "Camera moved to {:?} with distance x={:?}, y={:?} (trigger distance {})",
+    "Camera moved to {:?} with distance x={:?}, y={:?} (trigger distance x={}, y={})",
     current_world,
     distance_x,
     distance_y,
-    trigger_distance
+    trigger_distance_x,
+    trigger_distance_y
   );
 
-  if (distance_x >= trigger_distance) || (distance_y >= trigger_distance) {
-    event.send(UpdateWorldEvent {
-      is_forced_update: false,
-      tg: Point::new_tile_grid_from_world(current_world),
+  if (distance_x >= trigger_distance_x) || (distance_y >= trigger_distance_y) {
+    let from_cg = current_chunk.get_chunk_grid();
+    let to_cg = Point::new_chunk_grid_from_world(current_world);
+    event.send(CrossedChunkBoundaryEvent {
+      from_cg,
+      to_cg,
+      direction: Direction::from_points(&from_cg, &to_cg),
       w: current_world,
     });
   };
@@ -0,0 +1,78 @@
+//! Integrates the Bevy-free coordinate types of `coords::point` with Bevy, i.e. implements reflection for them and
+//! provides conversions from and to `Vec2` as well as all conversions that depend on the `TileSize` resource.
+use crate::coords::point::{ChunkGrid, CoordType, InternalGrid, Point, TileGrid, World};
+use crate::coords::tile_size::{chunk_height, chunk_width, tile_size};
+use bevy::prelude::Vec2;
+use bevy::reflect::{impl_reflect, impl_type_path};
+
+impl_type_path!((in crate::coords::point) World);
+impl_type_path!((in crate::coords::point) TileGrid);
+impl_type_path!((in crate::coords::point) ChunkGrid);
+impl_type_path!((in crate::coords::point) InternalGrid);
+
+impl_reflect!(
+  #[type_path = "crate::coords::point"]
+  struct Point<T: CoordType> {
+    x: i32,
+    y: i32,
+    #[reflect(ignore)]
+    _marker: std::marker::PhantomData<T>,
+  }
+);
+
+impl<T: CoordType> Point<T> {
+  pub fn to_vec2(&self) -> Vec2 {
+    Vec2::new(self.x as f32, self.y as f32)
+  }
+}
+
+impl Point<World> {
+  pub fn new_world_from_chunk_grid(cg: Point<ChunkGrid>) -> Self {
+    Self::new(cg.x * chunk_width(), cg.y * chunk_height())
+  }
+
+  pub fn new_world_from_tile_grid(tg: Point<TileGrid>) -> Self {
+    Self::new(tg.x * tile_size() as i32, tg.y * tile_size() as i32)
+  }
+
+  /// Returns a `Point` of type `World` with the `x` and `y` values rounded to the nearest integer to achieve this.
+  pub fn new_world_from_world_vec2(w: Vec2) -> Self {
+    Self::new(w.x.round() as i32, w.y.round() as i32)
+  }
+}
+
+impl Point<TileGrid> {
+  pub fn new_tile_grid_from_world(w: Point<World>) -> Self {
+    Self::new(
+      (w.x as f32 / tile_size() as f32).round() as i32,
+      (w.y as f32 / tile_size() as f32).round() as i32,
+    )
+  }
+
+  /// Returns a `Point` on the tile grid with the `x` and `y` values rounded to the nearest tile to achieve this. Used
+  /// to convert world coordinates to tile grid coordinates.
+  pub fn new_tile_grid_from_world_vec2(w: Vec2) -> Self {
+    Self::new(
+      ((w.x - (tile_size() as f32 / 2.)) / tile_size() as f32).round() as i32,
+      ((w.y + (tile_size() as f32 / 2.)) / tile_size() as f32).round() as i32,
+    )
+  }
+}
+
+impl Point<ChunkGrid> {
+  pub fn new_chunk_grid_from_world(w: Point<World>) -> Self {
+    Self::new(
+      ((w.x as f32 + 1.) / chunk_width() as f32).round() as i32,
+      ((w.y as f32 - 1.) / chunk_height() as f32).round() as i32,
+    )
+  }
+
+  /// Returns a `Point` on the chunk grid with the `x` and `y` values rounded to the nearest chunk to achieve this. Used
+  /// to convert world coordinates to chunk grid coordinates.
+  pub fn new_chunk_grid_from_world_vec2(w: Vec2) -> Self {
+    Self::new(
+      (w.x / chunk_width() as f32).round() as i32,
+      (w.y / chunk_height() as f32).round() as i32,
+    )
+  }
+}
@@ -1,8 +1,8 @@
-use crate::constants::TILE_SIZE;
 use crate::coords::point::*;
+use crate::coords::tile_size::tile_size;
 use std::fmt;
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Default)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Default, serde::Serialize, serde::Deserialize)]
 pub struct Coords {
   pub world: Point<World>,
   pub chunk_grid: Point<ChunkGrid>,
@@ -23,7 +23,7 @@ impl Coords {
   pub fn new_for_tile(ig: Point<InternalGrid>, tg: Point<TileGrid>) -> Self {
     let w = Point::new_world_from_tile_grid(tg.clone());
     Self {
-      chunk_grid: Point::new_chunk_grid_from_world(w.clone()),
+      chunk_grid: Point::new_chunk_grid_from_tile_grid(tg),
       world: w,
       tile_grid: tg,
       internal_grid: ig,
@@ -32,12 +32,12 @@ impl Coords {
 
   pub fn new_for_chunk(w: Point<World>, tg: Point<TileGrid>) -> Self {
     let cg = Point::new_chunk_grid_from_world(w.clone());
-    let world = Point::new_world(tg.x * TILE_SIZE as i32, tg.y * TILE_SIZE as i32);
+    let world = Point::new_world(tg.x * tile_size() as i32, tg.y * tile_size() as i32);
     if w != world {
       panic!("World coordinates do not match the tile grid coordinates");
     }
     Self {
-      world: Point::new_world(tg.x * TILE_SIZE as i32, tg.y * TILE_SIZE as i32),
+      world: Point::new_world(tg.x * tile_size() as i32, tg.y * tile_size() as i32),
       chunk_grid: cg,
       tile_grid: tg,
       internal_grid: Point::new_internal_grid(0, 0),
@@ -0,0 +1,8 @@
+//! The dimensions of the chunk grid. Kept free of Bevy dependencies, like `coords::point`, and re-exported by
+//! `constants`.
+
+/// The width (in tiles) of a chunk that is rendered on the screen. Chunks don't have to be square, e.g. wider chunks
+/// reduce the number of chunk boundaries crossed when mostly panning horizontally.
+pub const CHUNK_WIDTH: i32 = 16;
+/// The height (in tiles) of a chunk that is rendered on the screen.
+pub const CHUNK_HEIGHT: i32 = 16;
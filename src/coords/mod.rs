@@ -1,5 +1,13 @@
+mod bevy_support;
 pub mod coords;
+pub mod grid;
+pub mod picking;
 pub mod point;
+pub mod projection;
+pub mod tile_size;
 
 pub use coords::Coords;
+pub use picking::{PickedTile, TilePicker};
 pub use point::Point;
+pub use projection::WorldProjection;
+pub use tile_size::TileSize;
@@ -0,0 +1,87 @@
+use crate::coords::point::{ChunkGrid, InternalGrid, TileGrid, World};
+use crate::coords::projection::projection;
+use crate::coords::Point;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::{Camera, GlobalTransform, Query, Vec2, Window, With};
+use bevy::window::PrimaryWindow;
+use std::fmt;
+
+/// A translation on screen and the tile it points at. Tiles are picked by the `World` coordinates of the translation
+/// and not by their sprites, so that the result doesn't depend on which layers or objects have been spawned.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PickedTile {
+  /// The translation on screen, e.g. of the camera or the cursor, which is what sprite bounds are compared against.
+  pub translation: Vec2,
+  /// The `World` coordinates of the translation, rounded to the nearest integer.
+  pub w: Point<World>,
+  pub tg: Point<TileGrid>,
+  /// The chunk that contains the tile, i.e. the chunk whose top left tile is above and to the left of it.
+  pub cg: Point<ChunkGrid>,
+  /// The position of the tile within the chunk that contains it.
+  pub ig: Point<InternalGrid>,
+}
+
+impl PickedTile {
+  /// Picks the tile at the given translation on screen, taking the current projection into account.
+  pub fn from_translation(translation: Vec2) -> Self {
+    let w_vec2 = projection().unproject(translation);
+    let tg = Point::new_tile_grid_from_world_vec2(w_vec2);
+    let cg = Point::new_chunk_grid_from_tile_grid(tg);
+    let ig = Point::new_internal_grid_from_tile_grid(tg);
+
+    Self {
+      translation,
+      w: Point::new_world_from_world_vec2(w_vec2),
+      tg,
+      cg,
+      ig,
+    }
+  }
+
+  /// Returns the `World` coordinates of the top left corner of the tile.
+  pub fn tile_w(&self) -> Point<World> {
+    Point::new_world_from_tile_grid(self.tg)
+  }
+}
+
+impl fmt::Display for PickedTile {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{} {} {} {}", self.w, self.tg, self.cg, self.ig)
+  }
+}
+
+/// A system parameter that picks the tile under the cursor or at the center of the camera. Use this instead of
+/// converting translations manually, so that all tools agree on which tile and chunk a position belongs to.
+#[derive(SystemParam)]
+pub struct TilePicker<'w, 's> {
+  camera: Query<'w, 's, (&'static Camera, &'static GlobalTransform)>,
+  windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+}
+
+impl TilePicker<'_, '_> {
+  /// Returns the tile under the cursor, or `None` if the cursor is outside the primary window.
+  pub fn cursor(&self) -> Option<PickedTile> {
+    self.cursor_translation().map(PickedTile::from_translation)
+  }
+
+  /// Returns the tile at the center of the camera.
+  pub fn camera(&self) -> Option<PickedTile> {
+    self.camera_translation().map(PickedTile::from_translation)
+  }
+
+  /// Returns the translation on screen of the cursor, or `None` if the cursor is outside the primary window.
+  pub fn cursor_translation(&self) -> Option<Vec2> {
+    let (camera, camera_transform) = self.camera.get_single().ok()?;
+    let cursor = self.windows.get_single().ok()?.cursor_position()?;
+
+    camera.viewport_to_world_2d(camera_transform, cursor).ok()
+  }
+
+  pub fn camera_translation(&self) -> Option<Vec2> {
+    self
+      .camera
+      .get_single()
+      .ok()
+      .map(|(_, transform)| transform.translation().truncate())
+  }
+}
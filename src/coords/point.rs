@@ -1,11 +1,10 @@
-use crate::constants::{CHUNK_SIZE, TILE_SIZE};
-use crate::generation::lib::Direction;
-use bevy::prelude::Vec2;
-use bevy::reflect::{reflect_trait, Reflect};
+//! The coordinate types used throughout the application. This module deliberately has no Bevy dependencies, so that
+//! it can be shared with companion tools (e.g. save formats). Everything that integrates these types with Bevy lives in
+//! `coords::bevy_support` instead, including all conversions that depend on the configured tile size.
+use crate::coords::grid::{CHUNK_HEIGHT, CHUNK_WIDTH};
 use std::fmt;
-use std::ops::Add;
+use std::ops::{Add, Mul, Neg, Sub};
 
-#[reflect_trait]
 pub trait CoordType {
   fn type_name() -> &'static str
   where
@@ -14,7 +13,7 @@ pub trait CoordType {
 
 /// Represents the world coordinates of the application. Like every `Point`, it stores the `x` and `y` values as `i32`.
 /// Each `x`-`y` value pair represents a pixel in the world.
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Reflect)]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct World;
 
 impl CoordType for World {
@@ -24,8 +23,8 @@ impl CoordType for World {
 }
 
 /// Represents coordinates in the tile grid abstraction over the world coordinates. Each `Point` of type `TileGrid`
-/// represents a tile of `TILE_SIZE` in the world.
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Reflect)]
+/// represents a tile of `tile_size()` in the world.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct TileGrid;
 
 impl CoordType for TileGrid {
@@ -35,8 +34,8 @@ impl CoordType for TileGrid {
 }
 
 /// Represents coordinates in the tile grid abstraction over the world coordinates. Each `Point` of type `ChunkGrid`
-/// represents a chunk of `TILE_SIZE` * `CHUNK_SIZE` in the world.
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Reflect)]
+/// represents a chunk of `chunk_width()` * `chunk_height()` in the world.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct ChunkGrid;
 
 impl CoordType for ChunkGrid {
@@ -49,7 +48,7 @@ impl CoordType for ChunkGrid {
 /// other `Point`s in that the top left corner of the structure in which they are used is (0, 0) and the `x` and `y`
 /// values increase towards the bottom right corner, whereas all other `Point`s are based on the world coordinates i.e.
 /// not linked to structure that uses them.
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Reflect)]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct InternalGrid;
 
 impl CoordType for InternalGrid {
@@ -58,12 +57,13 @@ impl CoordType for InternalGrid {
   }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Reflect)]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(bound = "")]
 pub struct Point<T: CoordType> {
   pub x: i32,
   pub y: i32,
-  #[reflect(ignore)]
-  _marker: std::marker::PhantomData<T>,
+  #[serde(skip)]
+  pub(super) _marker: std::marker::PhantomData<T>,
 }
 
 impl<T: CoordType> fmt::Debug for Point<T> {
@@ -100,6 +100,42 @@ impl<T: CoordType> Add for Point<T> {
   }
 }
 
+impl<T: CoordType> Sub for Point<T> {
+  type Output = Self;
+
+  fn sub(self, other: Self) -> Self {
+    Self::new(self.x - other.x, self.y - other.y)
+  }
+}
+
+impl<T: CoordType> Neg for Point<T> {
+  type Output = Self;
+
+  fn neg(self) -> Self {
+    Self::new(-self.x, -self.y)
+  }
+}
+
+impl<T: CoordType> Mul<i32> for Point<T> {
+  type Output = Self;
+
+  fn mul(self, scalar: i32) -> Self {
+    Self::new(self.x * scalar, self.y * scalar)
+  }
+}
+
+impl<T: CoordType> From<(i32, i32)> for Point<T> {
+  fn from((x, y): (i32, i32)) -> Self {
+    Self::new(x, y)
+  }
+}
+
+impl<T: CoordType> From<Point<T>> for (i32, i32) {
+  fn from(point: Point<T>) -> Self {
+    (point.x, point.y)
+  }
+}
+
 impl<T: CoordType> Point<T> {
   pub fn new(x: i32, y: i32) -> Self {
     Self {
@@ -117,47 +153,15 @@ impl<T: CoordType> Point<T> {
     }
   }
 
-  pub fn from_direction(direction: &Direction) -> Self {
-    let (i, j) = match direction {
-      Direction::TopLeft => (-1, 1),
-      Direction::Top => (0, 1),
-      Direction::TopRight => (1, 1),
-      Direction::Left => (-1, 0),
-      Direction::Center => (0, 0),
-      Direction::Right => (1, 0),
-      Direction::BottomLeft => (-1, -1),
-      Direction::Bottom => (0, -1),
-      Direction::BottomRight => (1, -1),
-    };
-    Self::new(i, j)
-  }
-
   pub fn distance_to(&self, other: &Point<T>) -> f32 {
     ((self.x - other.x).pow(2) as f32 + (self.y - other.y).pow(2) as f32).sqrt()
   }
-
-  pub fn to_vec2(&self) -> Vec2 {
-    Vec2::new(self.x as f32, self.y as f32)
-  }
 }
 
 impl Point<World> {
   pub fn new_world(x: i32, y: i32) -> Self {
     Self::new(x, y)
   }
-
-  /// Returns a `Point` of type `World` with the `x` and `y` values rounded to the nearest integer to achieve this.
-  pub fn new_world_from_world_vec2(w: Vec2) -> Self {
-    Self::new(w.x.round() as i32, w.y.round() as i32)
-  }
-
-  pub fn new_world_from_chunk_grid(cg: Point<ChunkGrid>) -> Self {
-    Self::new(cg.x * CHUNK_SIZE * TILE_SIZE as i32, cg.y * CHUNK_SIZE * TILE_SIZE as i32)
-  }
-
-  pub fn new_world_from_tile_grid(tg: Point<TileGrid>) -> Self {
-    Self::new(tg.x * TILE_SIZE as i32, tg.y * TILE_SIZE as i32)
-  }
 }
 
 impl Point<InternalGrid> {
@@ -166,28 +170,17 @@ impl Point<InternalGrid> {
   pub fn new_internal_grid(x: i32, y: i32) -> Self {
     Self::new(x, y)
   }
+
+  /// Returns the position of the given tile within the chunk that contains it.
+  pub fn new_internal_grid_from_tile_grid(tg: Point<TileGrid>) -> Self {
+    Self::new(tg.x.rem_euclid(CHUNK_WIDTH), (-tg.y).rem_euclid(CHUNK_HEIGHT))
+  }
 }
 
 impl Point<TileGrid> {
   pub fn new_tile_grid(x: i32, y: i32) -> Self {
     Self::new(x, y)
   }
-
-  /// Returns a `Point` on the tile grid with the `x` and `y` values rounded to the nearest tile to achieve this. Used
-  /// to convert world coordinates to tile grid coordinates.
-  pub fn new_tile_grid_from_world_vec2(w: Vec2) -> Self {
-    Self::new(
-      ((w.x - (TILE_SIZE as f32 / 2.)) / TILE_SIZE as f32).round() as i32,
-      ((w.y + (TILE_SIZE as f32 / 2.)) / TILE_SIZE as f32).round() as i32,
-    )
-  }
-
-  pub fn new_tile_grid_from_world(w: Point<World>) -> Self {
-    Self::new(
-      (w.x as f32 / TILE_SIZE as f32).round() as i32,
-      (w.y as f32 / TILE_SIZE as f32).round() as i32,
-    )
-  }
 }
 
 impl Point<ChunkGrid> {
@@ -195,19 +188,8 @@ impl Point<ChunkGrid> {
     Self::new(x, y)
   }
 
-  /// Returns a `Point` on the chunk grid with the `x` and `y` values rounded to the nearest chunk to achieve this. Used
-  /// to convert world coordinates to chunk grid coordinates.
-  pub fn new_chunk_grid_from_world_vec2(w: Vec2) -> Self {
-    Self::new(
-      (w.x / (TILE_SIZE as f32 * CHUNK_SIZE as f32)).round() as i32,
-      (w.y / (TILE_SIZE as f32 * CHUNK_SIZE as f32)).round() as i32,
-    )
-  }
-
-  pub fn new_chunk_grid_from_world(w: Point<World>) -> Self {
-    Self::new(
-      ((w.x as f32 + 1.) / (TILE_SIZE as f32 * CHUNK_SIZE as f32)).round() as i32,
-      ((w.y as f32 - 1.) / (TILE_SIZE as f32 * CHUNK_SIZE as f32)).round() as i32,
-    )
+  /// Returns the chunk that contains the given tile, i.e. the chunk whose top left tile is above and to the left of it.
+  pub fn new_chunk_grid_from_tile_grid(tg: Point<TileGrid>) -> Self {
+    Self::new(tg.x.div_euclid(CHUNK_WIDTH), -(-tg.y).div_euclid(CHUNK_HEIGHT))
   }
 }
@@ -0,0 +1,121 @@
+use crate::constants::CHUNK_HEIGHT;
+use crate::coords::tile_size::tile_size;
+use crate::coords::Coords;
+use bevy::log::*;
+use bevy::prelude::{Quat, Resource, Transform, Vec2, Vec3};
+use std::f32::consts::{FRAC_1_SQRT_2, FRAC_PI_4};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// The projection that all spawned sprites and camera conversions use. Sprites are also spawned outside of systems
+/// (e.g. by the command queues of async tasks), which is why the value of the `WorldProjection` resource is mirrored
+/// here.
+static CURRENT_PROJECTION: AtomicU8 = AtomicU8::new(WorldProjection::TopDown as u8);
+
+/// The way the world is presented on screen. Must be configured once at startup by inserting this resource before
+/// adding the plugins, e.g. via `WorldProjection::from_args()`, and must not be modified afterwards. Only affects the
+/// presentation: all generation data and `World` coordinates are identical for every projection, and translations on
+/// screen (e.g. of the camera or the cursor) must be converted using `unproject` before they are used as `World`
+/// coordinates.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WorldProjection {
+  /// Renders every tile as an axis-aligned square.
+  #[default]
+  TopDown,
+  /// Renders every tile as a diamond that is twice as wide as it is high, with the top left corner of the world
+  /// pointing to the left. Object sprites remain upright and are y-sorted along the diagonal rows of tiles.
+  Isometric,
+}
+
+impl WorldProjection {
+  /// Reads the projection from the `--projection <top-down|isometric>` command line argument, falling back to the
+  /// default projection if the argument is not present. Returns an error if the value is missing or not a projection.
+  pub fn from_args() -> Result<Self, String> {
+    let args = std::env::args().collect::<Vec<String>>();
+    match args.iter().position(|arg| arg == "--projection") {
+      Some(i) => match args.get(i + 1).map(String::as_str) {
+        Some("top-down") => Ok(Self::TopDown),
+        Some("isometric") => Ok(Self::Isometric),
+        other => Err(format!(
+          "Failed to parse value of --projection, expected top-down or isometric but was [{}]",
+          other.unwrap_or_default()
+        )),
+      },
+      None => Ok(Self::default()),
+    }
+  }
+
+  /// Makes this projection the one used by all spawned sprites and camera conversions.
+  pub(crate) fn apply(&self) {
+    if *self != Self::default() {
+      info!("Using the {:?} projection instead of the default {:?}", self, Self::default());
+    }
+    CURRENT_PROJECTION.store(*self as u8, Ordering::Relaxed);
+  }
+
+  /// Converts `World` coordinates to a translation on screen.
+  pub fn project(&self, w: Vec2) -> Vec2 {
+    match self {
+      Self::TopDown => w,
+      Self::Isometric => Vec2::new((w.x - w.y) / 2., (w.x + w.y) / 4.),
+    }
+  }
+
+  /// Converts a translation on screen to `World` coordinates. The inverse of `project`.
+  pub fn unproject(&self, translation: Vec2) -> Vec2 {
+    match self {
+      Self::TopDown => translation,
+      Self::Isometric => Vec2::new(translation.x + 2. * translation.y, 2. * translation.y - translation.x),
+    }
+  }
+
+  /// Returns the transform of a tile entity, i.e. the parent of all terrain and object sprites of a tile, whose top
+  /// left corner is at the given `World` coordinates. In the isometric projection, the tile entity compresses its
+  /// children vertically, which turns the rotated terrain sprites into diamonds.
+  pub fn tile_transform(&self, w: Vec2) -> Transform {
+    let translation = self.project(w).extend(0.);
+    match self {
+      Self::TopDown => Transform::from_translation(translation),
+      Self::Isometric => Transform::from_translation(translation).with_scale(Vec3::new(1., 0.5, 1.)),
+    }
+  }
+
+  /// Returns the transform of a terrain sprite relative to its tile entity.
+  pub fn terrain_sprite_transform(&self, z: f32) -> Transform {
+    match self {
+      Self::TopDown => Transform::from_xyz(0., 0., z),
+      Self::Isometric => Transform::from_xyz(0., 0., z)
+        .with_rotation(Quat::from_rotation_z(FRAC_PI_4))
+        .with_scale(Vec3::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2, 1.)),
+    }
+  }
+
+  /// Returns the transform of an object sprite relative to its tile entity. The sprite is anchored at the bottom of the
+  /// tile, offset by the given number of pixels on screen, and is never distorted by the tile entity.
+  pub fn object_sprite_transform(&self, offset: Vec2, scale: f32, z: f32) -> Transform {
+    let tile_size = tile_size() as f32;
+    match self {
+      Self::TopDown => {
+        Transform::from_xyz(tile_size / 2. + offset.x, -tile_size + offset.y, z).with_scale(Vec3::new(scale, scale, 1.))
+      }
+      Self::Isometric => Transform::from_xyz(tile_size / 2. + offset.x, (-tile_size / 4. + offset.y) * 2., z)
+        .with_scale(Vec3::new(scale, scale * 2., 1.)),
+    }
+  }
+
+  /// Returns the position of the tile with the given coordinates in the order in which object sprites are drawn, where
+  /// sprites of tiles with a higher value are drawn in front of those with a lower value.
+  pub fn depth(&self, coords: &Coords) -> f32 {
+    match self {
+      Self::TopDown => (coords.internal_grid.y - coords.chunk_grid.y * CHUNK_HEIGHT) as f32,
+      Self::Isometric => -(coords.tile_grid.x + coords.tile_grid.y) as f32 / 2.,
+    }
+  }
+}
+
+/// Returns the projection that is currently used.
+pub fn projection() -> WorldProjection {
+  match CURRENT_PROJECTION.load(Ordering::Relaxed) {
+    1 => WorldProjection::Isometric,
+    _ => WorldProjection::TopDown,
+  }
+}
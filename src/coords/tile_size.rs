@@ -0,0 +1,108 @@
+use crate::constants::{CHUNK_HEIGHT, CHUNK_WIDTH, DEFAULT_TILE_SIZE};
+use bevy::log::*;
+use bevy::prelude::{Resource, UVec2};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// The tile size that all coordinate conversions use, mirroring the value of the `TileSize` resource. This is global
+/// state on purpose: coordinates are converted in plain functions such as `Point::new_chunk_grid_from_world`, which
+/// are called from within async tasks and other code without access to the ECS, and threading the tile size through
+/// all of them would touch nearly every coordinate conversion. It is safe as long as it is written exactly once, which
+/// `SharedResourcesPlugin` does via `TileSize::apply` before any resource is created or any task is spawned. Writing a
+/// different value afterwards would invalidate every coordinate that has been converted up to that point.
+static CURRENT_TILE_SIZE: AtomicU32 = AtomicU32::new(DEFAULT_TILE_SIZE);
+
+/// The size of a tile in pixels. Must be configured once at startup by inserting this resource before adding the
+/// plugins, e.g. via `TileSize::from_args()`, and must not be modified afterwards. Only art packs with the same tile size
+/// are used, and the app panics once the art packs are loaded if there is none. All pixel values in constants and rule
+/// sets are expressed relative to `DEFAULT_TILE_SIZE` and are scaled automatically.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileSize(u32);
+
+impl Default for TileSize {
+  fn default() -> Self {
+    Self(DEFAULT_TILE_SIZE)
+  }
+}
+
+impl TileSize {
+  /// Creates a new `TileSize`. Returns an error if `px` is zero or odd because many offsets rely on half a tile being a
+  /// whole number of pixels.
+  pub fn new(px: u32) -> Result<Self, String> {
+    if px == 0 || !px.is_multiple_of(2) {
+      return Err(format!(
+        "Tile size must be a positive, even number of pixels but was [{}]",
+        px
+      ));
+    }
+
+    Ok(Self(px))
+  }
+
+  /// Reads the tile size from the `--tile-size <px>` command line argument, falling back to the default tile size if
+  /// the argument is not present. Returns an error if the value is missing or not a valid tile size.
+  pub fn from_args() -> Result<Self, String> {
+    let args = std::env::args().collect::<Vec<String>>();
+    match args.iter().position(|arg| arg == "--tile-size") {
+      Some(i) => {
+        let value = args.get(i + 1).map(String::as_str).unwrap_or_default();
+        let px = value
+          .parse::<u32>()
+          .map_err(|_| format!("Failed to parse value of --tile-size [{}] as a number of pixels", value))?;
+        Self::new(px)
+      }
+      None => Ok(Self::default()),
+    }
+  }
+
+  pub fn px(&self) -> u32 {
+    self.0
+  }
+
+  /// Makes this tile size the one used by all coordinate conversions.
+  pub(crate) fn apply(&self) {
+    if self.0 != DEFAULT_TILE_SIZE {
+      info!(
+        "Using a tile size of {}px instead of the default {}px",
+        self.0, DEFAULT_TILE_SIZE
+      );
+    }
+    CURRENT_TILE_SIZE.store(self.0, Ordering::Relaxed);
+  }
+}
+
+/// Returns the size of a tile in pixels, as configured via the `TileSize` resource at startup.
+pub fn tile_size() -> u32 {
+  CURRENT_TILE_SIZE.load(Ordering::Relaxed)
+}
+
+/// Returns the width of a chunk in world coordinates, i.e. in pixels.
+pub fn chunk_width() -> i32 {
+  CHUNK_WIDTH * tile_size() as i32
+}
+
+/// Returns the height of a chunk in world coordinates, i.e. in pixels.
+pub fn chunk_height() -> i32 {
+  CHUNK_HEIGHT * tile_size() as i32
+}
+
+/// Returns the factor by which pixel values that are expressed relative to `DEFAULT_TILE_SIZE` must be multiplied.
+pub fn tile_scale() -> f32 {
+  tile_size() as f32 / DEFAULT_TILE_SIZE as f32
+}
+
+/// Scales a sprite size that is expressed relative to `DEFAULT_TILE_SIZE` to the current tile size.
+pub fn scaled_to_tile_size(size: UVec2) -> UVec2 {
+  size * tile_size() / DEFAULT_TILE_SIZE
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn new_rejects_zero_and_odd_tile_sizes() {
+    assert_eq!(TileSize::new(64).map(|tile_size| tile_size.px()), Ok(64));
+    assert!(TileSize::new(0).is_err());
+    assert!(TileSize::new(33).is_err());
+  }
+}
@@ -1,6 +1,9 @@
-use crate::coords::point::{ChunkGrid, TileGrid, World};
-use crate::coords::Point;
-use bevy::prelude::{App, Event, Plugin};
+use crate::coords::point::{ChunkGrid, InternalGrid, TileGrid, World};
+use crate::coords::projection::projection;
+use crate::coords::{PickedTile, Point};
+use crate::generation::lib::{Direction, TerrainType};
+use crate::generation::object::lib::ObjectName;
+use bevy::prelude::{App, Entity, Event, Plugin, Vec2};
 
 pub struct SharedEventsPlugin;
 
@@ -11,12 +14,17 @@ impl Plugin for SharedEventsPlugin {
       .add_event::<RegenerateWorldEvent>()
       .add_event::<ToggleDebugInfo>()
       .add_event::<MouseClickEvent>()
+      .add_event::<CrossedChunkBoundaryEvent>()
       .add_event::<UpdateWorldEvent>()
-      .add_event::<PruneWorldEvent>();
+      .add_event::<PruneWorldEvent>()
+      .add_event::<RerollObjectsEvent>()
+      .add_event::<RemoveObjectEvent>()
+      .add_event::<PlaceObjectEvent>()
+      .add_event::<PaintTerrainEvent>();
   }
 }
 
-#[derive(Event)]
+#[derive(Event, Clone, Copy)]
 /// An event that triggers a refresh of the metadata and allows triggering either a regeneration or pruning and
 /// updating of the world after.
 pub struct RefreshMetadata {
@@ -27,7 +35,11 @@ pub struct RefreshMetadata {
 #[derive(Event)]
 /// An event that triggers the regeneration of the world. It will cause the world entity and all its descendants to be
 /// removed before generating an entirely new world based on the current `Settings`.
-pub struct RegenerateWorldEvent {}
+pub struct RegenerateWorldEvent {
+  /// Whether the event was sent by the generation as a result of another event, e.g. a `RefreshMetadata`, rather than
+  /// by the user or a game layer. Derived events are not recorded, since replaying the original event sends them again.
+  pub is_derived: bool,
+}
 
 #[derive(Event)]
 /// An event that triggers the evaluation of the world, causing the generation of new chunks and/or the despawning of
@@ -37,8 +49,60 @@ pub struct UpdateWorldEvent {
   /// `PruneWorldEvent` after the update which would happen by default. Used when updating the world via the UI when
   /// the `CurrentChunk` has not changed.
   pub is_forced_update: bool,
+  /// Will cause the update to be evaluated without mutating the world. Instead, a `DryRunReport` describing the chunks
+  /// that would be spawned and pruned is produced. Used for debugging the spawn point and pruning logic.
+  pub is_dry_run: bool,
+  /// The location that triggered the update. All other representations of it are derived on demand.
+  pub w: Point<World>,
+}
+
+impl UpdateWorldEvent {
+  /// Creates an event that only updates the world if `w` is outside the bounds of the `CurrentChunk`.
+  pub fn new(w: Point<World>) -> Self {
+    Self {
+      is_forced_update: false,
+      is_dry_run: false,
+      w,
+    }
+  }
+
+  /// Creates an event for the given translation on screen (e.g. of the camera), rounded to the nearest world
+  /// coordinates.
+  pub fn from_translation(translation: Vec2) -> Self {
+    Self::new(Point::new_world_from_world_vec2(projection().unproject(translation)))
+  }
+
+  /// Creates an event that updates the world even if `w` is inside the bounds of the `CurrentChunk`.
+  pub fn forced(w: Point<World>) -> Self {
+    Self {
+      is_forced_update: true,
+      ..Self::new(w)
+    }
+  }
+
+  /// Creates an event that is only evaluated and reported without mutating the world.
+  pub fn dry_run(w: Point<World>) -> Self {
+    Self {
+      is_dry_run: true,
+      ..Self::new(w)
+    }
+  }
+
+  pub fn tg(&self) -> Point<TileGrid> {
+    Point::new_tile_grid_from_world(self.w)
+  }
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+/// An event that is sent whenever the camera moves outside the bounds of the `CurrentChunk`. The `direction` describes
+/// where `to_cg` is located relative to `from_cg` and `w` is the position of the camera at the time the boundary was
+/// crossed. Consumed internally to update the world but also intended for any other system that needs to react to
+/// the camera entering another chunk.
+pub struct CrossedChunkBoundaryEvent {
+  pub from_cg: Point<ChunkGrid>,
+  pub to_cg: Point<ChunkGrid>,
+  pub direction: Direction,
   pub w: Point<World>,
-  pub tg: Point<TileGrid>,
 }
 
 #[derive(Event)]
@@ -48,14 +112,55 @@ pub struct UpdateWorldEvent {
 pub struct PruneWorldEvent {
   pub despawn_all_chunks: bool,
   pub update_world_after: bool,
+  /// Whether the event was sent by the generation as a result of another event, e.g. at the end of a world update,
+  /// rather than by the user or a game layer. Derived events are not recorded, since replaying the original event sends
+  /// them again.
+  pub is_derived: bool,
 }
 
 #[derive(Event)]
-pub struct ToggleDebugInfo {}
+/// An event that triggers the removal of all objects of the currently spawned chunks and the re-running of the object
+/// generation for these chunks using a different object seed. The terrain of the chunks remains unchanged.
+pub struct RerollObjectsEvent {}
 
 #[derive(Event)]
-pub struct MouseClickEvent {
-  pub tile_w: Point<World>,
+/// An event that triggers the removal of a single object that has been spawned in the world. The object can either be
+/// identified by its entity or by the `ChunkGrid` and `InternalGrid` coordinates of the tile it was spawned on. Allows
+/// e.g. a game layer to implement chopping down trees without having to know about the generation internals. The entities
+/// of objects are reused once their chunk has been despawned, so an entity should only be used in the frame it was
+/// obtained in. Use the coordinates to refer to an object for longer.
+pub enum RemoveObjectEvent {
+  ByEntity(Entity),
+  ByCoords(Point<ChunkGrid>, Point<InternalGrid>),
+}
+
+#[derive(Event)]
+/// An event that triggers the placement of an object with the given `ObjectName` on the tile at the given `ChunkGrid`
+/// and `InternalGrid` coordinates, replacing any object that already exists there. By default, the placement is
+/// validated against the same terrain and tile type rule sets that are used during object generation. If
+/// `is_relaxed` is `true`, the tile type rules are ignored and the object only needs to exist for the terrain.
+pub struct PlaceObjectEvent {
+  pub name: ObjectName,
   pub cg: Point<ChunkGrid>,
+  pub ig: Point<InternalGrid>,
+  pub is_relaxed: bool,
+}
+
+#[derive(Event)]
+/// An event that triggers painting the given `TerrainType` onto all tiles within `radius` tiles of the tile at the
+/// given `TileGrid` coordinates. The chunks affected by the painted tiles are regenerated, including their objects.
+/// Sent by the terrain brush, which is a debugging tool for reproducing specific tile type configurations.
+pub struct PaintTerrainEvent {
   pub tg: Point<TileGrid>,
+  pub terrain: TerrainType,
+  pub radius: i32,
+}
+
+#[derive(Event)]
+pub struct ToggleDebugInfo {}
+
+#[derive(Event)]
+/// An event that is triggered when clicking on the world. Carries the tile that was clicked on.
+pub struct MouseClickEvent {
+  pub tile: PickedTile,
 }
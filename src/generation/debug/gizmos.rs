@@ -1,5 +1,6 @@
 use crate::constants::*;
-use crate::coords::Point;
+use crate::coords::tile_size::{chunk_height, chunk_width, tile_size};
+use crate::coords::TilePicker;
 use crate::resources::{CurrentChunk, Settings};
 use bevy::app::{App, Plugin};
 use bevy::gizmos::AppGizmoBuilder;
@@ -17,28 +18,23 @@ impl Plugin for GizmosPlugin {
 #[derive(Default, Reflect, GizmoConfigGroup)]
 struct DebugGizmos {}
 
-fn draw_gizmos_system(
-  mut gizmos: Gizmos,
-  settings: Res<Settings>,
-  current_chunk: Res<CurrentChunk>,
-  camera: Query<(&Camera, &GlobalTransform)>,
-) {
+fn draw_gizmos_system(mut gizmos: Gizmos, settings: Res<Settings>, current_chunk: Res<CurrentChunk>, picker: TilePicker) {
   if !settings.general.draw_gizmos {
     return;
   }
 
   let current_chunk_center_world = current_chunk.get_center_world();
   let current_chunk_world = current_chunk.get_world();
-  let chunk_size = TILE_SIZE as f32 * CHUNK_SIZE as f32;
-  let cam_position = camera.single().1.translation();
-  let camera_world = Point::new_world_from_world_vec2(cam_position.truncate());
+  let Some(camera_world) = picker.camera().map(|camera| camera.w) else {
+    return;
+  };
 
   // Tile grid
   gizmos
     .grid_2d(
       current_chunk_center_world.to_vec2(),
-      UVec2::new(CHUNK_SIZE as u32, CHUNK_SIZE as u32),
-      Vec2::new(TILE_SIZE as f32, TILE_SIZE as f32),
+      UVec2::new(CHUNK_WIDTH as u32, CHUNK_HEIGHT as u32),
+      Vec2::new(tile_size() as f32, tile_size() as f32),
       DARK,
     )
     .outer_edges();
@@ -48,13 +44,13 @@ fn draw_gizmos_system(
     .grid_2d(
       current_chunk_center_world.to_vec2(),
       UVec2::new(3, 3),
-      Vec2::new(chunk_size, chunk_size),
+      Vec2::new(chunk_width() as f32, chunk_height() as f32),
       DARK,
     )
     .outer_edges();
 
   // Center of the current chunk and view port
-  gizmos.circle_2d(current_chunk_center_world.to_vec2(), TILE_SIZE as f32, RED);
+  gizmos.circle_2d(current_chunk_center_world.to_vec2(), tile_size() as f32, RED);
 
   // Line from the current world position to the center of the current chunk
   gizmos.line_2d(camera_world.to_vec2(), current_chunk_world.to_vec2(), DARK);
@@ -1,14 +1,22 @@
 use crate::generation::debug::gizmos::GizmosPlugin;
+use crate::generation::debug::regeneration_diff::RegenerationDiffPlugin;
 use crate::generation::debug::tile_debugger::TileDebuggerPlugin;
+use crate::generation::debug::wfc_visualiser::WfcVisualiserDebugPlugin;
 use bevy::app::{App, Plugin};
 
 mod gizmos;
+mod regeneration_diff;
 pub mod tile_debugger;
+mod wfc_visualiser;
 
 pub struct DebugPlugin;
 
 impl Plugin for DebugPlugin {
   fn build(&self, app: &mut App) {
-    app.add_plugins(TileDebuggerPlugin).add_plugins(GizmosPlugin);
+    app
+      .add_plugins(TileDebuggerPlugin)
+      .add_plugins(GizmosPlugin)
+      .add_plugins(WfcVisualiserDebugPlugin)
+      .add_plugins(RegenerationDiffPlugin);
   }
 }
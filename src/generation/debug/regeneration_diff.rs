@@ -0,0 +1,211 @@
+use crate::constants::*;
+use crate::coords::point::TileGrid;
+use crate::coords::projection::projection;
+use crate::coords::tile_size::tile_size;
+use crate::coords::Point;
+use crate::events::{RefreshMetadata, RerollObjectsEvent};
+use crate::generation::lib::{ChunkComponent, TerrainType};
+use crate::generation::object::lib::ObjectName;
+use crate::generation::resources::NavData;
+use crate::resources::Settings;
+use crate::states::{AppState, GenerationState};
+use bevy::app::{App, Plugin, Update};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use bevy::utils::HashMap;
+
+pub struct RegenerationDiffPlugin;
+
+impl Plugin for RegenerationDiffPlugin {
+  fn build(&self, app: &mut App) {
+    app.init_resource::<RegenerationDiff>().add_systems(
+      Update,
+      (
+        take_snapshot_system,
+        compare_with_snapshot_system.run_if(in_state(GenerationState::Idling).and(state_changed::<GenerationState>)),
+        clear_regeneration_diff_system,
+      )
+        .chain()
+        .run_if(in_state(AppState::Running)),
+    );
+  }
+}
+
+/// The terrain and the object of a single tile.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TileSnapshot {
+  terrain: TerrainType,
+  object: Option<ObjectName>,
+}
+
+/// Holds the snapshot of all spawned tiles that is taken before the world is regenerated or its objects are rerolled,
+/// until it is compared with the world once the generation has completed.
+#[derive(Resource, Default)]
+struct RegenerationDiff {
+  snapshot: Option<HashMap<Point<TileGrid>, TileSnapshot>>,
+}
+
+/// Marks the tint of a tile that has changed between two generations. Spawned as a child of the chunk of the tile, so
+/// that it is despawned together with the chunk.
+#[derive(Component)]
+struct RegenerationDiffTintComponent;
+
+#[derive(Default)]
+struct DiffStatistics {
+  compared: usize,
+  terrain_changed: usize,
+  objects_added: usize,
+  objects_removed: usize,
+  objects_replaced: usize,
+}
+
+/// The events that trigger a new snapshot.
+#[derive(SystemParam)]
+struct SnapshotTriggers<'w, 's> {
+  refresh_metadata_events: EventReader<'w, 's, RefreshMetadata>,
+  reroll_objects_events: EventReader<'w, 's, RerollObjectsEvent>,
+}
+
+impl SnapshotTriggers<'_, '_> {
+  /// Consumes all pending events and returns whether there were any.
+  fn any(&mut self) -> bool {
+    self.refresh_metadata_events.read().count() + self.reroll_objects_events.read().count() > 0
+  }
+}
+
+fn take_snapshot_system(
+  mut commands: Commands,
+  mut triggers: SnapshotTriggers,
+  chunks: Query<&ChunkComponent>,
+  tints: Query<Entity, With<RegenerationDiffTintComponent>>,
+  nav_data: Res<NavData>,
+  settings: Res<Settings>,
+  mut diff: ResMut<RegenerationDiff>,
+) {
+  if !triggers.any() || !settings.general.enable_regeneration_diff {
+    return;
+  }
+  for entity in tints.iter() {
+    commands.entity(entity).despawn_recursive();
+  }
+  let snapshot: HashMap<Point<TileGrid>, TileSnapshot> = chunks
+    .iter()
+    .flat_map(|chunk_component| snapshot_chunk(chunk_component, &nav_data))
+    .collect();
+  debug!("Took a snapshot of {} tile(s) for the regeneration diff", snapshot.len());
+  diff.snapshot = Some(snapshot);
+}
+
+/// Compares the snapshot with all tiles that exist now, tints every tile that has changed and logs summary statistics.
+/// Tiles that only exist in either the snapshot or the current world are ignored.
+fn compare_with_snapshot_system(
+  mut commands: Commands,
+  chunks: Query<(Entity, &ChunkComponent)>,
+  nav_data: Res<NavData>,
+  mut diff: ResMut<RegenerationDiff>,
+) {
+  let Some(snapshot) = diff.snapshot.take() else {
+    return;
+  };
+  let mut statistics = DiffStatistics::default();
+  for (chunk_entity, chunk_component) in chunks.iter() {
+    for (tg, after) in snapshot_chunk(chunk_component, &nav_data) {
+      let Some(before) = snapshot.get(&tg) else {
+        continue;
+      };
+      statistics.compared += 1;
+      let is_terrain_changed = before.terrain != after.terrain;
+      if is_terrain_changed {
+        statistics.terrain_changed += 1;
+      }
+      match (before.object, after.object) {
+        (None, Some(_)) => statistics.objects_added += 1,
+        (Some(_), None) => statistics.objects_removed += 1,
+        (Some(a), Some(b)) if a != b => statistics.objects_replaced += 1,
+        _ => {}
+      }
+      if is_terrain_changed || before.object != after.object {
+        let colour = match is_terrain_changed {
+          true => REGENERATION_DIFF_TERRAIN_TINT,
+          false => REGENERATION_DIFF_OBJECT_TINT,
+        };
+        let tint = commands.spawn(tint(&tg, colour)).id();
+        commands.entity(chunk_entity).add_child(tint);
+      }
+    }
+  }
+  let percentage = |count: usize| count as f32 / statistics.compared.max(1) as f32 * 100.;
+  info!(
+    "Regeneration diff compared {} tile(s): terrain changed on {} ({:.1}%), objects changed on {} ({:.1}%) with {} \
+    added, {} removed and {} replaced",
+    statistics.compared,
+    statistics.terrain_changed,
+    percentage(statistics.terrain_changed),
+    statistics.objects_added + statistics.objects_removed + statistics.objects_replaced,
+    percentage(statistics.objects_added + statistics.objects_removed + statistics.objects_replaced),
+    statistics.objects_added,
+    statistics.objects_removed,
+    statistics.objects_replaced
+  );
+}
+
+fn clear_regeneration_diff_system(
+  mut commands: Commands,
+  tints: Query<Entity, With<RegenerationDiffTintComponent>>,
+  settings: Res<Settings>,
+  mut diff: ResMut<RegenerationDiff>,
+) {
+  if settings.general.enable_regeneration_diff {
+    return;
+  }
+  diff.snapshot = None;
+  for entity in tints.iter() {
+    commands.entity(entity).despawn_recursive();
+  }
+}
+
+/// Returns the terrain of every tile of the given chunk, together with the object on it according to the `NavData`,
+/// which reflects the collapsed object grid of the chunk.
+fn snapshot_chunk<'a>(
+  chunk_component: &'a ChunkComponent,
+  nav_data: &'a NavData,
+) -> impl Iterator<Item = (Point<TileGrid>, TileSnapshot)> + 'a {
+  let chunk_nav_data = nav_data.get(&chunk_component.coords.chunk_grid);
+  chunk_component
+    .layered_plane
+    .flat
+    .data
+    .iter()
+    .flatten()
+    .flatten()
+    .map(move |tile| {
+      let object = chunk_nav_data.and_then(|nav_data| nav_data.object(&tile.coords.internal_grid));
+      (
+        tile.coords.tile_grid,
+        TileSnapshot {
+          terrain: tile.terrain,
+          object,
+        },
+      )
+    })
+}
+
+fn tint(tg: &Point<TileGrid>, colour: Color) -> (Name, Sprite, Transform, RegenerationDiffTintComponent) {
+  let w = Point::new_world_from_tile_grid(*tg).to_vec2();
+  let mut transform = projection()
+    .tile_transform(w)
+    .mul_transform(projection().terrain_sprite_transform(0.));
+  transform.translation.z = REGENERATION_DIFF_OVERLAY_Z;
+  (
+    Name::new(format!("Regeneration Diff Tint {}", tg)),
+    Sprite {
+      color: colour,
+      custom_size: Some(Vec2::splat(tile_size() as f32)),
+      anchor: Anchor::TopLeft,
+      ..Default::default()
+    },
+    transform,
+    RegenerationDiffTintComponent,
+  )
+}
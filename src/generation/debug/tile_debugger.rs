@@ -1,12 +1,14 @@
 use crate::constants::*;
 use crate::coords::point::{TileGrid, World};
+use crate::coords::tile_size::tile_size;
 use crate::coords::Point;
 use crate::events::{MouseClickEvent, RegenerateWorldEvent, ToggleDebugInfo};
 use crate::generation::lib::{ObjectComponent, Tile, TileComponent};
-use crate::generation::resources::{ChunkComponentIndex, GenerationResourcesCollection};
+use crate::generation::resources::{ChunkComponentIndex, GenerationResourcesCollection, NavData};
 use crate::resources::Settings;
 use bevy::app::{App, Plugin, Update};
 use bevy::core::Name;
+use bevy::ecs::system::SystemParam;
 use bevy::log::*;
 use bevy::prelude::{
   default, Commands, Component, Entity, EventReader, JustifyText, OnAdd, OnRemove, Query, Res, ResMut, Resource, Text2d,
@@ -105,26 +107,40 @@ fn on_remove_tile_component_trigger(
   });
 }
 
+/// The indexes that are used to look up everything that exists at a clicked tile.
+#[derive(SystemParam)]
+struct ComponentIndexes<'w> {
+  objects: Res<'w, ObjectComponentIndex>,
+  tiles: Res<'w, TileComponentIndex>,
+  chunks: Res<'w, ChunkComponentIndex>,
+}
+
 fn on_left_mouse_click_trigger(
   trigger: Trigger<MouseClickEvent>,
-  object_index: Res<ObjectComponentIndex>,
-  tile_index: Res<TileComponentIndex>,
-  chunk_index: Res<ChunkComponentIndex>,
+  indexes: ComponentIndexes,
   resources: Res<GenerationResourcesCollection>,
+  nav_data: Res<NavData>,
   settings: Res<Settings>,
   mut commands: Commands,
 ) {
   if !settings.general.enable_tile_debugging {
     return;
   }
-  let event = trigger.event();
-  if let Some(tc) = tile_index.get_entities(event.tg).iter().max_by_key(|tc| tc.tile.layer) {
-    debug!("You are debugging {} {} {}", event.tile_w, event.cg, event.tg);
-    let object_component = object_index.get(event.tg);
-    commands.spawn(tile_info(&resources, &tc.tile, event.tile_w, &settings, &object_component));
+  let picked = trigger.event().tile;
+  if let Some(tc) = indexes.tiles.get_entities(picked.tg).iter().max_by_key(|tc| tc.tile.layer) {
+    debug!("You are debugging {} {} {}", picked.tile_w(), picked.cg, picked.tg);
+    let object_component = indexes.objects.get(picked.tg);
+    commands.spawn(tile_info(
+      &resources,
+      &nav_data,
+      &tc.tile,
+      picked.tile_w(),
+      &settings,
+      &object_component,
+    ));
     let parent_w = tc.tile.get_parent_chunk_w();
-    if let Some(parent_chunk) = chunk_index.get(&parent_w) {
-      debug!("Parent of {} is chunk {}/{}", event.tg, parent_w, event.cg);
+    if let Some(parent_chunk) = indexes.chunks.get(&parent_w) {
+      debug!("Parent of {} is chunk {}/{}", picked.tg, parent_w, picked.cg);
       for plane in &parent_chunk.layered_plane.planes {
         if let Some(tile) = plane.get_tile(tc.tile.coords.internal_grid) {
           let neighbours = plane.get_neighbours(tile);
@@ -135,12 +151,14 @@ fn on_left_mouse_click_trigger(
     } else {
       error!("Failed to find parent chunk at {} for tile at {:?}", parent_w, tc.tile.coords);
     }
-    if let Some(oc) = object_index.get(event.tg) {
+    if let Some(oc) = indexes.objects.get(picked.tg) {
       debug!("{:?}", oc);
     } else {
       debug!(
         "No object(s) found at {:?} {:?} which is inside {}",
-        event.tile_w, event.tg, event.cg
+        picked.tile_w(),
+        picked.tg,
+        picked.cg
       );
     }
   }
@@ -148,6 +166,7 @@ fn on_left_mouse_click_trigger(
 
 fn tile_info(
   resources: &GenerationResourcesCollection,
+  nav_data: &NavData,
   tile: &Tile,
   spawn_point: Point<World>,
   settings: &Res<Settings>,
@@ -164,10 +183,22 @@ fn tile_info(
   Transform,
   TileDebugInfoComponent,
 ) {
+  let ig = tile.coords.internal_grid;
+  let nav_flags = nav_data
+    .get(&Point::new_chunk_grid_from_world(tile.get_parent_chunk_w()))
+    .map(|nav| {
+      format!(
+        "{}{}{}",
+        if nav.is_walkable(&ig) { " (walkable)" } else { "" },
+        if nav.is_path(&ig) { " (path)" } else { "" },
+        if nav.is_door(&ig) { " (door)" } else { "" }
+      )
+    })
+    .unwrap_or_default();
   let object = if let Some(oc) = object_component_option {
     format!(
-      "\nObject: \n{:?}\n(Sprite {}, layer {})",
-      oc.object_name, oc.sprite_index, oc.layer
+      "\nObject: \n{:?}{}\n(Sprite {}, layer {})",
+      oc.object_name, nav_flags, oc.sprite_index, oc.layer
     )
   } else {
     "\nNo object sprite".to_string()
@@ -177,16 +208,17 @@ fn tile_info(
   } else {
     Visibility::Hidden
   };
-  let sprite_index = tile.tile_type.calculate_sprite_index(&tile.terrain, &tile.climate, resources);
+  let sprite_index = resources.get_static_sprite_index(tile);
   (
     Name::new(format!("Tile {:?} Debug Info", tile.coords.tile_grid)),
     Anchor::TopLeft,
     Text2d::new(format!(
-      "{}\n{} {}\n{:?}\n{:?}\n(Sprite {:?}, layer {:?})\n{}",
+      "{}\n{} {}\n{:?}{}\n{:?}\n(Sprite {:?}, layer {:?})\n{}",
       tile.coords.chunk_grid,
       tile.coords.tile_grid,
       tile.coords.internal_grid,
       tile.terrain,
+      if tile.is_beach { " (beach)" } else { "" },
       tile.tile_type,
       sprite_index,
       tile.layer,
@@ -197,7 +229,7 @@ fn tile_info(
       ..default()
     },
     TextLayout::new(JustifyText::Left, LineBreak::AnyCharacter),
-    TextBounds::new((TILE_SIZE as f32 - MARGIN) * 10., (TILE_SIZE as f32 - MARGIN) * 10.),
+    TextBounds::new((tile_size() as f32 - MARGIN) * 10., (tile_size() as f32 - MARGIN) * 10.),
     TextColor(LIGHT),
     visibility,
     Transform {
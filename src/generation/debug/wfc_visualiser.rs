@@ -0,0 +1,208 @@
+use crate::constants::*;
+use crate::coords::point::InternalGrid;
+use crate::coords::tile_size::tile_size;
+use crate::coords::{Point, TilePicker};
+use crate::generation::lib::{shared, TileData, TileDataComponent};
+use crate::generation::object::lib::ObjectGrid;
+use crate::generation::object::{stamp_rare_feature, stamp_rocky_features, WfcStepper};
+use crate::generation::resources::{GenerationResourcesCollection, Metadata, ObjectEdges, WfcVisualiser};
+use crate::resources::Settings;
+use bevy::app::{App, Plugin, Update};
+use bevy::core::Name;
+use bevy::ecs::system::SystemParam;
+use bevy::log::*;
+use bevy::prelude::{
+  default, Color, Commands, Component, DespawnRecursiveExt, DetectChanges, Entity, IntoSystemConfigs, JustifyText, Query,
+  Res, ResMut, Text2d, TextFont, Time, Transform, Vec3, With,
+};
+use bevy::text::{TextColor, TextLayout};
+use rand::prelude::StdRng;
+use rand::SeedableRng;
+
+pub struct WfcVisualiserDebugPlugin;
+
+impl Plugin for WfcVisualiserDebugPlugin {
+  fn build(&self, app: &mut App) {
+    app.add_systems(
+      Update,
+      (
+        start_wfc_visualiser_system,
+        stop_wfc_visualiser_system,
+        step_wfc_visualiser_system,
+        render_wfc_visualiser_system,
+      )
+        .chain(),
+    );
+  }
+}
+
+#[derive(Component)]
+struct WfcVisualiserCellComponent {
+  ig: Point<InternalGrid>,
+}
+
+/// The resources that the object generation process initialises the object grid of a chunk from, besides the tiles.
+#[derive(SystemParam)]
+struct ObjectGenerationInputs<'w> {
+  resources: Res<'w, GenerationResourcesCollection>,
+  metadata: Res<'w, Metadata>,
+  object_edges: Res<'w, ObjectEdges>,
+}
+
+/// Initialises the object grid for the chunk the camera is currently in, using the same inputs as the object
+/// generation process, and spawns a label for each cell.
+fn start_wfc_visualiser_system(
+  mut commands: Commands,
+  mut visualiser: ResMut<WfcVisualiser>,
+  tiles: Query<&TileDataComponent>,
+  existing_labels: Query<Entity, With<WfcVisualiserCellComponent>>,
+  picker: TilePicker,
+  inputs: ObjectGenerationInputs,
+  settings: Res<Settings>,
+) {
+  if !visualiser.is_start_requested {
+    return;
+  }
+  visualiser.is_start_requested = false;
+  let Some(cg) = picker.camera().map(|camera| camera.cg) else {
+    return;
+  };
+  let chunk_w = Point::new_world_from_chunk_grid(cg);
+  let tile_data = tiles
+    .iter()
+    .map(|t| t.tile_data)
+    .filter(|t| t.flat_tile.get_parent_chunk_w() == chunk_w)
+    .collect::<Vec<TileData>>();
+  if tile_data.is_empty() {
+    warn!("Failed to start WFC visualiser because chunk {} has no tiles", cg);
+    return;
+  }
+  for entity in existing_labels.iter() {
+    commands.entity(entity).despawn_recursive();
+  }
+  let is_rocky = inputs.metadata.biome.get(&cg).is_some_and(|biome| biome.is_rocky);
+  let object_settings = settings.object.for_biome(is_rocky);
+  let mut grid = ObjectGrid::new_initialised(cg, &inputs.resources.objects, &object_settings, &tile_data);
+  grid.apply_edge_constraints(&inputs.object_edges.get_for(&cg));
+  let object_seed = settings.object.object_seed(settings.world.get_object_seed());
+  stamp_rare_feature(&mut grid, &inputs.resources.objects, &settings.object, object_seed);
+  if is_rocky {
+    stamp_rocky_features(&mut grid, &inputs.resources.objects, &settings.object, object_seed);
+  }
+  let rng = StdRng::seed_from_u64(shared::calculate_seed(cg, object_seed));
+  for data in tile_data.iter() {
+    commands.spawn(cell_label(data));
+  }
+  visualiser.stepper = Some(WfcStepper::new(rng, grid, object_settings));
+  visualiser.cg = Some(cg);
+  visualiser.is_running = false;
+  info!("Started WFC visualiser for chunk {} with {} cells", cg, tile_data.len());
+}
+
+fn stop_wfc_visualiser_system(
+  mut commands: Commands,
+  mut visualiser: ResMut<WfcVisualiser>,
+  existing_labels: Query<Entity, With<WfcVisualiserCellComponent>>,
+) {
+  if !visualiser.is_stop_requested {
+    return;
+  }
+  visualiser.is_stop_requested = false;
+  for entity in existing_labels.iter() {
+    commands.entity(entity).despawn_recursive();
+  }
+  visualiser.stepper = None;
+  visualiser.cg = None;
+  visualiser.is_running = false;
+}
+
+fn step_wfc_visualiser_system(mut visualiser: ResMut<WfcVisualiser>, time: Res<Time>) {
+  if visualiser.stepper.is_none() || !visualiser.is_step_due(time.delta_secs()) {
+    return;
+  }
+  if let Some(stepper) = visualiser.stepper.as_mut() {
+    let has_entropy = stepper.step();
+    if !has_entropy {
+      info!(
+        "WFC visualiser completed after {} iterations and {} failures",
+        stepper.iteration_count(),
+        stepper.failure_count()
+      );
+      visualiser.is_running = false;
+    }
+  }
+}
+
+/// Updates the label of each cell to show its entropy. The cell collapsed during the last iteration is highlighted in
+/// red (or orange if the iteration failed), the cells updated by propagation in yellow, and collapsed cells are dimmed.
+fn render_wfc_visualiser_system(
+  visualiser: Res<WfcVisualiser>,
+  mut labels: Query<(&WfcVisualiserCellComponent, &mut Text2d, &mut TextColor)>,
+) {
+  if !visualiser.is_changed() {
+    return;
+  }
+  if let Some(stepper) = visualiser.stepper.as_ref() {
+    for (label, mut text, mut colour) in labels.iter_mut() {
+      if let Some(cell) = stepper.grid().get_cell(&label.ig) {
+        text.0 = if cell.is_collapsed {
+          format!("{}", cell.index)
+        } else {
+          format!("{}", cell.possible_states.len())
+        };
+        colour.0 = get_colour(stepper, &label.ig, cell.is_collapsed);
+      }
+    }
+  }
+}
+
+fn get_colour(stepper: &WfcStepper, ig: &Point<InternalGrid>, is_collapsed: bool) -> Color {
+  if stepper.collapsed() == Some(ig) {
+    if stepper.was_failure() {
+      ORANGE
+    } else {
+      RED
+    }
+  } else if stepper.propagated().contains(ig) {
+    YELLOW
+  } else if is_collapsed {
+    DARK
+  } else {
+    LIGHT
+  }
+}
+
+fn cell_label(
+  tile_data: &TileData,
+) -> (
+  Name,
+  Text2d,
+  TextFont,
+  TextLayout,
+  TextColor,
+  Transform,
+  WfcVisualiserCellComponent,
+) {
+  let tile_w = Point::new_world_from_tile_grid(tile_data.flat_tile.coords.tile_grid);
+  let ig = tile_data.flat_tile.coords.internal_grid;
+  (
+    Name::new(format!("WFC Visualiser Cell {:?}", ig)),
+    Text2d::new("?"),
+    TextFont {
+      font_size: 120.,
+      ..default()
+    },
+    TextLayout::new_with_justify(JustifyText::Center),
+    TextColor(LIGHT),
+    Transform {
+      scale: Vec3::splat(0.1),
+      translation: Vec3::new(
+        tile_w.x as f32 + tile_size() as f32 / 2.,
+        tile_w.y as f32 - tile_size() as f32 / 2.,
+        30000.,
+      ),
+      ..Default::default()
+    },
+    WfcVisualiserCellComponent { ig },
+  )
+}
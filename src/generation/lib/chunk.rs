@@ -1,9 +1,9 @@
 use crate::constants::*;
-use crate::coords::point::{ChunkGrid, TileGrid, World};
+use crate::coords::point::{ChunkGrid, InternalGrid, TileGrid, World};
 use crate::coords::{Coords, Point};
 use crate::generation::lib::debug_data::DebugData;
-use crate::generation::lib::{shared, Direction, DraftTile, LayeredPlane, TerrainType};
-use crate::generation::resources::{BiomeMetadataSet, Metadata};
+use crate::generation::lib::{shared, ChunkComponent, Direction, DraftTile, LayeredPlane, TerrainEdges, TerrainType};
+use crate::generation::resources::{BiomeMetadataSet, Climate, Metadata, WorldModifications};
 use crate::resources::Settings;
 use bevy::log::*;
 use noise::{BasicMulti, MultiFractal, NoiseFn, Perlin};
@@ -11,25 +11,56 @@ use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 
 /// A `Chunk` represents a single chunk of the world.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Chunk {
   pub coords: Coords,
   pub center: Point<World>,
   pub layered_plane: LayeredPlane,
+  /// The raw terrain noise along the borders of this chunk, which neighbouring chunks reuse instead of sampling it.
+  pub terrain_edges: TerrainEdges,
 }
 
 impl Chunk {
   /// Creates a new chunk from a draft chunk by converting the flat terrain data from the draft chunk into a
   /// `LayeredPlane`. As a result, a chunk has multiple layers of terrain data, each of which contains rich information
-  /// about the `Tile`s that make up the terrain including their `TileType`s.
-  pub fn new(w: Point<World>, tg: Point<TileGrid>, metadata: &Metadata, settings: &Settings) -> Self {
+  /// about the `Tile`s that make up the terrain including their `TileType`s. The raw noise found in `neighbour_edges`
+  /// is reused instead of being sampled again and any terrain altered in the `WorldModifications` is applied.
+  pub fn new(
+    w: Point<World>,
+    tg: Point<TileGrid>,
+    metadata: &Metadata,
+    world_modifications: &WorldModifications,
+    neighbour_edges: &TerrainEdges,
+    settings: &Settings,
+  ) -> Self {
     let coords = Coords::new_for_chunk(w, tg);
-    let data = generate_terrain_data(&tg, &coords.chunk_grid, metadata, settings);
+    let (data, terrain_edges) = generate_terrain_data(
+      &tg,
+      &coords.chunk_grid,
+      metadata,
+      world_modifications,
+      neighbour_edges,
+      settings,
+    );
     let layered_plane = LayeredPlane::new(data, settings);
     Chunk {
       coords,
-      center: Point::new_world(tg.x + (CHUNK_SIZE_PLUS_BUFFER / 2), tg.y + (CHUNK_SIZE_PLUS_BUFFER / 2)),
+      center: Point::new_world(tg.x + (CHUNK_WIDTH_PLUS_BUFFER / 2), tg.y + (CHUNK_HEIGHT_PLUS_BUFFER / 2)),
       layered_plane,
+      terrain_edges,
+    }
+  }
+
+  /// Recreates a `Chunk` from the `ChunkComponent` of an already spawned chunk. Allows processes that operate on
+  /// existing chunks (such as re-running the object generation) to reuse the terrain data instead of regenerating it.
+  /// The terrain edges are not restored, since they are only needed when generating the neighbours of a chunk.
+  pub fn from_component(chunk_component: &ChunkComponent) -> Self {
+    let tg = chunk_component.coords.tile_grid;
+    Chunk {
+      coords: chunk_component.coords,
+      center: Point::new_world(tg.x + (CHUNK_WIDTH_PLUS_BUFFER / 2), tg.y + (CHUNK_HEIGHT_PLUS_BUFFER / 2)),
+      layered_plane: chunk_component.layered_plane.clone(),
+      terrain_edges: TerrainEdges::default(),
     }
   }
 }
@@ -49,31 +80,51 @@ struct Distances {
 }
 
 /// Generates terrain data for a draft chunk based on Perlin noise. Expects `tg` to be a `Point` of type
-/// `TileGrid` that describes the top-left corner of the grid.
+/// `TileGrid` that describes the top-left corner of the grid. The raw noise of tiles along the borders of already
+/// generated neighbours is taken from `neighbour_edges` instead of being sampled again. Returns the draft tiles as well
+/// as the terrain edges of this chunk, which contain the raw noise along its borders.
 fn generate_terrain_data(
   tg: &Point<TileGrid>,
   cg: &Point<ChunkGrid>,
   metadata: &Metadata,
+  world_modifications: &WorldModifications,
+  neighbour_edges: &TerrainEdges,
   settings: &Settings,
-) -> Vec<Vec<Option<DraftTile>>> {
-  let start_time = shared::get_time();
+) -> (Vec<Vec<Option<DraftTile>>>, TerrainEdges) {
+  let span = shared::TimedSpan::new(info_span!("generate_draft_chunk", cg = %cg));
   let elevation_metadata = metadata
     .elevation
     .get(cg)
     .expect(format!("Failed to get elevation metadata for {}", cg).as_str());
   let biome_metadata = metadata.get_biome_metadata_for(cg);
-  let mut rng = StdRng::seed_from_u64(shared::calculate_seed(cg.clone(), settings.world.noise_seed));
-  let perlin: BasicMulti<Perlin> = BasicMulti::new(settings.world.noise_seed)
+  let mut rng = StdRng::seed_from_u64(shared::calculate_seed(*cg, settings.world.get_terrain_seed()));
+  let mut climate_rng = StdRng::seed_from_u64(shared::calculate_seed(*cg, settings.world.get_terrain_seed()).rotate_left(1));
+  let perlin: BasicMulti<Perlin> = BasicMulti::new(settings.world.get_terrain_seed())
     .set_octaves(settings.world.noise_octaves)
     .set_frequency(settings.world.noise_frequency)
     .set_persistence(settings.world.noise_persistence);
   let amplitude = settings.world.noise_amplitude;
   let strength = settings.world.noise_strength;
+  let mut samples = neighbour_edges.clone().into_samples();
+  let mut sample_noise = |tx: i32, ty: i32| -> f64 {
+    *samples
+      .entry(Point::new_tile_grid(tx, ty))
+      .or_insert_with(|| perlin.get([tx as f64, ty as f64]))
+  };
+  let calculate_noise = |noise: f64, ig: Point<InternalGrid>| -> (f64, f64) {
+    let clamped_noise = (noise * amplitude).clamp(-1., 1.);
+    let normalised_noise = (clamped_noise + 1.) / 2.;
+    let elevation_offset = elevation_metadata.calculate_for_point(ig);
+    (
+      ((normalised_noise * strength) + elevation_offset).clamp(0., 1.),
+      elevation_offset,
+    )
+  };
   let start = Point::new_tile_grid(tg.x - BUFFER_SIZE, tg.y + BUFFER_SIZE);
-  let end = Point::new_tile_grid(start.x + CHUNK_SIZE_PLUS_BUFFER - 1, start.y - CHUNK_SIZE_PLUS_BUFFER + 1);
+  let end = Point::new_tile_grid(start.x + CHUNK_WIDTH_PLUS_BUFFER - 1, start.y - CHUNK_HEIGHT_PLUS_BUFFER + 1);
   let center = Point::new_tile_grid((start.x + end.x) / 2, (start.y + end.y) / 2);
-  let max_distance = (CHUNK_SIZE_PLUS_BUFFER as f64) / 2.;
-  let mut tiles = vec![vec![None; CHUNK_SIZE_PLUS_BUFFER as usize]; CHUNK_SIZE_PLUS_BUFFER as usize];
+  let max_distance = (CHUNK_WIDTH_PLUS_BUFFER as f64 / 2., CHUNK_HEIGHT_PLUS_BUFFER as f64 / 2.);
+  let mut tiles = vec![vec![None; CHUNK_HEIGHT_PLUS_BUFFER as usize]; CHUNK_WIDTH_PLUS_BUFFER as usize];
   let mut ix = 0;
   let mut iy = 0;
 
@@ -82,14 +133,8 @@ fn generate_terrain_data(
       let tg = Point::new_tile_grid(tx, ty); // Final tile grid coordinates
       let ig = Point::new_internal_grid(ix, iy); // Adjusted later when converting to tile
 
-      // Calculate noise value
-      let noise = perlin.get([tx as f64, ty as f64]);
-      let clamped_noise = (noise * amplitude).clamp(-1., 1.);
-      let normalised_noise = (clamped_noise + 1.) / 2.;
-
-      // Adjust noise based on elevation metadata
-      let elevation_offset = elevation_metadata.calculate_for_point(ig, CHUNK_SIZE, BUFFER_SIZE);
-      let normalised_noise = ((normalised_noise * strength) + elevation_offset).clamp(0., 1.);
+      // Calculate noise value, adjusted based on elevation metadata
+      let (normalised_noise, elevation_offset) = calculate_noise(sample_noise(tx, ty), ig);
 
       // Calculate distances to chunk edge in all directions
       let distances = calculate_distances(start, end, center, max_distance, tx, ty);
@@ -105,14 +150,8 @@ fn generate_terrain_data(
       };
 
       // Determine terrain type based on the above
-      let terrain = match normalised_noise {
-        n if n > 0.75 => TerrainType::new(TerrainType::Land3, is_biome_edge),
-        n if n > 0.6 => TerrainType::new(TerrainType::Land2, is_biome_edge),
-        n if n > 0.45 => TerrainType::new(TerrainType::Land1, is_biome_edge),
-        n if n > 0.3 => TerrainType::new(TerrainType::ShallowWater, is_biome_edge),
-        _ => TerrainType::DeepWater,
-      };
-      let climate = biome_metadata.this.climate;
+      let terrain = TerrainType::new(terrain_from_noise(normalised_noise), is_biome_edge);
+      let climate = determine_climate(ix, iy, &biome_metadata, &mut climate_rng, settings);
 
       let tile = DraftTile::new(ig, tg, terrain, climate, debug_data);
       tiles[ix as usize][iy as usize] = Some(tile);
@@ -121,36 +160,125 @@ fn generate_terrain_data(
     iy += 1;
     ix = 0;
   }
-  trace!(
-    "Generated draft chunk at {:?} in {} ms on [{}]",
-    tg,
-    shared::get_time() - start_time,
-    shared::thread_name()
-  );
-
-  tiles
+
+  if settings.general.generate_beaches {
+    let beach_perlin: BasicMulti<Perlin> = BasicMulti::new(settings.world.get_terrain_seed())
+      .set_octaves(1)
+      .set_frequency(BEACH_NOISE_FREQUENCY);
+    generate_beaches(&mut tiles, start, &mut sample_noise, &calculate_noise, &beach_perlin);
+  }
+  apply_altered_terrain(&mut tiles, world_modifications);
+  span.finish(Level::TRACE, format_args!("Generated draft chunk at {:?}", tg));
+
+  (tiles, TerrainEdges::from_samples(tg, samples))
+}
+
+fn terrain_from_noise(normalised_noise: f64) -> TerrainType {
+  match normalised_noise {
+    n if n > SNOW_LINE => TerrainType::Land4,
+    n if n > 0.75 => TerrainType::Land3,
+    n if n > 0.6 => TerrainType::Land2,
+    n if n > 0.45 => TerrainType::Land1,
+    n if n > 0.3 => TerrainType::ShallowWater,
+    _ => TerrainType::DeepWater,
+  }
+}
+
+/// Determines the beach band along coastlines and widens the `Land1` (i.e. sand) strips along water accordingly. The
+/// width of the beach depends on the `Climate` of the tile and varies based on noise. Every land tile within the band
+/// is flagged as a beach tile and lowered to `Land1`, if necessary. Water beyond the edges of the draft chunk is taken
+/// into account by calculating the terrain of the tiles outside of it from noise, so that the beach band is continuous
+/// across chunk borders.
+fn generate_beaches(
+  tiles: &mut [Vec<Option<DraftTile>>],
+  start: Point<TileGrid>,
+  sample_noise: &mut impl FnMut(i32, i32) -> f64,
+  calculate_noise: &impl Fn(f64, Point<InternalGrid>) -> (f64, f64),
+  beach_perlin: &BasicMulti<Perlin>,
+) {
+  let width = CHUNK_WIDTH_PLUS_BUFFER + 2 * MAX_BEACH_WIDTH;
+  let height = CHUNK_HEIGHT_PLUS_BUFFER + 2 * MAX_BEACH_WIDTH;
+  let is_water: Vec<Vec<bool>> = (0..width)
+    .map(|x| {
+      (0..height)
+        .map(|y| {
+          let (ix, iy) = (x - MAX_BEACH_WIDTH, y - MAX_BEACH_WIDTH);
+          let terrain = match tiles.get(ix as usize).and_then(|column| column.get(iy as usize)) {
+            Some(Some(tile)) if ix >= 0 && iy >= 0 => tile.terrain,
+            _ => {
+              let noise = sample_noise(start.x + ix, start.y - iy);
+              terrain_from_noise(calculate_noise(noise, Point::new_internal_grid(ix, iy)).0)
+            }
+          };
+          terrain <= TerrainType::ShallowWater
+        })
+        .collect()
+    })
+    .collect();
+
+  for tile in tiles.iter_mut().flatten().flatten() {
+    if tile.terrain <= TerrainType::ShallowWater {
+      continue;
+    }
+    let ig = tile.coords.internal_grid;
+    let tg = tile.coords.tile_grid;
+    let variance = beach_perlin.get([tg.x as f64, tg.y as f64]) * BEACH_WIDTH_VARIANCE;
+    let width = ((beach_width(tile.climate) as f64 + variance).round() as i32).clamp(1, MAX_BEACH_WIDTH);
+    let is_beach = (-width..=width).any(|dx| {
+      (-width..=width).any(|dy| is_water[(ig.x + dx + MAX_BEACH_WIDTH) as usize][(ig.y + dy + MAX_BEACH_WIDTH) as usize])
+    });
+    if is_beach {
+      if tile.terrain > TerrainType::Land1 {
+        *tile = tile.clone_with_modified_terrain(TerrainType::Land1);
+      }
+      tile.is_beach = true;
+    }
+  }
+}
+
+/// Replaces the terrain of every tile whose terrain has been altered in the `WorldModifications`, e.g. by painting it
+/// using the terrain brush, including buffer tiles, so that the tile types along the edges of the altered area are
+/// determined correctly. Applied after generating the beaches, so that the altered terrain is never changed again.
+fn apply_altered_terrain(tiles: &mut [Vec<Option<DraftTile>>], world_modifications: &WorldModifications) {
+  for tile in tiles.iter_mut().flatten().flatten() {
+    if let Some(terrain) = world_modifications.get_terrain(&tile.coords.tile_grid) {
+      let is_beach = tile.is_beach && terrain > TerrainType::ShallowWater;
+      *tile = tile.clone_with_modified_terrain(terrain);
+      tile.is_beach = is_beach;
+    }
+  }
+}
+
+fn beach_width(climate: Climate) -> i32 {
+  match climate {
+    Climate::Dry => BEACH_WIDTH_DRY,
+    Climate::Moderate => BEACH_WIDTH_MODERATE,
+    Climate::Humid => BEACH_WIDTH_HUMID,
+  }
 }
 
 fn calculate_distances(
   start: Point<TileGrid>,
   end: Point<TileGrid>,
   center: Point<TileGrid>,
-  max_distance: f64,
+  max_distance: (f64, f64),
   tx: i32,
   ty: i32,
 ) -> Distances {
-  let distance_x = (tx - center.x).abs() as f64 / max_distance;
-  let distance_y = (ty - center.y).abs() as f64 / max_distance;
+  let distance_x = (tx - center.x).abs() as f64 / max_distance.0;
+  let distance_y = (ty - center.y).abs() as f64 / max_distance.1;
+  let (max_distance_x, max_distance_y) = max_distance;
+  let max_distance = max_distance_x.max(max_distance_y);
   let distance_from_center = distance_x.max(distance_y);
   let distances = Distances {
     top_left: (((tx - start.x).pow(2) + (ty - start.y).pow(2)) as f64).sqrt() / max_distance,
-    top: (ty - start.y).abs() as f64 / max_distance,
+    top: (ty - start.y).abs() as f64 / max_distance_y,
     top_right: (((end.x - tx).pow(2) + (ty - start.y).pow(2)) as f64).sqrt() / max_distance,
-    left: (tx - start.x).abs() as f64 / max_distance,
+    left: (tx - start.x).abs() as f64 / max_distance_x,
     center: distance_from_center,
-    right: (end.x - tx).abs() as f64 / max_distance,
+    right: (end.x - tx).abs() as f64 / max_distance_x,
     bottom_left: (((tx - start.x).pow(2) + (end.y - ty).pow(2)) as f64).sqrt() / max_distance,
-    bottom: (end.y - ty).abs() as f64 / max_distance,
+    bottom: (end.y - ty).abs() as f64 / max_distance_y,
     bottom_right: (((end.x - tx).pow(2) + (end.y - ty).pow(2)) as f64).sqrt() / max_distance,
   };
   trace!("tg({}, {}): Distances = {:?}", tx, ty, distances);
@@ -159,9 +287,11 @@ fn calculate_distances(
 }
 
 const INSIDE: i32 = 1;
-const OUTSIDE: i32 = CHUNK_SIZE + 1;
+const OUTSIDE_X: i32 = CHUNK_WIDTH + 1;
+const OUTSIDE_Y: i32 = CHUNK_HEIGHT + 1;
 const EXPANDED_INSIDE: i32 = 2;
-const EXPANDED_OUTSIDE: i32 = CHUNK_SIZE;
+const EXPANDED_OUTSIDE_X: i32 = CHUNK_WIDTH;
+const EXPANDED_OUTSIDE_Y: i32 = CHUNK_HEIGHT;
 
 /// Calculates if a tile `TerrainType` should be adjusted by checking if:
 /// 1. The tile is "far enough" from the center (otherwise it cannot be an edge)
@@ -188,19 +318,59 @@ fn is_tile_at_edge_of_biome(
   let is_considered_edge = rng.gen_bool(0.3);
   let direction = match (ix, iy, is_considered_edge) {
     (..INSIDE, ..INSIDE, _) => Direction::TopLeft,
-    (OUTSIDE.., ..INSIDE, _) => Direction::TopRight,
-    (..INSIDE, OUTSIDE.., _) => Direction::BottomLeft,
-    (OUTSIDE.., OUTSIDE.., _) => Direction::BottomRight,
+    (OUTSIDE_X.., ..INSIDE, _) => Direction::TopRight,
+    (..INSIDE, OUTSIDE_Y.., _) => Direction::BottomLeft,
+    (OUTSIDE_X.., OUTSIDE_Y.., _) => Direction::BottomRight,
     (_, ..INSIDE, _) => Direction::Top,
-    (_, OUTSIDE.., _) => Direction::Bottom,
-    (OUTSIDE.., _, _) => Direction::Right,
+    (_, OUTSIDE_Y.., _) => Direction::Bottom,
+    (OUTSIDE_X.., _, _) => Direction::Right,
     (..INSIDE, _, _) => Direction::Left,
-    (EXPANDED_INSIDE..EXPANDED_OUTSIDE, ..EXPANDED_INSIDE, true) => Direction::Top,
-    (EXPANDED_INSIDE..EXPANDED_OUTSIDE, EXPANDED_OUTSIDE.., true) => Direction::Bottom,
-    (EXPANDED_OUTSIDE.., EXPANDED_INSIDE..EXPANDED_OUTSIDE, true) => Direction::Right,
-    (..EXPANDED_INSIDE, EXPANDED_INSIDE..EXPANDED_OUTSIDE, true) => Direction::Left,
+    (EXPANDED_INSIDE..EXPANDED_OUTSIDE_X, ..EXPANDED_INSIDE, true) => Direction::Top,
+    (EXPANDED_INSIDE..EXPANDED_OUTSIDE_X, EXPANDED_OUTSIDE_Y.., true) => Direction::Bottom,
+    (EXPANDED_OUTSIDE_X.., EXPANDED_INSIDE..EXPANDED_OUTSIDE_Y, true) => Direction::Right,
+    (..EXPANDED_INSIDE, EXPANDED_INSIDE..EXPANDED_OUTSIDE_Y, true) => Direction::Left,
     _ => Direction::Center,
   };
 
   direction != Direction::Center && !biome_metadata.is_same_climate(&direction)
 }
+
+/// Determines the `Climate` of a tile. If climate blending is enabled, tiles close to a chunk border with a different
+/// climate may take on the climate of the neighbouring chunk instead. The probability of this happening is 50% right at
+/// the border and decreases the further away from the border the tile is. Since the neighbouring chunk does the same,
+/// the climates merge gradually instead of switching abruptly at the border.
+///
+/// Note that this dithers the existing sprite sets of both climates rather than using dedicated transition tiles, since
+/// the tile sets don't contain any sprites that combine two climates. Each tile is still rendered with the sprite set
+/// of exactly one climate, so the border is broken up rather than smoothed. Transition tiles would require a tile set
+/// row per pair of climates and tile type, which should replace this once such artwork exists.
+fn determine_climate(ix: i32, iy: i32, biome_metadata: &BiomeMetadataSet, rng: &mut StdRng, settings: &Settings) -> Climate {
+  let climate = biome_metadata.this.climate;
+  if !settings.general.enable_climate_blending {
+    return climate;
+  }
+  let last_rendered_x = CHUNK_WIDTH_PLUS_BUFFER - 1 - BUFFER_SIZE;
+  let last_rendered_y = CHUNK_HEIGHT_PLUS_BUFFER - 1 - BUFFER_SIZE;
+  let closest_edge = [
+    (Direction::Top, iy - BUFFER_SIZE),
+    (Direction::Right, last_rendered_x - ix),
+    (Direction::Bottom, last_rendered_y - iy),
+    (Direction::Left, ix - BUFFER_SIZE),
+  ]
+  .into_iter()
+  .filter(|(direction, _)| !biome_metadata.is_same_climate(direction))
+  .map(|(direction, distance)| (direction, distance.max(0)))
+  .min_by_key(|(_, distance)| *distance);
+
+  match closest_edge {
+    Some((direction, distance)) if distance < CLIMATE_BLEND_DISTANCE => {
+      let probability = 0.5 * (1. - (distance as f64 + 0.5) / CLIMATE_BLEND_DISTANCE as f64);
+      if rng.gen_bool(probability) {
+        biome_metadata.get(&direction).climate
+      } else {
+        climate
+      }
+    }
+    _ => climate,
+  }
+}
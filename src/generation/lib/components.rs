@@ -1,7 +1,9 @@
 use crate::coords::point::{ChunkGrid, World};
 use crate::coords::{Coords, Point};
 use crate::generation::lib::{Chunk, LayeredPlane, Tile, TileData};
-use crate::generation::object::lib::{ObjectData, ObjectName};
+use crate::generation::object::lib::{ObjectGenerationResult, ObjectName};
+use crate::resources::Settings;
+use crate::states::GenerationPhase;
 use bevy::prelude::{Component, Entity};
 use bevy::tasks::Task;
 
@@ -11,11 +13,13 @@ use bevy::tasks::Task;
 pub struct WorldComponent;
 
 /// A component that is attached to every chunk entity that is spawned in the world. Used in the `ChunkComponentIndex`
-/// but also by other core processes such as pruning the world.
+/// but also by other core processes such as pruning the world, which uses `spawned_at` to keep chunks that have only
+/// just been spawned.
 #[derive(Component, Debug, Clone, PartialEq)]
 pub struct ChunkComponent {
   pub coords: Coords,
   pub layered_plane: LayeredPlane,
+  pub spawned_at: u128,
 }
 
 /// A component that is attached to every tile sprite that is spawned in the world. Contains the tile data
@@ -26,6 +30,13 @@ pub struct TileComponent {
   pub parent_entity: Entity,
 }
 
+/// A component that is attached to every tile entity i.e. the parent of all `TileComponent`s of a tile. Contains the
+/// `TileData` of the tile which allows re-running processes such as the object generation for already spawned chunks.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct TileDataComponent {
+  pub tile_data: TileData,
+}
+
 /// A component that is attached to every object sprite that is spawned in the world. Use for, for example,
 /// debugging purposes.
 #[derive(Component, Debug, Clone, Eq, Hash, PartialEq)]
@@ -36,7 +47,11 @@ pub struct ObjectComponent {
   pub layer: i32,
 }
 
-#[derive(Debug)]
+/// The result of an object generation task, which contains the spawn data it was given if the task failed, so that it
+/// can be retried.
+pub type ObjectDataTaskResult = Result<ObjectGenerationResult, ((Chunk, Vec<TileData>), String)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum GenerationStage {
   Stage1,
   Stage2,
@@ -45,36 +60,74 @@ pub enum GenerationStage {
   Stage5,
   Stage6,
   Stage7,
+  /// Terminal stage of a component whose async tasks kept failing after all retries.
+  Failed,
+}
+
+impl GenerationStage {
+  /// Returns the publicly exposed `GenerationPhase` this stage belongs to.
+  pub fn phase(&self) -> GenerationPhase {
+    match self {
+      GenerationStage::Stage1 | GenerationStage::Stage2 => GenerationPhase::GeneratingTerrain,
+      GenerationStage::Stage3 | GenerationStage::Stage4 => GenerationPhase::Spawning,
+      GenerationStage::Stage5 | GenerationStage::Stage6 => GenerationPhase::GeneratingObjects,
+      GenerationStage::Stage7 | GenerationStage::Failed => GenerationPhase::Pruning,
+    }
+  }
 }
 
 /// The core component for the world generation process. Used by the world generation system. It is spawned to initiate
-/// process and is removed when the process is complete.
+/// process and is removed when the process is complete. All stages use the `settings` that were snapshotted when the
+/// component was created, so that changing the settings mid-generation never results in chunks that were generated
+/// with a mix of old and new settings.
 #[derive(Component, Debug)]
 pub struct WorldGenerationComponent {
   pub created_at: u128,
+  pub settings: Settings,
   pub stage: GenerationStage,
+  pub stage_started_at: u128,
+  pub stage_durations: Vec<(GenerationStage, u128)>,
   pub w: Point<World>,
   pub cg: Point<ChunkGrid>,
   pub suppress_pruning_world: bool,
+  pub retry_count: usize,
+  pub failure: Option<String>,
+  pub stuck_stage: Option<GenerationStage>,
   pub stage_0_metadata: bool,
-  pub stage_1_gen_task: Option<Task<Vec<Chunk>>>,
+  pub stage_1_placeholders: Vec<Entity>,
+  pub stage_1_gen_task: Option<Task<Result<Vec<Chunk>, String>>>,
   pub stage_2_chunks: Vec<Chunk>,
+  pub stage_3_spawned_chunks: Vec<Point<World>>,
   pub stage_3_spawn_data: Vec<(Chunk, Vec<TileData>)>,
   pub stage_4_spawn_data: Vec<(Chunk, Vec<TileData>)>,
-  pub stage_5_object_data: Vec<Task<Vec<ObjectData>>>,
+  pub stage_5_object_data: Vec<Task<ObjectDataTaskResult>>,
 }
 
 impl WorldGenerationComponent {
-  pub fn new(w: Point<World>, cg: Point<ChunkGrid>, suppress_pruning_world: bool, created_at: u128) -> Self {
+  pub fn new(
+    w: Point<World>,
+    cg: Point<ChunkGrid>,
+    suppress_pruning_world: bool,
+    created_at: u128,
+    settings: Settings,
+  ) -> Self {
     Self {
       created_at,
+      settings,
       stage: GenerationStage::Stage1,
+      stage_started_at: created_at,
+      stage_durations: vec![],
       w,
       cg,
       suppress_pruning_world,
+      retry_count: 0,
+      failure: None,
+      stuck_stage: None,
       stage_0_metadata: false,
+      stage_1_placeholders: vec![],
       stage_1_gen_task: None,
       stage_2_chunks: vec![],
+      stage_3_spawned_chunks: vec![],
       stage_3_spawn_data: vec![],
       stage_4_spawn_data: vec![],
       stage_5_object_data: vec![],
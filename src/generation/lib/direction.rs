@@ -1,5 +1,6 @@
-use crate::constants::{CHUNK_SIZE, TILE_SIZE};
+use crate::constants::{CHUNK_HEIGHT, CHUNK_WIDTH};
 use crate::coords::point::{ChunkGrid, CoordType, InternalGrid, TileGrid, World};
+use crate::coords::tile_size::{chunk_height, chunk_width};
 use crate::coords::Point;
 use cmp::Ordering;
 use std::cmp;
@@ -34,11 +35,10 @@ impl Direction {
   }
 
   pub fn from_chunk_w(chunk_world: &Point<World>, other_world: &Point<World>) -> Self {
-    let chunk_len = CHUNK_SIZE * TILE_SIZE as i32;
     let chunk_left = chunk_world.x;
-    let chunk_right = chunk_world.x + chunk_len - 1;
+    let chunk_right = chunk_world.x + chunk_width() - 1;
     let chunk_top = chunk_world.y;
-    let chunk_bottom = chunk_world.y - chunk_len + 1;
+    let chunk_bottom = chunk_world.y - chunk_height() + 1;
 
     to_direction(other_world, chunk_left, chunk_right, chunk_top, chunk_bottom)
   }
@@ -53,6 +53,23 @@ impl Direction {
   }
 }
 
+impl<T: CoordType> Point<T> {
+  pub fn from_direction(direction: &Direction) -> Self {
+    let (i, j) = match direction {
+      Direction::TopLeft => (-1, 1),
+      Direction::Top => (0, 1),
+      Direction::TopRight => (1, 1),
+      Direction::Left => (-1, 0),
+      Direction::Center => (0, 0),
+      Direction::Right => (1, 0),
+      Direction::BottomLeft => (-1, -1),
+      Direction::Bottom => (0, -1),
+      Direction::BottomRight => (1, -1),
+    };
+    Self::new(i, j)
+  }
+}
+
 impl PartialEq<Direction> for &Direction {
   fn eq(&self, other: &Direction) -> bool {
     **self == *other
@@ -60,27 +77,27 @@ impl PartialEq<Direction> for &Direction {
 }
 
 pub fn get_direction_points<T: CoordType + 'static>(point: &Point<T>) -> [(Direction, Point<T>); 9] {
-  let offset = calculate_offset::<T>();
+  let (offset_x, offset_y) = calculate_offset::<T>();
   let p = point;
   [
-    (Direction::TopLeft, Point::new(p.x - offset, p.y + offset)),
-    (Direction::Top, Point::new(p.x, p.y + offset)),
-    (Direction::TopRight, Point::new(p.x + offset, p.y + offset)),
-    (Direction::Left, Point::new(p.x - offset, p.y)),
+    (Direction::TopLeft, Point::new(p.x - offset_x, p.y + offset_y)),
+    (Direction::Top, Point::new(p.x, p.y + offset_y)),
+    (Direction::TopRight, Point::new(p.x + offset_x, p.y + offset_y)),
+    (Direction::Left, Point::new(p.x - offset_x, p.y)),
     (Direction::Center, Point::new(p.x, p.y)),
-    (Direction::Right, Point::new(p.x + offset, p.y)),
-    (Direction::BottomLeft, Point::new(p.x - offset, p.y - offset)),
-    (Direction::Bottom, Point::new(p.x, p.y - offset)),
-    (Direction::BottomRight, Point::new(p.x + offset, p.y - offset)),
+    (Direction::Right, Point::new(p.x + offset_x, p.y)),
+    (Direction::BottomLeft, Point::new(p.x - offset_x, p.y - offset_y)),
+    (Direction::Bottom, Point::new(p.x, p.y - offset_y)),
+    (Direction::BottomRight, Point::new(p.x + offset_x, p.y - offset_y)),
   ]
 }
 
-fn calculate_offset<T: CoordType + 'static>() -> i32 {
+fn calculate_offset<T: CoordType + 'static>() -> (i32, i32) {
   match std::any::TypeId::of::<T>() {
-    id if id == std::any::TypeId::of::<TileGrid>() => CHUNK_SIZE,
-    id if id == std::any::TypeId::of::<World>() => TILE_SIZE as i32 * CHUNK_SIZE,
-    id if id == std::any::TypeId::of::<InternalGrid>() => 1,
-    id if id == std::any::TypeId::of::<ChunkGrid>() => 1,
+    id if id == std::any::TypeId::of::<TileGrid>() => (CHUNK_WIDTH, CHUNK_HEIGHT),
+    id if id == std::any::TypeId::of::<World>() => (chunk_width(), chunk_height()),
+    id if id == std::any::TypeId::of::<InternalGrid>() => (1, 1),
+    id if id == std::any::TypeId::of::<ChunkGrid>() => (1, 1),
     id => panic!("Coord type {:?} not implemented for calculate_offset", id),
   }
 }
@@ -14,6 +14,9 @@ pub struct DraftTile {
   pub terrain: TerrainType,
   pub layer: i32,
   pub climate: Climate,
+  /// Whether the tile is part of the beach band along a coastline. Determined after all draft tiles of a chunk have
+  /// been generated.
+  pub is_beach: bool,
   pub debug_data: DebugData,
 }
 
@@ -30,6 +33,7 @@ impl DraftTile {
       terrain,
       climate,
       layer: terrain as i32,
+      is_beach: false,
       debug_data,
     }
   }
@@ -40,6 +44,7 @@ impl DraftTile {
       terrain,
       climate: self.climate,
       layer: terrain as i32,
+      is_beach: self.is_beach,
       debug_data: self.debug_data.clone(),
     }
   }
@@ -0,0 +1,83 @@
+use crate::generation::lib::{Chunk, TileData};
+use crate::generation::object::lib::ObjectGrid;
+use bevy::log::*;
+use bevy::prelude::Resource;
+use std::sync::{Arc, PoisonError, RwLock};
+
+type ChunkHook = Arc<dyn Fn(&mut Chunk) + Send + Sync>;
+type ObjectGridHook = Arc<dyn Fn(&mut ObjectGrid, &[TileData]) + Send + Sync>;
+
+/// The hooks that are invoked by the world generation. Chunks and objects are generated inside async tasks, which is
+/// why the value of the `GenerationHooks` resource is mirrored here.
+static CURRENT_GENERATION_HOOKS: RwLock<GenerationHooks> = RwLock::new(GenerationHooks::new());
+
+/// Callbacks that are invoked at key moments of the world generation, allowing custom features to be injected without
+/// changing the generation itself. Must be configured once at startup by inserting this resource before adding the
+/// plugins and must not be modified afterwards. Hooks are invoked inside async tasks in the order in which they were
+/// added. They must be deterministic for a given chunk, so that the world remains reproducible from its seed.
+#[derive(Resource, Clone)]
+pub struct GenerationHooks {
+  after_terrain_generation: Vec<ChunkHook>,
+  before_object_generation: Vec<ObjectGridHook>,
+}
+
+impl Default for GenerationHooks {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl GenerationHooks {
+  pub const fn new() -> Self {
+    Self {
+      after_terrain_generation: Vec::new(),
+      before_object_generation: Vec::new(),
+    }
+  }
+
+  /// Adds a hook that receives every chunk once its terrain, including the tile types, has been generated and before
+  /// it is spawned. Changes to the terrain are not reflected in the terrain edges that neighbouring chunks are
+  /// generated from.
+  pub fn after_terrain_generation(mut self, hook: impl Fn(&mut Chunk) + Send + Sync + 'static) -> Self {
+    self.after_terrain_generation.push(Arc::new(hook));
+    self
+  }
+
+  /// Adds a hook that receives the object grid of every chunk, together with the tiles of the chunk, right before the
+  /// wave function collapse algorithm is run. Edge constraints and rare features have been applied at this point.
+  /// Collapsing or constraining cells here lets the algorithm fill in the rest of the grid around them.
+  pub fn before_object_generation(mut self, hook: impl Fn(&mut ObjectGrid, &[TileData]) + Send + Sync + 'static) -> Self {
+    self.before_object_generation.push(Arc::new(hook));
+    self
+  }
+
+  /// Makes these hooks the ones invoked by the world generation.
+  pub(crate) fn apply(&self) {
+    let count = self.after_terrain_generation.len() + self.before_object_generation.len();
+    if count > 0 {
+      info!("Registered {} generation hook(s)", count);
+    }
+    *CURRENT_GENERATION_HOOKS.write().unwrap_or_else(PoisonError::into_inner) = self.clone();
+  }
+}
+
+/// Returns a copy of the current hooks, so that the lock is released before any of them is invoked. Since the hooks
+/// are never modified after startup, a poisoned lock still holds valid hooks.
+fn current_hooks() -> GenerationHooks {
+  CURRENT_GENERATION_HOOKS
+    .read()
+    .unwrap_or_else(PoisonError::into_inner)
+    .clone()
+}
+
+pub fn run_after_terrain_generation_hooks(chunk: &mut Chunk) {
+  for hook in current_hooks().after_terrain_generation.iter() {
+    hook(chunk);
+  }
+}
+
+pub fn run_before_object_generation_hooks(grid: &mut ObjectGrid, tile_data: &[TileData]) {
+  for hook in current_hooks().before_object_generation.iter() {
+    hook(grid, tile_data);
+  }
+}
@@ -1,4 +1,4 @@
-use crate::constants::CHUNK_SIZE_PLUS_BUFFER;
+use crate::constants::{CHUNK_HEIGHT_PLUS_BUFFER, CHUNK_WIDTH_PLUS_BUFFER};
 use crate::generation::lib::{DraftTile, Plane, TerrainType};
 use crate::resources::Settings;
 
@@ -19,7 +19,7 @@ impl LayeredPlane {
 
     // Create a plane for each layer
     for layer in 0..TerrainType::length() {
-      let mut current_layer = vec![vec![None; CHUNK_SIZE_PLUS_BUFFER as usize]; CHUNK_SIZE_PLUS_BUFFER as usize];
+      let mut current_layer = vec![vec![None; CHUNK_HEIGHT_PLUS_BUFFER as usize]; CHUNK_WIDTH_PLUS_BUFFER as usize];
 
       // Skip water layer because water is not rendered
       if layer == 0 {
@@ -29,8 +29,8 @@ impl LayeredPlane {
 
       // Populate the layer using the draft plane and adjust terrain, if necessary - as a result,
       // each tile on a layer above the first rendered layer has a tile below it too
-      for x in 0..draft_tiles[0].len() {
-        for y in 0..draft_tiles.len() {
+      for x in 0..draft_tiles.len() {
+        for y in 0..draft_tiles[0].len() {
           if let Some(tile) = &draft_tiles[x][y] {
             if tile.layer == layer as i32 {
               current_layer[x][y] = Some(tile.clone());
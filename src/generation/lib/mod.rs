@@ -3,26 +3,33 @@ mod components;
 mod debug_data;
 mod direction;
 mod draft_tile;
+mod generation_hooks;
 mod layered_plane;
 mod neighbours;
 mod plane;
 pub(crate) mod shared;
+mod terrain_edges;
 mod terrain_type;
 mod tile;
 mod tile_data;
 mod tile_type;
+mod walkability;
 
 pub use crate::resources::Settings;
 pub use chunk::Chunk;
 pub use components::{
-  ChunkComponent, GenerationStage, ObjectComponent, TileComponent, WorldComponent, WorldGenerationComponent,
+  ChunkComponent, GenerationStage, ObjectComponent, TileComponent, TileDataComponent, WorldComponent,
+  WorldGenerationComponent,
 };
 pub use direction::{get_direction_points, Direction};
 pub use draft_tile::DraftTile;
+pub use generation_hooks::{run_after_terrain_generation_hooks, run_before_object_generation_hooks, GenerationHooks};
 pub use layered_plane::LayeredPlane;
 pub use neighbours::{NeighbourTile, NeighbourTiles};
 pub use plane::Plane;
+pub use terrain_edges::TerrainEdges;
 pub use terrain_type::TerrainType;
 pub use tile::Tile;
 pub use tile_data::TileData;
 pub use tile_type::TileType;
+pub use walkability::{Walkability, WalkabilityRule};
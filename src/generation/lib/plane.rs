@@ -1,4 +1,4 @@
-use crate::constants::{BUFFER_SIZE, CHUNK_SIZE};
+use crate::constants::{BUFFER_SIZE, CHUNK_HEIGHT, CHUNK_WIDTH};
 use crate::coords::point::{CoordType, InternalGrid};
 use crate::coords::Point;
 use crate::generation::lib::{DraftTile, NeighbourTile, NeighbourTiles, Settings, TerrainType, Tile, TileType};
@@ -70,8 +70,8 @@ impl Plane {
 }
 
 fn determine_tile_types(draft_tiles: &Vec<Vec<Option<DraftTile>>>) -> Vec<Vec<Option<Tile>>> {
-  let y_len = draft_tiles.len();
-  let x_len = draft_tiles[0].len();
+  let x_len = draft_tiles.len();
+  let y_len = draft_tiles[0].len();
   let mut final_tiles = vec![vec![None; y_len]; x_len];
   for y in 0..y_len {
     for x in 0..x_len {
@@ -198,7 +198,7 @@ fn neighbour_points() -> Vec<(i32, i32)> {
 }
 
 fn get_draft_tile(x: i32, y: i32, from: &Vec<Vec<Option<DraftTile>>>) -> Option<&DraftTile> {
-  if x >= 0 && x < from[0].len() as i32 && y >= 0 && y < from.len() as i32 {
+  if x >= 0 && x < from.len() as i32 && y >= 0 && y < from[0].len() as i32 {
     from[x as usize][y as usize].as_ref()
   } else {
     None
@@ -212,10 +212,10 @@ fn get_draft_tile(x: i32, y: i32, from: &Vec<Vec<Option<DraftTile>>>) -> Option<
 /// For this to work, the `Point<TileGrid>` in `Coords` must be adjusted when creating a `Tile` from a `DraftTile`.
 fn resize_grid(final_tiles: Vec<Vec<Option<Tile>>>) -> Vec<Vec<Option<Tile>>> {
   let cut_off = BUFFER_SIZE as usize;
-  let mut cut_off_tiles = vec![vec![None; CHUNK_SIZE as usize]; CHUNK_SIZE as usize];
+  let mut cut_off_tiles = vec![vec![None; CHUNK_HEIGHT as usize]; CHUNK_WIDTH as usize];
 
-  for x in cut_off..final_tiles[0].len() - cut_off {
-    for y in cut_off..final_tiles.len() - cut_off {
+  for x in cut_off..final_tiles.len() - cut_off {
+    for y in cut_off..final_tiles[0].len() - cut_off {
       cut_off_tiles[x - cut_off][y - cut_off] = final_tiles[x][y];
     }
   }
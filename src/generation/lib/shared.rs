@@ -1,18 +1,61 @@
-use crate::coords::point::ChunkGrid;
+use crate::coords::point::{ChunkGrid, CoordType};
 use crate::coords::Point;
-use crate::generation::resources::GenerationResourcesCollection;
 use crate::resources::Settings;
-use bevy::ecs::system::SystemState;
+use bevy::core::Name;
+use bevy::ecs::component::Tick;
 use bevy::ecs::world::CommandQueue;
 use bevy::hierarchy::DespawnRecursiveExt;
-use bevy::prelude::{Commands, Component, Entity, Query, Res};
+use bevy::log::*;
+use bevy::prelude::{Commands, Component, DetectChanges, Entity, Query, Resource};
+use bevy::utils::tracing::span::EnteredSpan;
+use bevy::utils::tracing::Span;
+use std::fmt;
+use std::ops::Deref;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
 use std::thread;
-use std::time::SystemTime;
+use std::time::{Instant, SystemTime};
 
 pub trait CommandQueueTask {
   fn poll_once(&mut self) -> Option<CommandQueue>;
 }
 
+/// Enters the given `tracing` span for as long as it is alive and measures the time elapsed since. Use `finish` to log
+/// the duration, so that log messages report durations that are consistent with what the trace output (e.g.
+/// `bevy/trace_chrome`) shows.
+pub struct TimedSpan {
+  _span: EnteredSpan,
+  start: Instant,
+}
+
+impl TimedSpan {
+  pub fn new(span: Span) -> Self {
+    Self {
+      _span: span.entered(),
+      start: Instant::now(),
+    }
+  }
+
+  /// Logs the given message at the given level, followed by the time elapsed since the span was entered and the name of
+  /// the current thread, and exits the span. The message is only formatted if the level is enabled.
+  pub fn finish(self, level: Level, message: impl fmt::Display) {
+    let elapsed_ms = self.start.elapsed().as_millis();
+    match level {
+      Level::ERROR => error!("{} in {} ms on {}", message, elapsed_ms, thread_name()),
+      Level::WARN => warn!("{} in {} ms on {}", message, elapsed_ms, thread_name()),
+      Level::INFO => info!("{} in {} ms on {}", message, elapsed_ms, thread_name()),
+      Level::DEBUG => debug!("{} in {} ms on {}", message, elapsed_ms, thread_name()),
+      Level::TRACE => trace!("{} in {} ms on {}", message, elapsed_ms, thread_name()),
+    }
+  }
+}
+
+/// Returns a `Name` for an entity that is spawned in large numbers, or `None` if debug names are disabled, in which case
+/// the name is never formatted.
+pub fn debug_name(settings: &Settings, name: impl FnOnce() -> String) -> Option<Name> {
+  settings.general.debug_names.then(|| Name::new(name()))
+}
+
 pub fn thread_name() -> String {
   let thread = thread::current();
   let thread_name = thread.name().unwrap_or("Unnamed");
@@ -29,19 +72,58 @@ pub fn process_tasks<T: CommandQueueTask + Component>(mut commands: Commands, mu
   }
 }
 
-pub fn get_resources_and_settings(world: &mut bevy::ecs::world::World) -> (GenerationResourcesCollection, Settings) {
-  let (resources, settings) = {
-    let mut system_state = SystemState::<(Res<GenerationResourcesCollection>, Res<Settings>)>::new(world);
-    let (resources, settings) = system_state.get_mut(world);
-    (resources.clone(), settings.clone())
-  };
-  (resources, settings)
+/// Holds an immutable, reference counted copy of a resource which can be moved into async tasks without cloning the
+/// resource for every task. Intended to be used as a `Local` in the system scheduling the tasks. The copy is only
+/// refreshed when the resource has changed since it was taken, which is tracked using the change tick of the resource
+/// at that time rather than the last run of the system, since the system may not request a copy every time it runs.
+pub struct SharedResource<T: Resource + Clone>(Option<(Arc<T>, Tick)>);
+
+impl<T: Resource + Clone> Default for SharedResource<T> {
+  fn default() -> Self {
+    Self(None)
+  }
+}
+
+impl<T: Resource + Clone> SharedResource<T> {
+  pub fn get(&mut self, resource: &(impl Deref<Target = T> + DetectChanges)) -> Arc<T> {
+    match &self.0 {
+      Some((shared, last_changed)) if *last_changed == resource.last_changed() => shared.clone(),
+      _ => {
+        let shared = Arc::new((**resource).clone());
+        self.0 = Some((shared.clone(), resource.last_changed()));
+        shared
+      }
+    }
+  }
+}
+
+/// Runs the given function and catches any panic, returning the panic message as the error. Used in the body of async
+/// tasks, since a panicking task would otherwise never complete and leave the world generation process stuck.
+pub fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+  std::panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+    payload
+      .downcast_ref::<&str>()
+      .map(|message| message.to_string())
+      .or_else(|| payload.downcast_ref::<String>().cloned())
+      .unwrap_or_else(|| "Unknown panic".to_string())
+  })
 }
 
 pub fn get_time() -> u128 {
   SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis()
 }
 
+/// Returns a well-distributed hash of the given point, e.g. to deterministically pick one of several sprite variants.
+pub fn hash_point<T: CoordType>(p: Point<T>) -> u64 {
+  let mut hash = ((p.x as u32 as u64) << 32) | p.y as u32 as u64;
+  hash ^= hash >> 33;
+  hash = hash.wrapping_mul(0xff51afd7ed558ccd);
+  hash ^= hash >> 33;
+  hash = hash.wrapping_mul(0xc4ceb9fe1a85ec53);
+
+  hash ^ (hash >> 33)
+}
+
 pub fn calculate_seed(cg: Point<ChunkGrid>, seed: u32) -> u64 {
   let adjusted_x = cg.x as i64 + i32::MAX as i64;
   let adjusted_y = cg.y as i64 + i32::MAX as i64;
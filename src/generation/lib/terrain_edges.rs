@@ -0,0 +1,48 @@
+use crate::constants::{BUFFER_SIZE, CHUNK_HEIGHT, CHUNK_WIDTH, MAX_BEACH_WIDTH};
+use crate::coords::point::TileGrid;
+use crate::coords::Point;
+use bevy::utils::HashMap;
+
+/// The width of the band along each side of a chunk border for which the raw noise is shared. Covers the buffer as
+/// well as the band beyond it that is sampled when generating beaches, both of which lie within the neighbouring chunk.
+const SHARED_BAND_WIDTH: i32 = BUFFER_SIZE + MAX_BEACH_WIDTH;
+
+/// The raw terrain noise sampled along the borders of a chunk, keyed by `TileGrid` coordinates. Stored in the
+/// `Metadata` once a chunk has been generated, so that its neighbours can reuse these samples for their buffer and
+/// beach band instead of sampling the noise for them again.
+///
+/// Only the raw noise is shared, since it is a pure function of the tile coordinates and the terrain seed. Everything
+/// derived from it, such as the elevation offset, terrain type or climate, depends on the chunk the tile is generated
+/// for and is always calculated by that chunk, so that the generated terrain doesn't depend on the generation order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TerrainEdges {
+  noise: HashMap<Point<TileGrid>, f64>,
+}
+
+impl TerrainEdges {
+  /// Collects the samples within `SHARED_BAND_WIDTH` of the border of the chunk whose top-left rendered tile is at
+  /// the given `Point<TileGrid>`, on either side of the border.
+  pub fn from_samples(tg: &Point<TileGrid>, samples: HashMap<Point<TileGrid>, f64>) -> Self {
+    let (inner_left, inner_right) = (tg.x + SHARED_BAND_WIDTH, tg.x + CHUNK_WIDTH - 1 - SHARED_BAND_WIDTH);
+    let (inner_top, inner_bottom) = (tg.y - SHARED_BAND_WIDTH, tg.y - CHUNK_HEIGHT + 1 + SHARED_BAND_WIDTH);
+    let noise = samples
+      .into_iter()
+      .filter(|(tg, _)| !((inner_left..=inner_right).contains(&tg.x) && (inner_bottom..=inner_top).contains(&tg.y)))
+      .collect();
+
+    Self { noise }
+  }
+
+  pub fn get(&self, tg: &Point<TileGrid>) -> Option<f64> {
+    self.noise.get(tg).copied()
+  }
+
+  pub fn extend(&mut self, other: &TerrainEdges) {
+    self.noise.extend(other.noise.iter().map(|(tg, noise)| (*tg, *noise)));
+  }
+
+  /// Consumes the edges, returning the raw noise samples.
+  pub fn into_samples(self) -> HashMap<Point<TileGrid>, f64> {
+    self.noise
+  }
+}
@@ -2,13 +2,14 @@ use bevy::reflect::Reflect;
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
-#[derive(serde::Deserialize, Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Hash, Reflect)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Hash, Reflect)]
 pub enum TerrainType {
   DeepWater,
   ShallowWater,
   Land1,
   Land2,
   Land3,
+  Land4,
   Any,
 }
 
@@ -26,7 +27,7 @@ impl Display for TerrainType {
 
 impl TerrainType {
   pub fn length() -> usize {
-    5 // Ignore TerrainType:Any
+    6 // Ignore TerrainType:Any
   }
 
   pub fn from(i: usize) -> Self {
@@ -36,6 +37,7 @@ impl TerrainType {
       2 => TerrainType::Land1,
       3 => TerrainType::Land2,
       4 => TerrainType::Land3,
+      5 => TerrainType::Land4,
       _ => TerrainType::Any,
     }
   }
@@ -1,5 +1,6 @@
-use crate::constants::{BUFFER_SIZE, CHUNK_SIZE, TILE_SIZE};
+use crate::constants::{BUFFER_SIZE, CHUNK_HEIGHT, CHUNK_WIDTH};
 use crate::coords::point::{InternalGrid, World};
+use crate::coords::tile_size::tile_size;
 use crate::coords::{Coords, Point};
 use crate::generation::lib::debug_data::DebugData;
 use crate::generation::lib::{DraftTile, TerrainType, TileType};
@@ -8,12 +9,12 @@ use bevy::log::*;
 use bevy::reflect::Reflect;
 use std::fmt;
 
-/// A `Tile` represents a single tile of `TILE_SIZE` in the world. It contains information about its `Coords`,
+/// A `Tile` represents a single tile of `tile_size()` in the world. It contains information about its `Coords`,
 /// `TerrainType`, `TileType`, and layer. If created from a `DraftTile`, the `layer` of a `Tile` adds the y-coordinate
 /// of the world grid `Coords` to the layer from the `DraftTile` from which it was created. It also adjusts the
 /// `InternalGrid` `Coords` to account for the buffer of a "draft chunk" i.e. it shifts the `InternalGrid` `Coords` by the
 /// `BUFFER_SIZE` to towards the top-left, allowing for the outer tiles of the "draft chunk" to be cut off in a way that
-/// the `Tile`s in the resulting `Chunk` have `InternalGrid` `Coords` ranging from 0 to `CHUNK_SIZE`.
+/// the `Tile`s in the resulting `Chunk` have `InternalGrid` `Coords` ranging from 0 to `CHUNK_WIDTH` and `CHUNK_HEIGHT` respectively.
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect)]
 pub struct Tile {
   #[reflect(ignore)]
@@ -22,6 +23,7 @@ pub struct Tile {
   pub layer: i32,
   pub climate: Climate,
   pub tile_type: TileType,
+  pub is_beach: bool,
   pub debug_data: DebugData,
 }
 
@@ -47,14 +49,15 @@ impl Tile {
       layer: draft_tile.layer + draft_tile.coords.internal_grid.y,
       climate: draft_tile.climate,
       tile_type,
+      is_beach: draft_tile.is_beach,
       debug_data: draft_tile.debug_data,
     }
   }
 
   pub fn get_parent_chunk_w(&self) -> Point<World> {
     Point::new_world(
-      (self.coords.tile_grid.x - self.coords.internal_grid.x) * TILE_SIZE as i32,
-      (self.coords.tile_grid.y + self.coords.internal_grid.y) * TILE_SIZE as i32,
+      (self.coords.tile_grid.x - self.coords.internal_grid.x) * tile_size() as i32,
+      (self.coords.tile_grid.y + self.coords.internal_grid.y) * tile_size() as i32,
     )
   }
 
@@ -65,7 +68,7 @@ impl Tile {
 }
 
 pub fn is_marked_for_deletion(ig: &Point<InternalGrid>) -> bool {
-  ig.x < 0 || ig.y < 0 || ig.x > CHUNK_SIZE || ig.y > CHUNK_SIZE
+  ig.x < 0 || ig.y < 0 || ig.x > CHUNK_WIDTH || ig.y > CHUNK_HEIGHT
 }
 
 impl fmt::Debug for Tile {
@@ -75,6 +78,7 @@ impl fmt::Debug for Tile {
       .field("terrain", &self.terrain)
       .field("climate", &self.climate)
       .field("tile_type", &self.tile_type)
+      .field("is_beach", &self.is_beach)
       .finish()
   }
 }
@@ -1,6 +1,4 @@
 use crate::constants::*;
-use crate::generation::lib::TerrainType;
-use crate::generation::resources::{Climate, GenerationResourcesCollection};
 use bevy::reflect::Reflect;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Reflect, serde::Deserialize)]
@@ -28,37 +26,6 @@ impl TileType {
   pub fn get_sprite_index(&self, index_offset: usize) -> usize {
     get_sprite_index(self, index_offset)
   }
-
-  pub fn calculate_sprite_index(
-    &self,
-    terrain: &TerrainType,
-    climate: &Climate,
-    resources: &GenerationResourcesCollection,
-  ) -> usize {
-    get_sprite_index_from(&self, terrain, climate, resources)
-  }
-}
-
-fn get_sprite_index_from(
-  tile_type: &TileType,
-  terrain: &TerrainType,
-  climate: &Climate,
-  resources: &GenerationResourcesCollection,
-) -> usize {
-  match (terrain, climate) {
-    (TerrainType::DeepWater, _) => get_sprite_index(&tile_type, resources.deep_water.index_offset()),
-    (TerrainType::ShallowWater, _) => get_sprite_index(&tile_type, resources.shallow_water.index_offset()),
-    (TerrainType::Land1, Climate::Dry) => get_sprite_index(&tile_type, resources.land_dry_l1.index_offset()),
-    (TerrainType::Land1, Climate::Moderate) => get_sprite_index(&tile_type, resources.land_moderate_l1.index_offset()),
-    (TerrainType::Land1, Climate::Humid) => get_sprite_index(&tile_type, resources.land_humid_l1.index_offset()),
-    (TerrainType::Land2, Climate::Dry) => get_sprite_index(&tile_type, resources.land_dry_l2.index_offset()),
-    (TerrainType::Land2, Climate::Moderate) => get_sprite_index(&tile_type, resources.land_moderate_l2.index_offset()),
-    (TerrainType::Land2, Climate::Humid) => get_sprite_index(&tile_type, resources.land_humid_l2.index_offset()),
-    (TerrainType::Land3, Climate::Dry) => get_sprite_index(&tile_type, resources.land_dry_l3.index_offset()),
-    (TerrainType::Land3, Climate::Moderate) => get_sprite_index(&tile_type, resources.land_moderate_l3.index_offset()),
-    (TerrainType::Land3, Climate::Humid) => get_sprite_index(&tile_type, resources.land_humid_l3.index_offset()),
-    (TerrainType::Any, _) => panic!("{}", TERRAIN_TYPE_ERROR),
-  }
 }
 
 fn get_sprite_index(tile_type: &TileType, index_offset: usize) -> usize {
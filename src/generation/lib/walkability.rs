@@ -0,0 +1,45 @@
+use crate::generation::lib::{TerrainType, TileType};
+use bevy::utils::HashMap;
+
+/// A single rule of the `all.walkability.ruleset.ron` file. Applies to all tiles of the terrain type or, if a tile type
+/// is specified, only to the tiles of the terrain type with that tile type.
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct WalkabilityRule {
+  pub terrain: TerrainType,
+  #[serde(default)]
+  pub tile_type: Option<TileType>,
+  pub is_walkable: bool,
+}
+
+/// Determines whether a tile can be walked across based on its terrain type and tile type. A rule for a specific tile
+/// type takes precedence over the rule for the terrain type as a whole. Tiles without a matching rule are not walkable.
+#[derive(Debug, Clone, Default)]
+pub struct Walkability {
+  terrain_rules: HashMap<TerrainType, bool>,
+  tile_type_rules: HashMap<(TerrainType, TileType), bool>,
+}
+
+impl Walkability {
+  pub fn new(rules: Vec<WalkabilityRule>) -> Self {
+    let mut walkability = Self::default();
+    for rule in rules {
+      match rule.tile_type {
+        Some(tile_type) => walkability
+          .tile_type_rules
+          .insert((rule.terrain, tile_type), rule.is_walkable),
+        None => walkability.terrain_rules.insert(rule.terrain, rule.is_walkable),
+      };
+    }
+
+    walkability
+  }
+
+  pub fn is_walkable(&self, terrain: TerrainType, tile_type: TileType) -> bool {
+    self
+      .tile_type_rules
+      .get(&(terrain, tile_type))
+      .or_else(|| self.terrain_rules.get(&terrain))
+      .copied()
+      .unwrap_or(false)
+  }
+}
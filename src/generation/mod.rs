@@ -1,65 +1,247 @@
-use crate::constants::{CHUNK_SIZE, DESPAWN_DISTANCE, ORIGIN_CHUNK_GRID_SPAWN_POINT, ORIGIN_WORLD_SPAWN_POINT, TILE_SIZE};
-use crate::coords::point::World;
-use crate::coords::Point;
-use crate::events::{PruneWorldEvent, RegenerateWorldEvent, UpdateWorldEvent};
+use crate::components::PendingDespawnComponent;
+use crate::constants::{
+  CHUNK_PLACEHOLDER_Z, DARK_GREEN, DESPAWN_DISTANCE_IN_CHUNKS, FADE_OUT_DURATION, FRAME_TIME_GUARDRAIL_MAX_SKIPPED_FRAMES,
+  GREEN, MAX_GENERATION_TASK_RETRIES, ORPHANED_SPRITE_SWEEP_INTERVAL_SECS, YELLOW,
+};
+use crate::coords::point::{ChunkGrid, World};
+use crate::coords::projection::projection;
+use crate::coords::tile_size::{chunk_height, chunk_width};
+use crate::coords::{Point, WorldProjection};
+use crate::events::{
+  CrossedChunkBoundaryEvent, PruneWorldEvent, RegenerateWorldEvent, RerollObjectsEvent, UpdateWorldEvent,
+};
 use crate::generation::debug::DebugPlugin;
 use crate::generation::lib::{
-  get_direction_points, ChunkComponent, Direction, GenerationStage, WorldComponent, WorldGenerationComponent,
+  Chunk, ChunkComponent, Direction, GenerationHooks, GenerationStage, ObjectComponent, TileComponent, TileData,
+  TileDataComponent, WorldComponent, WorldGenerationComponent,
 };
 use crate::generation::object::ObjectGenerationPlugin;
-use crate::generation::resources::{ChunkComponentIndex, GenerationResourcesCollection, Metadata};
+use crate::generation::resources::{
+  despawn_chunk, ChunkComponentIndex, Climate, DryRunReport, GenerationResourcesCollection, GenerationStepper, Metadata,
+  NavData, ObjectEdges, PendingWorldUpdate, WfcStatistics, WorldModifications,
+};
 use crate::generation::world::WorldGenerationPlugin;
-use crate::resources::{CurrentChunk, Settings};
-use crate::states::{AppState, GenerationState};
+use crate::resources::{CurrentChunk, Settings, SpawnPoint, VisibleChunks};
+use crate::states::{AppState, GenerationPhase, GenerationState};
 use bevy::app::{App, Plugin};
 use bevy::core::Name;
-use bevy::hierarchy::BuildChildren;
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy::ecs::system::SystemParam;
+use bevy::hierarchy::{BuildChildren, ChildBuild, HierarchyQueryExt, Parent};
 use bevy::log::*;
 use bevy::prelude::{
-  in_state, Commands, DespawnRecursiveExt, Entity, EventReader, EventWriter, IntoSystemConfigs, Local, Mut, NextState,
-  OnExit, OnRemove, Query, Res, ResMut, Transform, Trigger, Update, Visibility, With,
+  in_state, Commands, Condition, DespawnRecursiveExt, Entity, EventReader, EventWriter, IntoSystemConfigs,
+  IntoSystemSetConfigs, Local, Mut, NextState, OnExit, OnRemove, Or, Query, Res, ResMut, Sprite, State, SystemSet, Time,
+  Timer, TimerMode, Transform, Trigger, Update, Vec2, Visibility, With, Without,
 };
+use bevy::sprite::Anchor;
 use bevy::tasks::{block_on, poll_once, AsyncComputeTaskPool};
+use bevy::time::common_conditions::on_timer;
+use bevy::utils::HashMap;
 use lib::shared;
+use lib::shared::SharedResource;
 use rand::prelude::StdRng;
 use rand::SeedableRng;
 use resources::GenerationResourcesPlugin;
+use std::time::Duration;
 
 mod debug;
 pub(crate) mod lib;
-mod object;
+pub(crate) mod object;
 pub mod resources;
 mod world;
 
 pub struct GenerationPlugin;
 
+/// The total number of orphaned tile and object sprites that have been despawned since the application started.
+pub const ORPHANED_SPRITES: DiagnosticPath = DiagnosticPath::const_new("orphaned_sprites");
+
 impl Plugin for GenerationPlugin {
   fn build(&self, app: &mut App) {
+    let hooks = app.world().get_resource::<GenerationHooks>().cloned().unwrap_or_default();
+    hooks.apply();
     app
+      .insert_resource(hooks)
       .add_plugins((
         GenerationResourcesPlugin,
         WorldGenerationPlugin,
         ObjectGenerationPlugin,
         DebugPlugin,
       ))
+      .configure_sets(Update, FrameTimeBudgetSet.run_if(is_within_frame_time_budget))
       .add_systems(OnExit(AppState::Initialising), initiate_world_generation_system)
-      .add_systems(Update, world_generation_system.run_if(in_state(GenerationState::Generating)))
       .add_systems(
         Update,
-        (regenerate_world_event, update_world_event, prune_world_event).run_if(in_state(AppState::Running)),
+        (
+          world_generation_system
+            .run_if(in_state(GenerationState::Generating).and(is_generation_not_frozen))
+            .in_set(FrameTimeBudgetSet),
+          update_generation_phase_system.run_if(in_state(GenerationState::Generating)),
+          stuck_component_watchdog_system.run_if(in_state(GenerationState::Generating)),
+        )
+          .chain(),
       )
+      .add_systems(
+        Update,
+        (
+          regenerate_world_event,
+          crossed_chunk_boundary_event.before(update_world_event),
+          update_world_event,
+          prune_world_event,
+          send_pending_world_update_system
+            .after(prune_world_event)
+            .before(update_world_event),
+          reroll_objects_event,
+        )
+          .run_if(in_state(AppState::Running)),
+      )
+      .add_systems(
+        Update,
+        despawn_orphaned_sprites_system
+          .run_if(in_state(AppState::Running).and(on_timer(Duration::from_secs_f32(ORPHANED_SPRITE_SWEEP_INTERVAL_SECS)))),
+      )
+      .register_diagnostic(Diagnostic::new(ORPHANED_SPRITES))
       .add_observer(on_remove_update_world_component_trigger);
   }
 }
 
+/// The systems that advance the world generation or process its tasks, which spawn the generated tiles and objects.
+/// All of them are paused while the frame time budget is exceeded, see `is_within_frame_time_budget`.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FrameTimeBudgetSet;
+
+fn is_generation_not_frozen(settings: Res<Settings>) -> bool {
+  !settings.general.freeze_generation
+}
+
+/// Returns `false` if the previous frame exceeded the frame time budget, which pauses all systems in the
+/// `FrameTimeBudgetSet` for this frame. Never pauses for more than `FRAME_TIME_GUARDRAIL_MAX_SKIPPED_FRAMES` consecutive
+/// frames. Evaluated once per frame for the entire set.
+fn is_within_frame_time_budget(settings: Res<Settings>, time: Res<Time>, mut skipped_frames: Local<u32>) -> bool {
+  if !settings.general.enable_frame_time_guardrail {
+    return true;
+  }
+  let frame_time_ms = time.delta_secs() * 1000.;
+  if frame_time_ms <= settings.general.frame_time_budget_ms || *skipped_frames >= FRAME_TIME_GUARDRAIL_MAX_SKIPPED_FRAMES {
+    if *skipped_frames > 0 {
+      debug!("Resuming world generation after pausing for {} frame(s)", *skipped_frames);
+    }
+    *skipped_frames = 0;
+    return true;
+  }
+  if *skipped_frames == 0 {
+    debug!(
+      "Pausing world generation because the last frame took {:.1} ms (budget: {} ms)",
+      frame_time_ms, settings.general.frame_time_budget_ms
+    );
+  }
+  *skipped_frames += 1;
+
+  false
+}
+
+/// Sets the `GenerationPhase` based on the least advanced `WorldGenerationComponent`.
+fn update_generation_phase_system(
+  world_generation_components: Query<&WorldGenerationComponent>,
+  current_phase: Res<State<GenerationPhase>>,
+  mut next_phase: ResMut<NextState<GenerationPhase>>,
+) {
+  if let Some(phase) = world_generation_components.iter().map(|c| c.stage.phase()).min() {
+    if phase != *current_phase.get() {
+      next_phase.set(phase);
+    }
+  }
+}
+
+/// Flags any `WorldGenerationComponent` that has remained in the same stage for longer than the configured timeout and,
+/// if enabled, force-advances it to the clean-up stage. Components are expected to remain in the same stage while the
+/// generation is frozen or stepped through, so they are never flagged in that case.
+fn stuck_component_watchdog_system(
+  mut world_generation_components: Query<&mut WorldGenerationComponent>,
+  settings: Res<Settings>,
+) {
+  if settings.general.freeze_generation || settings.general.step_through_generation {
+    return;
+  }
+  let now = shared::get_time();
+  let timeout_ms = (settings.general.stuck_component_timeout_secs * 1000.) as u128;
+  for mut component in world_generation_components.iter_mut() {
+    let elapsed_ms = now.saturating_sub(component.stage_started_at);
+    if elapsed_ms < timeout_ms || matches!(component.stage, GenerationStage::Stage7 | GenerationStage::Failed) {
+      continue;
+    }
+    if settings.general.force_advance_stuck_components {
+      warn!(
+        cg = %component.cg,
+        stage = ?component.stage,
+        elapsed_ms,
+        "⏱️ World generation component {} is stuck in stage [{:?}], skipping to the clean-up stage",
+        component.cg,
+        component.stage
+      );
+      component.stage_1_gen_task = None;
+      component.stage_2_chunks.clear();
+      component.stage_3_spawn_data.clear();
+      component.stage_4_spawn_data.clear();
+      component.stage_5_object_data.clear();
+      component.stage = GenerationStage::Stage7;
+    } else if component.stuck_stage != Some(component.stage) {
+      warn!(
+        cg = %component.cg,
+        stage = ?component.stage,
+        elapsed_ms,
+        "⏱️ World generation component {} has been stuck in stage [{:?}] for {} ms",
+        component.cg,
+        component.stage,
+        elapsed_ms
+      );
+      component.stuck_stage = Some(component.stage);
+    }
+  }
+}
+
+/// Includes tile and object sprites.
+type IsSprite = Or<(With<TileComponent>, With<ObjectComponent>)>;
+
+/// Despawns tile and object sprites that don't have a chunk among their ancestors. These can be left behind when a
+/// chunk is pruned or regenerated while sprites are still being spawned for it, e.g. if they end up being parented to
+/// an entity that no longer exists. They are invisible but still cost render time.
+fn despawn_orphaned_sprites_system(
+  mut commands: Commands,
+  mut diagnostics: Diagnostics,
+  mut total_count: Local<usize>,
+  sprites: Query<Entity, IsSprite>,
+  parents: Query<&Parent>,
+  chunks: Query<(), With<ChunkComponent>>,
+) {
+  let mut count = 0;
+  for entity in sprites.iter() {
+    let has_chunk_ancestor = parents.iter_ancestors(entity).any(|ancestor| chunks.contains(ancestor));
+    if !has_chunk_ancestor {
+      commands.entity(entity).despawn_recursive();
+      count += 1;
+    }
+  }
+  if count > 0 {
+    warn!("Despawned {} orphaned sprite(s) that were not part of any chunk", count);
+  }
+  *total_count += count;
+  diagnostics.add_measurement(&ORPHANED_SPRITES, || *total_count as f64);
+}
+
 /// Generates the world and all its objects. Called once before entering `AppState::Running`.
-fn initiate_world_generation_system(mut commands: Commands, mut next_state: ResMut<NextState<GenerationState>>) {
-  let w = ORIGIN_WORLD_SPAWN_POINT;
-  let cg = ORIGIN_CHUNK_GRID_SPAWN_POINT;
+fn initiate_world_generation_system(
+  mut commands: Commands,
+  mut next_state: ResMut<NextState<GenerationState>>,
+  spawn_point: Res<SpawnPoint>,
+  settings: Res<Settings>,
+) {
+  let w = spawn_point.w;
+  let cg = spawn_point.cg;
   debug!("Generating world with origin {} {}", w, cg);
   commands.spawn((
     Name::new(format!("Update World Component {}", w)),
-    WorldGenerationComponent::new(w, cg, false, shared::get_time()),
+    WorldGenerationComponent::new(w, cg, false, shared::get_time(), *settings),
   ));
   commands.spawn((
     Name::new("World"),
@@ -72,23 +254,29 @@ fn initiate_world_generation_system(mut commands: Commands, mut next_state: ResM
 
 /// Destroys the world and then generates a new one and all its objects. Called when a `RegenerateWorldEvent` is
 /// received. This is triggered by pressing a key or a button in the UI while the camera is within the bounds of the
-/// `Chunk` at the origin of the world.
+/// `Chunk` at the spawn point of the world.
 fn regenerate_world_event(
   mut commands: Commands,
   mut events: EventReader<RegenerateWorldEvent>,
   existing_world: Query<Entity, With<WorldComponent>>,
+  existing_chunks: Query<Entity, With<ChunkComponent>>,
   mut next_state: ResMut<NextState<GenerationState>>,
+  spawn_point: Res<SpawnPoint>,
+  settings: Res<Settings>,
 ) {
   let event_count = events.read().count();
   if event_count > 0 {
     let world = existing_world.get_single().expect("Failed to get existing world entity");
-    let w = ORIGIN_WORLD_SPAWN_POINT;
-    let cg = ORIGIN_CHUNK_GRID_SPAWN_POINT;
+    let w = spawn_point.w;
+    let cg = spawn_point.cg;
     debug!("Regenerating world with origin {} {}", w, cg);
+    for chunk in existing_chunks.iter() {
+      despawn_chunk(&mut commands, chunk);
+    }
     commands.entity(world).despawn_recursive();
     commands.spawn((
       Name::new(format!("Update World Component {}", cg)),
-      WorldGenerationComponent::new(w, cg, false, shared::get_time()),
+      WorldGenerationComponent::new(w, cg, false, shared::get_time(), *settings),
     ));
     commands.spawn((
       Name::new("World"),
@@ -100,45 +288,202 @@ fn regenerate_world_event(
   }
 }
 
+/// Requests an update of the world whenever the camera has moved into another chunk.
+fn crossed_chunk_boundary_event(
+  mut events: EventReader<CrossedChunkBoundaryEvent>,
+  mut update_world_event: EventWriter<UpdateWorldEvent>,
+) {
+  for event in events.read() {
+    debug!(
+      "Camera crossed chunk boundary from {} to {} ({:?})",
+      event.from_cg, event.to_cg, event.direction
+    );
+    update_world_event.send(UpdateWorldEvent::new(event.w));
+  }
+}
+
+/// The report of the most recent dry run and everything needed to create it, in addition to the `CurrentChunk`, the
+/// `VisibleChunks` and the `Settings`.
+#[derive(SystemParam)]
+struct DryRun<'w, 's> {
+  report: ResMut<'w, DryRunReport>,
+  chunk_index: Res<'w, ChunkComponentIndex>,
+  existing_chunks: Query<'w, 's, (Entity, &'static ChunkComponent), With<ChunkComponent>>,
+}
+
 /// Updates the world and all its objects. Called when an `UpdateWorldEvent` is received. Triggered when the camera
 /// moves outside the bounds of the `CurrentChunk` or when manually requesting a world re-generation while the camera
-/// is outside the bounds of the `Chunk` at origin spawn point.
+/// is outside the bounds of the `Chunk` at the spawn point.
 fn update_world_event(
   mut commands: Commands,
   mut events: EventReader<UpdateWorldEvent>,
   mut current_chunk: ResMut<CurrentChunk>,
   mut next_state: ResMut<NextState<GenerationState>>,
+  mut dry_run: DryRun,
+  visible_chunks: Res<VisibleChunks>,
+  settings: Res<Settings>,
 ) {
   for event in events.read() {
-    if current_chunk.contains(event.tg) && !event.is_forced_update {
-      debug!("{} is inside current chunk, ignoring event...", event.tg);
-      return;
+    if event.is_dry_run {
+      *dry_run.report = create_dry_run_report(
+        &current_chunk,
+        event,
+        &dry_run.chunk_index,
+        &visible_chunks,
+        &dry_run.existing_chunks,
+        &settings,
+      );
+      info!("{}", *dry_run.report);
+      continue;
+    }
+    if settings.general.freeze_generation {
+      debug!("Generation is frozen, ignoring update world event for {}...", event.tg());
+      continue;
     }
-    let new_parent_w = calculate_new_current_chunk_w(&mut current_chunk, &event);
+    if current_chunk.contains(event.tg()) && !event.is_forced_update {
+      debug!("{} is inside current chunk, ignoring event...", event.tg());
+      continue;
+    }
+    let new_parent_w = calculate_new_current_chunk_w(&current_chunk, &event);
     let new_parent_cg = Point::new_chunk_grid_from_world(new_parent_w);
     debug!("Updating world with new current chunk at {} {}", new_parent_w, new_parent_cg);
     commands.spawn((
       Name::new(format!("Update World Component {}", new_parent_w)),
-      WorldGenerationComponent::new(new_parent_w, new_parent_cg, event.is_forced_update, shared::get_time()),
+      WorldGenerationComponent::new(
+        new_parent_w,
+        new_parent_cg,
+        event.is_forced_update,
+        shared::get_time(),
+        *settings,
+      ),
     ));
     current_chunk.update(new_parent_w);
     next_state.set(GenerationState::Generating);
   }
 }
 
+/// The spawned chunks, along with their tiles and objects, that are considered when rerolling objects.
+#[derive(SystemParam)]
+struct SpawnedWorld<'w, 's> {
+  chunks: Query<'w, 's, (Entity, &'static ChunkComponent), Without<PendingDespawnComponent>>,
+  tile_data: Query<'w, 's, &'static TileDataComponent>,
+  objects: Query<'w, 's, Entity, With<ObjectComponent>>,
+}
+
+/// Removes all objects from the currently spawned chunks and re-runs the object generation for these chunks, skipping
+/// the terrain generation stages entirely. Called when a `RerollObjectsEvent` is received. The object seed is expected
+/// to have been changed before this event is sent, otherwise the exact same objects will be generated again.
+fn reroll_objects_event(
+  mut commands: Commands,
+  mut events: EventReader<RerollObjectsEvent>,
+  spawned: SpawnedWorld,
+  current_chunk: Res<CurrentChunk>,
+  settings: Res<Settings>,
+  mut object_edges: ResMut<ObjectEdges>,
+  mut next_state: ResMut<NextState<GenerationState>>,
+) {
+  let event_count = events.read().count();
+  if event_count == 0 {
+    return;
+  }
+  object_edges.clear();
+  for object_entity in spawned.objects.iter() {
+    commands.entity(object_entity).despawn_recursive();
+  }
+  let mut tile_data_by_chunk: HashMap<Entity, Vec<TileData>> = HashMap::new();
+  for tdc in spawned.tile_data.iter() {
+    tile_data_by_chunk
+      .entry(tdc.tile_data.chunk_entity)
+      .or_default()
+      .push(tdc.tile_data);
+  }
+  let spawn_data = spawned
+    .chunks
+    .iter()
+    .map(|(entity, chunk_component)| {
+      let tile_data = tile_data_by_chunk.remove(&entity).unwrap_or_default();
+      (Chunk::from_component(chunk_component), tile_data)
+    })
+    .collect::<Vec<(Chunk, Vec<TileData>)>>();
+  let w = current_chunk.get_world();
+  let cg = current_chunk.get_chunk_grid();
+  debug!("Rerolling objects for {} chunk(s) around {} {}", spawn_data.len(), w, cg);
+  let mut component = WorldGenerationComponent::new(w, cg, true, shared::get_time(), *settings);
+  component.stage = GenerationStage::Stage5;
+  component.stage_4_spawn_data = spawn_data;
+  commands.spawn((Name::new(format!("Reroll Objects Component {}", w)), component));
+  next_state.set(GenerationState::Generating);
+}
+
+/// Evaluates the `UpdateWorldEvent` without mutating the world and returns a report of the chunks that would be spawned
+/// and pruned. Mirrors the logic of the actual update i.e. no chunks are considered for pruning if the update is forced
+/// or if world pruning is disabled.
+fn create_dry_run_report(
+  current_chunk: &CurrentChunk,
+  event: &UpdateWorldEvent,
+  chunk_index: &ChunkComponentIndex,
+  visible_chunks: &VisibleChunks,
+  existing_chunks: &Query<(Entity, &ChunkComponent), With<ChunkComponent>>,
+  settings: &Settings,
+) -> DryRunReport {
+  let new_current_chunk_w = if current_chunk.contains(event.tg()) && !event.is_forced_update {
+    current_chunk.get_world()
+  } else {
+    calculate_new_current_chunk_w(current_chunk, event)
+  };
+  let chunks_to_spawn = calculate_chunk_spawn_points(chunk_index, visible_chunks, settings, &new_current_chunk_w)
+    .iter()
+    .map(|w| Point::new_chunk_grid_from_world(*w))
+    .collect();
+  let chunks_to_prune = if !event.is_forced_update && settings.general.enable_world_pruning {
+    calculate_chunks_to_despawn(existing_chunks, visible_chunks, &new_current_chunk_w, settings, false)
+      .iter()
+      .map(|(_, cg)| *cg)
+      .collect()
+  } else {
+    vec![]
+  };
+
+  DryRunReport {
+    current_cg: current_chunk.get_chunk_grid(),
+    new_current_cg: Point::new_chunk_grid_from_world(new_current_chunk_w),
+    chunks_to_spawn,
+    chunks_to_prune,
+  }
+}
+
 // TODO: Refactor this and ChunkComponentIndex to use cg instead of w
-fn calculate_new_current_chunk_w(current_chunk: &mut CurrentChunk, event: &UpdateWorldEvent) -> Point<World> {
+/// Returns the world coordinates of the chunk that becomes the new current chunk. Normally, this is the neighbour of
+/// the current chunk in the direction of the event. If the event is further away than that (e.g. because updates were
+/// deferred while the camera was moving fast), the chunk that contains the event is returned directly instead.
+fn calculate_new_current_chunk_w(current_chunk: &CurrentChunk, event: &UpdateWorldEvent) -> Point<World> {
   let current_chunk_w = current_chunk.get_world();
+  let center_w = current_chunk.get_center_world();
+  let (width, height) = (chunk_width(), chunk_height());
+  if (event.w.x - center_w.x).abs() > width * 3 / 2 || (event.w.y - center_w.y).abs() > height * 3 / 2 {
+    // A chunk extends to the right and downwards from its top left corner, so the chunk containing a point is found by
+    // rounding towards negative infinity on the x-axis and towards positive infinity on the y-axis
+    let cg = Point::new_chunk_grid(event.w.x.div_euclid(width), -(-event.w.y).div_euclid(height));
+    let new_parent_chunk_w = Point::new_world_from_chunk_grid(cg);
+    trace!(
+      "Update world event at {} {} is more than one chunk away from {} and will change the current chunk to {}",
+      event.w,
+      event.tg(),
+      current_chunk_w,
+      new_parent_chunk_w
+    );
+    return new_parent_chunk_w;
+  }
   let direction = Direction::from_chunk_w(&current_chunk_w, &event.w);
   let direction_point_w = Point::<World>::from_direction(&direction);
   let new_parent_chunk_w = Point::new_world(
-    current_chunk_w.x + (CHUNK_SIZE * TILE_SIZE as i32 * direction_point_w.x),
-    current_chunk_w.y + (CHUNK_SIZE * TILE_SIZE as i32 * direction_point_w.y),
+    current_chunk_w.x + (chunk_width() * direction_point_w.x),
+    current_chunk_w.y + (chunk_height() * direction_point_w.y),
   );
   trace!(
     "Update world event at {} {} will change the current chunk to be at [{:?}] of {} i.e. {}",
     event.w,
-    event.tg,
+    event.tg(),
     direction,
     current_chunk_w,
     new_parent_chunk_w
@@ -147,6 +492,22 @@ fn calculate_new_current_chunk_w(current_chunk: &mut CurrentChunk, event: &Updat
   new_parent_chunk_w
 }
 
+/// The copies of the resources that are moved into the async tasks of the world generation, see `SharedResource`.
+#[derive(SystemParam)]
+struct SharedResources<'s> {
+  metadata: Local<'s, SharedResource<Metadata>>,
+  resources: Local<'s, SharedResource<GenerationResourcesCollection>>,
+  world_modifications: Local<'s, SharedResource<WorldModifications>>,
+}
+
+/// The resources that collect the results of the object generation of each chunk, in addition to the spawned objects.
+#[derive(SystemParam)]
+struct ObjectGenerationResults<'w> {
+  nav_data: ResMut<'w, NavData>,
+  object_edges: ResMut<'w, ObjectEdges>,
+  wfc_statistics: ResMut<'w, WfcStatistics>,
+}
+
 /// Updates the world and all its objects. This is the core system that drives the generation of the world and all its
 /// objects. It is triggered when a `WorldGenerationComponent` is spawned.
 fn world_generation_system(
@@ -154,100 +515,287 @@ fn world_generation_system(
   existing_world: Query<Entity, With<WorldComponent>>,
   mut world_generation_components: Query<(Entity, &mut WorldGenerationComponent), With<WorldGenerationComponent>>,
   settings: Res<Settings>,
-  metadata: Res<Metadata>,
+  mut metadata: ResMut<Metadata>,
   resources: Res<GenerationResourcesCollection>,
+  mut shared: SharedResources,
   existing_chunks: Res<ChunkComponentIndex>,
+  visible_chunks: Res<VisibleChunks>,
+  chunk_components: Query<(Entity, &ChunkComponent)>,
+  world_modifications: Res<WorldModifications>,
+  mut results: ObjectGenerationResults,
+  mut stepper: ResMut<GenerationStepper>,
   mut prune_world_event: EventWriter<PruneWorldEvent>,
 ) {
   for (entity, mut component) in world_generation_components.iter_mut() {
-    let start_time = shared::get_time();
+    let is_stepping_through = settings.general.step_through_generation;
+    if is_stepping_through && !stepper.is_step_requested() {
+      return;
+    }
+    let span = shared::TimedSpan::new(info_span!("world_generation_stage", cg = %component.cg, stage = ?component.stage));
+    let previous_stage = component.stage;
     let world_entity = existing_world.get_single().expect("Failed to get existing world entity");
+    let snapshot = component.settings;
     match component.stage {
-      GenerationStage::Stage1 => stage_1_schedule_chunk_generation(&settings, &metadata, &existing_chunks, &mut component),
-      GenerationStage::Stage2 => stage_2_await_chunk_generation(&mut component, &existing_chunks),
+      GenerationStage::Stage1 => {
+        let spawn_points = calculate_chunk_spawn_points(&existing_chunks, &visible_chunks, &snapshot, &component.w);
+        stage_1_schedule_chunk_generation(
+          &mut commands,
+          &metadata,
+          &world_modifications,
+          &mut shared,
+          spawn_points,
+          world_entity,
+          &mut component,
+        )
+      }
+      GenerationStage::Stage2 => stage_2_await_chunk_generation(&mut metadata, &mut component, &existing_chunks),
       GenerationStage::Stage3 => {
         stage_3_spawn_chunks_and_empty_tiles(&mut commands, &mut component, world_entity, &existing_chunks)
       }
-      GenerationStage::Stage4 => stage_4_schedule_spawning_tiles(&mut commands, &settings, &mut component),
-      GenerationStage::Stage5 => stage_5_schedule_generating_object_data(&settings, &resources, &mut component),
-      GenerationStage::Stage6 => stage_6_schedule_spawning_objects(&mut commands, &settings, &mut component),
-      GenerationStage::Stage7 => stage_7_clean_up(&mut commands, &mut prune_world_event, entity, &mut component, &settings),
+      GenerationStage::Stage4 => stage_4_schedule_spawning_tiles(&mut commands, &snapshot, &mut component),
+      GenerationStage::Stage5 => stage_5_schedule_generating_object_data(
+        &snapshot,
+        &metadata,
+        &results.object_edges,
+        &resources,
+        &mut shared.resources,
+        &mut component,
+      ),
+      GenerationStage::Stage6 => stage_6_schedule_spawning_objects(
+        &mut commands,
+        &snapshot,
+        &mut metadata,
+        &resources,
+        &world_modifications,
+        &mut results,
+        &mut component,
+      ),
+      GenerationStage::Stage7 => stage_7_clean_up(
+        &mut commands,
+        &mut prune_world_event,
+        entity,
+        &mut component,
+        &settings,
+        &chunk_components,
+      ),
+      GenerationStage::Failed => clean_up_failed_component(&mut commands, entity, &mut component),
     }
-    trace!(
-      "World generation component {} reached stage [{:?}] which took {} ms",
-      component.cg,
-      component.stage,
-      shared::get_time() - start_time
+    span.finish(
+      Level::TRACE,
+      format_args!(
+        "World generation component {} reached stage [{:?}]",
+        component.cg, component.stage
+      ),
     );
+    if component.stage != previous_stage
+      || previous_stage == GenerationStage::Stage7
+      || previous_stage == GenerationStage::Failed
+    {
+      let now = shared::get_time();
+      let stage_duration = now - component.stage_started_at;
+      component.stage_durations.push((previous_stage, stage_duration));
+      component.stage_started_at = now;
+      if is_stepping_through {
+        stepper.complete_step();
+        debug!(
+          "Stepped world generation component {} from [{:?}] to [{:?}] after {} ms",
+          component.cg, previous_stage, component.stage, stage_duration
+        );
+      }
+    }
   }
 }
 
+/// Schedules the generation of the given chunks, which don't exist yet, once the metadata for them is available. Uses
+/// the settings snapshot of the component. Spawns a cheap placeholder for each of these chunks so that no holes are visible while the chunks are being generated. Once
+/// the metadata window is centred on the chunk of the component, any chunk that still lacks metadata lies outside of
+/// the window and is skipped with a warning rather than awaited forever.
 fn stage_1_schedule_chunk_generation(
-  settings: &Settings,
-  metadata: &Metadata,
-  existing_chunks: &Res<ChunkComponentIndex>,
+  commands: &mut Commands,
+  metadata: &ResMut<Metadata>,
+  world_modifications: &Res<WorldModifications>,
+  shared: &mut SharedResources,
+  spawn_points: Vec<Point<World>>,
+  world_entity: Entity,
   component: &mut Mut<WorldGenerationComponent>,
 ) {
+  let (spawn_points, missing_metadata): (Vec<Point<World>>, Vec<Point<World>>) = spawn_points
+    .into_iter()
+    .partition(|w| metadata.has_metadata_for(&Point::new_chunk_grid_from_world(*w)));
   if !component.stage_0_metadata {
-    if metadata.index.contains(&component.cg) {
+    if missing_metadata.is_empty() || metadata.current_chunk_cg == component.cg {
       component.stage_0_metadata = true;
     } else {
-      debug!("Awaiting metadata for {:?}", component.cg);
+      debug!(
+        "Awaiting metadata for {:?} to generate {} chunk(s)",
+        component.cg,
+        missing_metadata.len()
+      );
     }
   }
   if component.stage_0_metadata {
-    let settings = settings.clone();
-    let metadata = metadata.clone();
-    let spawn_points = calculate_chunk_spawn_points(&existing_chunks, &settings, &component.w);
+    if !missing_metadata.is_empty() {
+      warn!(
+        "Skipped generating {} chunk(s) around {} because they lie outside of the metadata window with a radius of [{}] \
+        around {}, increase the metadata radius to generate them: {:?}",
+        missing_metadata.len(),
+        component.cg,
+        component.settings.general.metadata_radius,
+        metadata.current_chunk_cg,
+        missing_metadata
+          .iter()
+          .map(|w| Point::new_chunk_grid_from_world(*w))
+          .collect::<Vec<Point<ChunkGrid>>>()
+      );
+    }
+    if component.stage_1_placeholders.is_empty() {
+      commands.entity(world_entity).with_children(|parent| {
+        for w in spawn_points.iter() {
+          if let Some(placeholder) = chunk_placeholder(metadata, w) {
+            component.stage_1_placeholders.push(parent.spawn(placeholder).id());
+          }
+        }
+      });
+    }
+    let settings = component.settings;
+    let metadata = shared.metadata.get(metadata);
+    let world_modifications = shared.world_modifications.get(world_modifications);
     let task_pool = AsyncComputeTaskPool::get();
-    let task = task_pool.spawn(async move { world::generate_chunks(spawn_points, metadata, &settings) });
+    let task = task_pool.spawn(async move {
+      shared::catch_panic(|| world::generate_chunks(spawn_points, &metadata, &world_modifications, &settings))
+    });
     component.stage_1_gen_task = Some(task);
     component.stage = GenerationStage::Stage2;
   }
 }
 
+/// Returns a solid sprite covering the chunk at the given world coordinates, coloured based on the climate of the chunk.
+/// Returns `None` for chunks without land, since the clear colour already looks like water, and in the isometric
+/// projection, since a single sprite can't be distorted into the shape of a chunk.
+fn chunk_placeholder(metadata: &Metadata, w: &Point<World>) -> Option<(Name, Sprite, Transform)> {
+  if projection() != WorldProjection::TopDown {
+    return None;
+  }
+  let cg = Point::new_chunk_grid_from_world(*w);
+  let bm = metadata.biome.get(&cg).filter(|bm| bm.has_land())?;
+  let color = match bm.climate {
+    Climate::Dry => YELLOW,
+    Climate::Moderate => GREEN,
+    Climate::Humid => DARK_GREEN,
+  };
+
+  Some((
+    Name::new(format!("Chunk Placeholder {}", cg)),
+    Sprite {
+      color,
+      custom_size: Some(Vec2::new(chunk_width() as f32, chunk_height() as f32)),
+      anchor: Anchor::TopLeft,
+      ..Default::default()
+    },
+    Transform::from_xyz(w.x as f32, w.y as f32, CHUNK_PLACEHOLDER_Z),
+  ))
+}
+
+/// Returns the world coordinates of all chunks that don't exist yet and are either within the generation radius around
+/// the new current chunk or visible through the camera. The chunks within the generation radius come first, followed
+/// by the remaining visible chunks ordered by their distance to the new current chunk.
 fn calculate_chunk_spawn_points(
-  existing_chunks: &Res<ChunkComponentIndex>,
+  existing_chunks: &ChunkComponentIndex,
+  visible_chunks: &VisibleChunks,
   settings: &Settings,
   new_parent_chunk_w: &Point<World>,
 ) -> Vec<Point<World>> {
   let mut spawn_points = Vec::new();
-  get_direction_points(&new_parent_chunk_w)
+  get_chunk_points_within_radius(new_parent_chunk_w, settings.general.generation_radius)
     .iter()
-    .for_each(|(direction, chunk_w)| {
+    .for_each(|(ring, chunk_w)| {
       if let Some(_) = existing_chunks.get(&chunk_w) {
-        trace!("✅  [{:?}] chunk at {:?} already exists", direction, chunk_w);
+        trace!("✅  Chunk at {:?} in ring {} already exists", chunk_w, ring);
       } else {
         if !settings.general.generate_neighbour_chunks && chunk_w != new_parent_chunk_w {
           trace!(
-            "❎  [{:?}] chunk at {:?} skipped because generating neighbours is disabled",
-            direction,
-            chunk_w
+            "❎  Chunk at {:?} in ring {} skipped because generating neighbours is disabled",
+            chunk_w,
+            ring
           );
           return;
         }
-        trace!("🚫 [{:?}] chunk at {:?} needs to be generated", direction, chunk_w);
+        trace!("🚫 Chunk at {:?} in ring {} needs to be generated", chunk_w, ring);
         spawn_points.push(chunk_w.clone());
       }
     });
+  if settings.general.generate_neighbour_chunks {
+    let mut visible_spawn_points = visible_chunks
+      .iter()
+      .filter(|w| existing_chunks.get(w).is_none() && !spawn_points.contains(w))
+      .copied()
+      .collect::<Vec<Point<World>>>();
+    visible_spawn_points.sort_by_key(|w| {
+      let (dx, dy) = (w.x - new_parent_chunk_w.x, w.y - new_parent_chunk_w.y);
+      (dx * dx + dy * dy, *w)
+    });
+    trace!(
+      "🚫 {} visible chunk(s) outside the generation radius need to be generated",
+      visible_spawn_points.len()
+    );
+    spawn_points.extend(visible_spawn_points);
+  }
 
   spawn_points
 }
 
-fn stage_2_await_chunk_generation(component: &mut Mut<WorldGenerationComponent>, existing_chunks: &ChunkComponentIndex) {
+/// Returns the world coordinates of all chunks within the given radius (in chunks) around the chunk at `center_w`,
+/// together with the ring each chunk is in. The result is ordered by priority, i.e. by the distance to the center
+/// chunk, so that the closest chunks are generated first.
+fn get_chunk_points_within_radius(center_w: &Point<World>, radius: i32) -> Vec<(i32, Point<World>)> {
+  let radius = radius.max(0);
+  let mut points = Vec::new();
+  for y in (-radius..=radius).rev() {
+    for x in -radius..=radius {
+      let ring = x.abs().max(y.abs());
+      points.push((
+        ring,
+        x * x + y * y,
+        Point::new_world(center_w.x + x * chunk_width(), center_w.y + y * chunk_height()),
+      ));
+    }
+  }
+  points.sort_by_key(|(ring, distance, _)| (*ring, *distance));
+
+  points.into_iter().map(|(ring, _, w)| (ring, w)).collect()
+}
+
+fn stage_2_await_chunk_generation(
+  metadata: &mut ResMut<Metadata>,
+  component: &mut Mut<WorldGenerationComponent>,
+  existing_chunks: &ChunkComponentIndex,
+) {
   if let Some(task) = component.stage_1_gen_task.as_mut() {
-    if task.is_finished() {
-      if let Some(mut chunks) = block_on(poll_once(task)) {
+    if !task.is_finished() {
+      return;
+    }
+    match block_on(poll_once(task)) {
+      Some(Ok(mut chunks)) => {
         chunks.retain_mut(|chunk| existing_chunks.get(&chunk.coords.world).is_none());
+        for chunk in chunks.iter() {
+          metadata
+            .terrain_edges
+            .insert(chunk.coords.chunk_grid, chunk.terrain_edges.clone());
+        }
         component.stage_2_chunks = chunks;
+      }
+      Some(Err(error)) => {
         component.stage_1_gen_task = None;
-        component.stage = GenerationStage::Stage3;
+        if record_task_failure(component, error) {
+          component.stage = GenerationStage::Stage1;
+        }
+        return;
       }
+      None => return,
     }
   }
-  if component.stage_1_gen_task.is_none() {
-    component.stage = GenerationStage::Stage3;
-  }
+  component.stage_1_gen_task = None;
+  component.stage = GenerationStage::Stage3;
 }
 
 fn stage_3_spawn_chunks_and_empty_tiles(
@@ -259,8 +807,10 @@ fn stage_3_spawn_chunks_and_empty_tiles(
   if !component.stage_2_chunks.is_empty() {
     let chunk = component.stage_2_chunks.remove(0);
     if existing_chunks.get(&chunk.coords.world).is_none() {
+      let settings = component.settings;
       commands.entity(world_entity).with_children(|parent| {
-        let tile_data = world::spawn_chunk(parent, &chunk);
+        let tile_data = world::spawn_chunk(parent, &chunk, &settings);
+        component.stage_3_spawned_chunks.push(chunk.coords.world);
         component.stage_3_spawn_data.push((chunk, tile_data));
       });
     }
@@ -272,7 +822,7 @@ fn stage_3_spawn_chunks_and_empty_tiles(
 
 fn stage_4_schedule_spawning_tiles(
   mut commands: &mut Commands,
-  settings: &Res<Settings>,
+  settings: &Settings,
   component: &mut Mut<WorldGenerationComponent>,
 ) {
   if !component.stage_3_spawn_data.is_empty() {
@@ -285,17 +835,32 @@ fn stage_4_schedule_spawning_tiles(
   }
 }
 
+/// Schedules the object generation for the next chunk of the component. The object grid edges of neighbouring chunks
+/// that currently exist are passed to the task, so that objects line up across chunk boundaries. Since the edges of
+/// the chunks of this component are only stored in stage 6, chunks generated together are not constrained by each
+/// other.
 fn stage_5_schedule_generating_object_data(
   settings: &Settings,
-  resources: &GenerationResourcesCollection,
+  metadata: &Metadata,
+  object_edges: &ObjectEdges,
+  resources: &Res<GenerationResourcesCollection>,
+  shared_resources: &mut SharedResource<GenerationResourcesCollection>,
   component: &mut Mut<WorldGenerationComponent>,
 ) {
   if !component.stage_4_spawn_data.is_empty() {
     let spawn_data = component.stage_4_spawn_data.remove(0);
-    let resources = resources.clone();
+    let neighbour_edges = object_edges.get_for(&spawn_data.0.coords.chunk_grid);
+    let is_rocky = metadata
+      .biome
+      .get(&spawn_data.0.coords.chunk_grid)
+      .is_some_and(|biome| biome.is_rocky);
+    let resources = shared_resources.get(resources);
     let settings = settings.clone();
     let task_pool = AsyncComputeTaskPool::get();
-    let task = task_pool.spawn(async move { object::generate_object_data(&resources, &settings, spawn_data) });
+    let task = task_pool.spawn(async move {
+      shared::catch_panic(|| object::generate_object_data(&resources, &settings, &spawn_data, &neighbour_edges, is_rocky))
+        .map_err(|error| (spawn_data, error))
+    });
     component.stage_5_object_data.push(task);
   }
   if component.stage_4_spawn_data.is_empty() {
@@ -306,37 +871,151 @@ fn stage_5_schedule_generating_object_data(
 fn stage_6_schedule_spawning_objects(
   mut commands: &mut Commands,
   settings: &Settings,
+  metadata: &mut ResMut<Metadata>,
+  resources: &GenerationResourcesCollection,
+  world_modifications: &WorldModifications,
+  results: &mut ObjectGenerationResults,
   component: &mut Mut<WorldGenerationComponent>,
 ) {
   if !component.stage_5_object_data.is_empty() {
     let cg = component.cg;
+    let mut failed_tasks = Vec::new();
     component.stage_5_object_data.retain_mut(|task| {
       if task.is_finished() {
-        let object_data = block_on(poll_once(task)).expect("Failed to get object data");
-        let mut rng = StdRng::seed_from_u64(shared::calculate_seed(cg, settings.world.noise_seed));
-        object::schedule_spawning_objects(&mut commands, &settings, &mut rng, object_data);
+        let result = match block_on(poll_once(task)).expect("Failed to get object data") {
+          Ok(result) => result,
+          Err(failure) => {
+            failed_tasks.push(failure);
+            return false;
+          }
+        };
+        let mut object_data = result.object_data;
+        results.wfc_statistics.record(result.failures);
+        if let Some(edges) = result.edges {
+          results.object_edges.insert(edges);
+        }
+        match result.rare_feature {
+          Some(rare_feature) => metadata.rare_features.insert(result.cg, rare_feature),
+          None => metadata.rare_features.remove(&result.cg),
+        };
+        let mut chunk_nav_data = result.nav_data;
+        chunk_nav_data.apply(&result.cg, world_modifications, &resources.objects);
+        results.nav_data.insert(result.cg, chunk_nav_data);
+        world_modifications.apply(&result.cg, &mut object_data, &result.tile_data, &resources.objects);
+        let object_seed = settings.object.object_seed(settings.world.get_object_seed());
+        let mut rng = StdRng::seed_from_u64(shared::calculate_seed(cg, object_seed));
+        object::schedule_spawning_objects(&mut commands, &settings, resources, &mut rng, object_data);
         false
       } else {
         true
       }
     });
+    for (spawn_data, error) in failed_tasks {
+      if record_task_failure(component, error) {
+        component.stage_4_spawn_data.push(spawn_data);
+      }
+    }
+    if component.stage == GenerationStage::Failed {
+      return;
+    }
   }
   if component.stage_5_object_data.is_empty() {
-    component.stage = GenerationStage::Stage7;
+    component.stage = if component.stage_4_spawn_data.is_empty() {
+      GenerationStage::Stage7
+    } else {
+      GenerationStage::Stage5
+    };
+  }
+}
+
+/// Records the failure of an async task of the given component. Returns `true` if the task should be retried and moves
+/// the component to `GenerationStage::Failed` otherwise.
+fn record_task_failure(component: &mut Mut<WorldGenerationComponent>, error: String) -> bool {
+  component.retry_count += 1;
+  if component.retry_count > MAX_GENERATION_TASK_RETRIES {
+    component.failure = Some(error);
+    component.stage = GenerationStage::Failed;
+    return false;
+  }
+  warn!(
+    "Async task of world generation component {} failed in stage [{:?}], retrying ({}/{}): {}",
+    component.cg, component.stage, component.retry_count, MAX_GENERATION_TASK_RETRIES, error
+  );
+
+  true
+}
+
+fn despawn_placeholders(commands: &mut Commands, component: &mut Mut<WorldGenerationComponent>) {
+  for placeholder in component.stage_1_placeholders.drain(..) {
+    if let Some(entity_commands) = commands.get_entity(placeholder) {
+      entity_commands.despawn_recursive();
+    }
   }
 }
 
+/// Gives up on a component whose async tasks kept failing. Any chunks that were already spawned by the component remain
+/// in the world.
+fn clean_up_failed_component(commands: &mut Commands, entity: Entity, component: &mut Mut<WorldGenerationComponent>) {
+  despawn_placeholders(commands, component);
+  let elapsed_ms = shared::get_time() - component.created_at;
+  error!(
+    cg = %component.cg,
+    retries = component.retry_count,
+    elapsed_ms,
+    error = component.failure.as_deref().unwrap_or("Unknown error"),
+    "❌ World generation component {} failed after {} retries",
+    component.cg,
+    MAX_GENERATION_TASK_RETRIES
+  );
+  commands.entity(entity).despawn_recursive();
+}
+
+/// Completes the world generation process and despawns the placeholders of the chunks, which have been replaced by
+/// the actual chunks at this point. If the settings have changed in a way that affects the generated world since
+/// the component was created and requeuing outdated chunks is enabled, the chunks spawned by this component are
+/// despawned again and a new component is spawned in its place, which generates them using the current settings.
 fn stage_7_clean_up(
   commands: &mut Commands,
   prune_world_event: &mut EventWriter<PruneWorldEvent>,
   entity: Entity,
   component: &mut Mut<WorldGenerationComponent>,
-  settings: &Res<Settings>,
+  settings: &Settings,
+  chunk_components: &Query<(Entity, &ChunkComponent)>,
 ) {
-  if !component.suppress_pruning_world && settings.general.enable_world_pruning {
+  despawn_placeholders(commands, component);
+  if settings.general.requeue_outdated_chunks
+    && !component.stage_3_spawned_chunks.is_empty()
+    && component.settings.is_generation_affected_by(settings)
+  {
+    for (chunk_entity, chunk_component) in chunk_components.iter() {
+      if component.stage_3_spawned_chunks.contains(&chunk_component.coords.world) {
+        despawn_chunk(commands, chunk_entity);
+      }
+    }
+    info!(
+      "🔁 World generation component {} used outdated settings, requeuing {} chunk(s)",
+      component.cg,
+      component.stage_3_spawned_chunks.len()
+    );
+    // The new component must be spawned before despawning this one to prevent the generation state from going idle
+    commands.spawn((
+      Name::new(format!("Update World Component {}", component.w)),
+      WorldGenerationComponent::new(
+        component.w,
+        component.cg,
+        component.suppress_pruning_world,
+        shared::get_time(),
+        *settings,
+      ),
+    ));
+    commands.entity(entity).despawn_recursive();
+    return;
+  }
+  if !component.suppress_pruning_world && component.settings.general.enable_world_pruning {
     prune_world_event.send(PruneWorldEvent {
       despawn_all_chunks: false,
       update_world_after: false,
+      is_derived: true,
     });
   }
   info!(
@@ -358,87 +1037,534 @@ fn on_remove_update_world_component_trigger(
   }
 }
 
+/// Prunes the world and, if requested by the event, registers a `PendingWorldUpdate` which is sent as an
+/// `UpdateWorldEvent` by `send_pending_world_update_system` once all pruned chunks have been despawned.
 pub fn prune_world_event(
   mut commands: Commands,
   mut prune_world_event: EventReader<PruneWorldEvent>,
-  mut update_world_event: EventWriter<UpdateWorldEvent>,
+  mut pending_world_update: ResMut<PendingWorldUpdate>,
   existing_chunks: Query<(Entity, &ChunkComponent), With<ChunkComponent>>,
   current_chunk: Res<CurrentChunk>,
-  mut delayed_update_world_event: Local<Option<UpdateWorldEvent>>,
+  visible_chunks: Res<VisibleChunks>,
+  settings: Res<Settings>,
 ) {
-  // Allows the `PruneWorldEvent` to trigger an `UpdateWorldEvent` after the world has been pruned. Doing this in the
-  // same frame will lead to race conditions and chunks been despawned just after they were spawned.
-  if let Some(event) = delayed_update_world_event.take() {
-    update_world_event.send(event);
-  }
-
   for event in prune_world_event.read() {
-    prune_world(
+    let pruned_chunks = prune_world(
       &mut commands,
       &existing_chunks,
       &current_chunk,
+      &visible_chunks,
+      &settings,
       event.despawn_all_chunks,
       event.update_world_after,
     );
     if event.update_world_after {
-      *delayed_update_world_event = Some(UpdateWorldEvent {
-        is_forced_update: true,
-        tg: current_chunk.get_tile_grid(),
-        w: current_chunk.get_world(),
-      });
+      pending_world_update.request(pruned_chunks);
     }
   }
 }
 
+/// Sends the `UpdateWorldEvent` requested by a `PruneWorldEvent` as soon as none of the chunks pruned by it exists
+/// anymore. Waits for as many frames as necessary.
+fn send_pending_world_update_system(
+  mut pending_world_update: ResMut<PendingWorldUpdate>,
+  mut update_world_event: EventWriter<UpdateWorldEvent>,
+  existing_chunks: Query<Entity, With<ChunkComponent>>,
+  current_chunk: Res<CurrentChunk>,
+) {
+  if pending_world_update.take_if_pruned(|entity| existing_chunks.contains(entity)) {
+    debug!(
+      "Pruning completed, sending pending world update for {}",
+      current_chunk.get_world()
+    );
+    update_world_event.send(UpdateWorldEvent::forced(current_chunk.get_world()));
+  }
+}
+
 fn prune_world(
   commands: &mut Commands,
   existing_chunks: &Query<(Entity, &ChunkComponent), With<ChunkComponent>>,
   current_chunk: &Res<CurrentChunk>,
+  visible_chunks: &VisibleChunks,
+  settings: &Settings,
   despawn_all_chunks: bool,
   update_world_after: bool,
-) {
-  let start_time = shared::get_time();
-  let chunks_to_despawn = calculate_chunks_to_despawn(existing_chunks, current_chunk, despawn_all_chunks);
-  for chunk_entity in chunks_to_despawn.iter() {
-    if let Some(entity) = commands.get_entity(*chunk_entity) {
-      entity.despawn_recursive();
+) -> Vec<Entity> {
+  let span = shared::TimedSpan::new(info_span!("prune_world"));
+  let chunks_to_despawn = calculate_chunks_to_despawn(
+    existing_chunks,
+    visible_chunks,
+    &current_chunk.get_world(),
+    settings,
+    despawn_all_chunks,
+  );
+  let is_fading_out = settings.general.enable_fade_out && !despawn_all_chunks;
+  for (chunk_entity, _) in chunks_to_despawn.iter() {
+    if is_fading_out {
+      commands.entity(*chunk_entity).insert_if_new(PendingDespawnComponent {
+        timer: Timer::from_seconds(FADE_OUT_DURATION, TimerMode::Once),
+      });
+    } else {
+      despawn_chunk(commands, *chunk_entity);
     }
   }
-  info!(
-    "World pruning (despawn_all_chunks={}, update_world_after={}) took {} ms on [{}]",
-    despawn_all_chunks,
-    update_world_after,
-    shared::get_time() - start_time,
-    shared::thread_name()
+  span.finish(
+    Level::INFO,
+    format_args!(
+      "Completed world pruning (despawn_all_chunks={}, update_world_after={})",
+      despawn_all_chunks, update_world_after
+    ),
   );
+
+  chunks_to_despawn.into_iter().map(|(entity, _)| entity).collect()
 }
 
+/// Returns all chunks that are further away from the current chunk than the despawn distance, except for the chunks that
+/// are visible through the camera, or all chunks if `despawn_all_chunks` is set.
 fn calculate_chunks_to_despawn(
   existing_chunks: &Query<(Entity, &ChunkComponent), With<ChunkComponent>>,
-  current_chunk: &Res<CurrentChunk>,
+  visible_chunks: &VisibleChunks,
+  current_chunk_w: &Point<World>,
+  settings: &Settings,
   despawn_all_chunks: bool,
-) -> Vec<Entity> {
+) -> Vec<(Entity, Point<ChunkGrid>)> {
   let mut chunks_to_despawn = Vec::new();
+  // Extends the default despawn distance by the diagonal of a chunk for every ring beyond the immediate neighbours and
+  // ensures that chunks just outside the visible area (e.g. of a large window) are kept, since the camera can be
+  // anywhere within the current chunk
+  let chunk_len = chunk_width().max(chunk_height()) as f32;
+  let despawn_distance = (chunk_len * DESPAWN_DISTANCE_IN_CHUNKS
+    + (settings.general.generation_radius - 1).max(0) as f32 * chunk_len * 2f32.sqrt())
+  .max(visible_chunks.radius() + chunk_len * 2f32.sqrt());
+  // Only pruning chunks that exceed the despawn distance by a margin prevents chunks at its border from being despawned
+  // and regenerated repeatedly when moving back and forth across a chunk boundary
+  let despawn_distance = despawn_distance + chunk_len * settings.general.prune_margin_in_chunks;
+  let min_lifetime_ms = (settings.general.min_chunk_lifetime_secs * 1000.) as u128;
+  let now = shared::get_time();
   for (entity, chunk_component) in existing_chunks.iter() {
     if despawn_all_chunks {
       trace!(
         "Despawning chunk at {:?} because all chunks have to be despawned",
         chunk_component.coords.chunk_grid
       );
-      chunks_to_despawn.push(entity);
+      chunks_to_despawn.push((entity, chunk_component.coords.chunk_grid));
       continue;
     }
-    let distance = current_chunk.get_world().distance_to(&chunk_component.coords.world);
-    if distance > DESPAWN_DISTANCE {
+    if visible_chunks.contains(&chunk_component.coords.world) {
+      continue;
+    }
+    let lifetime_ms = now.saturating_sub(chunk_component.spawned_at);
+    if lifetime_ms < min_lifetime_ms {
+      trace!(
+        "Keeping chunk at {:?} because it was only spawned {} ms ago",
+        chunk_component.coords.chunk_grid,
+        lifetime_ms
+      );
+      continue;
+    }
+    let distance = current_chunk_w.distance_to(&chunk_component.coords.world);
+    if distance > despawn_distance {
       trace!(
         "Despawning chunk at {:?} because it's {}px away from current chunk at {:?}",
         chunk_component.coords.chunk_grid,
         distance as i32,
-        current_chunk.get_chunk_grid()
+        current_chunk_w
       );
-      chunks_to_despawn.push(entity);
+      chunks_to_despawn.push((entity, chunk_component.coords.chunk_grid));
     }
   }
 
   chunks_to_despawn
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::constants::{CHUNK_HEIGHT, CHUNK_HEIGHT_PLUS_BUFFER, CHUNK_WIDTH, CHUNK_WIDTH_PLUS_BUFFER};
+  use crate::coords::Coords;
+  use crate::generation::lib::{LayeredPlane, TileType};
+  use crate::generation::object::lib::ObjectName;
+  use crate::generation::object::lib::{get_facing_point, ObjectGenerationResult, ObjectGrid, ObjectGridEdges};
+  use crate::generation::resources::{object_resources_from_files, SpritePool, WorldModification};
+  use bevy::prelude::Events;
+  use rand::Rng;
+
+  /// Generates the chunks at the given points, as well as the tile data that `spawn_chunk` would have created for
+  /// them, without spawning any entities.
+  fn generate_spawn_data(cgs: &[Point<ChunkGrid>], metadata: &Metadata, settings: &Settings) -> Vec<(Chunk, Vec<TileData>)> {
+    let spawn_points = cgs.iter().map(|cg| Point::new_world_from_chunk_grid(*cg)).collect();
+    let mut entity_count = 0;
+
+    world::generate_chunks(spawn_points, metadata, &WorldModifications::default(), settings)
+      .into_iter()
+      .map(|chunk| {
+        let tile_data = chunk
+          .layered_plane
+          .flat
+          .data
+          .iter()
+          .flatten()
+          .flatten()
+          .map(|tile| {
+            entity_count += 1;
+            TileData::new(Entity::from_raw(entity_count), Entity::PLACEHOLDER, *tile)
+          })
+          .collect();
+        (chunk, tile_data)
+      })
+      .collect()
+  }
+
+  /// Generates the objects for each of the given chunks in order, constraining each chunk by the object grid edges of
+  /// the chunks generated before it, like the world generation does. Returns the result for each chunk together with
+  /// the neighbour edges it was generated with.
+  fn generate_objects(
+    spawn_data: &[(Chunk, Vec<TileData>)],
+    metadata: &Metadata,
+    resources: &GenerationResourcesCollection,
+    settings: &Settings,
+  ) -> Vec<(ObjectGenerationResult, Vec<(Direction, ObjectGridEdges)>)> {
+    let mut object_edges = ObjectEdges::default();
+    spawn_data
+      .iter()
+      .map(|data| {
+        let cg = data.0.coords.chunk_grid;
+        let neighbour_edges = object_edges.get_for(&cg);
+        let is_rocky = metadata.biome.get(&cg).is_some_and(|biome| biome.is_rocky);
+        let result = object::generate_object_data(resources, settings, data, &neighbour_edges, is_rocky);
+        if let Some(edges) = &result.edges {
+          object_edges.insert(edges.clone());
+        }
+        (result, neighbour_edges)
+      })
+      .collect()
+  }
+
+  fn is_in_bounds(x: i32, y: i32) -> bool {
+    x >= 0 && y >= 0 && x < CHUNK_WIDTH && y < CHUNK_HEIGHT
+  }
+
+  #[test]
+  fn generated_chunks_satisfy_invariants() {
+    let resources = GenerationResourcesCollection {
+      objects: object_resources_from_files(),
+      ..Default::default()
+    };
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut multi_tile_object_count = 0;
+    for _ in 0..5 {
+      let mut settings = Settings::default();
+      settings.world.noise_seed = rng.gen();
+      let cg = Point::new_chunk_grid(rng.gen_range(-50..=50), rng.gen_range(-50..=50));
+      let cgs = [
+        cg,
+        Point::new_chunk_grid(cg.x + 1, cg.y),
+        Point::new_chunk_grid(cg.x, cg.y + 1),
+        Point::new_chunk_grid(cg.x + 1, cg.y + 1),
+      ];
+      let metadata = world::generate_metadata(cg, &settings, None);
+      let spawn_data = generate_spawn_data(&cgs, &metadata, &settings);
+      assert_eq!(spawn_data.len(), cgs.len(), "Failed to generate all chunks around {}", cg);
+      let results = generate_objects(&spawn_data, &metadata, &resources, &settings);
+      for ((chunk, tile_data), (result, neighbour_edges)) in spawn_data.iter().zip(results.iter()) {
+        let cg = chunk.coords.chunk_grid;
+        let seed = settings.world.noise_seed;
+
+        // No tile is left without a tile type
+        for plane in chunk.layered_plane.planes.iter().chain([&chunk.layered_plane.flat]) {
+          for tile in plane.data.iter().flatten().flatten() {
+            assert_ne!(
+              tile.tile_type,
+              TileType::Unknown,
+              "Tile {} in chunk {} with seed {} has an unknown tile type",
+              tile.coords.tile_grid,
+              cg,
+              seed
+            );
+          }
+        }
+
+        // Every object is placed on a tile of its own chunk
+        for object in result.object_data.iter() {
+          let coords = &object.tile_data.flat_tile.coords;
+          assert_eq!(
+            coords.chunk_grid, cg,
+            "Object of chunk {} with seed {} is placed in another chunk",
+            cg, seed
+          );
+          assert!(
+            is_in_bounds(coords.internal_grid.x, coords.internal_grid.y),
+            "Object {:?} in chunk {} with seed {} is out of bounds",
+            coords.internal_grid,
+            cg,
+            seed
+          );
+        }
+
+        // Every multi-tile object occupies its entire footprint
+        for (index, multi_tile_object) in resources.objects.multi_tile_objects.iter().enumerate() {
+          for object in result.object_data.iter().filter(|o| o.name == Some(multi_tile_object.anchor)) {
+            multi_tile_object_count += 1;
+            let anchor_ig = object.tile_data.flat_tile.coords.internal_grid;
+            for (x, y) in (0..multi_tile_object.height)
+              .flat_map(|y| (0..multi_tile_object.width).map(move |x| (x, y)))
+              .filter(|part| *part != (0, 0))
+            {
+              assert_eq!(
+                result
+                  .nav_data
+                  .object(&Point::new_internal_grid(anchor_ig.x + x as i32, anchor_ig.y + y as i32)),
+                Some(ObjectName::MultiTileFiller(index as u8, x, y)),
+                "[{:?}] at {:?} in chunk {} with seed {} is missing part ({}, {})",
+                multi_tile_object.anchor,
+                anchor_ig,
+                cg,
+                seed,
+                x,
+                y
+              );
+            }
+          }
+        }
+
+        // Every cell facing a neighbouring chunk is compatible with the cell on the other side, unless the constraint
+        // could not be satisfied in the first place and was therefore ignored
+        let edges = result.edges.as_ref().expect("Failed to get object grid edges");
+        let is_rocky = metadata.biome.get(&cg).is_some_and(|biome| biome.is_rocky);
+        let mut grid = ObjectGrid::new_initialised(cg, &resources.objects, &settings.object.for_biome(is_rocky), tile_data);
+        for (direction, neighbour) in neighbour_edges.iter() {
+          for reference in neighbour.cells.iter() {
+            let Some((ig, where_is_reference)) = get_facing_point(direction, &reference.ig) else {
+              continue;
+            };
+            let initial_cell = grid.get_cell(&ig).expect("Failed to get cell");
+            match initial_cell.clone_and_reduce(reference, &where_is_reference) {
+              Ok((_, reduced_cell)) => grid.set_cell(reduced_cell),
+              Err(_) => continue,
+            }
+            if let Some(cell) = edges.cells.iter().find(|cell| cell.ig == ig) {
+              assert!(
+                cell.verify(reference, &where_is_reference).is_ok(),
+                "[{:?}] at {:?} in chunk {} with seed {} is incompatible with [{:?}] in chunk {}",
+                cell.possible_states[0].name,
+                ig,
+                cg,
+                seed,
+                reference.possible_states[0].name,
+                neighbour.cg
+              );
+            }
+          }
+        }
+      }
+    }
+    assert!(multi_tile_object_count > 0, "No multi-tile object was generated");
+  }
+
+  #[test]
+  fn placed_objects_survive_regenerating_a_chunk() {
+    let resources = GenerationResourcesCollection {
+      objects: object_resources_from_files(),
+      ..Default::default()
+    };
+    for is_generating_objects in [true, false] {
+      let mut settings = Settings::default();
+      settings.world.noise_seed = 7;
+      settings.object.generate_objects = is_generating_objects;
+      let cg = Point::new_chunk_grid(3, -4);
+      let metadata = world::generate_metadata(cg, &settings, None);
+      let spawn_data = generate_spawn_data(&[cg], &metadata, &settings);
+      let (result, _) = generate_objects(&spawn_data, &metadata, &resources, &settings).remove(0);
+      let ig = result
+        .tile_data
+        .iter()
+        .map(|tile_data| tile_data.flat_tile.coords.internal_grid)
+        .find(|ig| {
+          result
+            .object_data
+            .iter()
+            .all(|object| object.tile_data.flat_tile.coords.internal_grid != *ig)
+            && result.nav_data.object(ig).is_none()
+        })
+        .expect("Failed to find an empty tile");
+      let mut world_modifications = WorldModifications::default();
+      let placed_object = WorldModification::PlacedObject {
+        name: ObjectName::SandStone1,
+        sprite_index: 3,
+      };
+      world_modifications.record(cg, ig, placed_object);
+
+      let spawn_data = generate_spawn_data(&[cg], &metadata, &settings);
+      let (result, _) = generate_objects(&spawn_data, &metadata, &resources, &settings).remove(0);
+      let mut object_data = result.object_data;
+      world_modifications.apply(&cg, &mut object_data, &result.tile_data, &resources.objects);
+      let mut nav_data = result.nav_data;
+      nav_data.apply(&cg, &world_modifications, &resources.objects);
+
+      let placed = object_data
+        .iter()
+        .filter(|object| object.tile_data.flat_tile.coords.internal_grid == ig)
+        .collect::<Vec<_>>();
+      assert_eq!(placed.len(), 1, "Expected exactly one object at {:?} in chunk {}", ig, cg);
+      assert_eq!(placed[0].name, Some(ObjectName::SandStone1));
+      assert_eq!(placed[0].sprite_index, 3);
+      assert_eq!(nav_data.object(&ig), Some(ObjectName::SandStone1));
+    }
+  }
+
+  #[test]
+  fn generated_terrain_does_not_depend_on_generation_order() {
+    let mut settings = Settings::default();
+    settings.world.noise_seed = 42;
+    let (a, b) = (Point::new_chunk_grid(2, -3), Point::new_chunk_grid(3, -3));
+    let metadata = world::generate_metadata(a, &settings, None);
+    let a_then_b = generate_spawn_data(&[a, b], &metadata, &settings);
+    let b_then_a = generate_spawn_data(&[b, a], &metadata, &settings);
+    let alone = generate_spawn_data(&[a], &metadata, &settings)
+      .into_iter()
+      .chain(generate_spawn_data(&[b], &metadata, &settings));
+    for (chunk, _) in alone {
+      let cg = chunk.coords.chunk_grid;
+      for other in [&a_then_b, &b_then_a] {
+        let (other, _) = other
+          .iter()
+          .find(|(other, _)| other.coords.chunk_grid == cg)
+          .expect("Failed to find chunk");
+        assert!(
+          chunk.layered_plane == other.layered_plane,
+          "Tiles of chunk {} differ depending on the generation order",
+          cg
+        );
+      }
+    }
+  }
+
+  /// Spawns a chunk with empty terrain at the given `Point<ChunkGrid>` that is old enough to be pruned.
+  fn spawn_chunk_component(app: &mut App, cg: Point<ChunkGrid>, settings: &Settings) -> Entity {
+    let w = Point::new_world_from_chunk_grid(cg);
+    let draft_tiles = vec![vec![None; CHUNK_HEIGHT_PLUS_BUFFER as usize]; CHUNK_WIDTH_PLUS_BUFFER as usize];
+    let chunk_component = ChunkComponent {
+      coords: Coords::new_for_chunk(w, Point::new_tile_grid_from_world(w)),
+      layered_plane: LayeredPlane::new(draft_tiles, settings),
+      spawned_at: 0,
+    };
+
+    app.world_mut().spawn(chunk_component).id()
+  }
+
+  /// Returns the number of `UpdateWorldEvent`s sent since the last call.
+  fn drain_update_world_events(app: &mut App) -> usize {
+    app.world_mut().resource_mut::<Events<UpdateWorldEvent>>().drain().count()
+  }
+
+  /// Toggling world pruning off and on again while a previous pruning is still fading out its chunks must replace the
+  /// pending world update, so that the update is sent exactly once after all chunks of the second pruning are gone.
+  #[test]
+  fn toggling_pruning_while_an_update_is_pending_sends_a_single_update() {
+    let mut settings = Settings::default();
+    settings.general.enable_fade_out = true;
+    settings.general.min_chunk_lifetime_secs = 0.;
+    let mut app = App::new();
+    app
+      .add_event::<PruneWorldEvent>()
+      .add_event::<UpdateWorldEvent>()
+      .init_resource::<PendingWorldUpdate>()
+      .init_resource::<SpritePool>()
+      .init_resource::<CurrentChunk>()
+      .init_resource::<VisibleChunks>()
+      .insert_resource(settings)
+      .add_systems(Update, (prune_world_event, send_pending_world_update_system).chain());
+    let near_chunk = spawn_chunk_component(&mut app, Point::new_chunk_grid(0, 0), &settings);
+    let far_chunk = spawn_chunk_component(&mut app, Point::new_chunk_grid(50, 50), &settings);
+
+    // The far chunk starts fading out, so the update remains pending
+    app.world_mut().send_event(PruneWorldEvent {
+      despawn_all_chunks: false,
+      update_world_after: true,
+      is_derived: false,
+    });
+    app.update();
+    assert!(app.world().get::<PendingDespawnComponent>(far_chunk).is_some());
+    assert!(app.world().get::<PendingDespawnComponent>(near_chunk).is_none());
+    assert_eq!(drain_update_world_events(&mut app), 0);
+
+    // Toggling pruning despawns all chunks immediately and replaces the pending update
+    app.world_mut().send_event(PruneWorldEvent {
+      despawn_all_chunks: true,
+      update_world_after: true,
+      is_derived: false,
+    });
+    app.update();
+    let remaining_chunks = app
+      .world_mut()
+      .query_filtered::<Entity, With<ChunkComponent>>()
+      .iter(app.world())
+      .count();
+    assert_eq!(remaining_chunks, 0);
+    assert_eq!(drain_update_world_events(&mut app), 1);
+
+    app.update();
+    assert_eq!(drain_update_world_events(&mut app), 0);
+  }
+
+  /// The chunks, as `(noise_seed, x, y)`, for which the generated objects are compared against a committed snapshot.
+  const SNAPSHOT_CHUNKS: [(u32, i32, i32); 3] = [(1, -5, 5), (7, 5, -15), (42, -15, -15)];
+
+  /// Returns the collapsed object grid of the given chunk as text, with one row of the grid per line and one object
+  /// name, or `-` if the cell is empty, per cell.
+  fn snapshot(result: &ObjectGenerationResult) -> String {
+    let mut grid = vec![vec!["-".to_string(); CHUNK_WIDTH as usize]; CHUNK_HEIGHT as usize];
+    for object in result.object_data.iter() {
+      let ig = object.tile_data.flat_tile.coords.internal_grid;
+      if let Some(name) = object.name {
+        grid[ig.y as usize][ig.x as usize] = format!("{:?}", name);
+      }
+    }
+
+    grid.iter().map(|row| row.join(" ") + "\n").collect()
+  }
+
+  /// Compares the objects generated for each of the `SNAPSHOT_CHUNKS` with the snapshots in `tests/fixtures/object-grids`.
+  /// Run with `BLESS_SNAPSHOTS=1` to write the current output to the fixtures instead, e.g. after an intentional change
+  /// to the rule sets or the generation process.
+  #[test]
+  fn generated_objects_match_snapshots() {
+    let resources = GenerationResourcesCollection {
+      objects: object_resources_from_files(),
+      ..Default::default()
+    };
+    let is_blessing = std::env::var("BLESS_SNAPSHOTS").is_ok_and(|value| value == "1");
+    for (seed, x, y) in SNAPSHOT_CHUNKS {
+      let mut settings = Settings::default();
+      settings.world.noise_seed = seed;
+      let cg = Point::new_chunk_grid(x, y);
+      let metadata = world::generate_metadata(cg, &settings, None);
+      let spawn_data = generate_spawn_data(&[cg], &metadata, &settings);
+      let (result, _) = generate_objects(&spawn_data, &metadata, &resources, &settings).remove(0);
+      let actual = snapshot(&result);
+      let path = format!(
+        "{}/tests/fixtures/object-grids/seed-{}-cg-{}-{}.txt",
+        env!("CARGO_MANIFEST_DIR"),
+        seed,
+        x,
+        y
+      );
+      if is_blessing {
+        std::fs::write(&path, &actual).unwrap_or_else(|e| panic!("Failed to write [{}]: {}", path, e));
+        continue;
+      }
+      let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Failed to read [{}], run with BLESS_SNAPSHOTS=1 to create it: {}", path, e));
+      assert!(
+        actual == expected,
+        "Objects generated for chunk {} with seed {} differ from [{}], run with BLESS_SNAPSHOTS=1 if this is \
+        intentional\n--- Expected ---\n{}--- Actual ---\n{}",
+        cg,
+        seed,
+        path,
+        expected,
+        actual
+      );
+    }
+  }
+}
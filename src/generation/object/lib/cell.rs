@@ -8,7 +8,30 @@ use bevy::prelude::Reflect;
 use rand::prelude::StdRng;
 use rand::Rng;
 
-pub struct PropagationFailure {}
+/// Describes a contradiction encountered during the propagation phase of the wave function collapse algorithm i.e.
+/// the situation in which a `Cell` has no possible states left that are permitted by the reference `Cell` at the given
+/// `Connection`. The terrain and tile type are those of the reference `Cell`, whose states were attempted. Used to
+/// aggregate failure statistics which helps to identify problematic rules.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PropagationFailure {
+  pub terrain: TerrainType,
+  pub tile_type: TileType,
+  pub connection: Connection,
+  pub attempted: Vec<ObjectName>,
+  pub neighbours: Vec<ObjectName>,
+}
+
+impl PropagationFailure {
+  fn new(cell: &Cell, reference_cell: &Cell, where_is_reference: &Connection) -> Self {
+    Self {
+      terrain: reference_cell.terrain,
+      tile_type: reference_cell.tile_type,
+      connection: *where_is_reference,
+      attempted: reference_cell.possible_states.iter().map(|s| s.name).collect(),
+      neighbours: cell.possible_states.iter().map(|s| s.name).collect(),
+    }
+  }
+}
 
 /// A `Cell` is a "placeholder" for an object. It is used in the `ObjectGrid`. This struct is used to represent a cell in
 /// the grid that can be collapsed to a single state. Once all `Cell`s in an `ObjectGrid` have been collapsed, they
@@ -22,6 +45,7 @@ pub struct Cell {
   is_being_monitored: bool,
   pub terrain: TerrainType,
   pub tile_type: TileType,
+  pub is_beach: bool,
   pub entropy: usize,
   pub possible_states: Vec<TerrainState>,
   pub index: i32,
@@ -36,6 +60,7 @@ impl Cell {
       is_being_monitored: false,
       terrain: TerrainType::Any,
       tile_type: TileType::Unknown,
+      is_beach: false,
       entropy: usize::MAX,
       possible_states: vec![],
       index: -1,
@@ -96,7 +121,7 @@ impl Cell {
     );
 
     match clone.possible_states.len() {
-      0 => Err(PropagationFailure {}),
+      0 => Err(PropagationFailure::new(self, reference_cell, where_is_reference)),
       _ => Ok((self.possible_states.len() != clone.possible_states.len(), clone)),
     }
   }
@@ -151,6 +176,20 @@ impl Cell {
     self.possible_states = vec![state.clone()];
   }
 
+  /// Collapses this cell to the state with the given name. Returns `false` and leaves the cell unchanged if the state is
+  /// not one of its possible states.
+  pub fn collapse_to(&mut self, name: ObjectName) -> bool {
+    let Some(state) = self.possible_states.iter().find(|state| state.name == name).cloned() else {
+      return false;
+    };
+    self.index = state.index;
+    self.is_collapsed = true;
+    self.entropy = 0;
+    self.possible_states = vec![state];
+
+    true
+  }
+
   pub fn verify(&self, reference_cell: &Cell, where_is_reference: &Connection) -> Result<(), PropagationFailure> {
     let where_is_self_for_reference = where_is_reference.opposite();
     let permitted_state_names = get_permitted_new_states(&reference_cell, &where_is_self_for_reference);
@@ -165,7 +204,7 @@ impl Cell {
         &mut self.clone(),
         &permitted_state_names,
       );
-      Err(PropagationFailure {})
+      Err(PropagationFailure::new(self, reference_cell, where_is_reference))
     } else {
       Ok(())
     }
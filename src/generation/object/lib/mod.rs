@@ -1,13 +1,28 @@
 mod cell;
 mod connection_type;
+mod multi_tile_object;
+mod object_category;
 mod object_data;
+mod object_definition;
 mod object_grid;
+mod object_light;
 mod object_name;
+mod object_placement;
+mod rare_feature;
 mod wfc_status;
 
-pub use cell::Cell;
+pub use cell::{Cell, PropagationFailure};
 pub use connection_type::Connection;
-pub use object_data::ObjectData;
-pub use object_grid::ObjectGrid;
+pub use multi_tile_object::{expand_rules, is_within_grid, MultiTileObject};
+pub use object_category::ObjectCategory;
+pub use object_data::{ObjectData, ObjectGenerationResult};
+pub use object_definition::{ObjectAtlas, ObjectDefinition, ObjectGroup, ObjectRegistry, ObjectShadow};
+pub use object_grid::{resolve_rules, ObjectGrid, ObjectGridEdges};
+pub use object_light::ObjectLight;
 pub use object_name::ObjectName;
+pub use object_placement::ObjectPlacement;
+pub use rare_feature::{RareFeature, RareFeatureTemplate};
 pub use wfc_status::IterationResult;
+
+#[cfg(test)]
+pub use object_grid::get_facing_point;
@@ -0,0 +1,319 @@
+use crate::constants::{CHUNK_HEIGHT, CHUNK_WIDTH};
+use crate::coords::point::InternalGrid;
+use crate::coords::Point;
+use crate::generation::lib::{TerrainType, TileType};
+use crate::generation::object::lib::connection_type::get_connection_points;
+use crate::generation::object::lib::{Connection, ObjectName};
+use crate::generation::resources::TerrainState;
+use bevy::log::*;
+use bevy::reflect::Reflect;
+use bevy::utils::HashMap;
+
+const CONNECTIONS: [Connection; 4] = [Connection::Top, Connection::Right, Connection::Bottom, Connection::Left];
+
+/// Describes an object that spans `width` x `height` cells. Only the `anchor` (the cell with the lowest `x` and `y`
+/// within the footprint) is defined in the rule set files and spawned as a sprite. All other cells are occupied by
+/// generated `ObjectName::MultiTileFiller`s whose rules are derived from the anchor's rules when loading the rule sets,
+/// which guarantees that the whole footprint is generated, or nothing at all.
+#[derive(serde::Deserialize, Debug, Clone, Reflect)]
+pub struct MultiTileObject {
+  pub anchor: ObjectName,
+  pub width: u8,
+  pub height: u8,
+}
+
+impl MultiTileObject {
+  /// Adds a `TerrainState` for each filler to the given states and rewrites the rules of the anchor as well as every
+  /// state that permits the anchor as a neighbour accordingly. States that permit the anchor are rewritten even if the
+  /// states don't contain the anchor itself, since the footprint may extend into a neighbouring cell of another
+  /// terrain.
+  pub fn expand_terrain_rules(&self, index: u8, states: &mut Vec<TerrainState>) {
+    let anchor = states.iter().find(|state| state.name == self.anchor).cloned();
+
+    // Any state that permits the anchor on one side must instead permit every part on the same side of the footprint,
+    // which excludes the anchor itself if that side of it is inside the footprint, so that the rules stay symmetric
+    for state in states.iter_mut() {
+      for (connection, names) in state.permitted_neighbours.iter_mut() {
+        if names.contains(&self.anchor) {
+          names.retain(|name| *name != self.anchor);
+          let parts = self
+            .parts()
+            .into_iter()
+            .filter(|(x, y)| self.get_neighbour_part(*x, *y, &connection.opposite()).is_none())
+            .map(|(x, y)| self.part_name(index, x, y));
+          names.extend(parts);
+        }
+      }
+    }
+
+    // Each part only permits its adjacent parts inside the footprint and whatever the anchor permits outside of it
+    let Some(anchor) = anchor else {
+      return;
+    };
+    for (x, y) in self.parts() {
+      let permitted_neighbours = CONNECTIONS
+        .iter()
+        .map(|connection| match self.get_neighbour_part(x, y, connection) {
+          Some((nx, ny)) => (*connection, vec![self.part_name(index, nx, ny)]),
+          None => (*connection, get_permitted_neighbours(&anchor, connection)),
+        })
+        .collect();
+      let footprint = self
+        .parts()
+        .into_iter()
+        .map(|(px, py)| Point::new_internal_grid(px as i32 - x as i32, py as i32 - y as i32))
+        .collect();
+      if (x, y) == (0, 0) {
+        if let Some(anchor) = states.iter_mut().find(|state| state.name == self.anchor) {
+          anchor.permitted_neighbours = permitted_neighbours;
+          anchor.footprint = footprint;
+        }
+      } else {
+        states.push(TerrainState {
+          name: self.part_name(index, x, y),
+          index: anchor.index,
+          atlas: anchor.atlas,
+          weight: anchor.weight,
+          permitted_neighbours,
+          is_beach_only: anchor.is_beach_only,
+          footprint,
+        });
+      }
+    }
+  }
+
+  /// Permits the fillers on every tile type on which the anchor is permitted.
+  pub fn expand_tile_type_rules(&self, index: u8, tile_type_rules: &mut HashMap<TileType, Vec<ObjectName>>) {
+    for names in tile_type_rules.values_mut() {
+      if names.contains(&self.anchor) {
+        let fillers = self
+          .parts()
+          .into_iter()
+          .filter(|part| *part != (0, 0))
+          .map(|(x, y)| self.part_name(index, x, y));
+        names.extend(fillers);
+      }
+    }
+  }
+
+  fn parts(&self) -> Vec<(u8, u8)> {
+    (0..self.height).flat_map(|y| (0..self.width).map(move |x| (x, y))).collect()
+  }
+
+  fn part_name(&self, index: u8, x: u8, y: u8) -> ObjectName {
+    if (x, y) == (0, 0) {
+      self.anchor
+    } else {
+      ObjectName::MultiTileFiller(index, x, y)
+    }
+  }
+
+  /// Returns the part of the footprint that the permitted neighbours of the part at (`x`, `y`) for the given
+  /// `Connection` apply to, if any. Note that the propagation applies them to the cell at the connection point of the
+  /// opposite `Connection`, see `Cell::clone_and_reduce`.
+  fn get_neighbour_part(&self, x: u8, y: u8, connection: &Connection) -> Option<(u8, u8)> {
+    get_connection_points(&Point::new_internal_grid(x as i32, y as i32))
+      .into_iter()
+      .find(|(c, _)| *c == connection.opposite())
+      .map(|(_, point)| point)
+      .filter(|point| point.x >= 0 && point.y >= 0 && point.x < self.width as i32 && point.y < self.height as i32)
+      .map(|point| (point.x as u8, point.y as u8))
+  }
+}
+
+fn get_permitted_neighbours(state: &TerrainState, connection: &Connection) -> Vec<ObjectName> {
+  state
+    .permitted_neighbours
+    .iter()
+    .filter(|(c, _)| c == connection)
+    .flat_map(|(_, names)| names.iter().cloned())
+    .collect()
+}
+
+/// Returns `true` if the entire footprint of the given state fits into the object grid when placed at `ig`. States
+/// that are not part of a multi-tile object always fit.
+pub fn is_within_grid(state: &TerrainState, ig: &Point<InternalGrid>) -> bool {
+  state.footprint.iter().all(|offset| {
+    let point = Point::new_internal_grid(ig.x + offset.x, ig.y + offset.y);
+    point.x >= 0 && point.y >= 0 && point.x < CHUNK_WIDTH && point.y < CHUNK_HEIGHT
+  })
+}
+
+/// Applies all multi-tile object definitions to the loaded rule sets.
+pub fn expand_rules(
+  multi_tile_objects: &[MultiTileObject],
+  terrain_rules: &mut HashMap<TerrainType, Vec<TerrainState>>,
+  tile_type_rules: &mut HashMap<TileType, Vec<ObjectName>>,
+) {
+  for (index, object) in multi_tile_objects.iter().enumerate() {
+    let index = index as u8;
+    for (terrain, states) in terrain_rules.iter_mut() {
+      let count = states.len();
+      object.expand_terrain_rules(index, states);
+      if states.len() != count {
+        debug!(
+          "Expanded [{}] rule set by {} filler state(s) for [{:?}] multi-tile object",
+          terrain,
+          states.len() - count,
+          object.anchor
+        );
+      }
+    }
+    object.expand_tile_type_rules(index, tile_type_rules);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::generation::object::lib::ObjectAtlas;
+
+  const ANCHOR: ObjectName = ObjectName::ForestTree1;
+  const OTHER: ObjectName = ObjectName::ForestBush1;
+
+  fn state(name: ObjectName, permitted: Vec<ObjectName>) -> TerrainState {
+    TerrainState {
+      name,
+      index: 1,
+      atlas: ObjectAtlas::default(),
+      weight: 1,
+      permitted_neighbours: CONNECTIONS.iter().map(|c| (*c, permitted.clone())).collect(),
+      is_beach_only: false,
+      footprint: vec![],
+    }
+  }
+
+  /// Expands the rules of a 2x2 multi-tile object in a rule set that contains only the anchor and one other object,
+  /// which both permit each other on every side.
+  fn expand() -> (Vec<TerrainState>, HashMap<TileType, Vec<ObjectName>>) {
+    let mut terrain_rules = HashMap::from([(
+      TerrainType::Land3,
+      vec![state(ANCHOR, vec![OTHER]), state(OTHER, vec![ANCHOR, OTHER])],
+    )]);
+    let mut tile_type_rules = HashMap::from([(TileType::Fill, vec![ANCHOR, OTHER]), (TileType::TopFill, vec![OTHER])]);
+    let object = MultiTileObject {
+      anchor: ANCHOR,
+      width: 2,
+      height: 2,
+    };
+    expand_rules(&[object], &mut terrain_rules, &mut tile_type_rules);
+
+    (terrain_rules.remove(&TerrainType::Land3).unwrap(), tile_type_rules)
+  }
+
+  fn get(states: &[TerrainState], name: ObjectName) -> &TerrainState {
+    states
+      .iter()
+      .find(|state| state.name == name)
+      .unwrap_or_else(|| panic!("Failed to find state [{:?}]", name))
+  }
+
+  fn part_name(x: i32, y: i32) -> ObjectName {
+    match (x, y) {
+      (0, 0) => ANCHOR,
+      (x, y) => ObjectName::MultiTileFiller(0, x as u8, y as u8),
+    }
+  }
+
+  fn is_part(point: &Point<InternalGrid>) -> bool {
+    (0..2).contains(&point.x) && (0..2).contains(&point.y)
+  }
+
+  #[test]
+  fn expand_rules_adds_a_state_for_each_filler() {
+    let (states, _) = expand();
+    assert_eq!(states.len(), 5);
+    for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+      let part = get(&states, part_name(x, y));
+      assert_eq!(part.footprint.len(), 4, "[{:?}] has an incomplete footprint", part.name);
+      assert!(part.footprint.contains(&Point::new_internal_grid(-x, -y)));
+      assert!(part.footprint.contains(&Point::new_internal_grid(1 - x, 1 - y)));
+    }
+    assert!(get(&states, OTHER).footprint.is_empty());
+  }
+
+  #[test]
+  fn expand_rules_links_parts_inside_and_keeps_anchor_rules_outside_of_footprint() {
+    let (states, _) = expand();
+    for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+      let part = get(&states, part_name(x, y));
+      for (connection, point) in get_connection_points(&Point::new_internal_grid(x, y)) {
+        // The cell at the connection point is constrained by the rules for the opposite connection
+        let expected = if is_part(&point) {
+          vec![part_name(point.x, point.y)]
+        } else {
+          vec![OTHER]
+        };
+        assert_eq!(
+          get_permitted_neighbours(part, &connection.opposite()),
+          expected,
+          "[{:?}] permits the wrong neighbours on its [{:?}] side",
+          part.name,
+          connection
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn expand_rules_permits_parts_only_on_the_outer_sides_of_the_footprint() {
+    let (states, tile_type_rules) = expand();
+    let other = get(&states, OTHER);
+    for connection in CONNECTIONS {
+      let permitted = get_permitted_neighbours(other, &connection);
+      for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+        // A part may only be next to another object on the sides where it is on the outside of the footprint
+        let is_outer_side = get_connection_points(&Point::new_internal_grid(x, y))
+          .into_iter()
+          .any(|(c, point)| c == connection && !is_part(&point));
+        assert_eq!(
+          permitted.contains(&part_name(x, y)),
+          is_outer_side,
+          "[{:?}] on the [{:?}] side of [{:?}]",
+          part_name(x, y),
+          connection,
+          OTHER
+        );
+      }
+    }
+    assert_eq!(tile_type_rules[&TileType::Fill].len(), 5);
+    assert_eq!(tile_type_rules[&TileType::TopFill], vec![OTHER]);
+  }
+
+  #[test]
+  fn expand_rules_rewrites_rule_sets_that_permit_but_do_not_contain_the_anchor() {
+    let mut terrain_rules = HashMap::from([(TerrainType::Land4, vec![state(OTHER, vec![ANCHOR, OTHER])])]);
+    let object = MultiTileObject {
+      anchor: ANCHOR,
+      width: 2,
+      height: 2,
+    };
+    expand_rules(&[object], &mut terrain_rules, &mut HashMap::new());
+
+    let states = &terrain_rules[&TerrainType::Land4];
+    assert_eq!(states.len(), 1);
+    for connection in CONNECTIONS {
+      assert_eq!(get_permitted_neighbours(&states[0], &connection).len(), 3);
+    }
+  }
+
+  #[test]
+  fn is_within_grid_only_accepts_footprints_that_fit_into_the_grid() {
+    let (states, _) = expand();
+    let anchor = get(&states, ANCHOR);
+    let filler = get(&states, part_name(1, 1));
+    assert!(is_within_grid(anchor, &Point::new_internal_grid(0, 0)));
+    assert!(is_within_grid(
+      anchor,
+      &Point::new_internal_grid(CHUNK_WIDTH - 2, CHUNK_HEIGHT - 2)
+    ));
+    assert!(!is_within_grid(anchor, &Point::new_internal_grid(CHUNK_WIDTH - 1, 0)));
+    assert!(!is_within_grid(anchor, &Point::new_internal_grid(0, CHUNK_HEIGHT - 1)));
+    assert!(is_within_grid(filler, &Point::new_internal_grid(1, 1)));
+    assert!(!is_within_grid(filler, &Point::new_internal_grid(0, 1)));
+    assert!(is_within_grid(
+      get(&states, OTHER),
+      &Point::new_internal_grid(CHUNK_WIDTH - 1, CHUNK_HEIGHT - 1)
+    ));
+  }
+}
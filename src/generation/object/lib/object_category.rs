@@ -0,0 +1,13 @@
+use bevy::reflect::Reflect;
+
+/// A broad grouping of objects that allows changing the density of similar objects without editing the rule sets.
+/// Assigned to each `ObjectName` in the `all.registry.ruleset.ron` file. Objects without a category (such as
+/// `ObjectName::Empty`) are never affected by any density setting.
+#[derive(serde::Deserialize, PartialEq, Debug, Clone, Copy, Reflect, Eq, Hash)]
+pub enum ObjectCategory {
+  Trees,
+  Rocks,
+  Flora,
+  Debris,
+  Props,
+}
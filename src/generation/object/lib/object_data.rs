@@ -1,5 +1,8 @@
+use crate::coords::point::ChunkGrid;
+use crate::coords::Point;
 use crate::generation::lib::TileData;
-use crate::generation::object::lib::{Cell, ObjectName};
+use crate::generation::object::lib::{Cell, ObjectAtlas, ObjectGridEdges, ObjectName, PropagationFailure, RareFeature};
+use crate::generation::resources::ChunkNavData;
 use bevy::log::*;
 
 /// Represents data associated with an object in the game world. Created as part of the object generation process and
@@ -8,14 +11,14 @@ use bevy::log::*;
 pub struct ObjectData {
   pub name: Option<ObjectName>,
   pub sprite_index: i32,
-  pub is_large_sprite: bool,
+  pub atlas: ObjectAtlas,
   pub tile_data: TileData,
 }
 
 impl ObjectData {
   pub fn from_wfc_cell(tile_data: &TileData, cell: &Cell) -> Self {
     let object_name = cell.possible_states[0].name;
-    let is_large_sprite = object_name.is_large_sprite();
+    let atlas = cell.possible_states[0].atlas;
     let sprite_index = cell.index;
     let possible_states_count = cell.possible_states.len();
     if sprite_index == -1 || possible_states_count > 1 || !cell.is_collapsed {
@@ -31,8 +34,38 @@ impl ObjectData {
     ObjectData {
       tile_data: tile_data.clone(),
       sprite_index,
-      is_large_sprite,
+      atlas,
       name: Some(object_name),
     }
   }
 }
+
+/// The outcome of the object generation process for a single chunk.
+#[derive(Debug, Clone)]
+pub struct ObjectGenerationResult {
+  pub cg: Point<ChunkGrid>,
+  pub object_data: Vec<ObjectData>,
+  pub failures: Vec<PropagationFailure>,
+  /// The edges of the object grid, which are `None` if no objects were generated.
+  pub edges: Option<ObjectGridEdges>,
+  /// The rare feature that was stamped onto the object grid, if any.
+  pub rare_feature: Option<RareFeature>,
+  /// The navigation data derived from the object grid, which remains available after the grid has been discarded.
+  pub nav_data: ChunkNavData,
+  /// The tile data of the chunk, which is needed to place objects on tiles for which no object was generated.
+  pub tile_data: Vec<TileData>,
+}
+
+impl ObjectGenerationResult {
+  pub fn empty(cg: Point<ChunkGrid>, nav_data: ChunkNavData, tile_data: Vec<TileData>) -> Self {
+    Self {
+      cg,
+      object_data: vec![],
+      failures: vec![],
+      edges: None,
+      rare_feature: None,
+      nav_data,
+      tile_data,
+    }
+  }
+}
@@ -0,0 +1,88 @@
+use crate::generation::object::lib::{ObjectCategory, ObjectName};
+use bevy::reflect::Reflect;
+use bevy::utils::HashMap;
+
+/// The sprite sheet that contains the sprite of an object.
+#[derive(serde::Deserialize, PartialEq, Debug, Clone, Copy, Reflect, Eq, Hash, Default)]
+pub enum ObjectAtlas {
+  /// The sprite sheet of the terrain and climate the object is placed on.
+  #[default]
+  Objects,
+  /// The sprite sheet with large sprites, such as trees, of the climate the object is placed on.
+  Trees,
+}
+
+/// A broad grouping of objects by their role in the world, e.g. used to toggle the visibility of objects.
+#[derive(serde::Deserialize, PartialEq, Debug, Clone, Copy, Reflect, Eq, Hash, Default)]
+pub enum ObjectGroup {
+  #[default]
+  Decoration,
+  /// The connected objects that form paths across the terrain.
+  Path,
+  /// The connected objects that form the remains of buildings.
+  Building,
+}
+
+/// Describes how an object is rendered and how other systems treat it. Loaded from the `all.registry.ruleset.ron` file,
+/// which must contain an entry for every `ObjectName` other than `ObjectName::MultiTileFiller`.
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct ObjectDefinition {
+  pub name: ObjectName,
+  /// The index of the sprite in the sprite sheet.
+  pub index: i32,
+  #[serde(default)]
+  pub atlas: ObjectAtlas,
+  #[serde(default)]
+  pub group: ObjectGroup,
+  /// The category whose density setting scales the weight of the object, if any.
+  #[serde(default)]
+  pub category: Option<ObjectCategory>,
+  #[serde(default = "default_is_walkable")]
+  pub is_walkable: bool,
+  /// The blob shadow rendered underneath the object, if any.
+  #[serde(default)]
+  pub shadow: Option<ObjectShadow>,
+}
+
+/// Describes the blob shadow of an object, i.e. an ellipse that fades out towards its edge.
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct ObjectShadow {
+  /// The width and height of the ellipse in pixels.
+  pub size: (f32, f32),
+  /// The offset in pixels of the center of the ellipse from the bottom center of the object's sprite.
+  #[serde(default)]
+  pub offset: (f32, f32),
+  /// The opacity at the center of the ellipse between `0.0` and `1.0`.
+  pub opacity: f32,
+}
+
+fn default_is_walkable() -> bool {
+  true
+}
+
+/// The definitions of all objects, keyed by their `ObjectName`. Use `ObjectResources::get_definition` to look up
+/// multi-tile fillers, which share the definition of their anchor.
+#[derive(Default, Debug, Clone)]
+pub struct ObjectRegistry {
+  definitions: HashMap<ObjectName, ObjectDefinition>,
+}
+
+impl ObjectRegistry {
+  pub fn new(definitions: Vec<ObjectDefinition>) -> Self {
+    Self {
+      definitions: definitions.into_iter().map(|d| (d.name, d)).collect(),
+    }
+  }
+
+  pub fn get(&self, name: ObjectName) -> Option<&ObjectDefinition> {
+    self.definitions.get(&name)
+  }
+
+  pub fn len(&self) -> usize {
+    self.definitions.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.definitions.is_empty()
+  }
+}
@@ -1,14 +1,24 @@
-use crate::constants::CHUNK_SIZE;
+use crate::constants::{CHUNK_HEIGHT, CHUNK_WIDTH};
 use crate::coords::point::{ChunkGrid, InternalGrid};
 use crate::coords::Point;
-use crate::generation::lib::{TerrainType, TileData, TileType};
+use crate::generation::lib::{Direction, TerrainType, TileData, TileType};
 use crate::generation::object::lib::connection_type::get_connection_points;
-use crate::generation::object::lib::{Cell, Connection, ObjectName};
-use crate::generation::resources::TerrainState;
+use crate::generation::object::lib::{is_within_grid, Cell, Connection, ObjectName, RareFeatureTemplate};
+use crate::generation::resources::{ObjectResources, TerrainState};
+use crate::resources::ObjectGenerationSettings;
 use bevy::log::*;
 use bevy::reflect::Reflect;
 use bevy::utils::HashMap;
 
+/// The collapsed `Cell`s on the outer rows and columns of the `ObjectGrid` of a chunk. Stored in the `ObjectEdges` once
+/// the wave function collapse for the chunk has completed, so that the facing cells of neighbouring chunks can be
+/// constrained by them.
+#[derive(Debug, Clone, Reflect)]
+pub struct ObjectGridEdges {
+  pub cg: Point<ChunkGrid>,
+  pub cells: Vec<Cell>,
+}
+
 /// An `ObjectGrid` is a 2D grid of `Cell`s, each of which representing the possible states of objects that may be
 /// spawned for the corresponding tile. The `ObjectGrid` is used to keep track of the state of each tile during the
 /// object generation process and is discarded once the object generation process is complete as the outcome is
@@ -22,16 +32,16 @@ pub struct ObjectGrid {
 
 impl ObjectGrid {
   fn new_uninitialised(cg: Point<ChunkGrid>) -> Self {
-    let grid: Vec<Vec<Cell>> = (0..CHUNK_SIZE)
-      .map(|y| (0..CHUNK_SIZE).map(|x| Cell::new(x, y)).collect())
+    let grid: Vec<Vec<Cell>> = (0..CHUNK_HEIGHT)
+      .map(|y| (0..CHUNK_WIDTH).map(|x| Cell::new(x, y)).collect())
       .collect();
     ObjectGrid { cg, grid }
   }
 
   pub fn new_initialised(
     cg: Point<ChunkGrid>,
-    terrain_rules: &HashMap<TerrainType, Vec<TerrainState>>,
-    tile_type_rules: &HashMap<TileType, Vec<ObjectName>>,
+    objects: &ObjectResources,
+    object_settings: &ObjectGenerationSettings,
     tile_data: &Vec<TileData>,
   ) -> Self {
     let mut grid = ObjectGrid::new_uninitialised(cg);
@@ -40,7 +50,13 @@ impl ObjectGrid {
       let terrain = data.flat_tile.terrain;
       let tile_type = data.flat_tile.tile_type;
       if let Some(cell) = grid.get_cell_mut(&ig) {
-        let relevant_rules = resolve_rules(tile_type, terrain_rules, tile_type_rules, terrain);
+        let is_beach = data.flat_tile.is_beach;
+        let relevant_rules = resolve_rules(tile_type, &objects.terrain_rules, &objects.tile_type_rules, terrain)
+          .into_iter()
+          .filter(|state| is_within_grid(state, &ig) && (is_beach || !state.is_beach_only))
+          .filter_map(|state| apply_density(state, objects, object_settings))
+          .collect();
+        cell.is_beach = is_beach;
         cell.initialise(terrain, tile_type, &relevant_rules);
         trace!(
           "Initialised {:?} as a [{:?}] [{:?}] cell with {:?} state(s)",
@@ -57,6 +73,89 @@ impl ObjectGrid {
     grid
   }
 
+  /// Returns the collapsed cells on the outer rows and columns of this grid.
+  pub fn get_edges(&self) -> ObjectGridEdges {
+    let cells = self
+      .grid
+      .iter()
+      .flatten()
+      .filter(|cell| cell.is_collapsed && is_on_edge(&cell.ig))
+      .cloned()
+      .collect();
+
+    ObjectGridEdges { cg: self.cg, cells }
+  }
+
+  /// Reduces the possible states of the cells facing the edges of the given neighbouring chunks to the states that are
+  /// permitted by the collapsed cells on the other side of the chunk boundary, so that objects line up across chunks.
+  /// Constraints that would leave a cell without any possible states are ignored, leaving the cell unconstrained.
+  pub fn apply_edge_constraints(&mut self, neighbour_edges: &[(Direction, ObjectGridEdges)]) {
+    let mut ignored_count = 0;
+    for (direction, edges) in neighbour_edges.iter() {
+      for reference in edges.cells.iter() {
+        let Some((ig, where_is_reference)) = get_facing_point(direction, &reference.ig) else {
+          continue;
+        };
+        let Some(cell) = self.get_cell(&ig) else {
+          continue;
+        };
+        match cell.clone_and_reduce(reference, &where_is_reference) {
+          Ok((true, mut reduced_cell)) => {
+            reduced_cell.entropy = reduced_cell.possible_states.len();
+            self.set_cell(reduced_cell);
+          }
+          Ok(_) => {}
+          Err(_) => ignored_count += 1,
+        }
+      }
+    }
+    if ignored_count > 0 {
+      debug!(
+        "Ignored {} edge constraint(s) of neighbouring chunks for object grid {} because they could not be satisfied",
+        ignored_count, self.cg
+      );
+    }
+  }
+
+  /// Collapses the cells covered by the given template, placed with its top left cell at the given origin, to the
+  /// objects of the template and reduces the possible states of their neighbours accordingly. Returns `false` and
+  /// leaves the grid unchanged if any of the objects is not permitted in its cell or contradicts its surroundings.
+  pub fn stamp(&mut self, template: &RareFeatureTemplate, origin: Point<InternalGrid>) -> bool {
+    let mut stamped_grid = self.clone();
+    let cells = template.cells(origin);
+    for (ig, name) in cells.iter() {
+      let Some(cell) = stamped_grid.get_cell_mut(ig) else {
+        return false;
+      };
+      if cell.is_collapsed || !cell.collapse_to(*name) {
+        return false;
+      }
+    }
+    for (ig, _) in cells.iter() {
+      let cell = stamped_grid.get_cell(ig).expect("Failed to get stamped cell").clone();
+      let mut reduced_cells = vec![];
+      for (connection, neighbour) in stamped_grid.get_neighbours(&cell) {
+        if neighbour.is_collapsed {
+          if neighbour.verify(&cell, &connection).is_err() {
+            return false;
+          }
+        } else {
+          match neighbour.clone_and_reduce(&cell, &connection) {
+            Ok((_, mut reduced_cell)) => {
+              reduced_cell.entropy = reduced_cell.possible_states.len();
+              reduced_cells.push(reduced_cell);
+            }
+            Err(_) => return false,
+          }
+        }
+      }
+      reduced_cells.into_iter().for_each(|cell| stamped_grid.set_cell(cell));
+    }
+    *self = stamped_grid;
+
+    true
+  }
+
   pub fn get_neighbours(&mut self, cell: &Cell) -> Vec<(Connection, &Cell)> {
     let point = cell.ig;
     let points: Vec<_> = get_connection_points(&point).into_iter().collect();
@@ -120,9 +219,41 @@ impl ObjectGrid {
   }
 }
 
+fn is_on_edge(ig: &Point<InternalGrid>) -> bool {
+  ig.x == 0 || ig.y == 0 || ig.x == CHUNK_WIDTH - 1 || ig.y == CHUNK_HEIGHT - 1
+}
+
+/// Returns the point of the cell in this chunk that faces the cell at the given point of the neighbouring chunk in the
+/// given direction, as well as where the latter is located relative to the former. Returns `None` if the given cell
+/// is not on the edge of the neighbouring chunk that faces this chunk.
+pub fn get_facing_point(direction: &Direction, ig: &Point<InternalGrid>) -> Option<(Point<InternalGrid>, Connection)> {
+  match direction {
+    Direction::Top if ig.y == CHUNK_HEIGHT - 1 => Some((Point::new_internal_grid(ig.x, 0), Connection::Top)),
+    Direction::Bottom if ig.y == 0 => Some((Point::new_internal_grid(ig.x, CHUNK_HEIGHT - 1), Connection::Bottom)),
+    Direction::Right if ig.x == 0 => Some((Point::new_internal_grid(CHUNK_WIDTH - 1, ig.y), Connection::Right)),
+    Direction::Left if ig.x == CHUNK_WIDTH - 1 => Some((Point::new_internal_grid(0, ig.y), Connection::Left)),
+    _ => None,
+  }
+}
+
+/// Scales the weight of the given state by the density of its `ObjectCategory`, if it has one. Returns `None` if the
+/// weight is scaled down to zero, so that the state is not considered at all.
+fn apply_density(
+  mut state: TerrainState,
+  objects: &ObjectResources,
+  object_settings: &ObjectGenerationSettings,
+) -> Option<TerrainState> {
+  if let Some(category) = objects.get_category(state.name) {
+    state.weight = (state.weight as f32 * object_settings.density(category)).round() as i32;
+  }
+
+  (state.weight > 0).then_some(state)
+}
+
 // TODO: Make resolving rules for each tile type part of the app initialisation process
 //  instead of repeating for each tile during the object generation process
-fn resolve_rules(
+/// Returns all terrain states that are permitted for a tile with the given terrain and tile type.
+pub fn resolve_rules(
   tile_type: TileType,
   terrain_rules: &HashMap<TerrainType, Vec<TerrainState>>,
   tile_type_rules: &HashMap<TileType, Vec<ObjectName>>,
@@ -0,0 +1,17 @@
+use crate::generation::object::lib::ObjectName;
+use bevy::reflect::Reflect;
+
+/// Describes the light emitted by an object. Loaded from the `all.light.ruleset.ron` file and used to spawn a glow
+/// for each object that is listed there. Objects without an entry don't emit any light.
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct ObjectLight {
+  pub name: ObjectName,
+  /// The radius of the glow in pixels.
+  pub radius: f32,
+  /// The colour of the glow as sRGB values between `0.0` and `1.0`.
+  pub colour: (f32, f32, f32),
+  /// The maximum relative reduction of the glow's intensity when flickering e.g. `0.2` allows for 80% to 100%. Use
+  /// `0.0` for a steady light.
+  #[serde(default)]
+  pub flicker: f32,
+}
@@ -1,6 +1,6 @@
 use bevy::reflect::Reflect;
 
-#[derive(serde::Deserialize, PartialEq, Debug, Clone, Copy, Reflect, Eq, Hash)]
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone, Copy, Reflect, Eq, Hash)]
 pub enum ObjectName {
   Empty,
   SandStone1,
@@ -69,17 +69,13 @@ pub enum ObjectName {
   ForestBush2,
   ForestBush3,
   ForestBush4,
+  /// A generated placeholder for the cell at offset (`x`, `y`) from the anchor of the multi-tile object at the given
+  /// index. Never used in rule set files and never spawned.
+  MultiTileFiller(u8, u8, u8),
 }
 
 impl ObjectName {
-  pub fn is_large_sprite(&self) -> bool {
-    matches!(
-      self,
-      ObjectName::ForestTree1
-        | ObjectName::ForestTree2
-        | ObjectName::ForestTree3
-        | ObjectName::ForestTree4
-        | ObjectName::ForestTree5
-    )
+  pub fn is_multi_tile_filler(&self) -> bool {
+    matches!(self, ObjectName::MultiTileFiller(..))
   }
 }
@@ -0,0 +1,37 @@
+use crate::constants::DEFAULT_TILE_SIZE;
+use crate::generation::object::lib::{ObjectAtlas, ObjectName};
+use bevy::reflect::Reflect;
+
+/// Describes how the sprite of an object is placed on its tile. Loaded from the `all.placement.ruleset.ron` file and
+/// used when spawning object sprites. Objects without an entry fall back to `ObjectPlacement::default_for`.
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct ObjectPlacement {
+  pub name: ObjectName,
+  /// The maximum distance in pixels that the sprite may be randomly offset from the tile on each axis. Use `0.0` to
+  /// align the sprite to the grid. Falls back to the radius of `ObjectPlacement::default_for` if omitted.
+  #[serde(default)]
+  pub jitter_radius: Option<f32>,
+  /// The distance in pixels from the bottom of the sprite that is used to y-sort the sprite against other objects.
+  #[serde(default)]
+  pub pivot_y: f32,
+  /// The maximum relative deviation from the original scale of the sprite e.g. `0.1` allows for 90% to 110%.
+  #[serde(default)]
+  pub scale_variance: f32,
+}
+
+impl ObjectPlacement {
+  /// Returns the placement that is used for objects that are not listed in the placement rule set, which matches the
+  /// behaviour prior to the introduction of placement rules.
+  pub fn default_for(name: ObjectName, atlas: ObjectAtlas) -> Self {
+    Self {
+      name,
+      jitter_radius: Some(if atlas == ObjectAtlas::Trees {
+        DEFAULT_TILE_SIZE as f32 / 3.0
+      } else {
+        0.0
+      }),
+      pivot_y: 0.0,
+      scale_variance: 0.0,
+    }
+  }
+}
@@ -0,0 +1,51 @@
+use crate::coords::point::{ChunkGrid, InternalGrid};
+use crate::coords::Point;
+use crate::generation::object::lib::ObjectName;
+use bevy::reflect::Reflect;
+
+/// Describes a rare, hand-crafted feature such as a meteorite crater that may be stamped onto the object grid of a
+/// chunk before running the wave function collapse algorithm. Loaded from the `all.rare-feature.ruleset.ron` file.
+#[derive(serde::Deserialize, Debug, Clone, Reflect)]
+pub struct RareFeatureTemplate {
+  /// The name of the feature, as shown in the debug UI.
+  pub name: String,
+  /// The relative likelihood of this template being selected when a chunk rolls a rare feature.
+  pub weight: i32,
+  /// The objects of the feature, row by row from top to bottom. Cells without an object are left to the wave
+  /// function collapse algorithm.
+  pub rows: Vec<Vec<Option<ObjectName>>>,
+}
+
+impl RareFeatureTemplate {
+  pub fn width(&self) -> i32 {
+    self.rows.iter().map(|row| row.len()).max().unwrap_or(0) as i32
+  }
+
+  pub fn height(&self) -> i32 {
+    self.rows.len() as i32
+  }
+
+  /// Returns the point and object of every cell of this template that has an object when placed at the given origin,
+  /// which is its top left cell.
+  pub fn cells(&self, origin: Point<InternalGrid>) -> Vec<(Point<InternalGrid>, ObjectName)> {
+    self
+      .rows
+      .iter()
+      .enumerate()
+      .flat_map(|(y, row)| {
+        row.iter().enumerate().filter_map(move |(x, name)| {
+          name.map(|name| (Point::new_internal_grid(origin.x + x as i32, origin.y + y as i32), name))
+        })
+      })
+      .collect()
+  }
+}
+
+/// A rare feature that was stamped onto the object grid of a chunk. Recorded in the `Metadata`.
+#[derive(Debug, Clone, Reflect)]
+pub struct RareFeature {
+  pub name: String,
+  pub cg: Point<ChunkGrid>,
+  /// The top left cell of the feature.
+  pub ig: Point<InternalGrid>,
+}
@@ -1,6 +1,8 @@
+use crate::generation::object::lib::PropagationFailure;
+
 #[derive(PartialEq)]
 pub enum IterationResult {
   Ok,
   Incomplete,
-  Failure,
+  Failure(PropagationFailure),
 }
@@ -1,16 +1,22 @@
 pub(crate) mod lib;
 mod object_generator;
+mod object_modifier;
 mod wfc;
 
 use crate::generation::object::object_generator::ObjectGeneratorPlugin;
+use crate::generation::object::object_modifier::ObjectModifierPlugin;
 use bevy::app::{App, Plugin};
 
 pub struct ObjectGenerationPlugin;
 
 impl Plugin for ObjectGenerationPlugin {
   fn build(&self, app: &mut App) {
-    app.add_plugins(ObjectGeneratorPlugin);
+    app.add_plugins((ObjectGeneratorPlugin, ObjectModifierPlugin));
   }
 }
 
-pub use crate::generation::object::object_generator::{generate_object_data, schedule_spawning_objects};
+pub use crate::generation::object::object_generator::{
+  generate_object_data, schedule_spawning_objects, stamp_rare_feature, stamp_rocky_features,
+};
+pub use crate::generation::object::object_modifier::ObjectModificationSet;
+pub use crate::generation::object::wfc::WfcStepper;
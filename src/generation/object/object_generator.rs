@@ -1,20 +1,32 @@
 use crate::constants::*;
+use crate::coords::projection::projection;
+use crate::coords::tile_size::{tile_scale, tile_size};
+use crate::coords::Point;
 use crate::generation::lib::shared::CommandQueueTask;
-use crate::generation::lib::{shared, Chunk, ObjectComponent, Tile, TileData};
-use crate::generation::object::lib::ObjectName;
-use crate::generation::object::lib::{ObjectData, ObjectGrid};
+use crate::generation::lib::{
+  run_before_object_generation_hooks, shared, Chunk, Direction, ObjectComponent, Tile, TileData,
+};
+use crate::generation::object::lib::{ObjectAtlas, ObjectName, ObjectPlacement};
+use crate::generation::object::lib::{
+  ObjectData, ObjectGenerationResult, ObjectGrid, ObjectGridEdges, RareFeature, RareFeatureTemplate,
+};
 use crate::generation::object::wfc;
 use crate::generation::object::wfc::WfcPlugin;
-use crate::generation::resources::{AssetCollection, GenerationResourcesCollection};
-use crate::resources::Settings;
+use crate::generation::resources::{
+  AssetCollection, ChunkNavData, GenerationResourcesCollection, ObjectResources, SpritePool, SpritePoolCategory,
+};
+use crate::generation::FrameTimeBudgetSet;
+use crate::resources::{ObjectGenerationSettings, Settings};
 use bevy::app::{App, Plugin, Update};
 use bevy::color::{Color, Luminance};
-use bevy::core::Name;
 use bevy::ecs::system::SystemState;
 use bevy::ecs::world::CommandQueue;
-use bevy::hierarchy::{BuildChildren, ChildBuild};
+use bevy::hierarchy::DespawnRecursiveExt;
 use bevy::log::*;
-use bevy::prelude::{Commands, Component, Entity, Query, Res, TextureAtlas, Transform};
+use bevy::prelude::{
+  Camera, Commands, Component, Entity, GlobalTransform, IntoSystemConfigs, Mut, Query, Res, TextureAtlas, Transform, Vec2,
+  With,
+};
 use bevy::sprite::{Anchor, Sprite};
 use bevy::tasks;
 use bevy::tasks::{block_on, AsyncComputeTaskPool, Task};
@@ -25,58 +37,199 @@ pub struct ObjectGeneratorPlugin;
 
 impl Plugin for ObjectGeneratorPlugin {
   fn build(&self, app: &mut App) {
-    app.add_plugins(WfcPlugin).add_systems(Update, process_async_tasks_system);
+    app
+      .add_plugins(WfcPlugin)
+      .add_systems(Update, process_async_tasks_system.in_set(FrameTimeBudgetSet));
   }
 }
 
+/// The task that spawns the sprite of a single object as a child of the tile entity it is placed on.
 #[derive(Component)]
-struct ObjectSpawnTask(Task<CommandQueue>);
+struct ObjectSpawnTask {
+  task: Task<CommandQueue>,
+  tile_entity: Entity,
+}
 
 impl CommandQueueTask for ObjectSpawnTask {
   fn poll_once(&mut self) -> Option<CommandQueue> {
-    block_on(tasks::poll_once(&mut self.0))
+    block_on(tasks::poll_once(&mut self.task))
   }
 }
 
+/// The randomised parts of the appearance of an object sprite, rolled when the spawn task is scheduled.
+struct SpriteAppearance {
+  offset: Vec2,
+  scale: f32,
+  colour: Color,
+}
+
+/// Generates the object data for the given chunk. The cells facing the given edges of neighbouring chunks are
+/// constrained by them and a rare feature may be stamped onto the object grid before running the wave function
+/// collapse algorithm. Rocky chunks use a higher rock density and are stamped with rocky features as well. Returns the
+/// edges of the resulting object grid as well, so that they can be used to constrain chunks generated later on.
 pub fn generate_object_data(
   resources: &GenerationResourcesCollection,
   settings: &Settings,
-  spawn_data: (Chunk, Vec<TileData>),
-) -> Vec<ObjectData> {
+  spawn_data: &(Chunk, Vec<TileData>),
+  neighbour_edges: &[(Direction, ObjectGridEdges)],
+  is_rocky: bool,
+) -> ObjectGenerationResult {
+  let chunk_cg = spawn_data.0.coords.chunk_grid;
   if !settings.object.generate_objects {
     debug!("Skipped object generation because it's disabled");
-    return vec![];
+    let nav_data = ChunkNavData::new(&spawn_data.1, &resources.objects.walkability);
+    return ObjectGenerationResult::empty(chunk_cg, nav_data, spawn_data.1.clone());
   }
-  let start_time = shared::get_time();
-  let chunk_cg = spawn_data.0.coords.chunk_grid;
-  let grid = ObjectGrid::new_initialised(
-    chunk_cg,
-    &resources.objects.terrain_rules,
-    &resources.objects.tile_type_rules,
-    &spawn_data.1,
-  );
-  let mut rng = StdRng::seed_from_u64(shared::calculate_seed(chunk_cg, settings.world.noise_seed));
+  let span = shared::TimedSpan::new(info_span!("generate_object_data", cg = %chunk_cg));
+  let object_settings = settings.object.for_biome(is_rocky);
+  let mut grid = ObjectGrid::new_initialised(chunk_cg, &resources.objects, &object_settings, &spawn_data.1);
+  grid.apply_edge_constraints(neighbour_edges);
+  let object_seed = settings.object.object_seed(settings.world.get_object_seed());
+  let rare_feature = stamp_rare_feature(&mut grid, &resources.objects, &settings.object, object_seed);
+  if is_rocky {
+    stamp_rocky_features(&mut grid, &resources.objects, &settings.object, object_seed);
+  }
+  run_before_object_generation_hooks(&mut grid, &spawn_data.1);
+  let mut rng = StdRng::seed_from_u64(shared::calculate_seed(chunk_cg, object_seed));
   let objects_count = grid.grid.len();
   let mut object_generation_data = (grid.clone(), spawn_data.1.clone());
-  let object_data = { wfc::determine_objects_in_grid(&mut rng, &mut object_generation_data, &settings) };
+  let (object_data, failures) = { wfc::determine_objects_in_grid(&mut rng, &mut object_generation_data, &settings) };
+  let nav_data = ChunkNavData::from_object_grid(&spawn_data.1, &object_generation_data.0, &resources.objects);
+  span.finish(
+    Level::DEBUG,
+    format_args!("Generated object data for {} objects for chunk {}", objects_count, chunk_cg),
+  );
+
+  ObjectGenerationResult {
+    cg: chunk_cg,
+    object_data,
+    failures,
+    edges: Some(object_generation_data.0.get_edges()),
+    rare_feature,
+    nav_data,
+    tile_data: object_generation_data.1,
+  }
+}
+
+/// Rolls whether the chunk of the given object grid contains a rare feature and, if so, stamps a randomly selected
+/// feature template onto the grid at the first of several random positions where it fits. Uses its own random number
+/// generator, so that rolling a feature doesn't affect the objects generated in chunks without one.
+pub fn stamp_rare_feature(
+  grid: &mut ObjectGrid,
+  objects: &ObjectResources,
+  object_settings: &ObjectGenerationSettings,
+  object_seed: u32,
+) -> Option<RareFeature> {
+  let seed = object_seed.wrapping_add(RARE_FEATURE_SEED_OFFSET);
+  let mut rng = StdRng::seed_from_u64(shared::calculate_seed(grid.cg, seed));
+  if objects.rare_features.is_empty() || !rng.gen_bool(object_settings.rare_feature_probability.clamp(0., 1.) as f64) {
+    return None;
+  }
+  let template = select_rare_feature_template(&mut rng, &objects.rare_features)?;
+  let (max_x, max_y) = (CHUNK_WIDTH - template.width(), CHUNK_HEIGHT - template.height());
+  if max_x < 0 || max_y < 0 {
+    warn!(
+      "Failed to stamp rare feature [{}] because it is larger than a chunk",
+      template.name
+    );
+    return None;
+  }
+  for _ in 0..RARE_FEATURE_PLACEMENT_ATTEMPTS {
+    let origin = Point::new_internal_grid(rng.gen_range(0..=max_x), rng.gen_range(0..=max_y));
+    if grid.stamp(template, origin) {
+      info!(
+        "Stamped rare feature [{}] onto chunk {} at {:?}",
+        template.name, grid.cg, origin
+      );
+      return Some(RareFeature {
+        name: template.name.clone(),
+        cg: grid.cg,
+        ig: origin,
+      });
+    }
+  }
   debug!(
-    "Generated object data for {} objects for chunk {} in {} ms on {}",
-    objects_count,
-    chunk_cg,
-    shared::get_time() - start_time,
-    shared::thread_name()
+    "Rolled rare feature [{}] for chunk {} but failed to find a position where it fits",
+    template.name, grid.cg
   );
 
-  object_data
+  None
+}
+
+/// Stamps up to `rocky_features_per_chunk` randomly selected rocky feature templates, such as boulder fields and
+/// cliffside outcrops, onto the object grid of a rocky chunk. Each template is placed at the first of several random
+/// positions where it fits, which limits cliffside outcrops to the matching cliff edges of the terrain. Returns the
+/// number of features that were stamped.
+pub fn stamp_rocky_features(
+  grid: &mut ObjectGrid,
+  objects: &ObjectResources,
+  object_settings: &ObjectGenerationSettings,
+  object_seed: u32,
+) -> usize {
+  let seed = object_seed.wrapping_add(ROCKY_FEATURE_SEED_OFFSET);
+  let mut rng = StdRng::seed_from_u64(shared::calculate_seed(grid.cg, seed));
+  let mut stamped_count = 0;
+  for _ in 0..object_settings.rocky_features_per_chunk {
+    let Some(template) = select_rare_feature_template(&mut rng, &objects.rocky_features) else {
+      break;
+    };
+    let (max_x, max_y) = (CHUNK_WIDTH - template.width(), CHUNK_HEIGHT - template.height());
+    if max_x < 0 || max_y < 0 {
+      warn!(
+        "Failed to stamp rocky feature [{}] because it is larger than a chunk",
+        template.name
+      );
+      continue;
+    }
+    for _ in 0..RARE_FEATURE_PLACEMENT_ATTEMPTS {
+      let origin = Point::new_internal_grid(rng.gen_range(0..=max_x), rng.gen_range(0..=max_y));
+      if grid.stamp(template, origin) {
+        trace!(
+          "Stamped rocky feature [{}] onto chunk {} at {:?}",
+          template.name,
+          grid.cg,
+          origin
+        );
+        stamped_count += 1;
+        break;
+      }
+    }
+  }
+  debug!(
+    "Stamped {} of {} rocky feature(s) onto chunk {}",
+    stamped_count, object_settings.rocky_features_per_chunk, grid.cg
+  );
+
+  stamped_count
+}
+
+fn select_rare_feature_template<'a>(
+  rng: &mut StdRng,
+  templates: &'a [RareFeatureTemplate],
+) -> Option<&'a RareFeatureTemplate> {
+  let total_weight: i32 = templates.iter().map(|template| template.weight.max(0)).sum();
+  if total_weight == 0 {
+    return None;
+  }
+  let mut target = rng.gen_range(0..total_weight);
+  for template in templates.iter() {
+    if target < template.weight.max(0) {
+      return Some(template);
+    }
+    target -= template.weight.max(0);
+  }
+
+  None
 }
 
 pub fn schedule_spawning_objects(
   commands: &mut Commands,
   settings: &Settings,
+  resources: &GenerationResourcesCollection,
   mut rng: &mut StdRng,
   object_data: Vec<ObjectData>,
 ) {
-  let start_time = shared::get_time();
+  let span = shared::TimedSpan::new(info_span!("schedule_spawning_objects"));
   let task_pool = AsyncComputeTaskPool::get();
   let object_data_len = object_data.len();
   let chunk_cg = if let Some(object_data) = object_data.first() {
@@ -85,29 +238,34 @@ pub fn schedule_spawning_objects(
     "cg(unknown)".to_string()
   };
   for object in object_data {
-    attach_task_to_tile_entity(commands, settings, &mut rng, task_pool, object);
+    let placement = resources
+      .objects
+      .get_placement(object.name.expect("Failed to get object name"));
+    attach_task_to_tile_entity(commands, settings, &mut rng, task_pool, object, placement);
   }
-  debug!(
-    "Scheduled {} object spawn tasks for chunk {} in {} ms on {}",
-    object_data_len,
-    chunk_cg,
-    shared::get_time() - start_time,
-    shared::thread_name()
+  span.finish(
+    Level::DEBUG,
+    format_args!("Scheduled {} object spawn tasks for chunk {}", object_data_len, chunk_cg),
   );
 }
 
 fn attach_task_to_tile_entity(
   commands: &mut Commands,
   settings: &Settings,
-  mut rng: &mut StdRng,
+  rng: &mut StdRng,
   task_pool: &AsyncComputeTaskPool,
   object_data: ObjectData,
+  placement: ObjectPlacement,
 ) {
   let sprite_index = object_data.sprite_index;
   let tile_data = object_data.tile_data.clone();
+  let tile_entity = tile_data.entity;
   let object_name = object_data.name.expect("Failed to get object name");
-  let (offset_x, offset_y) = get_sprite_offsets(&mut rng, &object_data);
-  let colour = get_randomised_colour(settings, &mut rng, &object_data);
+  let appearance = SpriteAppearance {
+    offset: get_sprite_offsets(settings, rng, &placement),
+    scale: get_randomised_scale(rng, &placement),
+    colour: get_randomised_colour(settings, rng, &object_data),
+  };
   let task = task_pool.spawn(async move {
     let mut command_queue = CommandQueue::default();
     command_queue.push(move |world: &mut bevy::prelude::World| {
@@ -115,36 +273,40 @@ fn attach_task_to_tile_entity(
         let mut system_state = SystemState::<Res<GenerationResourcesCollection>>::new(world);
         let resources = system_state.get_mut(world);
         resources
-          .get_object_collection(
-            tile_data.flat_tile.terrain,
-            tile_data.flat_tile.climate,
-            object_data.is_large_sprite,
-          )
+          .get_object_collection(tile_data.flat_tile.terrain, tile_data.flat_tile.climate, object_data.atlas)
           .clone()
       };
-      if let Ok(mut tile_data_entity) = world.get_entity_mut(tile_data.entity) {
-        tile_data_entity.with_children(|parent| {
-          parent.spawn(sprite(
-            &tile_data.flat_tile,
-            sprite_index,
-            &asset_collection,
-            object_name,
-            offset_x,
-            offset_y,
-            colour,
-          ));
-        });
+      let settings = *world.resource::<Settings>();
+      if world.get_entity(tile_data.entity).is_err() {
+        return;
+      }
+      let sprite = sprite(
+        &tile_data.flat_tile,
+        sprite_index,
+        &asset_collection,
+        object_name,
+        appearance,
+        placement.pivot_y * tile_scale(),
+      );
+      let entity = world.resource_scope(|world, mut sprite_pool: Mut<SpritePool>| {
+        sprite_pool.spawn(world, SpritePoolCategory::Object, tile_data.entity, sprite)
+      });
+      if let Some(name) = shared::debug_name(&settings, || format!("{:?} Object Sprite", object_name)) {
+        world.entity_mut(entity).insert(name);
       }
     });
     command_queue
   });
 
-  commands.spawn((Name::new("Object Spawn Task"), ObjectSpawnTask(task)));
+  let mut task_commands = commands.spawn(ObjectSpawnTask { task, tile_entity });
+  if let Some(name) = shared::debug_name(settings, || "Object Spawn Task".to_string()) {
+    task_commands.insert(name);
+  }
 }
 
 fn get_randomised_colour(settings: &Settings, rng: &mut StdRng, object_data: &ObjectData) -> Color {
   let base_color = Color::default();
-  if object_data.is_large_sprite && settings.object.enable_colour_variations {
+  if object_data.atlas == ObjectAtlas::Trees && settings.object.enable_colour_variations {
     let range = RGB_COLOUR_VARIATION;
     let r = (base_color.to_srgba().red + rng.gen_range(-range..range)).clamp(0.0, 1.0);
     let g = (base_color.to_srgba().green + rng.gen_range(-(range / 2.)..(range / 2.))).clamp(0.0, 1.0);
@@ -159,14 +321,27 @@ fn get_randomised_colour(settings: &Settings, rng: &mut StdRng, object_data: &Ob
   }
 }
 
-fn get_sprite_offsets(rng: &mut StdRng, object_data: &ObjectData) -> (f32, f32) {
-  if object_data.is_large_sprite {
-    (
-      rng.gen_range(-(TILE_SIZE as f32) / 3.0..=(TILE_SIZE as f32) / 3.0).round(),
-      rng.gen_range(-(TILE_SIZE as f32) / 3.0..=(TILE_SIZE as f32) / 3.0).round(),
+fn get_sprite_offsets(settings: &Settings, rng: &mut StdRng, placement: &ObjectPlacement) -> Vec2 {
+  if settings.object.align_objects_to_grid {
+    return Vec2::ZERO;
+  }
+  let radius = placement.jitter_radius.unwrap_or_default() * tile_scale();
+  if radius > 0. {
+    Vec2::new(
+      rng.gen_range(-radius..=radius).round(),
+      rng.gen_range(-radius..=radius).round(),
     )
   } else {
-    (0., 0.)
+    Vec2::ZERO
+  }
+}
+
+fn get_randomised_scale(rng: &mut StdRng, placement: &ObjectPlacement) -> f32 {
+  let variance = placement.scale_variance;
+  if variance > 0. {
+    rng.gen_range((1. - variance)..=(1. + variance))
+  } else {
+    1.
   }
 }
 
@@ -175,15 +350,11 @@ fn sprite(
   index: i32,
   asset_collection: &AssetCollection,
   object_name: ObjectName,
-  offset_x: f32,
-  offset_y: f32,
-  colour: Color,
-) -> (Name, Sprite, Transform, ObjectComponent) {
-  let base_z = (tile.coords.chunk_grid.y * CHUNK_SIZE) as f32;
-  let internal_z = tile.coords.internal_grid.y as f32;
-  let z = 10000. - base_z + internal_z - (offset_y / TILE_SIZE as f32);
+  appearance: SpriteAppearance,
+  pivot_y: f32,
+) -> (Sprite, Transform, ObjectComponent) {
+  let z = 10000. + projection().depth(&tile.coords) - ((appearance.offset.y + pivot_y) / tile_size() as f32);
   (
-    Name::new(format!("{:?} Object Sprite", object_name)),
     Sprite {
       anchor: Anchor::BottomCenter,
       texture_atlas: Option::from(TextureAtlas {
@@ -191,10 +362,10 @@ fn sprite(
         index: index as usize,
       }),
       image: asset_collection.stat.texture.clone(),
-      color: colour,
+      color: appearance.colour,
       ..Default::default()
     },
-    Transform::from_xyz(TILE_SIZE as f32 / 2. + offset_x, TILE_SIZE as f32 * -1. + offset_y, z),
+    projection().object_sprite_transform(appearance.offset, appearance.scale, z),
     ObjectComponent {
       coords: tile.coords,
       sprite_index: index as usize,
@@ -204,6 +375,43 @@ fn sprite(
   )
 }
 
-fn process_async_tasks_system(commands: Commands, object_spawn_tasks: Query<(Entity, &mut ObjectSpawnTask)>) {
-  shared::process_tasks(commands, object_spawn_tasks);
+/// Spawns the objects of all finished tasks, starting with the objects closest to the camera, so that the visible part
+/// of a chunk fills in first. At most `object_spawn_budget` objects are spawned per frame, leaving the remaining ones
+/// for the following frames.
+fn process_async_tasks_system(
+  mut commands: Commands,
+  mut object_spawn_tasks: Query<(Entity, &mut ObjectSpawnTask)>,
+  transforms: Query<&GlobalTransform>,
+  camera: Query<&GlobalTransform, With<Camera>>,
+  settings: Res<Settings>,
+) {
+  let camera_translation = camera
+    .get_single()
+    .map(|transform| transform.translation().truncate())
+    .unwrap_or_default();
+  let mut finished_tasks = object_spawn_tasks
+    .iter()
+    .filter(|(_, task)| task.task.is_finished())
+    .map(|(entity, task)| {
+      let distance = transforms
+        .get(task.tile_entity)
+        .map(|transform| transform.translation().truncate().distance_squared(camera_translation))
+        .unwrap_or(f32::MAX);
+      (entity, distance)
+    })
+    .collect::<Vec<(Entity, f32)>>();
+  finished_tasks.sort_by(|a, b| a.1.total_cmp(&b.1));
+  let budget = match settings.general.object_spawn_budget {
+    0 => finished_tasks.len(),
+    budget => budget,
+  };
+  for (entity, _) in finished_tasks.into_iter().take(budget) {
+    let Ok((_, mut task)) = object_spawn_tasks.get_mut(entity) else {
+      continue;
+    };
+    if let Some(mut command_queue) = task.poll_once() {
+      commands.append(&mut command_queue);
+      commands.entity(entity).despawn_recursive();
+    }
+  }
 }
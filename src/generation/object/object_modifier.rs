@@ -0,0 +1,257 @@
+use crate::coords::point::{ChunkGrid, InternalGrid};
+use crate::coords::Point;
+use crate::events::{PlaceObjectEvent, RemoveObjectEvent};
+use crate::generation::lib::{shared, ObjectComponent, TileData, TileDataComponent};
+use crate::generation::object::lib::{resolve_rules, ObjectData};
+use crate::generation::object::object_generator::schedule_spawning_objects;
+use crate::generation::resources::{GenerationResourcesCollection, NavData, WorldModification, WorldModifications};
+use crate::resources::Settings;
+use crate::states::AppState;
+use bevy::app::{App, Plugin, Update};
+use bevy::ecs::system::SystemParam;
+use bevy::hierarchy::DespawnRecursiveExt;
+use bevy::log::*;
+use bevy::prelude::{
+  in_state, Commands, Entity, EventReader, IntoSystemConfigs, OnAdd, OnRemove, Query, Res, ResMut, Resource, SystemSet,
+  Trigger,
+};
+use bevy::utils::HashMap;
+use rand::prelude::StdRng;
+use rand::SeedableRng;
+
+pub struct ObjectModifierPlugin;
+
+impl Plugin for ObjectModifierPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_resource::<ObjectEntityIndex>()
+      .init_resource::<TileDataIndex>()
+      .add_observer(on_add_object_component_trigger)
+      .add_observer(on_remove_object_component_trigger)
+      .add_observer(on_add_tile_data_component_trigger)
+      .add_observer(on_remove_tile_data_component_trigger)
+      .add_systems(
+        Update,
+        (remove_object_event, place_object_event)
+          .in_set(ObjectModificationSet)
+          .run_if(in_state(AppState::Running)),
+      );
+  }
+}
+
+/// The systems that process `RemoveObjectEvent`s and `PlaceObjectEvent`s. Systems that need to look up the objects
+/// referenced by these events before they are despawned can be ordered relative to this set.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ObjectModificationSet;
+
+type TileKey = (Point<ChunkGrid>, Point<InternalGrid>);
+
+/// Contains the entity of each spawned object, keyed by the `ChunkGrid` and `InternalGrid` coordinates of its tile, so
+/// that objects can be looked up without iterating over all of them.
+#[derive(Resource, Default)]
+struct ObjectEntityIndex {
+  map: HashMap<TileKey, Entity>,
+}
+
+/// Contains the `TileData` of each spawned tile, keyed by its `ChunkGrid` and `InternalGrid` coordinates.
+#[derive(Resource, Default)]
+struct TileDataIndex {
+  map: HashMap<TileKey, TileData>,
+}
+
+fn on_add_object_component_trigger(
+  trigger: Trigger<OnAdd, ObjectComponent>,
+  query: Query<&ObjectComponent>,
+  mut index: ResMut<ObjectEntityIndex>,
+) {
+  let oc = query.get(trigger.entity()).expect("Failed to get ObjectComponent");
+  index
+    .map
+    .insert((oc.coords.chunk_grid, oc.coords.internal_grid), trigger.entity());
+}
+
+fn on_remove_object_component_trigger(
+  trigger: Trigger<OnRemove, ObjectComponent>,
+  query: Query<&ObjectComponent>,
+  mut index: ResMut<ObjectEntityIndex>,
+) {
+  let oc = query.get(trigger.entity()).expect("Failed to get ObjectComponent");
+  let key = (oc.coords.chunk_grid, oc.coords.internal_grid);
+  if index.map.get(&key) == Some(&trigger.entity()) {
+    // Only removed if the key hasn't been taken by an object that has been spawned in its place since
+    index.map.remove(&key);
+  }
+}
+
+fn on_add_tile_data_component_trigger(
+  trigger: Trigger<OnAdd, TileDataComponent>,
+  query: Query<&TileDataComponent>,
+  mut index: ResMut<TileDataIndex>,
+) {
+  let tile_data = query
+    .get(trigger.entity())
+    .expect("Failed to get TileDataComponent")
+    .tile_data;
+  let coords = tile_data.flat_tile.coords;
+  index.map.insert((coords.chunk_grid, coords.internal_grid), tile_data);
+}
+
+fn on_remove_tile_data_component_trigger(
+  trigger: Trigger<OnRemove, TileDataComponent>,
+  query: Query<&TileDataComponent>,
+  mut index: ResMut<TileDataIndex>,
+) {
+  let tile_data = query
+    .get(trigger.entity())
+    .expect("Failed to get TileDataComponent")
+    .tile_data;
+  let key = (
+    tile_data.flat_tile.coords.chunk_grid,
+    tile_data.flat_tile.coords.internal_grid,
+  );
+  if index.map.get(&key).is_some_and(|t| t.entity == tile_data.entity) {
+    // Only removed if the key hasn't been taken by a tile that has been spawned in its place since
+    index.map.remove(&key);
+  }
+}
+
+/// Records the object modifications in the `WorldModifications` and keeps the `NavData` of the modified chunk in sync.
+#[derive(SystemParam)]
+struct ObjectModificationRecorder<'w> {
+  world_modifications: ResMut<'w, WorldModifications>,
+  nav_data: ResMut<'w, NavData>,
+}
+
+impl ObjectModificationRecorder<'_> {
+  fn record(
+    &mut self,
+    cg: Point<ChunkGrid>,
+    ig: Point<InternalGrid>,
+    modification: WorldModification,
+    resources: &GenerationResourcesCollection,
+  ) {
+    let name = match modification {
+      WorldModification::PlacedObject { name, .. } => Some(name),
+      _ => None,
+    };
+    self.world_modifications.record(cg, ig, modification);
+    if let Some(chunk_nav_data) = self.nav_data.get_mut(&cg) {
+      chunk_nav_data.set_object(&ig, name, &resources.objects);
+    }
+  }
+}
+
+/// Despawns the object identified by each `RemoveObjectEvent` and records the removal in the `WorldModifications`.
+/// Events that don't match any spawned object are ignored.
+fn remove_object_event(
+  mut commands: Commands,
+  mut events: EventReader<RemoveObjectEvent>,
+  objects: Query<(Entity, &ObjectComponent)>,
+  object_index: Res<ObjectEntityIndex>,
+  resources: Res<GenerationResourcesCollection>,
+  mut recorder: ObjectModificationRecorder,
+) {
+  for event in events.read() {
+    let entity = match event {
+      RemoveObjectEvent::ByEntity(entity) => Some(*entity),
+      RemoveObjectEvent::ByCoords(cg, ig) => object_index.map.get(&(*cg, *ig)).copied(),
+    };
+    let object = entity.and_then(|entity| objects.get(entity).ok());
+    if let Some((entity, oc)) = object {
+      debug!(
+        "Removing [{:?}] object at {} {}",
+        oc.object_name, oc.coords.chunk_grid, oc.coords.internal_grid
+      );
+      commands.entity(entity).despawn_recursive();
+      recorder.record(
+        oc.coords.chunk_grid,
+        oc.coords.internal_grid,
+        WorldModification::RemovedObject,
+        &resources,
+      );
+    } else {
+      warn!("Failed to remove object because it does not exist");
+    }
+  }
+}
+
+/// Validates each `PlaceObjectEvent` against the object rule sets and, if permitted, replaces any existing object on
+/// the tile with the requested object, using the same spawn path as the object generation process. Placed objects are
+/// recorded in the `WorldModifications`.
+fn place_object_event(
+  mut commands: Commands,
+  mut events: EventReader<PlaceObjectEvent>,
+  tile_index: Res<TileDataIndex>,
+  object_index: Res<ObjectEntityIndex>,
+  resources: Res<GenerationResourcesCollection>,
+  settings: Res<Settings>,
+  mut recorder: ObjectModificationRecorder,
+) {
+  for event in events.read() {
+    let tile_data = match tile_index.map.get(&(event.cg, event.ig)).copied() {
+      Some(tile_data) => tile_data,
+      None => {
+        warn!(
+          "Failed to place [{:?}] object because tile {} {} does not exist",
+          event.name, event.cg, event.ig
+        );
+        continue;
+      }
+    };
+    let sprite_index = match find_sprite_index(&resources, &tile_data, event) {
+      Some(sprite_index) => sprite_index,
+      None => {
+        warn!(
+          "Failed to place [{:?}] object at {} {} because it is not permitted on a [{:?}] [{:?}] tile",
+          event.name, event.cg, event.ig, tile_data.flat_tile.terrain, tile_data.flat_tile.tile_type
+        );
+        continue;
+      }
+    };
+    if let Some(entity) = object_index.map.get(&(event.cg, event.ig)) {
+      commands.entity(*entity).despawn_recursive();
+    }
+    let object_data = ObjectData {
+      name: Some(event.name),
+      sprite_index,
+      atlas: resources.objects.get_atlas(event.name),
+      tile_data,
+    };
+    let object_seed = settings.object.object_seed(settings.world.get_object_seed());
+    let mut rng = StdRng::seed_from_u64(shared::calculate_seed(event.cg, object_seed));
+    debug!("Placing [{:?}] object at {} {}", event.name, event.cg, event.ig);
+    recorder.record(
+      event.cg,
+      event.ig,
+      WorldModification::PlacedObject {
+        name: event.name,
+        sprite_index,
+      },
+      &resources,
+    );
+    schedule_spawning_objects(&mut commands, &settings, &resources, &mut rng, vec![object_data]);
+  }
+}
+
+fn find_sprite_index(
+  resources: &GenerationResourcesCollection,
+  tile_data: &TileData,
+  event: &PlaceObjectEvent,
+) -> Option<i32> {
+  let terrain = tile_data.flat_tile.terrain;
+  let states = if event.is_relaxed {
+    resources.objects.terrain_rules.get(&terrain).cloned().unwrap_or_default()
+  } else {
+    resolve_rules(
+      tile_data.flat_tile.tile_type,
+      &resources.objects.terrain_rules,
+      &resources.objects.tile_type_rules,
+      terrain,
+    )
+    .into_iter()
+    .filter(|state| tile_data.flat_tile.is_beach || !state.is_beach_only)
+    .collect()
+  };
+
+  states.iter().find(|state| state.name == event.name).map(|state| state.index)
+}
@@ -1,11 +1,17 @@
+mod wfc_stepper;
+
+use crate::coords::point::InternalGrid;
+use crate::coords::Point;
 use crate::generation::lib::{shared, TileData};
-use crate::generation::object::lib::{Cell, IterationResult, ObjectData, ObjectGrid};
-use crate::resources::Settings;
+use crate::generation::object::lib::{Cell, IterationResult, ObjectData, ObjectGrid, PropagationFailure};
+use crate::resources::{ObjectGenerationSettings, Settings};
 use bevy::app::{App, Plugin};
 use bevy::log::*;
 use rand::prelude::StdRng;
 use rand::Rng;
 
+pub use wfc_stepper::WfcStepper;
+
 pub struct WfcPlugin;
 
 impl Plugin for WfcPlugin {
@@ -13,12 +19,13 @@ impl Plugin for WfcPlugin {
 }
 
 /// The entry point for running the wave function collapse algorithm to determine the object sprites in the grid.
+/// Returns the resulting `ObjectData` as well as every `PropagationFailure` that was encountered along the way.
 pub fn determine_objects_in_grid(
   mut rng: &mut StdRng,
   object_generation_data: &mut (ObjectGrid, Vec<TileData>),
-  _settings: &Settings,
-) -> Vec<ObjectData> {
-  let start_time = shared::get_time();
+  settings: &Settings,
+) -> (Vec<ObjectData>, Vec<PropagationFailure>) {
+  let span = shared::TimedSpan::new(info_span!("wfc", cg = %object_generation_data.0.cg));
   let grid = &mut object_generation_data.0;
   let mut snapshots = vec![];
   let mut iter_count = 1;
@@ -26,19 +33,27 @@ pub fn determine_objects_in_grid(
   let mut snapshot_error_count: usize = 0;
   let mut iter_error_count: usize = 0;
   let mut total_error_count = 0;
+  let mut failures = vec![];
+  let mut wavefront = vec![];
 
   while has_entropy {
-    match iterate(&mut rng, grid) {
-      IterationResult::Failure => handle_failure(
-        grid,
-        &mut snapshots,
-        &mut iter_count,
-        &mut snapshot_error_count,
-        &mut iter_error_count,
-        &mut total_error_count,
-      ),
+    wavefront.clear();
+    match iterate(&mut rng, grid, &mut wavefront) {
+      IterationResult::Failure(failure) => {
+        failures.push(failure);
+        handle_failure(
+          grid,
+          &settings.object,
+          &mut snapshots,
+          &mut iter_count,
+          &mut snapshot_error_count,
+          &mut iter_error_count,
+          &mut total_error_count,
+        )
+      }
       result => handle_success(
         grid,
+        &settings.object,
         &mut snapshots,
         &mut iter_count,
         &mut has_entropy,
@@ -46,15 +61,25 @@ pub fn determine_objects_in_grid(
         result,
       ),
     }
+    if has_entropy && is_short_circuited(&settings.object, iter_count, total_error_count) {
+      warn!(
+        "Short-circuited wave function collapse for {} after {} iterations, leaving the remaining cells empty",
+        grid.cg,
+        iter_count + total_error_count
+      );
+      has_entropy = false;
+    }
   }
 
   let object_data = create_object_data(&object_generation_data.0, &object_generation_data.1);
-  log_summary(start_time, snapshot_error_count, total_error_count, &object_generation_data.0);
+  log_summary(span, snapshot_error_count, total_error_count, &object_generation_data.0);
 
-  object_data
+  (object_data, failures)
 }
 
-fn iterate(mut rng: &mut StdRng, grid: &mut ObjectGrid) -> IterationResult {
+/// Runs a single observation, collapse and propagation cycle. The collapsed cell and every cell that was updated
+/// during propagation are added to the `wavefront`, in that order.
+fn iterate(mut rng: &mut StdRng, grid: &mut ObjectGrid, wavefront: &mut Vec<Point<InternalGrid>>) -> IterationResult {
   // Observation: Get the cells with the lowest entropy
   let lowest_entropy_cells = grid.get_cells_with_lowest_entropy();
   if lowest_entropy_cells.is_empty() {
@@ -73,19 +98,21 @@ fn iterate(mut rng: &mut StdRng, grid: &mut ObjectGrid) -> IterationResult {
   // Propagation: Update every neighbours' states and the grid
   let mut stack: Vec<Cell> = vec![random_cell_clone];
   while let Some(cell) = stack.pop() {
+    wavefront.push(cell.ig);
     grid.set_cell(cell.clone());
     for (connection, neighbour) in grid.get_neighbours(&cell).iter_mut() {
       if !neighbour.is_collapsed {
-        if let Ok((has_changed, neighbour_cell)) = neighbour.clone_and_reduce(&cell, &connection) {
-          if has_changed {
-            stack.push(neighbour_cell);
+        match neighbour.clone_and_reduce(&cell, &connection) {
+          Ok((has_changed, neighbour_cell)) => {
+            if has_changed {
+              stack.push(neighbour_cell);
+            }
           }
-        } else {
-          return IterationResult::Failure;
+          Err(failure) => return IterationResult::Failure(failure),
         }
       } else {
-        if let Err(_) = neighbour.verify(&cell, &connection) {
-          return IterationResult::Failure;
+        if let Err(failure) = neighbour.verify(&cell, &connection) {
+          return IterationResult::Failure(failure);
         }
       }
     }
@@ -94,8 +121,16 @@ fn iterate(mut rng: &mut StdRng, grid: &mut ObjectGrid) -> IterationResult {
   IterationResult::Incomplete
 }
 
+/// Returns `true` if the total number of iterations, including failed ones, has reached the configured limit.
+fn is_short_circuited(settings: &ObjectGenerationSettings, iter_count: i32, total_error_count: i32) -> bool {
+  settings.short_circuit_after_iterations > 0 && iter_count + total_error_count >= settings.short_circuit_after_iterations
+}
+
+/// Restores a snapshot after a failed iteration. Every consecutive failure restores the most recent snapshot until
+/// `max_retries_per_snapshot` is reached, at which point the algorithm backtracks further by discarding snapshots.
 fn handle_failure(
   grid: &mut ObjectGrid,
+  settings: &ObjectGenerationSettings,
   snapshots: &mut Vec<ObjectGrid>,
   iter_count: &mut i32,
   snapshot_error_count: &mut usize,
@@ -104,7 +139,13 @@ fn handle_failure(
 ) {
   *iter_error_count += 1;
   *total_error_count += 1;
-  let snapshot_index = snapshots.len().saturating_sub(*iter_error_count);
+  let max_retries = settings.max_retries_per_snapshot.max(1);
+  let is_backtracking = (*iter_error_count).is_multiple_of(max_retries);
+  let snapshot_index = if is_backtracking {
+    snapshots.len().saturating_sub(*iter_error_count / max_retries)
+  } else {
+    snapshots.len().saturating_sub(1)
+  };
   let snapshot = snapshots.get(snapshot_index);
   if let Some(snapshot) = snapshot {
     grid.restore_from_snapshot(snapshot);
@@ -116,11 +157,14 @@ fn handle_failure(
     );
     *snapshot_error_count += 1;
   }
-  snapshots.truncate(snapshot_index);
+  if is_backtracking {
+    snapshots.truncate(snapshot_index);
+  }
 }
 
 fn handle_success(
   grid: &mut ObjectGrid,
+  settings: &ObjectGenerationSettings,
   snapshots: &mut Vec<ObjectGrid>,
   iter_count: &mut i32,
   has_entropy: &mut bool,
@@ -129,7 +173,7 @@ fn handle_success(
 ) {
   let current_entropy = grid.calculate_total_entropy();
   log_completion(grid, iter_count, iter_error_count, current_entropy);
-  if *iter_count % 10 == 0 {
+  if *iter_count % settings.snapshot_interval.max(1) == 0 {
     snapshots.push(grid.clone());
   }
   *has_entropy = result == IterationResult::Incomplete;
@@ -145,7 +189,7 @@ fn create_object_data(grid: &ObjectGrid, tile_data: &Vec<TileData>) -> Vec<Objec
       .filter_map(|tile_data| {
         grid
           .get_cell(&tile_data.flat_tile.coords.internal_grid)
-          .filter(|cell| cell.index != 0)
+          .filter(|cell| cell.is_collapsed && cell.index != 0 && !cell.possible_states[0].name.is_multi_tile_filler())
           .map(|cell| ObjectData::from_wfc_cell(tile_data, cell))
       })
       .collect::<Vec<ObjectData>>(),
@@ -180,43 +224,29 @@ fn log_failure(
   );
 }
 
-fn log_summary(start_time: u128, snapshot_error_count: usize, total_error_count: i32, grid: &ObjectGrid) {
+fn log_summary(span: shared::TimedSpan, snapshot_error_count: usize, total_error_count: i32, grid: &ObjectGrid) {
   match (total_error_count, snapshot_error_count) {
-    (0, 0) => {
-      trace!(
-        "Completed wave function collapse for {} in {} ms on [{}]",
-        grid.cg,
-        shared::get_time() - start_time,
-        shared::thread_name()
-      );
-    }
-    (1..15, 0) => {
-      debug!(
-        "Completed wave function collapse for {} (resolving {} errors) in {} ms on [{}]",
-        grid.cg,
-        total_error_count,
-        shared::get_time() - start_time,
-        shared::thread_name()
-      );
-    }
-    (15.., 0) => {
-      warn!(
-        "Completed wave function collapse for {} (resolving {} errors) in {} ms on [{}]",
-        grid.cg,
-        total_error_count,
-        shared::get_time() - start_time,
-        shared::thread_name()
-      );
-    }
-    _ => {
-      error!(
-        "Completed wave function collapse for {} (resolving {} errors and leaving {} unresolved) in {} ms on [{}]",
-        grid.cg,
-        total_error_count,
-        snapshot_error_count,
-        shared::get_time() - start_time,
-        shared::thread_name()
-      );
-    }
+    (0, 0) => span.finish(Level::TRACE, format_args!("Completed wave function collapse for {}", grid.cg)),
+    (1..15, 0) => span.finish(
+      Level::DEBUG,
+      format_args!(
+        "Completed wave function collapse for {} (resolving {} errors)",
+        grid.cg, total_error_count
+      ),
+    ),
+    (15.., 0) => span.finish(
+      Level::WARN,
+      format_args!(
+        "Completed wave function collapse for {} (resolving {} errors)",
+        grid.cg, total_error_count
+      ),
+    ),
+    _ => span.finish(
+      Level::ERROR,
+      format_args!(
+        "Completed wave function collapse for {} (resolving {} errors and leaving {} unresolved)",
+        grid.cg, total_error_count, snapshot_error_count
+      ),
+    ),
   }
 }
@@ -0,0 +1,119 @@
+use super::{handle_failure, handle_success, is_short_circuited, iterate};
+use crate::coords::point::InternalGrid;
+use crate::coords::Point;
+use crate::generation::object::lib::{IterationResult, ObjectGrid, PropagationFailure};
+use crate::resources::ObjectGenerationSettings;
+use rand::prelude::StdRng;
+
+/// Runs the wave function collapse algorithm one iteration at a time, exposing the intermediate state of the grid
+/// after each iteration. Produces the same result as `determine_objects_in_grid` when given the same inputs. Intended
+/// for debugging rule sets, not for generating objects.
+pub struct WfcStepper {
+  rng: StdRng,
+  settings: ObjectGenerationSettings,
+  grid: ObjectGrid,
+  snapshots: Vec<ObjectGrid>,
+  iter_count: i32,
+  has_entropy: bool,
+  snapshot_error_count: usize,
+  iter_error_count: usize,
+  total_error_count: i32,
+  failures: Vec<PropagationFailure>,
+  wavefront: Vec<Point<InternalGrid>>,
+  was_failure: bool,
+}
+
+impl WfcStepper {
+  pub fn new(rng: StdRng, grid: ObjectGrid, settings: ObjectGenerationSettings) -> Self {
+    Self {
+      rng,
+      settings,
+      grid,
+      snapshots: vec![],
+      iter_count: 1,
+      has_entropy: true,
+      snapshot_error_count: 0,
+      iter_error_count: 0,
+      total_error_count: 0,
+      failures: vec![],
+      wavefront: vec![],
+      was_failure: false,
+    }
+  }
+
+  /// Runs a single iteration, unless the grid has been fully collapsed already. Returns `true` while there is
+  /// entropy left in the grid.
+  pub fn step(&mut self) -> bool {
+    if !self.has_entropy {
+      return false;
+    }
+    self.wavefront.clear();
+    match iterate(&mut self.rng, &mut self.grid, &mut self.wavefront) {
+      IterationResult::Failure(failure) => {
+        self.was_failure = true;
+        self.failures.push(failure);
+        handle_failure(
+          &mut self.grid,
+          &self.settings,
+          &mut self.snapshots,
+          &mut self.iter_count,
+          &mut self.snapshot_error_count,
+          &mut self.iter_error_count,
+          &mut self.total_error_count,
+        )
+      }
+      result => {
+        self.was_failure = false;
+        handle_success(
+          &mut self.grid,
+          &self.settings,
+          &mut self.snapshots,
+          &mut self.iter_count,
+          &mut self.has_entropy,
+          &mut self.iter_error_count,
+          result,
+        )
+      }
+    }
+    if is_short_circuited(&self.settings, self.iter_count, self.total_error_count) {
+      self.has_entropy = false;
+    }
+
+    self.has_entropy
+  }
+
+  pub fn grid(&self) -> &ObjectGrid {
+    &self.grid
+  }
+
+  /// Returns the cell that was collapsed during the last iteration, if any.
+  pub fn collapsed(&self) -> Option<&Point<InternalGrid>> {
+    self.wavefront.first()
+  }
+
+  /// Returns all cells that were updated through propagation during the last iteration.
+  pub fn propagated(&self) -> &[Point<InternalGrid>] {
+    if self.wavefront.is_empty() {
+      &[]
+    } else {
+      &self.wavefront[1..]
+    }
+  }
+
+  pub fn is_complete(&self) -> bool {
+    !self.has_entropy
+  }
+
+  /// Returns `true` if the last iteration ran into a contradiction and a snapshot had to be restored.
+  pub fn was_failure(&self) -> bool {
+    self.was_failure
+  }
+
+  pub fn iteration_count(&self) -> i32 {
+    self.iter_count
+  }
+
+  pub fn failure_count(&self) -> usize {
+    self.failures.len()
+  }
+}
@@ -0,0 +1,275 @@
+use crate::constants::{ART_PACKS_PATH, ART_PACK_INDEX_FILE, DEFAULT_ART_PACK_NAME, DEFAULT_TILE_SIZE};
+use crate::coords::tile_size::{scaled_to_tile_size, tile_size};
+use crate::states::AppState;
+use bevy::app::{App, Plugin, Startup, Update};
+use bevy::asset::{Asset, AssetServer, Assets, Handle, LoadState};
+use bevy::log::*;
+use bevy::math::UVec2;
+use bevy::prelude::{in_state, Commands, IntoSystemConfigs, Res, ResMut, Resource, TypePath};
+use bevy_common_assets::ron::RonAssetPlugin;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+pub struct ArtPackPlugin;
+
+impl Plugin for ArtPackPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .add_plugins((
+        RonAssetPlugin::<ArtPackManifest>::new(&["art-pack.ron"]),
+        RonAssetPlugin::<ArtPackIndex>::new(&["index.ron"]),
+      ))
+      .init_resource::<ArtPacks>()
+      .add_systems(Startup, load_art_pack_index_system)
+      .add_systems(Update, load_art_pack_manifests_system.run_if(in_state(AppState::Loading)));
+  }
+}
+
+/// The default art pack, embedded in the binary so that the world can always be rendered, even if no art pack could be
+/// loaded from `ART_PACKS_PATH`.
+const FALLBACK_ART_PACK: &str = include_str!("../../../assets/art-packs/default.art-pack.ron");
+
+/// Lists the art pack manifests in `ART_PACKS_PATH`, relative to that folder. Folders can't be loaded on all platforms
+/// (e.g. on the web), so every art pack must be listed here.
+#[derive(serde::Deserialize, Asset, TypePath, Debug, Clone)]
+struct ArtPackIndex {
+  packs: Vec<String>,
+}
+
+/// Describes the tile sets and object sprite sheets used to render the world. Loaded from the `*.art-pack.ron` files
+/// listed in the art pack index in `ART_PACKS_PATH`, so that alternative art can be previewed without recompiling.
+#[derive(serde::Deserialize, Asset, TypePath, Debug, Clone)]
+pub struct ArtPackManifest {
+  pub name: String,
+  /// The size of a tile in pixels that the sprites of this art pack are drawn at. Defaults to `DEFAULT_TILE_SIZE`. Only
+  /// art packs that match the configured `TileSize` can be used.
+  #[serde(default = "default_tile_size")]
+  pub tile_size: u32,
+  pub placeholder: AtlasDefinition,
+  pub deep_water: AtlasDefinition,
+  pub shallow_water: AtlasDefinition,
+  pub land_dry_l1: AtlasDefinition,
+  pub land_dry_l2: AtlasDefinition,
+  pub land_dry_l3: AtlasDefinition,
+  pub land_moderate_l1: AtlasDefinition,
+  pub land_moderate_l2: AtlasDefinition,
+  pub land_moderate_l3: AtlasDefinition,
+  pub land_humid_l1: AtlasDefinition,
+  pub land_humid_l2: AtlasDefinition,
+  pub land_humid_l3: AtlasDefinition,
+  pub snow: AtlasDefinition,
+  pub objects: ObjectAtlasDefinitions,
+}
+
+impl ArtPackManifest {
+  fn fallback() -> Self {
+    ron::from_str(FALLBACK_ART_PACK).expect("Failed to parse the embedded default art pack")
+  }
+}
+
+fn default_tile_size() -> u32 {
+  DEFAULT_TILE_SIZE
+}
+
+impl Display for ArtPackManifest {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "[{}] art pack", self.name)
+  }
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct ObjectAtlasDefinitions {
+  pub water: AtlasDefinition,
+  pub shore: AtlasDefinition,
+  pub l1_dry: AtlasDefinition,
+  pub l1_moderate: AtlasDefinition,
+  pub l1_humid: AtlasDefinition,
+  pub l2_dry: AtlasDefinition,
+  pub l2_moderate: AtlasDefinition,
+  pub l2_humid: AtlasDefinition,
+  pub l3_dry: AtlasDefinition,
+  pub l3_moderate: AtlasDefinition,
+  pub l3_humid: AtlasDefinition,
+  /// The objects placed on snow. Optional, since the default art pack doesn't provide any.
+  #[serde(default)]
+  pub l4: Option<AtlasDefinition>,
+  pub trees_dry: AtlasDefinition,
+  pub trees_moderate: AtlasDefinition,
+  pub trees_humid: AtlasDefinition,
+}
+
+/// Describes a single texture atlas that consists of a grid of equally sized sprites.
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct AtlasDefinition {
+  /// The path to the texture, relative to the assets folder.
+  pub path: String,
+  pub columns: u32,
+  pub rows: u32,
+  /// The size of a single sprite in pixels, expressed relative to `DEFAULT_TILE_SIZE`. Defaults to a single tile.
+  #[serde(default)]
+  pub sprite_size: Option<(u32, u32)>,
+  /// The factor by which the sprite index of a tile type is multiplied. Defaults to the number of columns.
+  #[serde(default)]
+  pub index_offset: Option<usize>,
+  /// Whether the columns of each row are the frames of an animation.
+  #[serde(default)]
+  pub is_animated: bool,
+  /// The number of columns in the `Fill` row that contain alternative sprites, one of which is chosen for each `Fill`
+  /// tile. Defaults to a single sprite and is limited to the number of columns.
+  #[serde(default)]
+  pub fill_variants: Option<usize>,
+}
+
+impl AtlasDefinition {
+  /// Returns the size of a single sprite, scaled to the current tile size.
+  pub fn sprite_size(&self) -> UVec2 {
+    let (x, y) = self.sprite_size.unwrap_or((DEFAULT_TILE_SIZE, DEFAULT_TILE_SIZE));
+
+    scaled_to_tile_size(UVec2::new(x, y))
+  }
+
+  pub fn index_offset(&self) -> usize {
+    self.index_offset.unwrap_or(self.columns as usize)
+  }
+
+  pub fn fill_variants(&self) -> usize {
+    self.fill_variants.unwrap_or(1).clamp(1, self.columns.max(1) as usize)
+  }
+}
+
+/// All art packs listed in the art pack index, sorted by name, and the one that is currently used to render the world.
+/// A different art pack is selected by calling `request`, which causes the `GenerationResourcesCollection` to be
+/// rebuilt and all chunks to be re-spawned.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct ArtPacks {
+  index: Handle<ArtPackIndex>,
+  manifests: Option<Vec<Handle<ArtPackManifest>>>,
+  packs: Vec<ArtPackManifest>,
+  selected: usize,
+  requested: Option<usize>,
+}
+
+impl ArtPacks {
+  /// Returns `true` once the index and every art pack manifest listed in it have either been loaded or failed to load.
+  pub fn is_loaded(&self, asset_server: &AssetServer) -> bool {
+    self.manifests.as_ref().is_some_and(|manifests| {
+      manifests.iter().all(|handle| {
+        matches!(
+          asset_server.get_load_state(handle),
+          Some(LoadState::Loaded) | Some(LoadState::Failed(_))
+        )
+      })
+    })
+  }
+
+  pub fn names(&self) -> Vec<String> {
+    self.packs.iter().map(|pack| pack.name.clone()).collect()
+  }
+
+  pub fn selected(&self) -> usize {
+    self.selected
+  }
+
+  pub fn get_selected(&self) -> Option<&ArtPackManifest> {
+    self.packs.get(self.selected)
+  }
+
+  /// Requests switching to the art pack with the given index. Ignored if the art pack is already selected or doesn't
+  /// exist.
+  pub fn request(&mut self, index: usize) {
+    if index != self.selected && index < self.packs.len() {
+      self.requested = Some(index);
+    }
+  }
+
+  /// Selects and returns the requested art pack, if any.
+  pub fn take_requested(&mut self) -> Option<&ArtPackManifest> {
+    let index = self.requested.take()?;
+    self.selected = index;
+
+    self.packs.get(index)
+  }
+
+  /// Collects all loaded art pack manifests that match the configured tile size and selects `DEFAULT_ART_PACK_NAME` or,
+  /// if it doesn't exist, the first art pack found. Falls back to the embedded default art pack if none could be
+  /// loaded.
+  ///
+  /// # Panics
+  /// If no art pack, including the embedded default art pack, matches the configured tile size, since the texture
+  /// atlas layouts would not match the sprite sheets.
+  pub fn collect(&mut self, manifests: &Assets<ArtPackManifest>) {
+    let tile_size = tile_size();
+    self.packs = self
+      .manifests
+      .iter()
+      .flatten()
+      .filter_map(|handle| manifests.get(handle).cloned())
+      .filter(|pack| {
+        if pack.tile_size != tile_size {
+          warn!(
+            "Ignoring {} because it uses a tile size of {}px instead of {}px",
+            pack, pack.tile_size, tile_size
+          );
+        }
+        pack.tile_size == tile_size
+      })
+      .collect();
+    if self.packs.is_empty() {
+      let fallback = ArtPackManifest::fallback();
+      assert_eq!(
+        fallback.tile_size, tile_size,
+        "Failed to find an art pack in [{}/{}] that supports a tile size of {}px",
+        ART_PACKS_PATH, ART_PACK_INDEX_FILE, tile_size
+      );
+      warn!(
+        "Failed to load any art packs listed in [{}/{}], using the embedded default art pack instead",
+        ART_PACKS_PATH, ART_PACK_INDEX_FILE
+      );
+      self.packs.push(fallback);
+    }
+    self.packs.sort_by(|a, b| a.name.cmp(&b.name));
+    self.selected = self
+      .packs
+      .iter()
+      .position(|pack| pack.name == DEFAULT_ART_PACK_NAME)
+      .unwrap_or(0);
+    self.requested = None;
+    debug!(
+      "Found {} art pack(s) [{}], selected [{}]",
+      self.packs.len(),
+      self.names().join(", "),
+      self.packs[self.selected].name
+    );
+  }
+}
+
+fn load_art_pack_index_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+  commands.insert_resource(ArtPacks {
+    index: asset_server.load(format!("{}/{}", ART_PACKS_PATH, ART_PACK_INDEX_FILE)),
+    ..Default::default()
+  });
+}
+
+/// Loads every art pack manifest listed in the art pack index once the index has been loaded. If the index fails to
+/// load, no manifests are loaded, which causes the embedded default art pack to be used.
+fn load_art_pack_manifests_system(
+  asset_server: Res<AssetServer>,
+  indices: Res<Assets<ArtPackIndex>>,
+  mut art_packs: ResMut<ArtPacks>,
+) {
+  if art_packs.manifests.is_some() {
+    return;
+  }
+  if let Some(index) = indices.get(&art_packs.index) {
+    art_packs.manifests = Some(
+      index
+        .packs
+        .iter()
+        .map(|path| asset_server.load(format!("{}/{}", ART_PACKS_PATH, path)))
+        .collect(),
+    );
+  } else if let Some(LoadState::Failed(error)) = asset_server.get_load_state(&art_packs.index) {
+    warn!("Failed to load the art pack index: {}", error);
+    art_packs.manifests = Some(vec![]);
+  }
+}
@@ -1,9 +1,10 @@
+use crate::components::PendingDespawnComponent;
 use crate::coords::point::World;
 use crate::coords::Point;
 use crate::generation::lib::ChunkComponent;
 use bevy::app::{App, Plugin};
 use bevy::log::trace;
-use bevy::prelude::{OnAdd, OnRemove, Query, ResMut, Resource, Trigger};
+use bevy::prelude::{Has, OnAdd, OnRemove, Query, ResMut, Resource, Trigger};
 use bevy::utils::HashMap;
 
 pub struct ChunkComponentIndexPlugin;
@@ -13,12 +14,15 @@ impl Plugin for ChunkComponentIndexPlugin {
     app
       .init_resource::<ChunkComponentIndex>()
       .add_observer(on_add_chunk_component_trigger)
-      .add_observer(on_remove_chunk_component_trigger);
+      .add_observer(on_remove_chunk_component_trigger)
+      .add_observer(on_add_pending_despawn_component_trigger);
   }
 }
 
 /// Contains a clone of the `ChunkComponent` of each chunk entity that currently exists in the world. This index is
-/// kept up-to-date by observing the `OnAdd<ChunkComponent>` and `OnRemove<ChunkComponent>` triggers.
+/// kept up-to-date by observing the `OnAdd<ChunkComponent>` and `OnRemove<ChunkComponent>` triggers. Chunks that are
+/// fading out before being despawned are removed as soon as they get a `PendingDespawnComponent`, so that they are
+/// generated again if they are needed before the fade has completed.
 #[derive(Resource, Default)]
 pub struct ChunkComponentIndex {
   map: HashMap<Point<World>, ChunkComponent>,
@@ -46,10 +50,29 @@ fn on_add_chunk_component_trigger(
 
 fn on_remove_chunk_component_trigger(
   trigger: Trigger<OnRemove, ChunkComponent>,
-  query: Query<&ChunkComponent>,
+  query: Query<(&ChunkComponent, Has<PendingDespawnComponent>)>,
   mut index: ResMut<ChunkComponentIndex>,
 ) {
-  let cc = query.get(trigger.entity()).expect("Failed to get ChunkComponent");
+  let (cc, is_pending_despawn) = query.get(trigger.entity()).expect("Failed to get ChunkComponent");
+  if is_pending_despawn {
+    // Already removed and the key may have been taken by a chunk that has been generated in its place since
+    return;
+  }
   index.map.remove(&cc.coords.world);
   trace!("ChunkComponentIndex -> Removed ChunkComponent with key {:?}", cc.coords.world);
 }
+
+fn on_add_pending_despawn_component_trigger(
+  trigger: Trigger<OnAdd, PendingDespawnComponent>,
+  query: Query<&ChunkComponent>,
+  mut index: ResMut<ChunkComponentIndex>,
+) {
+  let Ok(cc) = query.get(trigger.entity()) else {
+    return;
+  };
+  index.map.remove(&cc.coords.world);
+  trace!(
+    "ChunkComponentIndex -> Removed ChunkComponent with key {:?} because it is pending despawn",
+    cc.coords.world
+  );
+}
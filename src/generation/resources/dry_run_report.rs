@@ -0,0 +1,52 @@
+use crate::constants::{CHUNK_HEIGHT, CHUNK_WIDTH};
+use crate::coords::point::ChunkGrid;
+use crate::coords::Point;
+use bevy::app::{App, Plugin};
+use bevy::prelude::Resource;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+pub struct DryRunReportPlugin;
+
+impl Plugin for DryRunReportPlugin {
+  fn build(&self, app: &mut App) {
+    app.init_resource::<DryRunReport>();
+  }
+}
+
+/// Contains the outcome of the most recent `UpdateWorldEvent` that was processed as a dry run i.e. the chunks that
+/// would have been spawned and pruned, had the event been processed normally. The world is never mutated when
+/// producing this report.
+#[derive(Resource, Default, Debug, Clone, PartialEq)]
+pub struct DryRunReport {
+  pub current_cg: Point<ChunkGrid>,
+  pub new_current_cg: Point<ChunkGrid>,
+  pub chunks_to_spawn: Vec<Point<ChunkGrid>>,
+  pub chunks_to_prune: Vec<Point<ChunkGrid>>,
+}
+
+impl DryRunReport {
+  /// Returns the estimated number of tiles that would have to be generated and spawned.
+  pub fn estimated_tile_count(&self) -> usize {
+    self.chunks_to_spawn.len() * (CHUNK_WIDTH * CHUNK_HEIGHT) as usize
+  }
+}
+
+impl Display for DryRunReport {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "Dry run report for moving the current chunk from {} to {}:\n\
+      ├─> Chunks to spawn ({}): {:?}\n\
+      ├─> Chunks to prune ({}): {:?}\n\
+      └─> Estimated cost: {} tiles",
+      self.current_cg,
+      self.new_current_cg,
+      self.chunks_to_spawn.len(),
+      self.chunks_to_spawn,
+      self.chunks_to_prune.len(),
+      self.chunks_to_prune,
+      self.estimated_tile_count()
+    )
+  }
+}
@@ -1,15 +1,21 @@
-use crate::constants::*;
-use crate::generation::lib::{TerrainType, TileType};
-use crate::generation::object::lib::{Connection, ObjectName};
-use crate::generation::resources::Climate;
+use crate::coords::point::InternalGrid;
+use crate::coords::Point;
+use crate::events::PruneWorldEvent;
+use crate::generation::lib::{shared, TerrainType, Tile, TileType, Walkability, WalkabilityRule};
+use crate::generation::object::lib::{
+  expand_rules, Connection, MultiTileObject, ObjectAtlas, ObjectCategory, ObjectDefinition, ObjectGroup, ObjectLight,
+  ObjectName, ObjectPlacement, ObjectRegistry, ObjectShadow, RareFeatureTemplate,
+};
+use crate::generation::resources::{ArtPackManifest, ArtPacks, AtlasDefinition, Climate};
 use crate::states::AppState;
 use bevy::app::{App, Plugin, Startup, Update};
 use bevy::asset::{Asset, AssetServer, Assets, Handle, LoadState};
+use bevy::ecs::system::SystemParam;
 use bevy::log::*;
 use bevy::math::UVec2;
 use bevy::prelude::{
-  in_state, Commands, Image, IntoSystemConfigs, NextState, OnExit, Reflect, Res, ResMut, Resource, TextureAtlasLayout,
-  TypePath,
+  in_state, Commands, EventWriter, Image, IntoSystemConfigs, NextState, OnExit, Reflect, Res, ResMut, Resource,
+  TextureAtlasLayout, TypePath,
 };
 use bevy::utils::{HashMap, HashSet};
 use bevy_common_assets::ron::RonAssetPlugin;
@@ -24,11 +30,21 @@ impl Plugin for GenerationResourcesCollectionPlugin {
       .add_plugins((
         RonAssetPlugin::<TerrainRuleSet>::new(&["terrain.ruleset.ron"]),
         RonAssetPlugin::<TileTypeRuleSet>::new(&["tile-type.ruleset.ron"]),
+        RonAssetPlugin::<MultiTileRuleSet>::new(&["multi-tile.ruleset.ron"]),
+        RonAssetPlugin::<PlacementRuleSet>::new(&["placement.ruleset.ron"]),
+        RonAssetPlugin::<RegistryRuleSet>::new(&["registry.ruleset.ron"]),
+        RonAssetPlugin::<LightRuleSet>::new(&["light.ruleset.ron"]),
+        RonAssetPlugin::<RareFeatureRuleSet>::new(&["rare-feature.ruleset.ron"]),
+        RonAssetPlugin::<WalkabilityRuleSet>::new(&["walkability.ruleset.ron"]),
       ))
       .init_resource::<GenerationResourcesCollection>()
       .add_systems(Startup, load_rule_sets_system)
       .add_systems(Update, check_loading_state.run_if(in_state(AppState::Loading)))
-      .add_systems(OnExit(AppState::Loading), initialise_resources_system);
+      .add_systems(
+        OnExit(AppState::Loading),
+        (initialise_resources_system, initialise_art_pack_system).chain(),
+      )
+      .add_systems(Update, switch_art_pack_system.run_if(in_state(AppState::Running)));
   }
 }
 
@@ -61,9 +77,23 @@ impl Display for TerrainRuleSet {
 #[derive(serde::Deserialize, Debug, Clone, Reflect)]
 pub struct TerrainState {
   pub name: ObjectName,
+  /// The index of the sprite in the sprite sheet. Derived from the object registry instead of being read from the rule
+  /// set files.
+  #[serde(skip)]
   pub index: i32,
+  /// The sprite sheet that contains the sprite. Derived from the object registry instead of being read from the rule
+  /// set files.
+  #[serde(skip)]
+  pub atlas: ObjectAtlas,
   pub weight: i32,
   pub permitted_neighbours: Vec<(Connection, Vec<ObjectName>)>,
+  /// Whether this state is only permitted on tiles that are part of the beach band along a coastline.
+  #[serde(default)]
+  pub is_beach_only: bool,
+  /// The offsets to all cells occupied by the multi-tile object this state is part of, if any. Derived from the
+  /// multi-tile object definitions instead of being read from the rule set files.
+  #[serde(skip)]
+  pub footprint: Vec<Point<InternalGrid>>,
 }
 
 #[derive(Resource, Default, Debug, Clone)]
@@ -86,6 +116,123 @@ pub struct TileTypeState {
   pub permitted_self: Vec<ObjectName>,
 }
 
+#[derive(Resource, Default, Debug, Clone)]
+struct MultiTileRuleSetHandle(Handle<MultiTileRuleSet>);
+
+#[derive(serde::Deserialize, Asset, TypePath, Debug, Clone)]
+struct MultiTileRuleSet {
+  objects: Vec<MultiTileObject>,
+}
+
+impl Display for MultiTileRuleSet {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "Multi-tile rule set with {} objects", self.objects.len())
+  }
+}
+
+#[derive(Resource, Default, Debug, Clone)]
+struct PlacementRuleSetHandle(Handle<PlacementRuleSet>);
+
+#[derive(serde::Deserialize, Asset, TypePath, Debug, Clone)]
+struct PlacementRuleSet {
+  objects: Vec<ObjectPlacement>,
+}
+
+impl Display for PlacementRuleSet {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "Placement rule set with {} objects", self.objects.len())
+  }
+}
+
+#[derive(Resource, Default, Debug, Clone)]
+struct RegistryRuleSetHandle(Handle<RegistryRuleSet>);
+
+#[derive(serde::Deserialize, Asset, TypePath, Debug, Clone)]
+struct RegistryRuleSet {
+  objects: Vec<ObjectDefinition>,
+}
+
+impl Display for RegistryRuleSet {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "Registry rule set with {} objects", self.objects.len())
+  }
+}
+
+#[derive(Resource, Default, Debug, Clone)]
+struct LightRuleSetHandle(Handle<LightRuleSet>);
+
+#[derive(serde::Deserialize, Asset, TypePath, Debug, Clone)]
+struct LightRuleSet {
+  objects: Vec<ObjectLight>,
+}
+
+impl Display for LightRuleSet {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "Light rule set with {} objects", self.objects.len())
+  }
+}
+
+#[derive(Resource, Default, Debug, Clone)]
+struct WalkabilityRuleSetHandle(Handle<WalkabilityRuleSet>);
+
+#[derive(serde::Deserialize, Asset, TypePath, Debug, Clone)]
+struct WalkabilityRuleSet {
+  rules: Vec<WalkabilityRule>,
+}
+
+impl Display for WalkabilityRuleSet {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "Walkability rule set with {} rules", self.rules.len())
+  }
+}
+
+#[derive(Resource, Default, Debug, Clone)]
+struct RareFeatureRuleSetHandle(Handle<RareFeatureRuleSet>);
+
+#[derive(serde::Deserialize, Asset, TypePath, Debug, Clone)]
+struct RareFeatureRuleSet {
+  features: Vec<RareFeatureTemplate>,
+  #[serde(default)]
+  rocky_features: Vec<RareFeatureTemplate>,
+}
+
+impl Display for RareFeatureRuleSet {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "Rare feature rule set with {} features and {} rocky features",
+      self.features.len(),
+      self.rocky_features.len()
+    )
+  }
+}
+
+/// The handles of all rule sets, loaded in [`load_rule_sets_system`].
+#[derive(SystemParam)]
+struct RuleSetHandles<'w> {
+  terrain: Res<'w, TerrainRuleSetHandle>,
+  tile_type: Res<'w, TileTypeRuleSetHandle>,
+  multi_tile: Res<'w, MultiTileRuleSetHandle>,
+  placement: Res<'w, PlacementRuleSetHandle>,
+  registry: Res<'w, RegistryRuleSetHandle>,
+  light: Res<'w, LightRuleSetHandle>,
+  rare_feature: Res<'w, RareFeatureRuleSetHandle>,
+  walkability: Res<'w, WalkabilityRuleSetHandle>,
+}
+
+/// The assets that the [`RuleSetHandles`] point to.
+#[derive(SystemParam)]
+struct RuleSetAssets<'w> {
+  terrain: ResMut<'w, Assets<TerrainRuleSet>>,
+  tile_type: ResMut<'w, Assets<TileTypeRuleSet>>,
+  multi_tile: ResMut<'w, Assets<MultiTileRuleSet>>,
+  placement: ResMut<'w, Assets<PlacementRuleSet>>,
+  registry: ResMut<'w, Assets<RegistryRuleSet>>,
+  light: ResMut<'w, Assets<LightRuleSet>>,
+  rare_feature: ResMut<'w, Assets<RareFeatureRuleSet>>,
+  walkability: ResMut<'w, Assets<WalkabilityRuleSet>>,
+}
+
 fn load_rule_sets_system(mut commands: Commands, asset_server: Res<AssetServer>) {
   let mut rule_set_handles = Vec::new();
   for i in 0..TerrainType::length() {
@@ -99,21 +246,61 @@ fn load_rule_sets_system(mut commands: Commands, asset_server: Res<AssetServer>)
   commands.insert_resource(TerrainRuleSetHandle(rule_set_handles));
   let handle = asset_server.load("objects/all.tile-type.ruleset.ron");
   commands.insert_resource(TileTypeRuleSetHandle(handle));
+  let handle = asset_server.load("objects/all.multi-tile.ruleset.ron");
+  commands.insert_resource(MultiTileRuleSetHandle(handle));
+  let handle = asset_server.load("objects/all.placement.ruleset.ron");
+  commands.insert_resource(PlacementRuleSetHandle(handle));
+  let handle = asset_server.load("objects/all.registry.ruleset.ron");
+  commands.insert_resource(RegistryRuleSetHandle(handle));
+  let handle = asset_server.load("objects/all.light.ruleset.ron");
+  commands.insert_resource(LightRuleSetHandle(handle));
+  let handle = asset_server.load("objects/all.rare-feature.ruleset.ron");
+  commands.insert_resource(RareFeatureRuleSetHandle(handle));
+  let handle = asset_server.load("objects/all.walkability.ruleset.ron");
+  commands.insert_resource(WalkabilityRuleSetHandle(handle));
 }
 
 fn check_loading_state(
   asset_server: Res<AssetServer>,
-  terrain_handles: Res<TerrainRuleSetHandle>,
-  tile_type_handle: Res<TileTypeRuleSetHandle>,
+  handles: RuleSetHandles,
+  art_packs: Res<ArtPacks>,
   mut state: ResMut<NextState<AppState>>,
 ) {
-  for handle in &terrain_handles.0 {
+  for handle in &handles.terrain.0 {
     if is_loading(asset_server.get_load_state(handle)) {
       info_once!("Waiting for assets to load...");
       return;
     }
   }
-  if is_loading(asset_server.get_load_state(&tile_type_handle.0)) {
+  if is_loading(asset_server.get_load_state(&handles.tile_type.0)) {
+    info_once!("Waiting for assets to load...");
+    return;
+  }
+  if is_loading(asset_server.get_load_state(&handles.multi_tile.0)) {
+    info_once!("Waiting for assets to load...");
+    return;
+  }
+  if is_loading(asset_server.get_load_state(&handles.placement.0)) {
+    info_once!("Waiting for assets to load...");
+    return;
+  }
+  if is_loading(asset_server.get_load_state(&handles.registry.0)) {
+    info_once!("Waiting for assets to load...");
+    return;
+  }
+  if is_loading(asset_server.get_load_state(&handles.light.0)) {
+    info_once!("Waiting for assets to load...");
+    return;
+  }
+  if is_loading(asset_server.get_load_state(&handles.rare_feature.0)) {
+    info_once!("Waiting for assets to load...");
+    return;
+  }
+  if is_loading(asset_server.get_load_state(&handles.walkability.0)) {
+    info_once!("Waiting for assets to load...");
+    return;
+  }
+  if !art_packs.is_loaded(&asset_server) {
     info_once!("Waiting for assets to load...");
     return;
   }
@@ -147,6 +334,7 @@ pub struct GenerationResourcesCollection {
   pub land_humid_l1: AssetCollection,
   pub land_humid_l2: AssetCollection,
   pub land_humid_l3: AssetCollection,
+  pub snow: AssetCollection,
   pub objects: ObjectResources,
 }
 
@@ -154,6 +342,13 @@ pub struct GenerationResourcesCollection {
 pub struct ObjectResources {
   pub terrain_rules: HashMap<TerrainType, Vec<TerrainState>>,
   pub tile_type_rules: HashMap<TileType, Vec<ObjectName>>,
+  pub multi_tile_objects: Vec<MultiTileObject>,
+  pub placement_rules: HashMap<ObjectName, ObjectPlacement>,
+  pub registry: ObjectRegistry,
+  pub light_rules: HashMap<ObjectName, ObjectLight>,
+  pub rare_features: Vec<RareFeatureTemplate>,
+  pub rocky_features: Vec<RareFeatureTemplate>,
+  pub walkability: Walkability,
   pub water: AssetCollection,
   pub shore: AssetCollection,
   pub l1_dry: AssetCollection,
@@ -165,12 +360,109 @@ pub struct ObjectResources {
   pub l3_dry: AssetCollection,
   pub l3_moderate: AssetCollection,
   pub l3_humid: AssetCollection,
+  pub l4: AssetCollection,
   pub trees_dry: AssetCollection,
   pub trees_moderate: AssetCollection,
   pub trees_humid: AssetCollection,
 }
 
+impl ObjectResources {
+  /// Returns the placement rule of the given object, falling back to `ObjectPlacement::default_for` for objects and
+  /// values that are not listed in the placement rule set.
+  pub fn get_placement(&self, name: ObjectName) -> ObjectPlacement {
+    let default = ObjectPlacement::default_for(name, self.get_atlas(name));
+    match self.placement_rules.get(&name) {
+      Some(placement) => ObjectPlacement {
+        jitter_radius: placement.jitter_radius.or(default.jitter_radius),
+        ..*placement
+      },
+      None => default,
+    }
+  }
+
+  pub fn get_light(&self, name: ObjectName) -> Option<ObjectLight> {
+    self.light_rules.get(&name).copied()
+  }
+
+  /// Returns the definition of the given object from the object registry, if any. Multi-tile fillers share the
+  /// definition of their anchor.
+  pub fn get_definition(&self, name: ObjectName) -> Option<&ObjectDefinition> {
+    let name = match name {
+      ObjectName::MultiTileFiller(index, ..) => self.multi_tile_objects.get(index as usize)?.anchor,
+      name => name,
+    };
+
+    self.registry.get(name)
+  }
+
+  pub fn get_category(&self, name: ObjectName) -> Option<ObjectCategory> {
+    self.get_definition(name)?.category
+  }
+
+  pub fn get_atlas(&self, name: ObjectName) -> ObjectAtlas {
+    self.get_definition(name).map(|d| d.atlas).unwrap_or_default()
+  }
+
+  pub fn get_group(&self, name: ObjectName) -> ObjectGroup {
+    self.get_definition(name).map(|d| d.group).unwrap_or_default()
+  }
+
+  pub fn get_shadow(&self, name: ObjectName) -> Option<ObjectShadow> {
+    self.get_definition(name)?.shadow
+  }
+}
+
 impl GenerationResourcesCollection {
+  /// Replaces all textures and texture atlas layouts with the ones described by the given art pack. Object rules are
+  /// not affected.
+  pub fn apply_art_pack(
+    &mut self,
+    manifest: &ArtPackManifest,
+    asset_server: &AssetServer,
+    layouts: &mut Assets<TextureAtlasLayout>,
+  ) {
+    // Placeholder tile set
+    self.placeholder = asset_collection(asset_server, layouts, &manifest.placeholder).stat;
+
+    // Detailed tile sets
+    self.deep_water = asset_collection(asset_server, layouts, &manifest.deep_water);
+    self.shallow_water = asset_collection(asset_server, layouts, &manifest.shallow_water);
+    self.land_dry_l1 = asset_collection(asset_server, layouts, &manifest.land_dry_l1);
+    self.land_dry_l2 = asset_collection(asset_server, layouts, &manifest.land_dry_l2);
+    self.land_dry_l3 = asset_collection(asset_server, layouts, &manifest.land_dry_l3);
+    self.land_moderate_l1 = asset_collection(asset_server, layouts, &manifest.land_moderate_l1);
+    self.land_moderate_l2 = asset_collection(asset_server, layouts, &manifest.land_moderate_l2);
+    self.land_moderate_l3 = asset_collection(asset_server, layouts, &manifest.land_moderate_l3);
+    self.land_humid_l1 = asset_collection(asset_server, layouts, &manifest.land_humid_l1);
+    self.land_humid_l2 = asset_collection(asset_server, layouts, &manifest.land_humid_l2);
+    self.land_humid_l3 = asset_collection(asset_server, layouts, &manifest.land_humid_l3);
+    self.snow = asset_collection(asset_server, layouts, &manifest.snow);
+
+    // Objects
+    let definitions = &manifest.objects;
+    self.objects.water = asset_collection(asset_server, layouts, &definitions.water);
+    self.objects.shore = asset_collection(asset_server, layouts, &definitions.shore);
+    self.objects.l1_dry = asset_collection(asset_server, layouts, &definitions.l1_dry);
+    self.objects.l1_moderate = asset_collection(asset_server, layouts, &definitions.l1_moderate);
+    self.objects.l1_humid = asset_collection(asset_server, layouts, &definitions.l1_humid);
+    self.objects.l2_dry = asset_collection(asset_server, layouts, &definitions.l2_dry);
+    self.objects.l2_moderate = asset_collection(asset_server, layouts, &definitions.l2_moderate);
+    self.objects.l2_humid = asset_collection(asset_server, layouts, &definitions.l2_humid);
+    self.objects.l3_dry = asset_collection(asset_server, layouts, &definitions.l3_dry);
+    self.objects.l3_moderate = asset_collection(asset_server, layouts, &definitions.l3_moderate);
+    self.objects.l3_humid = asset_collection(asset_server, layouts, &definitions.l3_humid);
+    self.objects.l4 = definitions
+      .l4
+      .as_ref()
+      .map(|definition| asset_collection(asset_server, layouts, definition))
+      .unwrap_or_default();
+    self.objects.trees_dry = asset_collection(asset_server, layouts, &definitions.trees_dry);
+    self.objects.trees_moderate = asset_collection(asset_server, layouts, &definitions.trees_moderate);
+    self.objects.trees_humid = asset_collection(asset_server, layouts, &definitions.trees_humid);
+    info!("Applied {}", manifest);
+    audit_object_sprite_indices(self, layouts);
+  }
+
   pub fn get_terrain_collection(&self, terrain: TerrainType, climate: Climate) -> &AssetCollection {
     match (terrain, climate) {
       (TerrainType::DeepWater, _) => &self.deep_water,
@@ -184,12 +476,21 @@ impl GenerationResourcesCollection {
       (TerrainType::Land3, Climate::Dry) => &self.land_dry_l3,
       (TerrainType::Land3, Climate::Moderate) => &self.land_moderate_l3,
       (TerrainType::Land3, Climate::Humid) => &self.land_humid_l3,
+      (TerrainType::Land4, _) => &self.snow,
       (TerrainType::Any, _) => panic!("You must not use TerrainType::Any when rendering tiles"),
     }
   }
 
-  pub fn get_object_collection(&self, terrain: TerrainType, climate: Climate, is_large_sprite: bool) -> &AssetCollection {
-    match (terrain, climate, is_large_sprite) {
+  /// Returns the index of the sprite used to render the given tile without animations.
+  pub fn get_static_sprite_index(&self, tile: &Tile) -> usize {
+    self
+      .get_terrain_collection(tile.terrain, tile.climate)
+      .stat
+      .sprite_index(tile.tile_type, shared::hash_point(tile.coords.tile_grid))
+  }
+
+  pub fn get_object_collection(&self, terrain: TerrainType, climate: Climate, atlas: ObjectAtlas) -> &AssetCollection {
+    match (terrain, climate, atlas == ObjectAtlas::Trees) {
       (TerrainType::DeepWater, _, _) => &self.objects.water,
       (TerrainType::ShallowWater, _, _) => &self.objects.shore,
       (TerrainType::Land1, Climate::Dry, _) => &self.objects.l1_dry,
@@ -204,6 +505,7 @@ impl GenerationResourcesCollection {
       (TerrainType::Land3, Climate::Dry, _) => &self.objects.l3_dry,
       (TerrainType::Land3, Climate::Moderate, _) => &self.objects.l3_moderate,
       (TerrainType::Land3, Climate::Humid, _) => &self.objects.l3_humid,
+      (TerrainType::Land4, _, _) => &self.objects.l4,
       (TerrainType::Any, _, _) => panic!("You must not use TerrainType::Any when rendering tiles"),
     }
   }
@@ -216,17 +518,13 @@ pub struct AssetCollection {
   pub animated_tile_types: HashSet<TileType>,
 }
 
-impl AssetCollection {
-  pub fn index_offset(&self) -> usize {
-    self.stat.index_offset
-  }
-}
-
 #[derive(Debug, Clone)]
 pub struct AssetPack {
   pub texture: Handle<Image>,
   pub texture_atlas_layout: Handle<TextureAtlasLayout>,
   pub index_offset: usize,
+  /// The number of alternative sprites for `Fill` tiles, located in the columns of the `Fill` row.
+  pub fill_variants: usize,
 }
 
 impl Default for AssetPack {
@@ -235,177 +533,184 @@ impl Default for AssetPack {
       texture: Handle::default(),
       texture_atlas_layout: Handle::default(),
       index_offset: 1,
+      fill_variants: 1,
     }
   }
 }
 
 impl AssetPack {
-  pub fn new(texture: Handle<Image>, texture_atlas_layout: Handle<TextureAtlasLayout>) -> Self {
-    Self {
-      texture,
-      texture_atlas_layout,
-      index_offset: 1,
+  /// Returns the sprite index for the given tile type. `Fill` tiles use one of the `fill_variants`, chosen using the
+  /// given hash, so that large areas of the same terrain don't look flat.
+  pub fn sprite_index(&self, tile_type: TileType, hash: u64) -> usize {
+    let index = tile_type.get_sprite_index(self.index_offset);
+    if tile_type == TileType::Fill && self.fill_variants > 1 {
+      return index + (hash % self.fill_variants as u64) as usize;
     }
+
+    index
   }
 }
 
 fn initialise_resources_system(
-  asset_server: Res<AssetServer>,
-  mut layouts: ResMut<Assets<TextureAtlasLayout>>,
   mut asset_collection: ResMut<GenerationResourcesCollection>,
-  terrain_rule_set_handle: Res<TerrainRuleSetHandle>,
-  mut terrain_rule_set_assets: ResMut<Assets<TerrainRuleSet>>,
-  tile_type_rule_set_handle: Res<TileTypeRuleSetHandle>,
-  mut tile_type_rule_set_assets: ResMut<Assets<TileTypeRuleSet>>,
+  handles: RuleSetHandles,
+  mut assets: RuleSetAssets,
 ) {
-  // Placeholder tile set
-  let default_layout = TextureAtlasLayout::from_grid(
-    UVec2::splat(TILE_SIZE),
-    TILE_SET_PLACEHOLDER_COLUMNS,
-    TILE_SET_PLACEHOLDER_ROWS,
-    None,
-    None,
-  );
-  let default_texture_atlas_layout = layouts.add(default_layout);
-  asset_collection.placeholder = AssetPack::new(asset_server.load(TILE_SET_PLACEHOLDER_PATH), default_texture_atlas_layout);
-
-  // Detailed tile sets
-  asset_collection.deep_water = tile_set_static(&asset_server, &mut layouts, TS_WATER_PATH);
-  asset_collection.shallow_water = tile_set_default_animations(&asset_server, &mut layouts, TS_SHORE_PATH);
-  asset_collection.land_dry_l1 = tile_set_default_animations(&asset_server, &mut layouts, TS_LAND_DRY_L1_PATH);
-  asset_collection.land_dry_l2 = tile_set_static(&asset_server, &mut layouts, TS_LAND_DRY_L2_PATH);
-  asset_collection.land_dry_l3 = tile_set_static(&asset_server, &mut layouts, TS_LAND_DRY_L3_PATH);
-  asset_collection.land_moderate_l1 = tile_set_default_animations(&asset_server, &mut layouts, TS_LAND_MODERATE_L1_PATH);
-  asset_collection.land_moderate_l2 = tile_set_static(&asset_server, &mut layouts, TS_LAND_MODERATE_L2_PATH);
-  asset_collection.land_moderate_l3 = tile_set_static(&asset_server, &mut layouts, TS_LAND_MODERATE_L3_PATH);
-  asset_collection.land_humid_l1 = tile_set_default_animations(&asset_server, &mut layouts, TS_LAND_HUMID_L1_PATH);
-  asset_collection.land_humid_l2 = tile_set_static(&asset_server, &mut layouts, TS_LAND_HUMID_L2_PATH);
-  asset_collection.land_humid_l3 = tile_set_static(&asset_server, &mut layouts, TS_LAND_HUMID_L3_PATH);
-
-  // Objects: Trees
-  let static_trees_layout = TextureAtlasLayout::from_grid(TREES_OBJ_SIZE, TREES_OBJ_COLUMNS, TREES_OBJ_ROWS, None, None);
-  let static_trees_atlas_layout = layouts.add(static_trees_layout);
-  asset_collection.objects.trees_dry.stat =
-    AssetPack::new(asset_server.load(TREES_DRY_OBJ_PATH), static_trees_atlas_layout.clone());
-  asset_collection.objects.trees_moderate.stat =
-    AssetPack::new(asset_server.load(TREES_MODERATE_OBJ_PATH), static_trees_atlas_layout.clone());
-  asset_collection.objects.trees_humid.stat =
-    AssetPack::new(asset_server.load(TREES_HUMID_OBJ_PATH), static_trees_atlas_layout);
-
-  // Objects: Terrain
-  asset_collection.objects.water = object_assets_static(&asset_server, &mut layouts, WATER_DEEP_OBJ_PATH);
-  asset_collection.objects.shore = object_assets_static(&asset_server, &mut layouts, WATER_SHALLOW_OBJ_PATH);
-  asset_collection.objects.l1_dry = object_assets_static(&asset_server, &mut layouts, OBJ_L1_DRY_PATH);
-  asset_collection.objects.l1_moderate = object_assets_static(&asset_server, &mut layouts, OBJ_L1_MODERATE_PATH);
-  asset_collection.objects.l1_humid = object_assets_static(&asset_server, &mut layouts, OBJ_L1_HUMID_PATH);
-  asset_collection.objects.l2_dry = object_assets_static(&asset_server, &mut layouts, OBJ_L2_DRY_PATH);
-  asset_collection.objects.l2_moderate = object_assets_static(&asset_server, &mut layouts, OBJ_L2_MODERATE_PATH);
-  asset_collection.objects.l2_humid = object_assets_static(&asset_server, &mut layouts, OBJ_L2_HUMID_PATH);
-  asset_collection.objects.l3_dry = object_assets_static(&asset_server, &mut layouts, OBJ_L3_DRY_PATH);
-  asset_collection.objects.l3_moderate = object_assets_static(&asset_server, &mut layouts, OBJ_L3_MODERATE_PATH);
-  asset_collection.objects.l3_humid = object_assets_static(&asset_server, &mut layouts, OBJ_L3_HUMID_PATH);
-
   // Objects: Rule sets for wave function collapse
-  asset_collection.objects.terrain_rules = terrain_rules(terrain_rule_set_handle, &mut terrain_rule_set_assets);
-  asset_collection.objects.tile_type_rules = tile_type_rules(tile_type_rule_set_handle, &mut tile_type_rule_set_assets);
-}
-
-fn tile_set_static(
-  asset_server: &Res<AssetServer>,
-  layout: &mut Assets<TextureAtlasLayout>,
-  tile_set_path: &str,
-) -> AssetCollection {
-  let static_layout = TextureAtlasLayout::from_grid(
-    UVec2::splat(TILE_SIZE),
-    DEFAULT_STATIC_TILE_SET_COLUMNS,
-    TILE_SET_ROWS,
-    None,
-    None,
+  asset_collection.objects.registry = registry(&handles.registry, &mut assets.registry);
+  asset_collection.objects.terrain_rules = terrain_rules(&handles.terrain, &mut assets.terrain);
+  asset_collection.objects.tile_type_rules = tile_type_rules(&handles.tile_type, &mut assets.tile_type);
+  asset_collection.objects.multi_tile_objects = multi_tile_objects(&handles.multi_tile, &mut assets.multi_tile);
+  asset_collection.objects.placement_rules = placement_rules(&handles.placement, &mut assets.placement);
+  asset_collection.objects.light_rules = light_rules(&handles.light, &mut assets.light);
+  (
+    asset_collection.objects.rare_features,
+    asset_collection.objects.rocky_features,
+  ) = rare_features(&handles.rare_feature, &mut assets.rare_feature);
+  asset_collection.objects.walkability = walkability(&handles.walkability, &mut assets.walkability);
+  let objects = &mut asset_collection.objects;
+  apply_registry(&objects.registry, &mut objects.terrain_rules);
+  expand_rules(
+    &objects.multi_tile_objects,
+    &mut objects.terrain_rules,
+    &mut objects.tile_type_rules,
   );
-  let texture_atlas_layout = layout.add(static_layout);
+}
 
-  AssetCollection {
-    stat: AssetPack::new(asset_server.load(tile_set_path.to_string()), texture_atlas_layout.clone()),
-    anim: None,
-    animated_tile_types: HashSet::new(),
+fn initialise_art_pack_system(
+  asset_server: Res<AssetServer>,
+  mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+  mut asset_collection: ResMut<GenerationResourcesCollection>,
+  mut art_packs: ResMut<ArtPacks>,
+  manifests: Res<Assets<ArtPackManifest>>,
+) {
+  art_packs.collect(&manifests);
+  if let Some(manifest) = art_packs.get_selected() {
+    asset_collection.apply_art_pack(manifest, &asset_server, &mut layouts);
   }
 }
 
-fn tile_set_default_animations(
-  asset_server: &Res<AssetServer>,
-  layout: &mut Assets<TextureAtlasLayout>,
-  tile_set_path: &str,
-) -> AssetCollection {
-  let animated_tile_set_layout = TextureAtlasLayout::from_grid(
-    UVec2::splat(TILE_SIZE),
-    DEFAULT_ANIMATED_TILE_SET_COLUMNS,
-    TILE_SET_ROWS,
-    None,
-    None,
-  );
-  let atlas_layout = layout.add(animated_tile_set_layout);
-
-  AssetCollection {
-    stat: AssetPack {
-      texture: asset_server.load(tile_set_path.to_string()),
-      texture_atlas_layout: atlas_layout.clone(),
-      index_offset: DEFAULT_ANIMATED_TILE_SET_COLUMNS as usize,
-    },
-    anim: Some(AssetPack {
-      texture: asset_server.load(tile_set_path.to_string()),
-      texture_atlas_layout: atlas_layout,
-      index_offset: DEFAULT_ANIMATED_TILE_SET_COLUMNS as usize,
-    }),
-    animated_tile_types: {
-      let tile_types = [
-        TileType::InnerCornerBottomLeft,
-        TileType::InnerCornerBottomRight,
-        TileType::InnerCornerTopLeft,
-        TileType::InnerCornerTopRight,
-        TileType::OuterCornerBottomLeft,
-        TileType::OuterCornerBottomRight,
-        TileType::OuterCornerTopLeft,
-        TileType::OuterCornerTopRight,
-        TileType::TopLeftToBottomRightBridge,
-        TileType::TopRightToBottomLeftBridge,
-        TileType::TopFill,
-        TileType::BottomFill,
-        TileType::RightFill,
-        TileType::LeftFill,
-        TileType::Single,
-      ];
-      insert(&tile_types)
-    },
+/// Rebuilds the textures of the `GenerationResourcesCollection` from the requested art pack, if any, and re-spawns all
+/// chunks so that they are rendered using the new art pack.
+fn switch_art_pack_system(
+  asset_server: Res<AssetServer>,
+  mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+  mut asset_collection: ResMut<GenerationResourcesCollection>,
+  mut art_packs: ResMut<ArtPacks>,
+  mut prune_world_event: EventWriter<PruneWorldEvent>,
+) {
+  if let Some(manifest) = art_packs.take_requested() {
+    asset_collection.apply_art_pack(manifest, &asset_server, &mut layouts);
+    prune_world_event.send(PruneWorldEvent {
+      despawn_all_chunks: true,
+      update_world_after: true,
+      is_derived: false,
+    });
   }
 }
 
-fn insert(tile_types: &[TileType; 15]) -> HashSet<TileType> {
-  let mut set = HashSet::new();
-  for tile_type in tile_types {
-    set.insert(*tile_type);
+/// Cross-references the sprite index of every state in the terrain rule sets with the texture atlas layout of each
+/// object collection the state may be rendered with. Logs all out-of-range indices at once, since they would otherwise
+/// silently render the wrong sprite (or none at all) at runtime.
+fn audit_object_sprite_indices(asset_collection: &GenerationResourcesCollection, layouts: &Assets<TextureAtlasLayout>) {
+  let mut problems = Vec::new();
+  let mut checked = 0;
+  for (terrain, states) in asset_collection.objects.terrain_rules.iter() {
+    for state in states.iter().filter(|state| !state.name.is_multi_tile_filler()) {
+      for climate in [Climate::Dry, Climate::Moderate, Climate::Humid] {
+        let collection = asset_collection.get_object_collection(*terrain, climate, state.atlas);
+        let Some(layout) = layouts.get(&collection.stat.texture_atlas_layout) else {
+          problems.push(format!(
+            "[{:?}] on [{:?}] [{:?}] terrain: texture atlas layout not found",
+            state.name, climate, terrain
+          ));
+          continue;
+        };
+        checked += 1;
+        if state.index < 0 || state.index as usize >= layout.textures.len() {
+          let (columns, rows) = atlas_dimensions(layout);
+          problems.push(format!(
+            "[{:?}] on [{:?}] [{:?}] terrain: index {} is out of range for an atlas with {} columns and {} rows",
+            state.name, climate, terrain, state.index, columns, rows
+          ));
+        }
+      }
+    }
+  }
+  if problems.is_empty() {
+    debug!(
+      "Audited {} object sprite indices, all of which exist in their texture atlas",
+      checked
+    );
+  } else {
+    error!(
+      "Found {} problem(s) when auditing {} object sprite indices:\n  - {}",
+      problems.len(),
+      checked,
+      problems.join("\n  - ")
+    );
   }
+}
+
+/// Returns the number of columns and rows of a texture atlas layout created from a grid of equally sized sprites.
+fn atlas_dimensions(layout: &TextureAtlasLayout) -> (u32, u32) {
+  let sprite_size = layout
+    .textures
+    .first()
+    .map(|rect| rect.size())
+    .unwrap_or(UVec2::ONE)
+    .max(UVec2::ONE);
 
-  set
+  (layout.size.x / sprite_size.x, layout.size.y / sprite_size.y)
 }
 
-fn object_assets_static(
-  asset_server: &Res<AssetServer>,
-  layout: &mut Assets<TextureAtlasLayout>,
-  tile_set_path: &str,
+fn asset_collection(
+  asset_server: &AssetServer,
+  layouts: &mut Assets<TextureAtlasLayout>,
+  definition: &AtlasDefinition,
 ) -> AssetCollection {
-  let static_layout = TextureAtlasLayout::from_grid(DEFAULT_OBJ_SIZE, DEFAULT_OBJ_COLUMNS, DEFAULT_OBJ_ROWS, None, None);
-  let static_atlas_layout = layout.add(static_layout);
+  let layout = TextureAtlasLayout::from_grid(definition.sprite_size(), definition.columns, definition.rows, None, None);
+  let asset_pack = AssetPack {
+    texture: asset_server.load(definition.path.clone()),
+    texture_atlas_layout: layouts.add(layout),
+    index_offset: definition.index_offset(),
+    fill_variants: definition.fill_variants(),
+  };
 
   AssetCollection {
-    stat: AssetPack::new(asset_server.load(tile_set_path.to_string()), static_atlas_layout.clone()),
-    anim: None,
-    animated_tile_types: HashSet::new(),
+    anim: definition.is_animated.then(|| asset_pack.clone()),
+    stat: asset_pack,
+    animated_tile_types: if definition.is_animated {
+      animated_tile_types()
+    } else {
+      HashSet::new()
+    },
   }
 }
 
+fn animated_tile_types() -> HashSet<TileType> {
+  HashSet::from([
+    TileType::InnerCornerBottomLeft,
+    TileType::InnerCornerBottomRight,
+    TileType::InnerCornerTopLeft,
+    TileType::InnerCornerTopRight,
+    TileType::OuterCornerBottomLeft,
+    TileType::OuterCornerBottomRight,
+    TileType::OuterCornerTopLeft,
+    TileType::OuterCornerTopRight,
+    TileType::TopLeftToBottomRightBridge,
+    TileType::TopRightToBottomLeftBridge,
+    TileType::TopFill,
+    TileType::BottomFill,
+    TileType::RightFill,
+    TileType::LeftFill,
+    TileType::Single,
+  ])
+}
+
 fn terrain_rules(
-  terrain_rule_set_handle: Res<TerrainRuleSetHandle>,
-  terrain_rule_set_assets: &mut ResMut<Assets<TerrainRuleSet>>,
+  terrain_rule_set_handle: &TerrainRuleSetHandle,
+  terrain_rule_set_assets: &mut Assets<TerrainRuleSet>,
 ) -> HashMap<TerrainType, Vec<TerrainState>> {
   let mut rule_sets = HashMap::new();
   for handle in terrain_rule_set_handle.0.iter() {
@@ -434,8 +739,8 @@ fn terrain_rules(
 }
 
 fn tile_type_rules(
-  tile_type_rule_set_handle: Res<TileTypeRuleSetHandle>,
-  tile_type_rule_set_assets: &mut ResMut<Assets<TileTypeRuleSet>>,
+  tile_type_rule_set_handle: &TileTypeRuleSetHandle,
+  tile_type_rule_set_assets: &mut Assets<TileTypeRuleSet>,
 ) -> HashMap<TileType, Vec<ObjectName>> {
   if let Some(rule_set) = tile_type_rule_set_assets.remove(&tile_type_rule_set_handle.0) {
     debug!("Loaded: Tile type rule set for {} tiles", rule_set.states.len());
@@ -448,3 +753,174 @@ fn tile_type_rules(
 
   HashMap::new()
 }
+
+fn multi_tile_objects(
+  multi_tile_rule_set_handle: &MultiTileRuleSetHandle,
+  multi_tile_rule_set_assets: &mut Assets<MultiTileRuleSet>,
+) -> Vec<MultiTileObject> {
+  if let Some(rule_set) = multi_tile_rule_set_assets.remove(&multi_tile_rule_set_handle.0) {
+    debug!("Loaded: {}", rule_set);
+    return rule_set.objects;
+  }
+
+  vec![]
+}
+
+fn placement_rules(
+  placement_rule_set_handle: &PlacementRuleSetHandle,
+  placement_rule_set_assets: &mut Assets<PlacementRuleSet>,
+) -> HashMap<ObjectName, ObjectPlacement> {
+  if let Some(rule_set) = placement_rule_set_assets.remove(&placement_rule_set_handle.0) {
+    debug!("Loaded: {}", rule_set);
+    return rule_set
+      .objects
+      .into_iter()
+      .map(|placement| (placement.name, placement))
+      .collect();
+  }
+
+  HashMap::new()
+}
+
+fn registry(
+  registry_rule_set_handle: &RegistryRuleSetHandle,
+  registry_rule_set_assets: &mut Assets<RegistryRuleSet>,
+) -> ObjectRegistry {
+  if let Some(rule_set) = registry_rule_set_assets.remove(&registry_rule_set_handle.0) {
+    debug!("Loaded: {}", rule_set);
+    return ObjectRegistry::new(rule_set.objects);
+  }
+
+  ObjectRegistry::default()
+}
+
+/// Sets the sprite index and sprite sheet of every state in the terrain rule sets using the object registry. States of
+/// objects that are missing from the registry are given an invalid index, which is reported by the sprite index audit.
+fn apply_registry(registry: &ObjectRegistry, terrain_rules: &mut HashMap<TerrainType, Vec<TerrainState>>) {
+  for (terrain, states) in terrain_rules.iter_mut() {
+    for state in states.iter_mut() {
+      match registry.get(state.name) {
+        Some(definition) => {
+          state.index = definition.index;
+          state.atlas = definition.atlas;
+        }
+        None => {
+          error!(
+            "[{:?}] in the [{:?}] terrain rule set is not defined in the object registry",
+            state.name, terrain
+          );
+          state.index = -1;
+        }
+      }
+    }
+  }
+}
+
+fn walkability(
+  walkability_rule_set_handle: &WalkabilityRuleSetHandle,
+  walkability_rule_set_assets: &mut Assets<WalkabilityRuleSet>,
+) -> Walkability {
+  if let Some(rule_set) = walkability_rule_set_assets.remove(&walkability_rule_set_handle.0) {
+    debug!("Loaded: {}", rule_set);
+    return Walkability::new(rule_set.rules);
+  }
+
+  Walkability::default()
+}
+
+fn light_rules(
+  light_rule_set_handle: &LightRuleSetHandle,
+  light_rule_set_assets: &mut Assets<LightRuleSet>,
+) -> HashMap<ObjectName, ObjectLight> {
+  if let Some(rule_set) = light_rule_set_assets.remove(&light_rule_set_handle.0) {
+    debug!("Loaded: {}", rule_set);
+    return rule_set.objects.into_iter().map(|light| (light.name, light)).collect();
+  }
+
+  HashMap::new()
+}
+
+fn rare_features(
+  rare_feature_rule_set_handle: &RareFeatureRuleSetHandle,
+  rare_feature_rule_set_assets: &mut Assets<RareFeatureRuleSet>,
+) -> (Vec<RareFeatureTemplate>, Vec<RareFeatureTemplate>) {
+  if let Some(rule_set) = rare_feature_rule_set_assets.remove(&rare_feature_rule_set_handle.0) {
+    debug!("Loaded: {}", rule_set);
+    return (rule_set.features, rule_set.rocky_features);
+  }
+
+  (vec![], vec![])
+}
+
+/// Builds the object rules of the `ObjectResources` from the rule set files in the `assets/objects` folder without an
+/// `AssetServer`, so that the generation process can be tested and benchmarked without running the app. No textures
+/// are loaded.
+#[cfg(any(test, feature = "bench"))]
+pub fn object_resources_from_files() -> ObjectResources {
+  fn load<T: Asset + serde::de::DeserializeOwned>(assets: &mut Assets<T>, file_name: &str) -> Handle<T> {
+    let path = format!("{}/assets/objects/{}", env!("CARGO_MANIFEST_DIR"), file_name);
+    let content = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("Failed to read [{}]: {}", path, e));
+    let rule_set: T = ron::from_str(&content).unwrap_or_else(|e| panic!("Failed to parse [{}]: {}", path, e));
+
+    assets.add(rule_set)
+  }
+
+  let mut terrain_rule_set_assets = Assets::<TerrainRuleSet>::default();
+  let mut terrain_rule_set_handles = (0..TerrainType::length())
+    .map(|i| TerrainType::from(i).to_string().to_lowercase())
+    .map(|name| load(&mut terrain_rule_set_assets, &format!("{}.terrain.ruleset.ron", name)))
+    .collect::<Vec<_>>();
+  terrain_rule_set_handles.push(load(&mut terrain_rule_set_assets, "any.terrain.ruleset.ron"));
+  let mut tile_type_rule_set_assets = Assets::default();
+  let tile_type_rule_set_handle = load(&mut tile_type_rule_set_assets, "all.tile-type.ruleset.ron");
+  let mut multi_tile_rule_set_assets = Assets::default();
+  let multi_tile_rule_set_handle = load(&mut multi_tile_rule_set_assets, "all.multi-tile.ruleset.ron");
+  let mut placement_rule_set_assets = Assets::default();
+  let placement_rule_set_handle = load(&mut placement_rule_set_assets, "all.placement.ruleset.ron");
+  let mut registry_rule_set_assets = Assets::default();
+  let registry_rule_set_handle = load(&mut registry_rule_set_assets, "all.registry.ruleset.ron");
+  let mut light_rule_set_assets = Assets::default();
+  let light_rule_set_handle = load(&mut light_rule_set_assets, "all.light.ruleset.ron");
+  let mut rare_feature_rule_set_assets = Assets::default();
+  let rare_feature_rule_set_handle = load(&mut rare_feature_rule_set_assets, "all.rare-feature.ruleset.ron");
+  let mut walkability_rule_set_assets = Assets::default();
+  let walkability_rule_set_handle = load(&mut walkability_rule_set_assets, "all.walkability.ruleset.ron");
+
+  let mut objects = ObjectResources {
+    registry: registry(
+      &RegistryRuleSetHandle(registry_rule_set_handle),
+      &mut registry_rule_set_assets,
+    ),
+    terrain_rules: terrain_rules(&TerrainRuleSetHandle(terrain_rule_set_handles), &mut terrain_rule_set_assets),
+    tile_type_rules: tile_type_rules(
+      &TileTypeRuleSetHandle(tile_type_rule_set_handle),
+      &mut tile_type_rule_set_assets,
+    ),
+    multi_tile_objects: multi_tile_objects(
+      &MultiTileRuleSetHandle(multi_tile_rule_set_handle),
+      &mut multi_tile_rule_set_assets,
+    ),
+    placement_rules: placement_rules(
+      &PlacementRuleSetHandle(placement_rule_set_handle),
+      &mut placement_rule_set_assets,
+    ),
+    light_rules: light_rules(&LightRuleSetHandle(light_rule_set_handle), &mut light_rule_set_assets),
+    walkability: walkability(
+      &WalkabilityRuleSetHandle(walkability_rule_set_handle),
+      &mut walkability_rule_set_assets,
+    ),
+    ..Default::default()
+  };
+  (objects.rare_features, objects.rocky_features) = rare_features(
+    &RareFeatureRuleSetHandle(rare_feature_rule_set_handle),
+    &mut rare_feature_rule_set_assets,
+  );
+  apply_registry(&objects.registry, &mut objects.terrain_rules);
+  expand_rules(
+    &objects.multi_tile_objects,
+    &mut objects.terrain_rules,
+    &mut objects.tile_type_rules,
+  );
+
+  objects
+}
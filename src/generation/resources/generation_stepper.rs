@@ -0,0 +1,38 @@
+use bevy::app::{App, Plugin};
+use bevy::prelude::Resource;
+
+pub struct GenerationStepperPlugin;
+
+impl Plugin for GenerationStepperPlugin {
+  fn build(&self, app: &mut App) {
+    app.init_resource::<GenerationStepper>();
+  }
+}
+
+/// Used to step through the world generation process one `GenerationStage` transition at a time when
+/// `step_through_generation` is enabled. A requested step remains pending until a `WorldGenerationComponent` has
+/// actually transitioned to its next stage, so that requesting a step while awaiting async tasks is never lost.
+#[derive(Resource, Default)]
+pub struct GenerationStepper {
+  is_step_requested: bool,
+  step_count: usize,
+}
+
+impl GenerationStepper {
+  pub fn request_step(&mut self) {
+    self.is_step_requested = true;
+  }
+
+  pub fn is_step_requested(&self) -> bool {
+    self.is_step_requested
+  }
+
+  pub fn complete_step(&mut self) {
+    self.is_step_requested = false;
+    self.step_count += 1;
+  }
+
+  pub fn step_count(&self) -> usize {
+    self.step_count
+  }
+}
@@ -1,6 +1,7 @@
 use crate::coords::point::{ChunkGrid, InternalGrid};
 use crate::coords::Point;
-use crate::generation::lib::{get_direction_points, Direction};
+use crate::generation::lib::{get_direction_points, Direction, TerrainEdges, TerrainType};
+use crate::generation::object::lib::RareFeature;
 use bevy::app::{App, Plugin};
 use bevy::log::*;
 use bevy::prelude::{Reflect, ReflectResource, Resource};
@@ -25,6 +26,11 @@ impl Plugin for MetadataPlugin {
 /// For example, `ElevationMetadata` is used in tile generation to ensure seamless terrain transitions across chunks
 /// which allows you to configure smooth transitions from water in the west, through coastal areas and grassy plains,
 /// to forests in the east.
+///
+/// Similarly, `rare_features` holds the rare feature stamped onto each chunk that contains one. Likewise,
+/// `terrain_edges` holds the raw terrain noise along the borders of each generated chunk, which neighbouring chunks
+/// reuse instead of sampling it again. The object grid edges of each chunk are held by the `ObjectEdges` and the
+/// terrain that has been painted using the terrain brush by the `WorldModifications` instead.
 #[derive(Resource, Default, Clone, Reflect)]
 #[reflect(Resource)]
 pub struct Metadata {
@@ -32,9 +38,38 @@ pub struct Metadata {
   pub index: Vec<Point<ChunkGrid>>,
   pub elevation: HashMap<Point<ChunkGrid>, ElevationMetadata>,
   pub biome: HashMap<Point<ChunkGrid>, BiomeMetadata>,
+  pub rare_features: HashMap<Point<ChunkGrid>, RareFeature>,
+  #[reflect(ignore)]
+  pub terrain_edges: HashMap<Point<ChunkGrid>, TerrainEdges>,
 }
 
 impl Metadata {
+  /// Returns `true` if the metadata for the given `Point<ChunkGrid>` and all of its adjacent chunks exists, which is
+  /// required to generate the chunk.
+  pub fn has_metadata_for(&self, cg: &Point<ChunkGrid>) -> bool {
+    get_direction_points(cg)
+      .iter()
+      .all(|(_, point)| self.biome.contains_key(point) && self.elevation.contains_key(point))
+  }
+
+  /// Returns the combined terrain edges of all chunks surrounding the given `Point<ChunkGrid>` that have already been
+  /// generated, either according to this metadata or to `pending`, which holds the edges of chunks that have been
+  /// generated in the same batch but not yet been added to the metadata.
+  pub fn get_terrain_edges_for(
+    &self,
+    cg: &Point<ChunkGrid>,
+    pending: &HashMap<Point<ChunkGrid>, TerrainEdges>,
+  ) -> TerrainEdges {
+    let mut neighbour_edges = TerrainEdges::default();
+    get_direction_points(cg)
+      .iter()
+      .filter(|(direction, _)| *direction != Direction::Center)
+      .filter_map(|(_, point)| pending.get(point).or_else(|| self.terrain_edges.get(point)))
+      .for_each(|edges| neighbour_edges.extend(edges));
+
+    neighbour_edges
+  }
+
   /// Returns the biome metadata for the given `Point<ChunkGrid>` which includes the biome metadata for the four
   /// adjacent chunks as well.
   pub fn get_biome_metadata_for(&self, cg: &Point<ChunkGrid>) -> BiomeMetadataSet {
@@ -97,7 +132,7 @@ impl Display for ElevationMetadata {
 
 impl ElevationMetadata {
   /// Give it a `Point<InternalGrid>` and it will calculate the elevation offset you need to apply for that point.
-  pub fn calculate_for_point(&self, ig: Point<InternalGrid>, _grid_size: i32, _grid_buffer: i32) -> f64 {
+  pub fn calculate_for_point(&self, ig: Point<InternalGrid>) -> f64 {
     if !self.is_enabled {
       return 0.0;
     }
@@ -140,6 +175,11 @@ impl BiomeMetadata {
       climate,
     }
   }
+
+  /// Returns `true` if the chunk can contain any terrain above shallow water.
+  pub fn has_land(&self) -> bool {
+    self.max_layer > TerrainType::ShallowWater as i32
+  }
 }
 
 #[derive(Debug)]
@@ -1,19 +1,69 @@
+mod art_pack;
 mod chunk_component_index;
+mod dry_run_report;
 mod generation_resources_collection;
+mod generation_stepper;
 mod metadata;
+mod nav_data;
+mod object_edges;
+mod pending_world_update;
+mod sprite_pool;
+mod stage_timings;
+mod terrain_statistics;
+mod wfc_statistics;
+mod wfc_visualiser;
+mod world_modifications;
 
+use crate::generation::resources::art_pack::ArtPackPlugin;
 use crate::generation::resources::chunk_component_index::ChunkComponentIndexPlugin;
-use crate::generation::resources::generation_resources_collection::GenerationResourcesCollectionPlugin;
+use crate::generation::resources::dry_run_report::DryRunReportPlugin;
+use crate::generation::resources::generation_stepper::GenerationStepperPlugin;
+use crate::generation::resources::nav_data::NavDataPlugin;
+use crate::generation::resources::object_edges::ObjectEdgesPlugin;
+use crate::generation::resources::pending_world_update::PendingWorldUpdatePlugin;
+use crate::generation::resources::sprite_pool::SpritePoolPlugin;
+use crate::generation::resources::stage_timings::StageTimingsPlugin;
+use crate::generation::resources::terrain_statistics::TerrainStatisticsPlugin;
+use crate::generation::resources::wfc_statistics::WfcStatisticsPlugin;
+use crate::generation::resources::wfc_visualiser::WfcVisualiserPlugin;
 use bevy::app::{App, Plugin};
 
 pub struct GenerationResourcesPlugin;
 
 impl Plugin for GenerationResourcesPlugin {
   fn build(&self, app: &mut App) {
-    app.add_plugins((GenerationResourcesCollectionPlugin, ChunkComponentIndexPlugin, MetadataPlugin));
+    app.add_plugins((
+      GenerationResourcesCollectionPlugin,
+      ChunkComponentIndexPlugin,
+      MetadataPlugin,
+      WfcStatisticsPlugin,
+      DryRunReportPlugin,
+      GenerationStepperPlugin,
+      WfcVisualiserPlugin,
+      WorldModificationsPlugin,
+      TerrainStatisticsPlugin,
+      PendingWorldUpdatePlugin,
+      ArtPackPlugin,
+      NavDataPlugin,
+      ObjectEdgesPlugin,
+      StageTimingsPlugin,
+      SpritePoolPlugin,
+    ));
   }
 }
 
+pub use crate::generation::resources::art_pack::*;
 pub use crate::generation::resources::chunk_component_index::*;
+pub use crate::generation::resources::dry_run_report::*;
 pub use crate::generation::resources::generation_resources_collection::*;
+pub use crate::generation::resources::generation_stepper::*;
 pub use crate::generation::resources::metadata::*;
+pub use crate::generation::resources::nav_data::*;
+pub use crate::generation::resources::object_edges::*;
+pub use crate::generation::resources::pending_world_update::*;
+pub use crate::generation::resources::sprite_pool::*;
+pub use crate::generation::resources::stage_timings::*;
+pub use crate::generation::resources::terrain_statistics::*;
+pub use crate::generation::resources::wfc_statistics::*;
+pub use crate::generation::resources::wfc_visualiser::*;
+pub use crate::generation::resources::world_modifications::*;
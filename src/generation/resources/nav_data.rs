@@ -0,0 +1,228 @@
+use crate::components::PendingDespawnComponent;
+use crate::constants::{CHUNK_HEIGHT, CHUNK_WIDTH};
+use crate::coords::point::{ChunkGrid, InternalGrid};
+use crate::coords::Point;
+use crate::generation::lib::{ChunkComponent, TileData, Walkability};
+use crate::generation::object::lib::{ObjectGrid, ObjectGroup, ObjectName};
+use crate::generation::resources::{ObjectResources, WorldModification, WorldModifications};
+use bevy::app::{App, Plugin};
+use bevy::log::*;
+use bevy::prelude::{Has, OnAdd, OnRemove, Query, ResMut, Resource, Trigger};
+use bevy::utils::{HashMap, HashSet};
+
+pub struct NavDataPlugin;
+
+impl Plugin for NavDataPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_resource::<NavData>()
+      .add_observer(on_remove_chunk_component_trigger)
+      .add_observer(on_add_pending_despawn_component_trigger);
+  }
+}
+
+/// Contains the `ChunkNavData` of every chunk whose objects have been generated, keyed by its `ChunkGrid` coordinates.
+/// Unlike the `ObjectGrid`, which is discarded once the objects of a chunk have been generated, this data remains
+/// available to runtime consumers until the chunk is despawned. Kept up-to-date when objects are placed or removed.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct NavData {
+  chunks: HashMap<Point<ChunkGrid>, ChunkNavData>,
+}
+
+impl NavData {
+  pub fn get(&self, cg: &Point<ChunkGrid>) -> Option<&ChunkNavData> {
+    self.chunks.get(cg)
+  }
+
+  pub fn get_mut(&mut self, cg: &Point<ChunkGrid>) -> Option<&mut ChunkNavData> {
+    self.chunks.get_mut(cg)
+  }
+
+  pub fn insert(&mut self, cg: Point<ChunkGrid>, nav_data: ChunkNavData) {
+    self.chunks.insert(cg, nav_data);
+  }
+}
+
+/// The navigation data of a single chunk. A cell is walkable if its tile is walkable according to the walkability rule
+/// set and its object, if any, is walkable according to the object registry. Path cells are the cells occupied by objects of the `ObjectGroup::Path` and doors
+/// are the path cells that are orthogonally adjacent to an object of the `ObjectGroup::Building`, i.e. where a path
+/// leads into a building.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkNavData {
+  is_walkable_tile: Vec<bool>,
+  objects: Vec<Option<ObjectName>>,
+  walkable: Vec<bool>,
+  path_cells: HashSet<Point<InternalGrid>>,
+  building_cells: HashSet<Point<InternalGrid>>,
+  doors: HashSet<Point<InternalGrid>>,
+}
+
+impl ChunkNavData {
+  /// Creates the navigation data for a chunk without any objects, based on the terrain and tile type of the given
+  /// tiles. Cells without a tile are not walkable.
+  pub fn new(tile_data: &[TileData], walkability: &Walkability) -> Self {
+    let cell_count = (CHUNK_WIDTH * CHUNK_HEIGHT) as usize;
+    let mut is_walkable_tile = vec![false; cell_count];
+    for data in tile_data.iter() {
+      if let Some(i) = index(&data.flat_tile.coords.internal_grid) {
+        is_walkable_tile[i] |= walkability.is_walkable(data.flat_tile.terrain, data.flat_tile.tile_type);
+      }
+    }
+
+    Self {
+      walkable: is_walkable_tile.clone(),
+      is_walkable_tile,
+      objects: vec![None; cell_count],
+      path_cells: HashSet::new(),
+      building_cells: HashSet::new(),
+      doors: HashSet::new(),
+    }
+  }
+
+  /// Creates the navigation data for a chunk from the given tiles and the collapsed cells of its object grid.
+  pub fn from_object_grid(tile_data: &[TileData], grid: &ObjectGrid, objects: &ObjectResources) -> Self {
+    let mut nav_data = Self::new(tile_data, &objects.walkability);
+    for cell in grid.grid.iter().flatten().filter(|cell| cell.is_collapsed) {
+      if let Some(state) = cell.possible_states.first() {
+        nav_data.set_cell(&cell.ig, Some(state.name), objects);
+      }
+    }
+    nav_data.update_doors();
+
+    nav_data
+  }
+
+  /// Applies all `WorldModification`s of the chunk at the given `ChunkGrid` coordinates.
+  pub fn apply(&mut self, cg: &Point<ChunkGrid>, world_modifications: &WorldModifications, objects: &ObjectResources) {
+    for (x, y) in (0..CHUNK_HEIGHT).flat_map(|y| (0..CHUNK_WIDTH).map(move |x| (x, y))) {
+      let ig = Point::new_internal_grid(x, y);
+      match world_modifications.get(cg, &ig) {
+        Some(WorldModification::RemovedObject) => self.clear_object(&ig, objects),
+        Some(WorldModification::PlacedObject { name, .. }) => {
+          self.clear_object(&ig, objects);
+          self.set_cell(&ig, Some(*name), objects);
+        }
+        None => {}
+      }
+    }
+    self.update_doors();
+  }
+
+  /// Replaces the object in the given cell, including the fillers of a multi-tile object anchored in it, and updates
+  /// the walkability, path cells and doors accordingly.
+  pub fn set_object(&mut self, ig: &Point<InternalGrid>, name: Option<ObjectName>, objects: &ObjectResources) {
+    self.clear_object(ig, objects);
+    self.set_cell(ig, name, objects);
+    self.update_doors();
+  }
+
+  pub fn object(&self, ig: &Point<InternalGrid>) -> Option<ObjectName> {
+    index(ig).and_then(|i| self.objects[i])
+  }
+
+  pub fn is_walkable(&self, ig: &Point<InternalGrid>) -> bool {
+    index(ig).is_some_and(|i| self.walkable[i])
+  }
+
+  pub fn is_path(&self, ig: &Point<InternalGrid>) -> bool {
+    self.path_cells.contains(ig)
+  }
+
+  pub fn is_door(&self, ig: &Point<InternalGrid>) -> bool {
+    self.doors.contains(ig)
+  }
+
+  fn clear_object(&mut self, ig: &Point<InternalGrid>, objects: &ObjectResources) {
+    let fillers = self
+      .objects
+      .iter()
+      .enumerate()
+      .filter_map(|(i, name)| match name {
+        Some(ObjectName::MultiTileFiller(_, x, y)) => {
+          let filler_ig = point(i);
+          (filler_ig.x - *x as i32 == ig.x && filler_ig.y - *y as i32 == ig.y).then_some(filler_ig)
+        }
+        _ => None,
+      })
+      .collect::<Vec<Point<InternalGrid>>>();
+    for filler_ig in fillers.iter() {
+      self.set_cell(filler_ig, None, objects);
+    }
+    self.set_cell(ig, None, objects);
+  }
+
+  fn set_cell(&mut self, ig: &Point<InternalGrid>, name: Option<ObjectName>, objects: &ObjectResources) {
+    let Some(i) = index(ig) else {
+      return;
+    };
+    let name = name.filter(|name| *name != ObjectName::Empty);
+    let definition = name.and_then(|name| objects.get_definition(name));
+    let group = definition.map(|definition| definition.group).unwrap_or_default();
+    self.objects[i] = name;
+    self.walkable[i] = self.is_walkable_tile[i] && definition.is_none_or(|definition| definition.is_walkable);
+    match group {
+      ObjectGroup::Path => self.path_cells.insert(*ig),
+      _ => self.path_cells.remove(ig),
+    };
+    match group {
+      ObjectGroup::Building => self.building_cells.insert(*ig),
+      _ => self.building_cells.remove(ig),
+    };
+  }
+
+  fn update_doors(&mut self) {
+    self.doors = self
+      .path_cells
+      .iter()
+      .filter(|ig| {
+        [(0, -1), (1, 0), (0, 1), (-1, 0)]
+          .iter()
+          .any(|(dx, dy)| self.building_cells.contains(&Point::new_internal_grid(ig.x + dx, ig.y + dy)))
+      })
+      .copied()
+      .collect();
+  }
+}
+
+fn index(ig: &Point<InternalGrid>) -> Option<usize> {
+  if ig.x < 0 || ig.y < 0 || ig.x >= CHUNK_WIDTH || ig.y >= CHUNK_HEIGHT {
+    return None;
+  }
+
+  Some((ig.y * CHUNK_WIDTH + ig.x) as usize)
+}
+
+fn point(index: usize) -> Point<InternalGrid> {
+  Point::new_internal_grid(index as i32 % CHUNK_WIDTH, index as i32 / CHUNK_WIDTH)
+}
+
+fn on_remove_chunk_component_trigger(
+  trigger: Trigger<OnRemove, ChunkComponent>,
+  query: Query<(&ChunkComponent, Has<PendingDespawnComponent>)>,
+  mut nav_data: ResMut<NavData>,
+) {
+  let (cc, is_pending_despawn) = query.get(trigger.entity()).expect("Failed to get ChunkComponent");
+  if is_pending_despawn {
+    // Already removed and the chunk may have been generated again since
+    return;
+  }
+  if nav_data.chunks.remove(&cc.coords.chunk_grid).is_some() {
+    trace!("NavData -> Removed navigation data of chunk {}", cc.coords.chunk_grid);
+  }
+}
+
+fn on_add_pending_despawn_component_trigger(
+  trigger: Trigger<OnAdd, PendingDespawnComponent>,
+  query: Query<&ChunkComponent>,
+  mut nav_data: ResMut<NavData>,
+) {
+  let Ok(cc) = query.get(trigger.entity()) else {
+    return;
+  };
+  if nav_data.chunks.remove(&cc.coords.chunk_grid).is_some() {
+    trace!(
+      "NavData -> Removed navigation data of chunk {} pending despawn",
+      cc.coords.chunk_grid
+    );
+  }
+}
@@ -0,0 +1,90 @@
+use crate::components::PendingDespawnComponent;
+use crate::coords::point::ChunkGrid;
+use crate::coords::Point;
+use crate::generation::lib::{get_direction_points, ChunkComponent, Direction};
+use crate::generation::object::lib::ObjectGridEdges;
+use bevy::app::{App, Plugin};
+use bevy::log::*;
+use bevy::prelude::{Has, OnAdd, OnRemove, Query, ResMut, Resource, Trigger};
+use bevy::utils::HashMap;
+
+pub struct ObjectEdgesPlugin;
+
+impl Plugin for ObjectEdgesPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_resource::<ObjectEdges>()
+      .add_observer(on_remove_chunk_component_trigger)
+      .add_observer(on_add_pending_despawn_component_trigger);
+  }
+}
+
+/// Contains the `ObjectGridEdges` of every existing chunk whose objects have been generated, keyed by its `ChunkGrid`
+/// coordinates. Used to constrain the facing cells of chunks generated later on, so that objects line up across chunk
+/// boundaries. The edges of a chunk are removed as soon as it is despawned.
+///
+/// Since only chunks that are generated after their neighbour are constrained by it, the objects generated for a chunk
+/// depend on the order in which it and its neighbours are generated, e.g. on the direction from which the camera
+/// approaches it. To limit this, chunks that are generated together are never constrained by each other, so that the
+/// outcome doesn't depend on which of their tasks completes first. The objects of a chunk without any neighbours that
+/// existed before it was generated only depend on its position and the settings.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct ObjectEdges {
+  chunks: HashMap<Point<ChunkGrid>, ObjectGridEdges>,
+}
+
+impl ObjectEdges {
+  /// Returns the object grid edges of the chunks that are adjacent to the given `Point<ChunkGrid>` in a cardinal
+  /// direction, provided objects have been generated for them.
+  pub fn get_for(&self, cg: &Point<ChunkGrid>) -> Vec<(Direction, ObjectGridEdges)> {
+    get_direction_points(cg)
+      .into_iter()
+      .filter(|(direction, _)| {
+        matches!(
+          direction,
+          Direction::Top | Direction::Right | Direction::Bottom | Direction::Left
+        )
+      })
+      .filter_map(|(direction, point)| self.chunks.get(&point).map(|edges| (direction, edges.clone())))
+      .collect()
+  }
+
+  pub fn insert(&mut self, edges: ObjectGridEdges) {
+    self.chunks.insert(edges.cg, edges);
+  }
+
+  pub fn clear(&mut self) {
+    self.chunks.clear();
+  }
+}
+
+fn on_remove_chunk_component_trigger(
+  trigger: Trigger<OnRemove, ChunkComponent>,
+  query: Query<(&ChunkComponent, Has<PendingDespawnComponent>)>,
+  mut object_edges: ResMut<ObjectEdges>,
+) {
+  let (cc, is_pending_despawn) = query.get(trigger.entity()).expect("Failed to get ChunkComponent");
+  if is_pending_despawn {
+    // Already removed and the chunk may have been generated again since
+    return;
+  }
+  if object_edges.chunks.remove(&cc.coords.chunk_grid).is_some() {
+    trace!("ObjectEdges -> Removed object grid edges of chunk {}", cc.coords.chunk_grid);
+  }
+}
+
+fn on_add_pending_despawn_component_trigger(
+  trigger: Trigger<OnAdd, PendingDespawnComponent>,
+  query: Query<&ChunkComponent>,
+  mut object_edges: ResMut<ObjectEdges>,
+) {
+  let Ok(cc) = query.get(trigger.entity()) else {
+    return;
+  };
+  if object_edges.chunks.remove(&cc.coords.chunk_grid).is_some() {
+    trace!(
+      "ObjectEdges -> Removed object grid edges of chunk {} pending despawn",
+      cc.coords.chunk_grid
+    );
+  }
+}
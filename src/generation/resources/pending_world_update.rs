@@ -0,0 +1,91 @@
+use bevy::app::{App, Plugin};
+use bevy::prelude::{Entity, Resource};
+
+pub struct PendingWorldUpdatePlugin;
+
+impl Plugin for PendingWorldUpdatePlugin {
+  fn build(&self, app: &mut App) {
+    app.init_resource::<PendingWorldUpdate>();
+  }
+}
+
+/// Holds a world update that was requested by a `PruneWorldEvent`. The update must only be processed once all chunks
+/// that were despawned by the pruning no longer exist, otherwise chunks can be despawned just after they were spawned
+/// by the update.
+#[derive(Resource, Default)]
+pub struct PendingWorldUpdate {
+  is_requested: bool,
+  pruned_chunks: Vec<Entity>,
+}
+
+impl PendingWorldUpdate {
+  /// Requests a world update once the given chunk entities have been despawned. Replaces any pending request.
+  pub fn request(&mut self, pruned_chunks: Vec<Entity>) {
+    self.is_requested = true;
+    self.pruned_chunks = pruned_chunks;
+  }
+
+  /// Returns `true` and clears the request if an update has been requested and none of the pruned chunks exists
+  /// anymore, as determined by the given function.
+  pub fn take_if_pruned(&mut self, exists: impl Fn(Entity) -> bool) -> bool {
+    if !self.is_requested || self.pruned_chunks.iter().any(|entity| exists(*entity)) {
+      return false;
+    }
+    self.is_requested = false;
+    self.pruned_chunks.clear();
+
+    true
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use bevy::utils::HashSet;
+
+  #[test]
+  fn does_not_fire_without_request() {
+    let mut pending = PendingWorldUpdate::default();
+    assert!(!pending.take_if_pruned(|_| false));
+  }
+
+  #[test]
+  fn waits_while_pruned_chunks_exist_then_fires_once() {
+    let (a, b) = (Entity::from_raw(1), Entity::from_raw(2));
+    let mut existing: HashSet<Entity> = [a, b].into_iter().collect();
+    let mut pending = PendingWorldUpdate::default();
+    pending.request(vec![a, b]);
+    assert!(!pending.take_if_pruned(|entity| existing.contains(&entity)));
+    existing.remove(&a);
+    assert!(!pending.take_if_pruned(|entity| existing.contains(&entity)));
+    existing.remove(&b);
+    assert!(pending.take_if_pruned(|entity| existing.contains(&entity)));
+    assert!(!pending.take_if_pruned(|entity| existing.contains(&entity)));
+  }
+
+  #[test]
+  fn fires_immediately_if_nothing_was_pruned() {
+    let mut pending = PendingWorldUpdate::default();
+    pending.request(vec![]);
+    assert!(pending.take_if_pruned(|_| true));
+    assert!(!pending.take_if_pruned(|_| true));
+  }
+
+  /// Toggling world pruning off and on again despawns all chunks and requests an update, after which the world is
+  /// regenerated. A second toggle before the first pruning completed must replace the first request, so that the
+  /// update is only sent once all chunks of the second pruning are gone, and only once.
+  #[test]
+  fn toggling_pruning_replaces_pending_request() {
+    let (a, b, c) = (Entity::from_raw(1), Entity::from_raw(2), Entity::from_raw(3));
+    let mut existing: HashSet<Entity> = [a, b, c].into_iter().collect();
+    let mut pending = PendingWorldUpdate::default();
+    pending.request(vec![a]);
+    pending.request(vec![b, c]);
+    existing.remove(&a);
+    assert!(!pending.take_if_pruned(|entity| existing.contains(&entity)));
+    existing.remove(&b);
+    existing.remove(&c);
+    assert!(pending.take_if_pruned(|entity| existing.contains(&entity)));
+    assert!(!pending.take_if_pruned(|entity| existing.contains(&entity)));
+  }
+}
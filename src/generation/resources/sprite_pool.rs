@@ -0,0 +1,131 @@
+use crate::constants::SPRITE_POOL_CAPACITY;
+use crate::generation::lib::{ObjectComponent, TileComponent};
+use bevy::app::{App, Plugin, Update};
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy::ecs::world::Command;
+use bevy::hierarchy::{BuildChildren, Children, DespawnRecursiveExt};
+use bevy::log::*;
+use bevy::prelude::{Bundle, Commands, Entity, Mut, Res, Resource, World};
+use bevy::utils::HashMap;
+
+pub struct SpritePoolPlugin;
+
+impl Plugin for SpritePoolPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_resource::<SpritePool>()
+      .register_diagnostic(Diagnostic::new(SPRITE_POOL_REUSE_RATE).with_suffix("%"))
+      .add_systems(Update, measure_sprite_pool_system);
+  }
+}
+
+/// The percentage of tile and object sprites that were spawned by reusing a recycled entity since the application
+/// started.
+pub const SPRITE_POOL_REUSE_RATE: DiagnosticPath = DiagnosticPath::const_new("sprite_pool_reuse_rate");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpritePoolCategory {
+  Tile,
+  Object,
+}
+
+/// Holds the entities of the tile and object sprites of despawned chunks, so that they can be reused when sprites are
+/// spawned for new chunks instead of churning through entities every time the world is pruned and updated. Recycled
+/// entities have no components and no parent. Every component of a sprite, including its `Transform` and
+/// `TextureAtlas`, is inserted again when the entity is reused. At most `SPRITE_POOL_CAPACITY` entities are kept per
+/// category.
+#[derive(Resource, Default)]
+pub struct SpritePool {
+  entities: HashMap<SpritePoolCategory, Vec<Entity>>,
+  spawned_count: usize,
+  reused_count: usize,
+}
+
+impl SpritePool {
+  /// Inserts the bundle into a recycled entity of the given category if there is one, or spawns a new entity otherwise,
+  /// and adds it as a child of `parent`.
+  pub fn spawn<B: Bundle>(&mut self, world: &mut World, category: SpritePoolCategory, parent: Entity, bundle: B) -> Entity {
+    let recycled = self
+      .entities
+      .get_mut(&category)
+      .and_then(|entities| entities.pop())
+      .filter(|entity| world.get_entity(*entity).is_ok());
+    let entity = match recycled {
+      Some(entity) => {
+        self.reused_count += 1;
+        world.entity_mut(entity).insert(bundle).id()
+      }
+      None => {
+        self.spawned_count += 1;
+        world.spawn(bundle).id()
+      }
+    };
+    world.entity_mut(parent).add_child(entity);
+
+    entity
+  }
+
+  /// Returns the percentage of sprites that were spawned by reusing a recycled entity.
+  pub fn reuse_rate(&self) -> f64 {
+    let total_count = self.spawned_count + self.reused_count;
+    if total_count == 0 {
+      return 0.;
+    }
+
+    self.reused_count as f64 / total_count as f64 * 100.
+  }
+
+  /// Detaches all tile and object sprites of the given chunk, despawns their children (e.g. the glow of a light source),
+  /// removes all of their components and adds them to the pool.
+  fn recycle_chunk(&mut self, world: &mut World, chunk: Entity) {
+    let mut recycled_count = 0;
+    let mut stack = vec![chunk];
+    while let Some(entity) = stack.pop() {
+      let Some(children) = world.get::<Children>(entity) else {
+        continue;
+      };
+      for child in children.iter().copied().collect::<Vec<Entity>>() {
+        let category = match (world.get::<TileComponent>(child), world.get::<ObjectComponent>(child)) {
+          (Some(_), _) => SpritePoolCategory::Tile,
+          (_, Some(_)) => SpritePoolCategory::Object,
+          _ => {
+            stack.push(child);
+            continue;
+          }
+        };
+        let entities = self.entities.entry(category).or_default();
+        if entities.len() >= SPRITE_POOL_CAPACITY {
+          continue;
+        }
+        world.entity_mut(child).despawn_descendants().remove_parent().clear();
+        entities.push(child);
+        recycled_count += 1;
+      }
+    }
+    trace!("Recycled {} sprite(s) of chunk {}", recycled_count, chunk);
+  }
+}
+
+/// Recycles the tile and object sprites of a chunk into the `SpritePool` and then despawns the chunk with everything
+/// else that belongs to it.
+struct DespawnChunkCommand(Entity);
+
+impl Command for DespawnChunkCommand {
+  fn apply(self, world: &mut World) {
+    if world.get_entity(self.0).is_err() {
+      return;
+    }
+    world.resource_scope(|world, mut sprite_pool: Mut<SpritePool>| sprite_pool.recycle_chunk(world, self.0));
+    world.entity_mut(self.0).despawn_recursive();
+  }
+}
+
+/// Despawns the given chunk, recycling its tile and object sprites into the `SpritePool`. Use this instead of
+/// `despawn_recursive` whenever a chunk is despawned while the world continues to exist.
+pub fn despawn_chunk(commands: &mut Commands, chunk: Entity) {
+  commands.queue(DespawnChunkCommand(chunk));
+}
+
+fn measure_sprite_pool_system(mut diagnostics: Diagnostics, sprite_pool: Res<SpritePool>) {
+  diagnostics.add_measurement(&SPRITE_POOL_REUSE_RATE, || sprite_pool.reuse_rate());
+}
@@ -0,0 +1,79 @@
+use crate::constants::STAGE_TIMINGS_SAMPLE_SIZE;
+use crate::generation::lib::{GenerationStage, WorldGenerationComponent};
+use bevy::app::{App, Plugin};
+use bevy::prelude::{OnRemove, Query, ResMut, Resource, Trigger};
+use std::collections::{BTreeMap, VecDeque};
+
+pub struct StageTimingsPlugin;
+
+impl Plugin for StageTimingsPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_resource::<StageTimings>()
+      .add_observer(on_remove_world_generation_component_trigger);
+  }
+}
+
+/// Keeps the durations (in ms) of each `GenerationStage` across the last `STAGE_TIMINGS_SAMPLE_SIZE`
+/// `WorldGenerationComponent`s that went through it. Intended to make it obvious which stage is the bottleneck of the
+/// world generation on a given machine.
+#[derive(Resource, Default)]
+pub struct StageTimings {
+  durations: BTreeMap<GenerationStage, VecDeque<u128>>,
+}
+
+/// The distribution of the durations (in ms) of a single `GenerationStage`.
+pub struct StageTimingSummary {
+  pub stage: GenerationStage,
+  pub sample_count: usize,
+  pub p50: u128,
+  pub p95: u128,
+  pub max: u128,
+}
+
+impl StageTimings {
+  pub fn record(&mut self, stage_durations: &[(GenerationStage, u128)]) {
+    for (stage, duration) in stage_durations.iter() {
+      let durations = self.durations.entry(*stage).or_default();
+      if durations.len() >= STAGE_TIMINGS_SAMPLE_SIZE {
+        durations.pop_front();
+      }
+      durations.push_back(*duration);
+    }
+  }
+
+  /// Returns a summary for each stage for which durations have been recorded, in the order of the stages.
+  pub fn summaries(&self) -> Vec<StageTimingSummary> {
+    self
+      .durations
+      .iter()
+      .filter(|(_, durations)| !durations.is_empty())
+      .map(|(stage, durations)| {
+        let mut sorted = durations.iter().copied().collect::<Vec<u128>>();
+        sorted.sort_unstable();
+        let percentile = |p: f32| sorted[((sorted.len() - 1) as f32 * p).round() as usize];
+        StageTimingSummary {
+          stage: *stage,
+          sample_count: sorted.len(),
+          p50: percentile(0.5),
+          p95: percentile(0.95),
+          max: sorted[sorted.len() - 1],
+        }
+      })
+      .collect()
+  }
+
+  pub fn clear(&mut self) {
+    self.durations.clear();
+  }
+}
+
+fn on_remove_world_generation_component_trigger(
+  trigger: Trigger<OnRemove, WorldGenerationComponent>,
+  query: Query<&WorldGenerationComponent>,
+  mut stage_timings: ResMut<StageTimings>,
+) {
+  if let Ok(component) = query.get(trigger.entity()) {
+    stage_timings.record(&component.stage_durations);
+  }
+}
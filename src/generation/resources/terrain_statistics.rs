@@ -0,0 +1,86 @@
+use crate::constants::TERRAIN_STATISTICS_PATH;
+use crate::generation::lib::{ChunkComponent, ObjectComponent};
+use bevy::app::{App, Plugin};
+use bevy::log::*;
+use bevy::prelude::Resource;
+use std::collections::BTreeMap;
+
+pub struct TerrainStatisticsPlugin;
+
+impl Plugin for TerrainStatisticsPlugin {
+  fn build(&self, app: &mut App) {
+    app.init_resource::<TerrainStatistics>();
+  }
+}
+
+/// A report on the composition of all chunks that currently exist in the world. Percentages are relative to the total
+/// number of tiles and are keyed by the `Debug` representation of the respective type, so that the report can be
+/// exported as JSON as is. Intended to help with balancing the noise and generation settings.
+#[derive(Resource, Default, Debug, Clone, serde::Serialize)]
+pub struct TerrainStatistics {
+  pub chunk_count: usize,
+  pub tile_count: usize,
+  pub terrain: BTreeMap<String, f32>,
+  pub tile_types: BTreeMap<String, f32>,
+  pub climates: BTreeMap<String, f32>,
+  pub beach: f32,
+  pub object_count: usize,
+  pub objects: BTreeMap<String, usize>,
+}
+
+impl TerrainStatistics {
+  /// Creates a report by walking the flat plane of every given chunk and counting the given objects.
+  pub fn analyse<'a>(
+    chunks: impl Iterator<Item = &'a ChunkComponent>,
+    objects: impl Iterator<Item = &'a ObjectComponent>,
+  ) -> Self {
+    let mut statistics = Self::default();
+    let mut terrain = BTreeMap::new();
+    let mut tile_types = BTreeMap::new();
+    let mut climates = BTreeMap::new();
+    let mut beach_count = 0;
+    for chunk in chunks {
+      statistics.chunk_count += 1;
+      for tile in chunk.layered_plane.flat.data.iter().flatten().flatten() {
+        statistics.tile_count += 1;
+        *terrain.entry(format!("{:?}", tile.terrain)).or_insert(0) += 1;
+        *tile_types.entry(format!("{:?}", tile.tile_type)).or_insert(0) += 1;
+        *climates.entry(format!("{:?}", tile.climate)).or_insert(0) += 1;
+        if tile.is_beach {
+          beach_count += 1;
+        }
+      }
+    }
+    for object in objects {
+      statistics.object_count += 1;
+      *statistics.objects.entry(format!("{:?}", object.object_name)).or_insert(0) += 1;
+    }
+    let tile_count = statistics.tile_count.max(1) as f32;
+    let to_percentages = |counts: BTreeMap<String, usize>| {
+      counts
+        .into_iter()
+        .map(|(key, count)| (key, count as f32 / tile_count * 100.))
+        .collect::<BTreeMap<String, f32>>()
+    };
+    statistics.terrain = to_percentages(terrain);
+    statistics.tile_types = to_percentages(tile_types);
+    statistics.climates = to_percentages(climates);
+    statistics.beach = beach_count as f32 / tile_count * 100.;
+
+    statistics
+  }
+
+  /// Writes the report as JSON to `TERRAIN_STATISTICS_PATH`.
+  pub fn export(&self) {
+    match serde_json::to_string_pretty(self) {
+      Ok(content) => match std::fs::write(TERRAIN_STATISTICS_PATH, content) {
+        Ok(_) => info!(
+          "Exported terrain statistics for {} chunk(s) to [{}]",
+          self.chunk_count, TERRAIN_STATISTICS_PATH
+        ),
+        Err(e) => error!("Failed to write terrain statistics to [{}]: {}", TERRAIN_STATISTICS_PATH, e),
+      },
+      Err(e) => error!("Failed to serialise terrain statistics: {}", e),
+    }
+  }
+}
@@ -0,0 +1,50 @@
+use crate::generation::object::lib::PropagationFailure;
+use bevy::app::{App, Plugin};
+use bevy::prelude::Resource;
+use bevy::utils::HashMap;
+use std::cmp::Reverse;
+
+pub struct WfcStatisticsPlugin;
+
+impl Plugin for WfcStatisticsPlugin {
+  fn build(&self, app: &mut App) {
+    app.init_resource::<WfcStatistics>();
+  }
+}
+
+/// Aggregates every `PropagationFailure` that was encountered while running the wave function collapse algorithm
+/// across all chunks. Failures are keyed by the terrain and tile type of the reference cell, the connection to the
+/// reference cell, the states that were attempted by the reference cell, and the remaining states of the failing cell.
+/// Intended to help with tuning the rule sets by making it obvious which rules cause the most backtracking.
+#[derive(Resource, Default)]
+pub struct WfcStatistics {
+  failures: HashMap<PropagationFailure, usize>,
+  total_count: usize,
+}
+
+impl WfcStatistics {
+  pub fn record(&mut self, failures: Vec<PropagationFailure>) {
+    for failure in failures {
+      *self.failures.entry(failure).or_insert(0) += 1;
+      self.total_count += 1;
+    }
+  }
+
+  pub fn total_count(&self) -> usize {
+    self.total_count
+  }
+
+  /// Returns all recorded failures and the number of times they occurred, sorted by the count in descending order.
+  /// Failures with the same count are sorted by their description, so that the order is stable between frames.
+  pub fn get_sorted(&self) -> Vec<(&PropagationFailure, usize)> {
+    let mut failures: Vec<(&PropagationFailure, usize)> = self.failures.iter().map(|(f, c)| (f, *c)).collect();
+    failures.sort_by_key(|(failure, count)| (Reverse(*count), format!("{:?}", failure)));
+
+    failures
+  }
+
+  pub fn clear(&mut self) {
+    self.failures.clear();
+    self.total_count = 0;
+  }
+}
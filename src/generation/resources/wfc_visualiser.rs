@@ -0,0 +1,64 @@
+use crate::constants::WFC_VISUALISER_STEPS_PER_SECOND;
+use crate::coords::point::ChunkGrid;
+use crate::coords::Point;
+use crate::generation::object::WfcStepper;
+use bevy::app::{App, Plugin};
+use bevy::prelude::Resource;
+
+pub struct WfcVisualiserPlugin;
+
+impl Plugin for WfcVisualiserPlugin {
+  fn build(&self, app: &mut App) {
+    app.init_resource::<WfcVisualiser>();
+  }
+}
+
+/// The state of the wave function collapse visualiser which re-runs the algorithm for a single chunk on the main
+/// thread, one iteration at a time, so that the entropy of each cell can be inspected. Controlled via the UI.
+#[derive(Resource)]
+pub struct WfcVisualiser {
+  pub cg: Option<Point<ChunkGrid>>,
+  pub stepper: Option<WfcStepper>,
+  pub is_running: bool,
+  pub steps_per_second: f32,
+  pub is_start_requested: bool,
+  pub is_stop_requested: bool,
+  pub is_step_requested: bool,
+  time_since_last_step: f32,
+}
+
+impl Default for WfcVisualiser {
+  fn default() -> Self {
+    Self {
+      cg: None,
+      stepper: None,
+      is_running: false,
+      steps_per_second: WFC_VISUALISER_STEPS_PER_SECOND,
+      is_start_requested: false,
+      is_stop_requested: false,
+      is_step_requested: false,
+      time_since_last_step: 0.,
+    }
+  }
+}
+
+impl WfcVisualiser {
+  /// Returns `true` if the next iteration is due, either because a single step was requested or because the
+  /// visualiser is running and enough time has passed since the last iteration.
+  pub fn is_step_due(&mut self, delta_seconds: f32) -> bool {
+    if self.is_step_requested {
+      self.is_step_requested = false;
+      return true;
+    }
+    if !self.is_running {
+      return false;
+    }
+    self.time_since_last_step += delta_seconds;
+    if self.time_since_last_step >= 1. / self.steps_per_second.max(0.1) {
+      self.time_since_last_step = 0.;
+      return true;
+    }
+
+    false
+  }
+}
@@ -0,0 +1,194 @@
+use crate::coords::point::{ChunkGrid, InternalGrid, TileGrid};
+use crate::coords::Point;
+use crate::generation::lib::{TerrainType, TileData};
+use crate::generation::object::lib::{ObjectData, ObjectName};
+use crate::generation::resources::ObjectResources;
+use bevy::app::{App, Plugin};
+use bevy::log::*;
+use bevy::prelude::Resource;
+use bevy::utils::{HashMap, HashSet};
+
+pub struct WorldModificationsPlugin;
+
+impl Plugin for WorldModificationsPlugin {
+  fn build(&self, app: &mut App) {
+    app.init_resource::<WorldModifications>();
+  }
+}
+
+/// A modification of the objects of the procedurally generated world that was made by the user or a game layer.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum WorldModification {
+  RemovedObject,
+  PlacedObject { name: ObjectName, sprite_index: i32 },
+}
+
+/// A sparse overlay of all modifications of the procedurally generated world, keyed by the `ChunkGrid` and
+/// `InternalGrid` coordinates of the tile they were made on, so that they survive chunks being pruned, regenerated or
+/// rerolled:
+/// - `modifications` holds the `WorldModification`s, which are applied to the object data of every chunk before its
+///   objects are spawned. Only the latest modification of each tile is kept.
+/// - `terrain` holds the altered terrain type of each tile, e.g. painted using the terrain brush, which overrides the
+///   generated terrain whenever the chunk containing the tile is generated.
+///
+/// Both are serialised as a list of entries, since JSON doesn't support keys that aren't strings.
+#[derive(Resource, Default, Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WorldModifications {
+  #[serde(with = "entries")]
+  modifications: HashMap<(Point<ChunkGrid>, Point<InternalGrid>), WorldModification>,
+  #[serde(with = "entries")]
+  terrain: HashMap<(Point<ChunkGrid>, Point<InternalGrid>), TerrainType>,
+}
+
+impl WorldModifications {
+  pub fn alter_terrain(&mut self, tg: Point<TileGrid>, terrain: TerrainType) {
+    let (cg, ig) = (
+      Point::new_chunk_grid_from_tile_grid(tg),
+      Point::new_internal_grid_from_tile_grid(tg),
+    );
+    trace!("Recorded altered terrain at {} {}: {:?}", cg, ig, terrain);
+    self.terrain.insert((cg, ig), terrain);
+  }
+
+  /// Returns the altered terrain type of the given tile, if any.
+  pub fn get_terrain(&self, tg: &Point<TileGrid>) -> Option<TerrainType> {
+    if self.terrain.is_empty() {
+      return None;
+    }
+    let key = (
+      Point::new_chunk_grid_from_tile_grid(*tg),
+      Point::new_internal_grid_from_tile_grid(*tg),
+    );
+
+    self.terrain.get(&key).copied()
+  }
+
+  pub fn record(&mut self, cg: Point<ChunkGrid>, ig: Point<InternalGrid>, modification: WorldModification) {
+    trace!("Recorded world modification at {} {}: {:?}", cg, ig, modification);
+    self.modifications.insert((cg, ig), modification);
+  }
+
+  /// Discards the `WorldModification` of the given tile, if any, e.g. because the terrain it was made on has changed.
+  pub fn remove(&mut self, cg: &Point<ChunkGrid>, ig: &Point<InternalGrid>) {
+    if let Some(modification) = self.modifications.remove(&(*cg, *ig)) {
+      trace!("Discarded world modification at {} {}: {:?}", cg, ig, modification);
+    }
+  }
+
+  pub fn get(&self, cg: &Point<ChunkGrid>, ig: &Point<InternalGrid>) -> Option<&WorldModification> {
+    self.modifications.get(&(*cg, *ig))
+  }
+
+  /// Applies all modifications of the chunk at the given `Point<ChunkGrid>` to the given object data. Objects placed on
+  /// tiles for which no object was generated are added using the given tile data of the chunk.
+  pub fn apply(
+    &self,
+    cg: &Point<ChunkGrid>,
+    object_data: &mut Vec<ObjectData>,
+    tile_data: &[TileData],
+    objects: &ObjectResources,
+  ) {
+    if self.modifications.is_empty() {
+      return;
+    }
+    let count = object_data.len();
+    let mut occupied = HashSet::new();
+    object_data.retain_mut(|data| {
+      let ig = data.tile_data.flat_tile.coords.internal_grid;
+      occupied.insert(ig);
+      match self.get(cg, &ig) {
+        Some(WorldModification::RemovedObject) => false,
+        Some(WorldModification::PlacedObject { name, sprite_index }) => {
+          data.name = Some(*name);
+          data.sprite_index = *sprite_index;
+          data.atlas = objects.get_atlas(*name);
+          true
+        }
+        None => true,
+      }
+    });
+    let removed_count = count - object_data.len();
+    let mut placed_count = 0;
+    for data in tile_data {
+      let ig = data.flat_tile.coords.internal_grid;
+      if occupied.contains(&ig) {
+        continue;
+      }
+      if let Some(WorldModification::PlacedObject { name, sprite_index }) = self.get(cg, &ig) {
+        object_data.push(ObjectData {
+          name: Some(*name),
+          sprite_index: *sprite_index,
+          atlas: objects.get_atlas(*name),
+          tile_data: *data,
+        });
+        placed_count += 1;
+      }
+    }
+    if removed_count > 0 || placed_count > 0 {
+      debug!(
+        "Re-applied world modifications to chunk {}, removing {} and placing {} object(s)",
+        cg, removed_count, placed_count
+      );
+    }
+  }
+}
+
+/// Serialises a map as a list of its entries and deserialises it from such a list.
+mod entries {
+  use bevy::utils::HashMap;
+  use serde::{Deserialize, Deserializer, Serialize, Serializer};
+  use std::hash::Hash;
+
+  pub fn serialize<K: Serialize, V: Serialize, S: Serializer>(
+    map: &HashMap<K, V>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(map.iter())
+  }
+
+  pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<HashMap<K, V>, D::Error>
+  where
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    D: Deserializer<'de>,
+  {
+    Vec::<(K, V)>::deserialize(deserializer).map(|entries| entries.into_iter().collect())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::generation::object::lib::ObjectName;
+
+  #[test]
+  fn world_modifications_survive_a_json_round_trip() {
+    let mut world_modifications = WorldModifications::default();
+    let (cg, ig) = (Point::new_chunk_grid(-1, 2), Point::new_internal_grid(3, 4));
+    world_modifications.record(cg, ig, WorldModification::RemovedObject);
+    world_modifications.record(
+      cg,
+      Point::new_internal_grid(5, 6),
+      WorldModification::PlacedObject {
+        name: ObjectName::SandStone1,
+        sprite_index: 7,
+      },
+    );
+    let tg = Point::new_tile_grid(-20, 35);
+    world_modifications.alter_terrain(tg, TerrainType::Land2);
+
+    let json = serde_json::to_string(&world_modifications).expect("Failed to serialise world modifications");
+    let restored: WorldModifications = serde_json::from_str(&json).expect("Failed to deserialise world modifications");
+
+    assert_eq!(restored.get(&cg, &ig), Some(&WorldModification::RemovedObject));
+    assert_eq!(
+      restored.get(&cg, &Point::new_internal_grid(5, 6)),
+      Some(&WorldModification::PlacedObject {
+        name: ObjectName::SandStone1,
+        sprite_index: 7,
+      })
+    );
+    assert_eq!(restored.get_terrain(&tg), Some(TerrainType::Land2));
+    assert_eq!(restored.get_terrain(&Point::new_tile_grid(0, 0)), None);
+  }
+}
@@ -4,11 +4,12 @@ use crate::coords::Point;
 use crate::events::{PruneWorldEvent, RefreshMetadata, RegenerateWorldEvent};
 use crate::generation::lib::{shared, TerrainType};
 use crate::generation::resources::{BiomeMetadata, Climate, ElevationMetadata, Metadata};
-use crate::resources::{CurrentChunk, GenerationMetadataSettings, Settings};
+use crate::resources::{CurrentChunk, GenerationMetadataSettings, Settings, SpawnPoint, SpawnPointCriterion};
 use crate::states::AppState;
 use bevy::app::{App, Plugin, Update};
 use bevy::log::*;
-use bevy::prelude::{EventReader, EventWriter, NextState, OnEnter, Res, ResMut};
+use bevy::prelude::{EventReader, EventWriter, IntoSystemConfigs, NextState, OnEnter, Res, ResMut, Resource};
+use bevy::tasks::{block_on, poll_once, AsyncComputeTaskPool, Task};
 use noise::{BasicMulti, MultiFractal, NoiseFn, Perlin};
 use rand::prelude::StdRng;
 use rand::{Rng, SeedableRng};
@@ -19,90 +20,180 @@ pub struct MetadataGeneratorPlugin;
 impl Plugin for MetadataGeneratorPlugin {
   fn build(&self, app: &mut App) {
     app
+      .init_resource::<MetadataTask>()
       .add_systems(OnEnter(AppState::Initialising), initialise_metadata)
-      .add_systems(Update, (update_metadata, refresh_metadata_event));
+      .add_systems(
+        Update,
+        (refresh_metadata_event, update_metadata, swap_metadata_system).chain(),
+      );
+  }
+}
+
+/// Holds the task that generates the next `Metadata` in the background, which is swapped in as a whole once complete
+/// so that the world generation never sees partially generated metadata. Scheduling a new task drops the previous one
+/// (cancelling it) to ensure that outdated metadata never replaces more recent metadata.
+#[derive(Resource, Default)]
+struct MetadataTask {
+  task: Option<Task<Metadata>>,
+  requested_cg: Point<ChunkGrid>,
+  refresh: Option<RefreshMetadata>,
+}
+
+impl MetadataTask {
+  /// Schedules the generation of the metadata for the given chunk, reusing the `previous` metadata where possible. Any
+  /// action requested by a previous `RefreshMetadata` event is retained unless a new one is provided. Since a refresh
+  /// implies that the settings may have changed, the metadata is always generated from scratch while one is pending.
+  fn schedule(
+    &mut self,
+    cg: Point<ChunkGrid>,
+    settings: &Settings,
+    previous: Option<Metadata>,
+    refresh: Option<RefreshMetadata>,
+  ) {
+    let settings = *settings;
+    let previous = previous.filter(|_| refresh.is_none() && self.refresh.is_none());
+    let task_pool = AsyncComputeTaskPool::get();
+    self.task = Some(task_pool.spawn(async move { generate_metadata(cg, &settings, previous) }));
+    self.requested_cg = cg;
+    self.refresh = refresh.or(self.refresh.take());
   }
 }
 
 /// This function is intended to be used to generate performance intensive metadata for the world prior to running the
-/// main loop.
+/// main loop. Unlike all subsequent updates, the metadata is generated synchronously here because there is no world
+/// yet that could be kept responsive.
 fn initialise_metadata(
-  metadata: ResMut<Metadata>,
-  current_chunk: Res<CurrentChunk>,
+  mut metadata: ResMut<Metadata>,
+  mut metadata_task: ResMut<MetadataTask>,
+  mut current_chunk: ResMut<CurrentChunk>,
+  mut spawn_point: ResMut<SpawnPoint>,
   settings: Res<Settings>,
   mut next_state: ResMut<NextState<AppState>>,
 ) {
-  regenerate_metadata(metadata, current_chunk.get_chunk_grid(), &settings);
+  if let Some(cg) = select_spawn_point(&settings) {
+    *spawn_point = SpawnPoint::from_chunk_grid(cg);
+    current_chunk.update(spawn_point.w);
+  }
+  *metadata = generate_metadata(current_chunk.get_chunk_grid(), &settings, None);
+  metadata_task.requested_cg = current_chunk.get_chunk_grid();
   next_state.set(AppState::Running);
 }
 
-/// Currently we're always regenerating the metadata for the entire grid. This is to allow changing the step size in
-/// the UI without having visual artifacts due to already generated metadata that is then incorrect. If this becomes
-/// a performance issue, we can change it but as of now, it's never taken anywhere near 1 ms.
-fn update_metadata(mut metadata: ResMut<Metadata>, current_chunk: Res<CurrentChunk>, settings: Res<Settings>) {
-  if metadata.current_chunk_cg == current_chunk.get_chunk_grid() {
+/// Only generates the metadata for chunks that are entering the metadata window and evicts the metadata of chunks
+/// that are leaving it. The metadata of the entire window is only regenerated when refreshing the metadata, which
+/// avoids visual artifacts when changing e.g. the step size in the UI. The metadata is generated in the background,
+/// so this doesn't stall any frames even for large windows.
+fn update_metadata(
+  mut metadata_task: ResMut<MetadataTask>,
+  metadata: Res<Metadata>,
+  current_chunk: Res<CurrentChunk>,
+  settings: Res<Settings>,
+) {
+  if metadata_task.requested_cg == current_chunk.get_chunk_grid() {
     return;
   }
-  metadata.current_chunk_cg = current_chunk.get_chunk_grid();
-  regenerate_metadata(metadata, current_chunk.get_chunk_grid(), &settings);
+  metadata_task.schedule(current_chunk.get_chunk_grid(), &settings, Some(metadata.clone()), None);
 }
 
 /// Refreshes the metadata based on the current chunk and settings. Used when manually triggering a world regeneration
-/// via the UI or using a keyboard shortcut. Triggers the action intended to be invoked by the user once the metadata
-/// has been refreshed.
+/// via the UI or using a keyboard shortcut. The action intended to be invoked by the user is triggered once the
+/// refreshed metadata has been swapped in.
 fn refresh_metadata_event(
-  metadata: ResMut<Metadata>,
+  mut metadata_task: ResMut<MetadataTask>,
   current_chunk: Res<CurrentChunk>,
   settings: Res<Settings>,
   mut refresh_metadata_event: EventReader<RefreshMetadata>,
+) {
+  if let Some(event) = refresh_metadata_event.read().last() {
+    metadata_task.schedule(current_chunk.get_chunk_grid(), &settings, None, Some(*event));
+  }
+}
+
+/// Replaces the `Metadata` resource with the newly generated metadata once the background task has completed and
+/// triggers the action requested by the `RefreshMetadata` event, if any. The rare features and terrain edges stored in
+/// the meantime are carried over unless the metadata was refreshed, since the world is re-generated in that case
+/// anyway.
+fn swap_metadata_system(
+  mut metadata_task: ResMut<MetadataTask>,
+  mut metadata: ResMut<Metadata>,
+  settings: Res<Settings>,
   mut regenerate_world_event: EventWriter<RegenerateWorldEvent>,
   mut prune_world_event: EventWriter<PruneWorldEvent>,
 ) {
-  if let Some(event) = refresh_metadata_event.read().last() {
-    regenerate_metadata(metadata, current_chunk.get_chunk_grid(), &settings);
+  let Some(task) = metadata_task.task.as_mut() else {
+    return;
+  };
+  let Some(new_metadata) = block_on(poll_once(task)) else {
+    return;
+  };
+  metadata_task.task = None;
+  let mut rare_features = std::mem::take(&mut metadata.rare_features);
+  let mut terrain_edges = std::mem::take(&mut metadata.terrain_edges);
+  *metadata = new_metadata;
+  let is_refreshed = metadata_task.refresh.is_some();
+  rare_features.retain(|cg, _| !is_refreshed && metadata.biome.contains_key(cg));
+  terrain_edges.retain(|cg, _| !is_refreshed && metadata.biome.contains_key(cg));
+  metadata.rare_features = rare_features;
+  metadata.terrain_edges = terrain_edges;
+  if let Some(event) = metadata_task.refresh.take() {
     if event.regenerate_world_after {
-      regenerate_world_event.send(RegenerateWorldEvent {});
+      regenerate_world_event.send(RegenerateWorldEvent { is_derived: true });
     } else if event.prune_then_update_world_after && settings.general.enable_world_pruning {
       prune_world_event.send(PruneWorldEvent {
         despawn_all_chunks: true,
         update_world_after: true,
+        is_derived: true,
       });
     }
   }
 }
 
-fn regenerate_metadata(mut metadata: ResMut<Metadata>, cg: Point<ChunkGrid>, settings: &Settings) {
-  let start_time = shared::get_time();
+/// Generates the metadata for all chunks within the metadata window around the given chunk. The metadata of chunks
+/// that is already present in `previous` is reused and the metadata of chunks outside the window is evicted. Since the
+/// metadata of a chunk only depends on its position and the settings, the result is identical to generating the
+/// metadata from scratch, as long as `previous` was generated with the same settings.
+pub(crate) fn generate_metadata(cg: Point<ChunkGrid>, settings: &Settings, previous: Option<Metadata>) -> Metadata {
+  let span = shared::TimedSpan::new(info_span!("generate_metadata", cg = %cg));
   let metadata_settings = settings.metadata;
-  let perlin: BasicMulti<Perlin> = BasicMulti::new(settings.world.noise_seed)
+  let perlin: BasicMulti<Perlin> = BasicMulti::new(settings.world.get_terrain_seed())
     .set_octaves(1)
     .set_frequency(metadata_settings.biome_noise_frequency);
-  metadata.index.clear();
-  (cg.x - METADATA_GRID_APOTHEM..=cg.x + METADATA_GRID_APOTHEM).for_each(|x| {
-    (cg.y - METADATA_GRID_APOTHEM..=cg.y + METADATA_GRID_APOTHEM).for_each(|y| {
+  // Chunks at the edge of the generation radius require the metadata of their neighbours too
+  let apothem = settings.general.metadata_radius.max(settings.general.generation_radius + 1);
+  let is_within_window = |point: &Point<ChunkGrid>| (point.x - cg.x).abs() <= apothem && (point.y - cg.y).abs() <= apothem;
+  let mut metadata = previous.unwrap_or_default();
+  metadata.current_chunk_cg = cg;
+  metadata.index.retain(|point| is_within_window(point));
+  metadata.elevation.retain(|point, _| is_within_window(point));
+  metadata.biome.retain(|point, _| is_within_window(point));
+  let reused_count = metadata.index.len();
+  (cg.x - apothem..=cg.x + apothem).for_each(|x| {
+    (cg.y - apothem..=cg.y + apothem).for_each(|y| {
       let cg = Point::new_chunk_grid(x, y);
+      if metadata.biome.contains_key(&cg) && metadata.elevation.contains_key(&cg) {
+        return;
+      }
       generate_elevation_metadata(&mut metadata, x, y, &metadata_settings);
       generate_biome_metadata(&mut metadata, &settings, &perlin, cg);
       metadata.index.push(cg);
     })
   });
-  debug!(
-    "Updated metadata based on current chunk {} in {} ms on {}",
-    cg,
-    shared::get_time() - start_time,
-    shared::thread_name()
+  span.finish(
+    Level::DEBUG,
+    format_args!(
+      "Generated metadata for {} chunk(s) and reused {} based on current chunk {}",
+      metadata.index.len() - reused_count,
+      reused_count,
+      cg
+    ),
   );
+
+  metadata
 }
 
-fn generate_elevation_metadata(
-  metadata: &mut ResMut<Metadata>,
-  x: i32,
-  y: i32,
-  metadata_settings: &GenerationMetadataSettings,
-) {
-  let grid_size = (CHUNK_SIZE as f32 - 1.) as f64;
-  let (x_range, x_step) = calculate_range_and_step_size(x, grid_size, metadata_settings);
-  let (y_range, y_step) = calculate_range_and_step_size(y, grid_size, metadata_settings);
+fn generate_elevation_metadata(metadata: &mut Metadata, x: i32, y: i32, metadata_settings: &GenerationMetadataSettings) {
+  let (x_range, x_step) = calculate_range_and_step_size(x, (CHUNK_WIDTH - 1) as f64, metadata_settings);
+  let (y_range, y_step) = calculate_range_and_step_size(y, (CHUNK_HEIGHT - 1) as f64, metadata_settings);
   let em = ElevationMetadata {
     is_enabled: !y_range.start.is_nan() || !y_range.end.is_nan() || !x_range.start.is_nan() || !x_range.end.is_nan(),
     x_step,
@@ -156,14 +247,72 @@ fn step_size(range_start: f64, range_end: f64, grid_size: f64, is_positive: bool
   ((range_end - range_start) / grid_size) * modifier
 }
 
-fn generate_biome_metadata(
-  metadata: &mut ResMut<Metadata>,
+/// Searches the chunks around the origin, ring by ring, for the closest chunk that matches the configured
+/// `SpawnPointCriterion`. Returns `None` if the criterion is `SpawnPointCriterion::Origin` or if no chunk within
+/// `SPAWN_POINT_SEARCH_RADIUS` matches it.
+fn select_spawn_point(settings: &Settings) -> Option<Point<ChunkGrid>> {
+  let criterion = settings.general.spawn_point_criterion;
+  if criterion == SpawnPointCriterion::Origin {
+    return None;
+  }
+  let span = shared::TimedSpan::new(info_span!("select_spawn_point", criterion = ?criterion));
+  let perlin: BasicMulti<Perlin> = BasicMulti::new(settings.world.get_terrain_seed())
+    .set_octaves(1)
+    .set_frequency(settings.metadata.biome_noise_frequency);
+  let origin = ORIGIN_CHUNK_GRID_SPAWN_POINT;
+  for radius in 0..=SPAWN_POINT_SEARCH_RADIUS {
+    for y in (origin.y - radius..=origin.y + radius).rev() {
+      for x in origin.x - radius..=origin.x + radius {
+        if (x - origin.x).abs() != radius && (y - origin.y).abs() != radius {
+          continue;
+        }
+        let cg = Point::new_chunk_grid(x, y);
+        if is_matching_spawn_point(criterion, settings, &perlin, cg) {
+          span.finish(Level::INFO, format_args!("Selected {} as [{:?}] spawn point", cg, criterion));
+          return Some(cg);
+        }
+      }
+    }
+  }
+  warn!(
+    "Failed to find a [{:?}] spawn point within {} chunks of the origin, spawning at origin instead",
+    criterion, SPAWN_POINT_SEARCH_RADIUS
+  );
+
+  None
+}
+
+fn is_matching_spawn_point(
+  criterion: SpawnPointCriterion,
   settings: &Settings,
   perlin: &BasicMulti<Perlin>,
   cg: Point<ChunkGrid>,
-) {
-  let mut rng = StdRng::seed_from_u64(shared::calculate_seed(cg, settings.world.noise_seed));
-  let rainfall = (perlin.get([cg.x as f64, cg.y as f64]) + 1.) / 2.;
+) -> bool {
+  let bm = calculate_biome_metadata(settings, perlin, cg);
+  if !bm.has_land() {
+    return false;
+  }
+  match criterion {
+    SpawnPointCriterion::Origin => true,
+    SpawnPointCriterion::Coastal => [(0, 1), (1, 0), (0, -1), (-1, 0)].iter().any(|(x, y)| {
+      let neighbour = calculate_biome_metadata(settings, perlin, Point::new_chunk_grid(cg.x + x, cg.y + y));
+      !neighbour.has_land()
+    }),
+    SpawnPointCriterion::Dry => bm.climate == Climate::Dry,
+    SpawnPointCriterion::Humid => bm.climate == Climate::Humid,
+    SpawnPointCriterion::Rocky => bm.is_rocky,
+  }
+}
+
+fn generate_biome_metadata(metadata: &mut Metadata, settings: &Settings, perlin: &BasicMulti<Perlin>, cg: Point<ChunkGrid>) {
+  let bm = calculate_biome_metadata(settings, perlin, cg);
+  trace!("Generated: {:?}", bm);
+  metadata.biome.insert(cg, bm);
+}
+
+fn calculate_biome_metadata(settings: &Settings, perlin: &BasicMulti<Perlin>, cg: Point<ChunkGrid>) -> BiomeMetadata {
+  let mut rng = StdRng::seed_from_u64(shared::calculate_seed(cg, settings.world.get_terrain_seed()));
+  let rainfall = calculate_rainfall(&settings.metadata, perlin, cg);
   let climate = Climate::from(rainfall);
   let is_rocky = rng.gen_bool(BIOME_IS_ROCKY_PROBABILITY);
   let max_layer = match rainfall {
@@ -172,7 +321,77 @@ fn generate_biome_metadata(
     n if n > 0.25 => TerrainType::Land1,
     _ => TerrainType::ShallowWater,
   };
-  let bm = BiomeMetadata::new(cg, is_rocky, rainfall as f32, max_layer as i32, climate);
-  trace!("Generated: {:?}", bm);
-  metadata.biome.insert(cg, bm);
+
+  BiomeMetadata::new(cg, is_rocky, rainfall as f32, max_layer as i32, climate)
+}
+
+/// Returns the rainfall of the given chunk between `0.0` and `1.0`, which is determined by the biome noise and, if
+/// enabled, the climate gradient. The gradient increases linearly from `0.0` to `1.0` between the chunks at the
+/// climate gradient distance behind and ahead of the origin, and is constant beyond them.
+fn calculate_rainfall(
+  metadata_settings: &GenerationMetadataSettings,
+  perlin: &BasicMulti<Perlin>,
+  cg: Point<ChunkGrid>,
+) -> f64 {
+  let noise = (perlin.get([cg.x as f64, cg.y as f64]) + 1.) / 2.;
+  let strength = metadata_settings.climate_gradient_strength;
+  if strength <= 0. {
+    return noise;
+  }
+  let distance = metadata_settings.climate_gradient_direction.distance(cg) as f64;
+  let max_distance = metadata_settings.climate_gradient_distance.max(1) as f64;
+  let gradient = ((distance / max_distance).clamp(-1., 1.) + 1.) / 2.;
+
+  noise * (1. - strength) + gradient * strength
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Returns the metadata of every chunk as sorted, comparable strings, since the metadata types don't implement
+  /// `PartialEq` and store floats.
+  fn snapshot(metadata: &Metadata) -> (Vec<String>, Vec<String>, Vec<Point<ChunkGrid>>) {
+    let mut elevation = metadata
+      .elevation
+      .iter()
+      .map(|(cg, em)| format!("{} {:?}", cg, em))
+      .collect::<Vec<String>>();
+    let mut biome = metadata
+      .biome
+      .iter()
+      .map(|(cg, bm)| format!("{} {:?}", cg, bm))
+      .collect::<Vec<String>>();
+    let mut index = metadata.index.clone();
+    elevation.sort();
+    biome.sort();
+    index.sort_by_key(|cg| (cg.x, cg.y));
+
+    (elevation, biome, index)
+  }
+
+  #[test]
+  fn incremental_metadata_equals_metadata_generated_from_scratch() {
+    let mut rng = StdRng::seed_from_u64(42);
+    for _ in 0..10 {
+      let mut settings = Settings::default();
+      settings.world.noise_seed = rng.gen();
+      settings.general.metadata_radius = rng.gen_range(1..=4);
+      let mut cg = Point::new_chunk_grid(rng.gen_range(-20..=20), rng.gen_range(-20..=20));
+      let mut metadata = generate_metadata(cg, &settings, None);
+      for _ in 0..10 {
+        cg = Point::new_chunk_grid(cg.x + rng.gen_range(-3..=3), cg.y + rng.gen_range(-3..=3));
+        metadata = generate_metadata(cg, &settings, Some(metadata));
+        let from_scratch = generate_metadata(cg, &settings, None);
+        assert_eq!(metadata.current_chunk_cg, from_scratch.current_chunk_cg);
+        assert_eq!(
+          snapshot(&metadata),
+          snapshot(&from_scratch),
+          "Incremental metadata for {} differs from the metadata generated from scratch with seed {}",
+          cg,
+          settings.world.noise_seed
+        );
+      }
+    }
+  }
 }
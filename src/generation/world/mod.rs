@@ -1,18 +1,28 @@
 use crate::generation::world::metadata_generator::MetadataGeneratorPlugin;
 use crate::generation::world::post_processor::PostProcessorPlugin;
+use crate::generation::world::terrain_painter::TerrainPainterPlugin;
 use crate::generation::world::world_generator::WorldGeneratorPlugin;
 use bevy::app::{App, Plugin};
 
 mod metadata_generator;
 mod post_processor;
+mod terrain_painter;
 mod world_generator;
 
 pub struct WorldGenerationPlugin;
 
 impl Plugin for WorldGenerationPlugin {
   fn build(&self, app: &mut App) {
-    app.add_plugins((MetadataGeneratorPlugin, WorldGeneratorPlugin, PostProcessorPlugin));
+    app.add_plugins((
+      MetadataGeneratorPlugin,
+      WorldGeneratorPlugin,
+      PostProcessorPlugin,
+      TerrainPainterPlugin,
+    ));
   }
 }
 
 pub use crate::generation::world::world_generator::{generate_chunks, schedule_tile_spawning_tasks, spawn_chunk};
+
+#[cfg(test)]
+pub(crate) use crate::generation::world::metadata_generator::generate_metadata;
@@ -12,7 +12,7 @@ impl Plugin for PostProcessorPlugin {
 }
 
 pub(crate) fn process(mut chunk: Chunk, settings: &Settings) -> Chunk {
-  let start_time = shared::get_time();
+  let span = shared::TimedSpan::new(info_span!("post_process_chunk", cg = %chunk.coords.chunk_grid));
   for layer in 1..TerrainType::length() {
     let layer_name = TerrainType::from(layer);
     if layer < settings.general.spawn_from_layer || layer > settings.general.spawn_up_to_layer {
@@ -21,12 +21,7 @@ pub(crate) fn process(mut chunk: Chunk, settings: &Settings) -> Chunk {
     }
     clear_single_tiles_from_chunk_with_no_fill_below(layer, &mut chunk);
   }
-  trace!(
-    "Pre-processed chunk {} in {} ms on [{}]",
-    chunk.coords.chunk_grid,
-    shared::get_time() - start_time,
-    shared::thread_name()
-  );
+  span.finish(Level::TRACE, format_args!("Pre-processed chunk {}", chunk.coords.chunk_grid));
 
   chunk
 }
@@ -0,0 +1,130 @@
+use crate::coords::point::{ChunkGrid, TileGrid};
+use crate::coords::Point;
+use crate::events::{MouseClickEvent, PaintTerrainEvent};
+use crate::generation::lib::{shared, ChunkComponent, WorldGenerationComponent};
+use crate::generation::resources::{despawn_chunk, Metadata, WorldModifications};
+use crate::resources::{CurrentChunk, Settings};
+use crate::states::{AppState, GenerationState};
+use bevy::app::{App, Plugin, Update};
+use bevy::ecs::system::SystemParam;
+use bevy::log::*;
+use bevy::prelude::{
+  in_state, Commands, Entity, EventReader, EventWriter, IntoSystemConfigs, NextState, Query, Res, ResMut, State, Trigger,
+};
+use bevy::utils::HashSet;
+
+pub struct TerrainPainterPlugin;
+
+impl Plugin for TerrainPainterPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .add_observer(on_left_mouse_click_trigger)
+      .add_systems(Update, paint_terrain_event.run_if(in_state(AppState::Running)));
+  }
+}
+
+fn on_left_mouse_click_trigger(
+  trigger: Trigger<MouseClickEvent>,
+  settings: Res<Settings>,
+  mut paint_terrain_event: EventWriter<PaintTerrainEvent>,
+) {
+  if !settings.general.enable_terrain_brush {
+    return;
+  }
+  paint_terrain_event.send(PaintTerrainEvent {
+    tg: trigger.event().tile.tg,
+    terrain: settings.general.terrain_brush_type,
+    radius: settings.general.terrain_brush_radius,
+  });
+}
+
+/// Regenerates the world around the current chunk, provided that it isn't being generated already.
+#[derive(SystemParam)]
+struct WorldRegeneration<'w> {
+  current_chunk: Res<'w, CurrentChunk>,
+  generation_state: Res<'w, State<GenerationState>>,
+  next_state: ResMut<'w, NextState<GenerationState>>,
+}
+
+impl WorldRegeneration<'_> {
+  fn is_generating(&self) -> bool {
+    *self.generation_state.get() != GenerationState::Idling
+  }
+
+  fn start(&mut self, commands: &mut Commands, settings: &Settings) {
+    let w = self.current_chunk.get_world();
+    let cg = self.current_chunk.get_chunk_grid();
+    let mut component_commands = commands.spawn(WorldGenerationComponent::new(w, cg, true, shared::get_time(), *settings));
+    if let Some(name) = shared::debug_name(settings, || format!("Paint Terrain Component {}", w)) {
+      component_commands.insert(name);
+    }
+    self.next_state.set(GenerationState::Generating);
+  }
+}
+
+/// Records the painted terrain of each `PaintTerrainEvent` in the `WorldModifications`, from where it is applied
+/// whenever the chunks containing the painted tiles are generated. All spawned chunks that contain a painted tile or one
+/// of its neighbours (whose tile types depend on it) are then despawned and regenerated, which also re-runs the object
+/// generation for them. Object modifications of the painted tiles are discarded, since they may not be valid for the
+/// new terrain. Events are ignored while the world is being generated, as the chunks regenerated here could otherwise
+/// be overwritten by chunks that are already being generated.
+fn paint_terrain_event(
+  mut commands: Commands,
+  mut events: EventReader<PaintTerrainEvent>,
+  chunks: Query<(Entity, &ChunkComponent)>,
+  mut metadata: ResMut<Metadata>,
+  mut world_modifications: ResMut<WorldModifications>,
+  settings: Res<Settings>,
+  mut regeneration: WorldRegeneration,
+) {
+  let mut affected_chunks: HashSet<Point<ChunkGrid>> = HashSet::new();
+  for event in events.read() {
+    if regeneration.is_generating() {
+      warn!(
+        "Ignored painting [{:?}] terrain at {} because the world is being generated",
+        event.terrain, event.tg
+      );
+      continue;
+    }
+    let painted_tiles = tiles_within_radius(&event.tg, event.radius);
+    debug!(
+      "Painting [{:?}] terrain onto {} tile(s) around {}",
+      event.terrain,
+      painted_tiles.len(),
+      event.tg
+    );
+    for tg in painted_tiles {
+      world_modifications.alter_terrain(tg, event.terrain);
+      world_modifications.remove(
+        &Point::new_chunk_grid_from_tile_grid(tg),
+        &Point::new_internal_grid_from_tile_grid(tg),
+      );
+      affected_chunks.extend((-1..=1).flat_map(|dx| {
+        (-1..=1).map(move |dy| Point::new_chunk_grid_from_tile_grid(Point::new_tile_grid(tg.x + dx, tg.y + dy)))
+      }));
+    }
+  }
+  if affected_chunks.is_empty() {
+    return;
+  }
+  let mut despawned_count = 0;
+  for (entity, chunk_component) in chunks.iter() {
+    let cg = chunk_component.coords.chunk_grid;
+    if affected_chunks.contains(&cg) {
+      despawn_chunk(&mut commands, entity);
+      metadata.terrain_edges.remove(&cg);
+      despawned_count += 1;
+    }
+  }
+  debug!("Regenerating {} chunk(s) affected by painted terrain", despawned_count);
+  regeneration.start(&mut commands, &settings);
+}
+
+/// Returns all tiles whose distance to the given tile is at most `radius` tiles, including the tile itself.
+fn tiles_within_radius(tg: &Point<TileGrid>, radius: i32) -> Vec<Point<TileGrid>> {
+  (-radius..=radius)
+    .flat_map(|dx| (-radius..=radius).map(move |dy| (dx, dy)))
+    .filter(|(dx, dy)| dx * dx + dy * dy <= radius * radius)
+    .map(|(dx, dy)| Point::new_tile_grid(tg.x + dx, tg.y + dy))
+    .collect()
+}
@@ -1,27 +1,36 @@
 use crate::components::{AnimationComponent, AnimationTimer};
-use crate::constants::{ANIMATION_LENGTH, CHUNK_SIZE, DEFAULT_ANIMATION_FRAME_DURATION, TERRAIN_TYPE_ERROR};
-use crate::coords::point::World;
+use crate::constants::{ANIMATION_LENGTH, CHUNK_HEIGHT, CHUNK_WIDTH, DEFAULT_ANIMATION_FRAME_DURATION, TERRAIN_TYPE_ERROR};
+use crate::coords::point::{ChunkGrid, World};
+use crate::coords::projection::projection;
 use crate::coords::Point;
 use crate::generation::lib::shared::CommandQueueTask;
-use crate::generation::lib::{shared, Chunk, ChunkComponent, TerrainType, Tile, TileComponent, TileData};
-use crate::generation::resources::{AssetPack, Climate, GenerationResourcesCollection, Metadata};
+use crate::generation::lib::{
+  run_after_terrain_generation_hooks, shared, Chunk, ChunkComponent, TerrainEdges, TerrainType, Tile, TileComponent,
+  TileData, TileDataComponent,
+};
+use crate::generation::resources::{
+  AssetPack, Climate, GenerationResourcesCollection, Metadata, SpritePool, SpritePoolCategory, WorldModifications,
+};
 use crate::generation::world::post_processor;
+use crate::generation::FrameTimeBudgetSet;
 use crate::resources::Settings;
 use bevy::app::{App, Plugin, Update};
-use bevy::core::Name;
 use bevy::ecs::world::CommandQueue;
-use bevy::hierarchy::{BuildChildren, ChildBuild, ChildBuilder, WorldChildBuilder};
+use bevy::hierarchy::{BuildChildren, ChildBuild, ChildBuilder};
 use bevy::log::*;
-use bevy::prelude::{Commands, Component, Entity, Query, Sprite, TextureAtlas, Timer, TimerMode, Transform, Visibility};
+use bevy::prelude::{
+  Commands, Component, Entity, IntoSystemConfigs, Mut, Query, Sprite, TextureAtlas, Timer, TimerMode, Transform, Visibility,
+};
 use bevy::sprite::Anchor;
 use bevy::tasks;
 use bevy::tasks::{block_on, AsyncComputeTaskPool, Task};
+use bevy::utils::HashMap;
 
 pub struct WorldGeneratorPlugin;
 
 impl Plugin for WorldGeneratorPlugin {
   fn build(&self, app: &mut App) {
-    app.add_systems(Update, process_async_tasks_system);
+    app.add_systems(Update, process_async_tasks_system.in_set(FrameTimeBudgetSet));
   }
 }
 
@@ -34,61 +43,84 @@ impl CommandQueueTask for TileSpawnTask {
   }
 }
 
-pub fn generate_chunks(spawn_points: Vec<Point<World>>, metadata: Metadata, settings: &Settings) -> Vec<Chunk> {
-  let start_time = shared::get_time();
+pub fn generate_chunks(
+  spawn_points: Vec<Point<World>>,
+  metadata: &Metadata,
+  world_modifications: &WorldModifications,
+  settings: &Settings,
+) -> Vec<Chunk> {
+  let span = shared::TimedSpan::new(info_span!("generate_chunks"));
   let mut chunks: Vec<Chunk> = Vec::new();
+  let mut generated_edges: HashMap<Point<ChunkGrid>, TerrainEdges> = HashMap::new();
   for chunk_w in spawn_points {
+    let cg = Point::new_chunk_grid_from_world(chunk_w);
+    if !metadata.has_metadata_for(&cg) {
+      error!(
+        "Failed to generate chunk {} because the metadata for it or its neighbours is missing",
+        cg
+      );
+      continue;
+    }
     let chunk_tg = Point::new_tile_grid_from_world(chunk_w.clone());
-    let mut chunk = Chunk::new(chunk_w.clone(), chunk_tg, &metadata, &settings);
+    let neighbour_edges = metadata.get_terrain_edges_for(&cg, &generated_edges);
+    let mut chunk = Chunk::new(
+      chunk_w.clone(),
+      chunk_tg,
+      metadata,
+      world_modifications,
+      &neighbour_edges,
+      &settings,
+    );
+    generated_edges.insert(chunk.coords.chunk_grid, chunk.terrain_edges.clone());
     chunk = post_processor::process(chunk, &settings);
+    run_after_terrain_generation_hooks(&mut chunk);
     chunks.push(chunk);
   }
-  debug!(
-    "Generated {} chunks in {} ms on [{}]",
-    chunks.len(),
-    shared::get_time() - start_time,
-    shared::thread_name()
-  );
+  span.finish(Level::DEBUG, format_args!("Generated {} chunks", chunks.len()));
 
   chunks
 }
 
-pub fn spawn_chunk(world_child_builder: &mut ChildBuilder, chunk: &Chunk) -> Vec<TileData> {
+pub fn spawn_chunk(world_child_builder: &mut ChildBuilder, chunk: &Chunk, settings: &Settings) -> Vec<TileData> {
   let mut tile_data = Vec::new();
-  let chunk_end_tg = chunk.coords.tile_grid + Point::new(CHUNK_SIZE - 1, -CHUNK_SIZE + 1);
-  world_child_builder
-    .spawn((
-      Name::new(format!(
-        "Chunk {} {} to {}",
-        chunk.coords.world, chunk.coords.tile_grid, chunk_end_tg
-      )),
-      Transform::default(),
-      Visibility::default(),
-      ChunkComponent {
-        layered_plane: chunk.layered_plane.clone(),
-        coords: chunk.coords.clone(),
-      },
-    ))
-    .with_children(|parent| {
-      for cell in chunk.layered_plane.flat.data.iter().flatten() {
-        if let Some(tile) = cell {
-          let tile_entity = parent
-            .spawn((
-              Name::new("Tile ".to_string() + &tile.coords.tile_grid.to_string()),
-              Transform::from_xyz(tile.coords.world.x as f32, tile.coords.world.y as f32, 0.),
-              Visibility::default(),
-            ))
-            .id();
-          tile_data.push(TileData::new(tile_entity, parent.parent_entity(), tile.clone()));
+  let mut chunk_commands = world_child_builder.spawn((
+    Transform::default(),
+    Visibility::default(),
+    ChunkComponent {
+      layered_plane: chunk.layered_plane.clone(),
+      coords: chunk.coords.clone(),
+      spawned_at: shared::get_time(),
+    },
+  ));
+  if let Some(name) = shared::debug_name(settings, || {
+    let chunk_end_tg = chunk.coords.tile_grid + Point::new(CHUNK_WIDTH - 1, -CHUNK_HEIGHT + 1);
+    format!("Chunk {} {} to {}", chunk.coords.world, chunk.coords.tile_grid, chunk_end_tg)
+  }) {
+    chunk_commands.insert(name);
+  }
+  chunk_commands.with_children(|parent| {
+    let chunk_entity = parent.parent_entity();
+    for cell in chunk.layered_plane.flat.data.iter().flatten() {
+      if let Some(tile) = cell {
+        let mut tile_commands = parent.spawn((
+          projection().tile_transform(tile.coords.world.to_vec2()),
+          Visibility::default(),
+        ));
+        if let Some(name) = shared::debug_name(settings, || format!("Tile {}", tile.coords.tile_grid)) {
+          tile_commands.insert(name);
         }
+        let data = TileData::new(tile_commands.id(), chunk_entity, tile.clone());
+        tile_commands.insert(TileDataComponent { tile_data: data });
+        tile_data.push(data);
       }
-    });
+    }
+  });
 
   tile_data
 }
 
 pub fn schedule_tile_spawning_tasks(commands: &mut Commands, settings: &Settings, spawn_data: (Chunk, Vec<TileData>)) {
-  let start_time = shared::get_time();
+  let span = shared::TimedSpan::new(info_span!("schedule_tile_spawning_tasks", cg = %spawn_data.0.coords.chunk_grid));
   let task_pool = AsyncComputeTaskPool::get();
 
   for tile_data in spawn_data.1 {
@@ -105,35 +137,45 @@ pub fn schedule_tile_spawning_tasks(commands: &mut Commands, settings: &Settings
         if let Some(tile) = plane.get_tile(tile_data.flat_tile.coords.internal_grid) {
           if let Some(mut tile_entity) = commands.get_entity(tile_data.entity) {
             tile_entity.with_children(|parent| {
-              attach_task_to_tile_entity(task_pool, parent, tile_data, tile.clone());
+              attach_task_to_tile_entity(task_pool, parent, settings, tile_data, tile.clone());
             });
           }
         }
       }
     }
   }
-  debug!(
-    "Scheduled spawning tiles for chunk {} in {} ms on [{}]",
-    spawn_data.0.coords.chunk_grid,
-    shared::get_time() - start_time,
-    shared::thread_name()
+  span.finish(
+    Level::DEBUG,
+    format_args!("Scheduled spawning tiles for chunk {}", spawn_data.0.coords.chunk_grid),
   );
 }
 
-fn attach_task_to_tile_entity(task_pool: &AsyncComputeTaskPool, parent: &mut ChildBuilder, tile_data: TileData, tile: Tile) {
+fn attach_task_to_tile_entity(
+  task_pool: &AsyncComputeTaskPool,
+  parent: &mut ChildBuilder,
+  settings: &Settings,
+  tile_data: TileData,
+  tile: Tile,
+) {
   let task = task_pool.spawn(async move {
     let mut command_queue = CommandQueue::default();
     command_queue.push(move |world: &mut bevy::prelude::World| {
-      let (resources, settings) = shared::get_resources_and_settings(world);
-      if let Ok(mut tile_data_entity) = world.get_entity_mut(tile_data.entity) {
-        tile_data_entity.with_children(|parent| {
-          spawn_tile(tile_data, &tile, &resources, settings, parent);
-        });
+      let settings = *world.resource::<Settings>();
+      if world.get_entity(tile_data.entity).is_err() {
+        return;
       }
+      world.resource_scope(|world, resources: Mut<GenerationResourcesCollection>| {
+        world.resource_scope(|world, mut sprite_pool: Mut<SpritePool>| {
+          spawn_tile(tile_data, &tile, &resources, settings, &mut sprite_pool, world);
+        });
+      });
     });
     command_queue
   });
-  parent.spawn((Name::new("Tile Spawn Task"), TileSpawnTask(task)));
+  let mut task_commands = parent.spawn(TileSpawnTask(task));
+  if let Some(name) = shared::debug_name(settings, || "Tile Spawn Task".to_string()) {
+    task_commands.insert(name);
+  }
 }
 
 fn resolve_asset_pack<'a>(tile: &Tile, resources: &'a GenerationResourcesCollection) -> (bool, &'a AssetPack) {
@@ -156,21 +198,36 @@ fn spawn_tile(
   tile: &Tile,
   resources: &GenerationResourcesCollection,
   settings: Settings,
-  parent: &mut WorldChildBuilder,
+  sprite_pool: &mut SpritePool,
+  world: &mut bevy::prelude::World,
 ) {
+  let parent = tile_data.entity;
   if !settings.general.draw_terrain_sprites {
-    parent.spawn(placeholder_sprite(&tile, tile_data.chunk_entity, &resources));
+    let sprite = placeholder_sprite(&tile, tile_data.chunk_entity, &resources);
+    let entity = sprite_pool.spawn(world, SpritePoolCategory::Tile, parent, sprite);
+    if let Some(name) = shared::debug_name(&settings, || format!("Placeholder {:?} Sprite", tile.terrain)) {
+      world.entity_mut(entity).insert(name);
+    }
     return;
   }
-  if settings.general.animate_terrain_sprites {
-    let (is_animated_tile, anim_asset_pack) = resolve_asset_pack(&tile, &resources);
-    if is_animated_tile {
-      parent.spawn(animated_terrain_sprite(&tile, tile_data.chunk_entity, &anim_asset_pack));
-    } else {
-      parent.spawn(static_terrain_sprite(&tile, tile_data.chunk_entity, &resources));
+  let anim_asset_pack = match settings.general.animate_terrain_sprites {
+    true => Some(resolve_asset_pack(&tile, &resources)).and_then(|(is_animated, pack)| is_animated.then_some(pack)),
+    false => None,
+  };
+  if let Some(anim_asset_pack) = anim_asset_pack {
+    let sprite = animated_terrain_sprite(&tile, tile_data.chunk_entity, &anim_asset_pack);
+    let entity = sprite_pool.spawn(world, SpritePoolCategory::Tile, parent, sprite);
+    if let Some(name) = shared::debug_name(&settings, || {
+      format!("{:?} {:?} Sprite (Animated)", tile.tile_type, tile.terrain)
+    }) {
+      world.entity_mut(entity).insert(name);
     }
   } else {
-    parent.spawn(static_terrain_sprite(&tile, tile_data.chunk_entity, &resources));
+    let sprite = static_terrain_sprite(&tile, tile_data.chunk_entity, &resources);
+    let entity = sprite_pool.spawn(world, SpritePoolCategory::Tile, parent, sprite);
+    if let Some(name) = shared::debug_name(&settings, || format!("{:?} {:?} Sprite", tile.tile_type, tile.terrain)) {
+      world.entity_mut(entity).insert(name);
+    }
   }
 }
 
@@ -178,9 +235,8 @@ fn placeholder_sprite(
   tile: &Tile,
   chunk: Entity,
   resources: &GenerationResourcesCollection,
-) -> (Name, Sprite, Transform, TileComponent) {
+) -> (Sprite, Transform, TileComponent) {
   (
-    Name::new(format!("Placeholder {:?} Sprite", tile.terrain)),
     Sprite {
       anchor: Anchor::TopLeft,
       texture_atlas: Some(TextureAtlas {
@@ -190,7 +246,7 @@ fn placeholder_sprite(
       image: resources.placeholder.texture.clone(),
       ..Default::default()
     },
-    Transform::from_xyz(0.0, 0.0, tile.layer as f32),
+    projection().terrain_sprite_transform(tile.layer as f32),
     TileComponent {
       tile: tile.clone(),
       parent_entity: chunk,
@@ -202,10 +258,9 @@ fn static_terrain_sprite(
   tile: &Tile,
   chunk: Entity,
   resources: &GenerationResourcesCollection,
-) -> (Name, Transform, Sprite, TileComponent) {
+) -> (Transform, Sprite, TileComponent) {
   (
-    Name::new(format!("{:?} {:?} Sprite", tile.tile_type, tile.terrain)),
-    Transform::from_xyz(0.0, 0.0, tile.layer as f32),
+    projection().terrain_sprite_transform(tile.layer as f32),
     Sprite {
       anchor: Anchor::TopLeft,
       texture_atlas: Some(TextureAtlas {
@@ -221,11 +276,10 @@ fn static_terrain_sprite(
           (TerrainType::Land3, Climate::Dry) => resources.land_dry_l3.stat.texture_atlas_layout.clone(),
           (TerrainType::Land3, Climate::Moderate) => resources.land_moderate_l3.stat.texture_atlas_layout.clone(),
           (TerrainType::Land3, Climate::Humid) => resources.land_humid_l3.stat.texture_atlas_layout.clone(),
+          (TerrainType::Land4, _) => resources.snow.stat.texture_atlas_layout.clone(),
           (TerrainType::Any, _) => panic!("{}", TERRAIN_TYPE_ERROR),
         },
-        index: tile
-          .tile_type
-          .calculate_sprite_index(&tile.terrain, &tile.climate, &resources),
+        index: resources.get_static_sprite_index(tile),
       }),
       image: match (tile.terrain, tile.climate) {
         (TerrainType::DeepWater, _) => resources.deep_water.stat.texture.clone(),
@@ -239,6 +293,7 @@ fn static_terrain_sprite(
         (TerrainType::Land3, Climate::Dry) => resources.land_dry_l3.stat.texture.clone(),
         (TerrainType::Land3, Climate::Moderate) => resources.land_moderate_l3.stat.texture.clone(),
         (TerrainType::Land3, Climate::Humid) => resources.land_humid_l3.stat.texture.clone(),
+        (TerrainType::Land4, _) => resources.snow.stat.texture.clone(),
         (TerrainType::Any, _) => panic!("{}", TERRAIN_TYPE_ERROR),
       },
       ..Default::default()
@@ -254,15 +309,14 @@ fn animated_terrain_sprite(
   tile: &Tile,
   chunk: Entity,
   asset_pack: &AssetPack,
-) -> (Name, Transform, Sprite, TileComponent, AnimationComponent) {
+) -> (Transform, Sprite, TileComponent, AnimationComponent) {
   let index = tile.tile_type.get_sprite_index(asset_pack.index_offset);
   let frame_duration = match tile.terrain {
     TerrainType::ShallowWater => DEFAULT_ANIMATION_FRAME_DURATION / 2.,
     _ => DEFAULT_ANIMATION_FRAME_DURATION,
   };
   (
-    Name::new(format!("{:?} {:?} Sprite (Animated)", tile.tile_type, tile.terrain)),
-    Transform::from_xyz(0.0, 0.0, tile.layer as f32),
+    projection().terrain_sprite_transform(tile.layer as f32),
     Sprite {
       anchor: Anchor::TopLeft,
       texture_atlas: Some(TextureAtlas {
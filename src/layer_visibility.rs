@@ -0,0 +1,74 @@
+use crate::generation::lib::{ObjectComponent, TileComponent};
+use crate::generation::resources::GenerationResourcesCollection;
+use crate::resources::{LayerVisibility, Settings};
+use bevy::app::{App, Plugin, Update};
+use bevy::prelude::*;
+
+pub struct LayerVisibilityPlugin;
+
+impl Plugin for LayerVisibilityPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .add_systems(Update, layer_visibility_system)
+      .add_observer(on_add_tile_component_trigger)
+      .add_observer(on_add_object_component_trigger);
+  }
+}
+
+/// Shows or hides the tile and object sprites of all spawned chunks whenever the layer visibility settings change.
+fn layer_visibility_system(
+  settings: Res<Settings>,
+  resources: Res<GenerationResourcesCollection>,
+  mut tiles: Query<(&TileComponent, &mut Visibility), Without<ObjectComponent>>,
+  mut objects: Query<(&ObjectComponent, &mut Visibility), Without<TileComponent>>,
+  mut previous: Local<Option<LayerVisibility>>,
+) {
+  let layers = settings.general.layer_visibility;
+  if !settings.is_changed() || *previous == Some(layers) {
+    return;
+  }
+  *previous = Some(layers);
+  for (tile, mut visibility) in tiles.iter_mut() {
+    visibility.set_if_neq(to_visibility(layers.is_terrain_visible(tile.tile.terrain)));
+  }
+  for (object, mut visibility) in objects.iter_mut() {
+    let group = resources.objects.get_group(object.object_name);
+    visibility.set_if_neq(to_visibility(layers.is_object_visible(group)));
+  }
+}
+
+/// Hides newly spawned tile sprites if their terrain layer is hidden.
+fn on_add_tile_component_trigger(
+  trigger: Trigger<OnAdd, TileComponent>,
+  mut tiles: Query<(&TileComponent, &mut Visibility)>,
+  settings: Res<Settings>,
+) {
+  if let Ok((tile, mut visibility)) = tiles.get_mut(trigger.entity()) {
+    if !settings.general.layer_visibility.is_terrain_visible(tile.tile.terrain) {
+      *visibility = Visibility::Hidden;
+    }
+  }
+}
+
+/// Hides newly spawned object sprites if their group of objects is hidden.
+fn on_add_object_component_trigger(
+  trigger: Trigger<OnAdd, ObjectComponent>,
+  mut objects: Query<(&ObjectComponent, &mut Visibility)>,
+  settings: Res<Settings>,
+  resources: Res<GenerationResourcesCollection>,
+) {
+  if let Ok((object, mut visibility)) = objects.get_mut(trigger.entity()) {
+    let group = resources.objects.get_group(object.object_name);
+    if !settings.general.layer_visibility.is_object_visible(group) {
+      *visibility = Visibility::Hidden;
+    }
+  }
+}
+
+fn to_visibility(is_visible: bool) -> Visibility {
+  if is_visible {
+    Visibility::Inherited
+  } else {
+    Visibility::Hidden
+  }
+}
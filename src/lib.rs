@@ -0,0 +1,72 @@
+mod animations;
+mod camera;
+mod components;
+pub mod constants;
+mod controls;
+pub mod coords;
+mod events;
+mod generation;
+mod layer_visibility;
+mod lighting;
+mod occlusion;
+mod ocean;
+pub mod prelude;
+mod recording;
+mod resources;
+mod shadows;
+mod states;
+mod stress_test;
+#[cfg(test)]
+mod test_harness;
+mod ui;
+
+use crate::animations::AnimationsPlugin;
+use crate::camera::CameraPlugin;
+use crate::controls::ControlPlugin;
+use crate::events::SharedEventsPlugin;
+use crate::generation::GenerationPlugin;
+use crate::layer_visibility::LayerVisibilityPlugin;
+use crate::lighting::LightingPlugin;
+use crate::occlusion::OcclusionPlugin;
+use crate::ocean::OceanPlugin;
+use crate::recording::RecordingPlugin;
+use crate::resources::SharedResourcesPlugin;
+use crate::shadows::ShadowsPlugin;
+use crate::states::AppStatePlugin;
+use crate::stress_test::StressTestPlugin;
+use crate::ui::UiPlugin;
+use bevy::app::{PluginGroup, PluginGroupBuilder};
+
+/// The internals used by the benchmarks in the `benches` folder, which are not part of the public API.
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench {
+  pub use crate::generation::lib::shared::SharedResource;
+  pub use crate::generation::resources::{object_resources_from_files, GenerationResourcesCollection};
+}
+
+/// All plugins that make up the application, in the order in which they are expected to be added. Requires the
+/// `DefaultPlugins` (with `ImagePlugin::default_nearest()`), `PanCamPlugin` and `DefaultInspectorConfigPlugin` to be
+/// added by the host application, as is done in `main.rs`.
+pub struct ProceduralGenerationPlugins;
+
+impl PluginGroup for ProceduralGenerationPlugins {
+  fn build(self) -> PluginGroupBuilder {
+    PluginGroupBuilder::start::<Self>()
+      .add(CameraPlugin)
+      .add(AppStatePlugin)
+      .add(GenerationPlugin)
+      .add(AnimationsPlugin)
+      .add(LightingPlugin)
+      .add(ShadowsPlugin)
+      .add(OcclusionPlugin)
+      .add(OceanPlugin)
+      .add(LayerVisibilityPlugin)
+      .add(SharedEventsPlugin)
+      .add(SharedResourcesPlugin)
+      .add(ControlPlugin)
+      .add(UiPlugin)
+      .add(RecordingPlugin)
+      .add(StressTestPlugin)
+  }
+}
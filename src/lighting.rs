@@ -0,0 +1,276 @@
+use crate::components::LightSourceComponent;
+use crate::constants::*;
+use crate::coords::tile_size::{tile_scale, tile_size};
+use crate::generation::lib::{shared, ObjectComponent};
+use crate::generation::object::lib::ObjectLight;
+use crate::generation::resources::GenerationResourcesCollection;
+use crate::resources::Settings;
+use crate::states::AppState;
+use bevy::app::{App, Plugin, Startup, Update};
+use bevy::asset::RenderAssetUsages;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::render::mesh::MeshVertexBufferLayoutRef;
+use bevy::render::render_resource::{
+  AsBindGroup, BlendComponent, BlendFactor, BlendOperation, BlendState, Extent3d, RenderPipelineDescriptor, ShaderRef,
+  SpecializedMeshPipelineError, TextureDimension, TextureFormat,
+};
+use bevy::sprite::{AlphaMode2d, Material2d, Material2dKey, Material2dPlugin};
+use bevy::utils::HashMap;
+
+pub struct LightingPlugin;
+
+impl Plugin for LightingPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .add_plugins(Material2dPlugin::<GlowMaterial>::default())
+      .add_systems(Startup, setup_lighting_system)
+      .add_systems(OnEnter(AppState::Initialising), initialise_glow_meshes_system)
+      .add_systems(Update, (darkness_overlay_system, toggle_glows_system))
+      .add_observer(on_add_object_component_trigger);
+  }
+}
+
+/// The material shared by all glows and one mesh per colour and flicker of the light rules. The colour and flicker of a
+/// glow are stored in the vertex colours of its mesh and the phase of its flickering is derived from its position in the
+/// shader, so that all glows can share one material. The meshes of all light rules are added once the rules have been
+/// loaded and are kept in `meshes`, so that light sources spawned in new chunks reuse an existing mesh instead of adding
+/// a new one.
+#[derive(Resource)]
+struct GlowAssets {
+  material: Handle<GlowMaterial>,
+  meshes: HashMap<[u32; 4], Handle<Mesh>>,
+}
+
+impl GlowAssets {
+  /// Returns the mesh of a glow with the colour and flicker of the given light, adding it if it doesn't exist yet.
+  fn get_mesh(&mut self, light: &ObjectLight, meshes: &mut Assets<Mesh>) -> Handle<Mesh> {
+    let (r, g, b) = light.colour;
+    let flicker = light.flicker.clamp(0., 1.);
+    self
+      .meshes
+      .entry([r.to_bits(), g.to_bits(), b.to_bits(), flicker.to_bits()])
+      .or_insert_with(|| {
+        let colour = Color::srgb(r, g, b).to_linear();
+        let mesh = Mesh::from(Rectangle::new(1., 1.));
+        let colours = vec![[colour.red, colour.green, colour.blue, flicker]; mesh.count_vertices()];
+        meshes.add(mesh.with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colours))
+      })
+      .clone()
+  }
+}
+
+/// The material of a glow, which is added to the colour of whatever is behind it rather than blended with it, so that
+/// lights brighten the darkness overlay instead of merely tinting it. Flickering is animated in the shader.
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+pub(crate) struct GlowMaterial {
+  #[uniform(0)]
+  alpha: f32,
+  #[uniform(1)]
+  flicker_speed: f32,
+  #[texture(2)]
+  #[sampler(3)]
+  texture: Handle<Image>,
+}
+
+impl Material2d for GlowMaterial {
+  fn vertex_shader() -> ShaderRef {
+    LIGHT_GLOW_SHADER_PATH.into()
+  }
+
+  fn fragment_shader() -> ShaderRef {
+    LIGHT_GLOW_SHADER_PATH.into()
+  }
+
+  fn alpha_mode(&self) -> AlphaMode2d {
+    AlphaMode2d::Blend
+  }
+
+  fn specialize(
+    descriptor: &mut RenderPipelineDescriptor,
+    _layout: &MeshVertexBufferLayoutRef,
+    _key: Material2dKey<Self>,
+  ) -> Result<(), SpecializedMeshPipelineError> {
+    if let Some(target) = descriptor
+      .fragment
+      .as_mut()
+      .and_then(|fragment| fragment.targets.first_mut())
+      .and_then(|target| target.as_mut())
+    {
+      target.blend = Some(BlendState {
+        color: BlendComponent {
+          src_factor: BlendFactor::SrcAlpha,
+          dst_factor: BlendFactor::One,
+          operation: BlendOperation::Add,
+        },
+        alpha: BlendComponent::OVER,
+      });
+    }
+
+    Ok(())
+  }
+}
+
+#[derive(Component)]
+struct DarknessOverlay;
+
+/// Generates the material used for all glows and spawns the darkness overlay, which is hidden until lighting is
+/// enabled.
+fn setup_lighting_system(
+  mut commands: Commands,
+  mut images: ResMut<Assets<Image>>,
+  mut materials: ResMut<Assets<GlowMaterial>>,
+) {
+  commands.insert_resource(GlowAssets {
+    material: materials.add(GlowMaterial {
+      alpha: LIGHT_GLOW_ALPHA,
+      flicker_speed: LIGHT_FLICKER_SPEED,
+      texture: images.add(glow_image()),
+    }),
+    meshes: HashMap::new(),
+  });
+  commands.spawn((
+    Name::new("Darkness Overlay"),
+    Sprite {
+      color: VERY_DARK.with_alpha(0.),
+      custom_size: Some(Vec2::splat(DARKNESS_OVERLAY_SIZE)),
+      ..default()
+    },
+    Transform::from_xyz(0., 0., DARKNESS_OVERLAY_Z),
+    Visibility::Hidden,
+    DarknessOverlay,
+  ));
+}
+
+fn initialise_glow_meshes_system(
+  resources: Res<GenerationResourcesCollection>,
+  mut glow_assets: ResMut<GlowAssets>,
+  mut meshes: ResMut<Assets<Mesh>>,
+) {
+  for light in resources.objects.light_rules.values() {
+    glow_assets.get_mesh(light, &mut meshes);
+  }
+}
+
+/// Returns a white, circular texture whose alpha fades out quadratically from the center to the edge.
+fn glow_image() -> Image {
+  let size = LIGHT_GLOW_TEXTURE_SIZE;
+  let center = (size as f32 - 1.) / 2.;
+  let mut data = Vec::with_capacity((size * size * 4) as usize);
+  for y in 0..size {
+    for x in 0..size {
+      let distance = Vec2::new(x as f32 - center, y as f32 - center).length() / center;
+      let alpha = (1. - distance).clamp(0., 1.).powi(2);
+      data.extend_from_slice(&[255, 255, 255, (alpha * 255.) as u8]);
+    }
+  }
+
+  Image::new(
+    Extent3d {
+      width: size,
+      height: size,
+      depth_or_array_layers: 1,
+    },
+    TextureDimension::D2,
+    data,
+    TextureFormat::Rgba8UnormSrgb,
+    RenderAssetUsages::RENDER_WORLD,
+  )
+}
+
+/// Everything that is needed to spawn the glow of an object.
+#[derive(SystemParam)]
+struct GlowSpawner<'w> {
+  resources: Res<'w, GenerationResourcesCollection>,
+  glow_assets: ResMut<'w, GlowAssets>,
+  meshes: ResMut<'w, Assets<Mesh>>,
+  settings: Res<'w, Settings>,
+}
+
+impl GlowSpawner<'_> {
+  /// Spawns a glow as a child of the given object if it emits light according to the light rule set.
+  fn spawn(&mut self, commands: &mut Commands, entity: Entity, object: &ObjectComponent) {
+    let Some(light) = self.resources.objects.get_light(object.object_name) else {
+      return;
+    };
+    let mut glow = commands.spawn((
+      Mesh2d(self.glow_assets.get_mesh(&light, &mut self.meshes)),
+      MeshMaterial2d(self.glow_assets.material.clone()),
+      Transform::from_xyz(0., tile_size() as f32 / 2., LIGHT_GLOW_Z_OFFSET).with_scale(Vec3::new(
+        light.radius * tile_scale() * 2.,
+        light.radius * tile_scale() * 2.,
+        1.,
+      )),
+      LightSourceComponent,
+    ));
+    if let Some(name) = shared::debug_name(&self.settings, || format!("{:?} Glow", object.object_name)) {
+      glow.insert(name);
+    }
+    let glow = glow.id();
+    commands.entity(entity).add_child(glow);
+  }
+}
+
+/// Spawns a glow as a child of every object that emits light according to the light rule set, as long as lighting is
+/// enabled.
+fn on_add_object_component_trigger(
+  trigger: Trigger<OnAdd, ObjectComponent>,
+  mut commands: Commands,
+  objects: Query<&ObjectComponent>,
+  mut glow_spawner: GlowSpawner,
+) {
+  if !glow_spawner.settings.general.enable_lighting {
+    return;
+  }
+  if let Ok(object) = objects.get(trigger.entity()) {
+    glow_spawner.spawn(&mut commands, trigger.entity(), object);
+  }
+}
+
+/// Spawns the glows of all objects that emit light when lighting is enabled and despawns all glows when it is disabled,
+/// so that glows only exist while they are visible.
+fn toggle_glows_system(
+  mut commands: Commands,
+  objects: Query<(Entity, &ObjectComponent, Option<&Children>)>,
+  glows: Query<Entity, With<LightSourceComponent>>,
+  mut glow_spawner: GlowSpawner,
+  mut is_enabled: Local<bool>,
+) {
+  if glow_spawner.settings.general.enable_lighting == *is_enabled {
+    return;
+  }
+  *is_enabled = glow_spawner.settings.general.enable_lighting;
+  if *is_enabled {
+    for (entity, object, children) in objects.iter() {
+      if !children.is_some_and(|children| children.iter().any(|child| glows.contains(*child))) {
+        glow_spawner.spawn(&mut commands, entity, object);
+      }
+    }
+  } else {
+    for glow in glows.iter() {
+      commands.entity(glow).despawn_recursive();
+    }
+  }
+}
+
+/// Keeps the darkness overlay centered on the camera and applies the lighting settings to it.
+fn darkness_overlay_system(
+  settings: Res<Settings>,
+  camera: Query<&GlobalTransform, With<Camera>>,
+  mut overlay: Query<(&mut Transform, &mut Sprite, &mut Visibility), With<DarknessOverlay>>,
+) {
+  let Ok((mut transform, mut sprite, mut visibility)) = overlay.get_single_mut() else {
+    return;
+  };
+  if !settings.general.enable_lighting {
+    *visibility = Visibility::Hidden;
+    return;
+  }
+  if let Ok(camera) = camera.get_single() {
+    let translation = camera.translation();
+    transform.translation.x = translation.x;
+    transform.translation.y = translation.y;
+  }
+  sprite.color.set_alpha(settings.general.darkness);
+  *visibility = Visibility::Visible;
+}
@@ -1,24 +1,3 @@
-mod animations;
-mod camera;
-mod components;
-mod constants;
-mod controls;
-mod coords;
-mod events;
-mod generation;
-mod resources;
-mod states;
-mod ui;
-
-use crate::animations::AnimationsPlugin;
-use crate::camera::CameraPlugin;
-use crate::constants::{WINDOW_HEIGHT, WINDOW_WIDTH};
-use crate::controls::ControlPlugin;
-use crate::events::SharedEventsPlugin;
-use crate::generation::GenerationPlugin;
-use crate::resources::SharedResourcesPlugin;
-use crate::states::AppStatePlugin;
-use crate::ui::UiPlugin;
 use bevy::asset::AssetMetaCheck;
 use bevy::audio::{AudioPlugin, SpatialScale};
 use bevy::input::common_conditions::input_toggle_active;
@@ -27,9 +6,21 @@ use bevy::window::{PresentMode, WindowResolution};
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_inspector_egui::DefaultInspectorConfigPlugin;
 use bevy_pancam::PanCamPlugin;
+use procedural_generation_2::constants::{WINDOW_HEIGHT, WINDOW_WIDTH};
+use procedural_generation_2::prelude::{ProceduralGenerationPlugins, TileSize, WorldProjection};
 
 fn main() {
+  let tile_size = TileSize::from_args().unwrap_or_else(|error| {
+    eprintln!("{}", error);
+    std::process::exit(2);
+  });
+  let projection = WorldProjection::from_args().unwrap_or_else(|error| {
+    eprintln!("{}", error);
+    std::process::exit(2);
+  });
   App::new()
+    .insert_resource(tile_size)
+    .insert_resource(projection)
     .add_plugins(
       DefaultPlugins
         .set(AssetPlugin {
@@ -43,7 +34,7 @@ fn main() {
             title: "Procedural Generation 2".into(),
             resolution: WindowResolution::new(WINDOW_WIDTH, WINDOW_HEIGHT),
             present_mode: PresentMode::AutoVsync,
-            resizable: false,
+            resizable: true,
             ..default()
           }),
           ..default()
@@ -55,16 +46,7 @@ fn main() {
         .build(),
     )
     .add_plugins(PanCamPlugin::default())
-    .add_plugins((
-      CameraPlugin,
-      AppStatePlugin,
-      GenerationPlugin,
-      AnimationsPlugin,
-      SharedEventsPlugin,
-      SharedResourcesPlugin,
-      ControlPlugin,
-      UiPlugin,
-    ))
+    .add_plugins(ProceduralGenerationPlugins)
     .add_plugins(DefaultInspectorConfigPlugin)
     .add_plugins(WorldInspectorPlugin::default().run_if(input_toggle_active(false, KeyCode::F1)))
     .run();
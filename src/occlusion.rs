@@ -0,0 +1,93 @@
+use crate::components::{FadeInComponent, FadeOutComponent};
+use crate::constants::*;
+use crate::coords::tile_size::tile_size;
+use crate::coords::TilePicker;
+use crate::generation::lib::ObjectComponent;
+use crate::generation::object::lib::{ObjectAtlas, ObjectGroup};
+use crate::generation::resources::GenerationResourcesCollection;
+use crate::resources::Settings;
+use bevy::app::{App, Plugin, Update};
+use bevy::prelude::*;
+
+pub struct OcclusionPlugin;
+
+impl Plugin for OcclusionPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .add_observer(on_add_object_component_trigger)
+      .add_systems(Update, occlusion_fade_system);
+  }
+}
+
+/// Marks the sprite of a large object, i.e. a tree or a building, which can hide the tiles behind it.
+#[derive(Component)]
+struct OccludingObjectComponent;
+
+fn on_add_object_component_trigger(
+  trigger: Trigger<OnAdd, ObjectComponent>,
+  query: Query<&ObjectComponent>,
+  resources: Res<GenerationResourcesCollection>,
+  mut commands: Commands,
+) {
+  let Ok(object) = query.get(trigger.entity()) else {
+    return;
+  };
+  let name = object.object_name;
+  if resources.objects.get_atlas(name) == ObjectAtlas::Trees || resources.objects.get_group(name) == ObjectGroup::Building {
+    commands.entity(trigger.entity()).insert(OccludingObjectComponent);
+  }
+}
+
+/// Excludes sprites that are fading in or fading out.
+type NotFading = (Without<FadeInComponent>, Without<FadeOutComponent>);
+
+/// Fades every tree and building that the cursor is behind to `OCCLUSION_FADE_ALPHA` and gradually restores the
+/// opacity of all others. Sprites that are still fading in are left alone until their `FadeInComponent` is removed and
+/// sprites of chunks that are fading out before being despawned are left alone entirely.
+fn occlusion_fade_system(
+  picker: TilePicker,
+  layouts: Res<Assets<TextureAtlasLayout>>,
+  settings: Res<Settings>,
+  time: Res<Time>,
+  mut query: Query<(&mut Sprite, &GlobalTransform), (With<OccludingObjectComponent>, NotFading)>,
+) {
+  let cursor = settings
+    .general
+    .enable_occlusion_fade
+    .then(|| picker.cursor_translation())
+    .flatten();
+  let max_change = OCCLUSION_FADE_SPEED * time.delta_secs();
+  for (mut sprite, transform) in query.iter_mut() {
+    let is_occluding = cursor.is_some_and(|cursor| is_behind(cursor, &sprite, transform, &layouts));
+    let target_alpha = if is_occluding { OCCLUSION_FADE_ALPHA } else { 1. };
+    let alpha = sprite.color.alpha();
+    if alpha != target_alpha {
+      sprite
+        .color
+        .set_alpha(alpha + (target_alpha - alpha).clamp(-max_change, max_change));
+    }
+  }
+}
+
+/// Returns `true` if the given world translation lies within the footprint of the sprite, excluding the tile the
+/// object stands on, since a cursor on that tile is in front of rather than behind the object. Object sprites are
+/// anchored at their bottom center.
+fn is_behind(translation: Vec2, sprite: &Sprite, transform: &GlobalTransform, layouts: &Assets<TextureAtlasLayout>) -> bool {
+  let Some(atlas) = sprite.texture_atlas.as_ref() else {
+    return false;
+  };
+  let Some(rect) = layouts.get(&atlas.layout).and_then(|layout| layout.textures.get(atlas.index)) else {
+    return false;
+  };
+  let (scale, _, position) = transform.to_scale_rotation_translation();
+  let size = rect.size().as_vec2() * scale.truncate();
+  let base = position.truncate();
+  let footprint = Rect::new(
+    base.x - size.x / 2.,
+    base.y + tile_size() as f32 / 2.,
+    base.x + size.x / 2.,
+    base.y + size.y,
+  );
+
+  footprint.contains(translation)
+}
@@ -0,0 +1,102 @@
+use crate::constants::*;
+use crate::coords::tile_size::{tile_scale, tile_size};
+use crate::resources::Settings;
+use bevy::app::{App, Plugin, Startup, Update};
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use std::f32::consts::TAU;
+
+pub struct OceanPlugin;
+
+impl Plugin for OceanPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .add_systems(Startup, setup_ocean_backdrop_system)
+      .add_systems(Update, ocean_backdrop_system);
+  }
+}
+
+#[derive(Component)]
+struct OceanBackdrop;
+
+/// Spawns a large, tiled sprite of deep water behind all chunks, so that the area beyond the generated world looks
+/// like an infinite ocean rather than the clear colour.
+fn setup_ocean_backdrop_system(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+  commands.spawn((
+    Name::new("Ocean Backdrop"),
+    Sprite {
+      image: images.add(ocean_image()),
+      custom_size: Some(Vec2::splat(OCEAN_BACKDROP_SIZE * tile_scale())),
+      image_mode: SpriteImageMode::Tiled {
+        tile_x: true,
+        tile_y: true,
+        stretch_value: 1.,
+      },
+      ..default()
+    },
+    Transform::from_xyz(0., 0., OCEAN_BACKDROP_Z),
+    Visibility::Hidden,
+    OceanBackdrop,
+  ));
+}
+
+/// Returns a seamlessly tileable texture of deep water with a few lighter wave crests. Waves are computed per sprite
+/// pixel rather than per texture pixel, so that the texture matches the pixel art at any tile size.
+fn ocean_image() -> Image {
+  let size = ocean_texture_size();
+  let scale = tile_scale().max(1.);
+  let pixels = size as f32 / scale;
+  let base = DEEP_WATER_BLUE.to_srgba().to_u8_array();
+  let crest = WATER_BLUE.to_srgba().to_u8_array();
+  let mut data = Vec::with_capacity((size * size * 4) as usize);
+  for y in 0..size {
+    for x in 0..size {
+      let u = (x as f32 / scale).floor() / pixels;
+      let v = (y as f32 / scale).floor() / pixels;
+      let wave = (TAU * (2. * u + v)).sin() * (TAU * (3. * v - u)).sin();
+      data.extend_from_slice(if wave > OCEAN_WAVE_CREST_THRESHOLD { &crest } else { &base });
+    }
+  }
+
+  Image::new(
+    Extent3d {
+      width: size,
+      height: size,
+      depth_or_array_layers: 1,
+    },
+    TextureDimension::D2,
+    data,
+    TextureFormat::Rgba8UnormSrgb,
+    RenderAssetUsages::RENDER_WORLD,
+  )
+}
+
+fn ocean_texture_size() -> u32 {
+  OCEAN_TEXTURE_SIZE_IN_TILES * tile_size()
+}
+
+/// Keeps the ocean backdrop centred on the camera and scrolls it slowly to animate the waves. The position is snapped
+/// to the size of the texture, so that the waves don't move along with the camera.
+fn ocean_backdrop_system(
+  time: Res<Time>,
+  settings: Res<Settings>,
+  camera: Query<&GlobalTransform, With<Camera>>,
+  mut backdrop: Query<(&mut Transform, &mut Visibility), With<OceanBackdrop>>,
+) {
+  let Ok((mut transform, mut visibility)) = backdrop.get_single_mut() else {
+    return;
+  };
+  if !settings.general.enable_ocean_backdrop {
+    *visibility = Visibility::Hidden;
+    return;
+  }
+  if let Ok(camera) = camera.get_single() {
+    let size = ocean_texture_size() as f32;
+    let translation = camera.translation();
+    let scroll = ((time.elapsed_secs() * OCEAN_SCROLL_SPEED * tile_scale()) % size).floor();
+    transform.translation.x = (translation.x / size).floor() * size + scroll;
+    transform.translation.y = (translation.y / size).floor() * size + (scroll / 2.).floor();
+  }
+  *visibility = Visibility::Visible;
+}
@@ -0,0 +1,55 @@
+//! Re-exports the types required to embed the procedural generation into another Bevy application.
+//!
+//! Everything that is exported here is considered stable: it will not be renamed, moved or removed without a
+//! corresponding entry in the changelog. Fields and variants may still be added. Anything that is only reachable via
+//! other paths is an implementation detail and may change at any time.
+//!
+//! The `World` coordinate type is deliberately not part of the prelude to avoid clashing with Bevy's `World`. Use
+//! `coords::point::World` instead.
+
+pub use crate::ProceduralGenerationPlugins;
+
+// Plugins
+pub use crate::animations::AnimationsPlugin;
+pub use crate::camera::CameraPlugin;
+pub use crate::controls::ControlPlugin;
+pub use crate::events::SharedEventsPlugin;
+pub use crate::generation::GenerationPlugin;
+pub use crate::layer_visibility::LayerVisibilityPlugin;
+pub use crate::lighting::LightingPlugin;
+pub use crate::occlusion::OcclusionPlugin;
+pub use crate::ocean::OceanPlugin;
+pub use crate::recording::RecordingPlugin;
+pub use crate::resources::SharedResourcesPlugin;
+pub use crate::shadows::ShadowsPlugin;
+pub use crate::states::AppStatePlugin;
+pub use crate::stress_test::StressTestPlugin;
+pub use crate::ui::UiPlugin;
+
+// Events
+pub use crate::events::{
+  CrossedChunkBoundaryEvent, PlaceObjectEvent, PruneWorldEvent, RefreshMetadata, RegenerateWorldEvent, RemoveObjectEvent,
+  RerollObjectsEvent, UpdateWorldEvent,
+};
+pub use crate::generation::lib::Direction;
+pub use crate::generation::object::lib::ObjectName;
+
+// Resources
+pub use crate::generation::resources::{Metadata, WorldModification, WorldModifications};
+pub use crate::resources::{
+  CurrentChunk, GeneralGenerationSettings, GenerationMetadataSettings, LayerVisibility, ObjectGenerationSettings, Settings,
+  SpawnPoint, SpawnPointCriterion, WorldGenerationSettings,
+};
+
+// Generation hooks
+pub use crate::generation::lib::{Chunk, GenerationHooks, TileData};
+pub use crate::generation::object::lib::ObjectGrid;
+
+// States
+pub use crate::states::{AppState, GenerationPhase, GenerationState};
+
+// Coordinates
+pub use crate::coords::point::{ChunkGrid, CoordType, InternalGrid, TileGrid};
+pub use crate::coords::projection::projection;
+pub use crate::coords::tile_size::{tile_scale, tile_size};
+pub use crate::coords::{Coords, PickedTile, Point, TilePicker, TileSize, WorldProjection};
@@ -0,0 +1,475 @@
+use crate::constants::SESSION_RECORDING_PATH;
+use crate::coords::projection::projection;
+use crate::coords::Point;
+use crate::events::{
+  PaintTerrainEvent, PlaceObjectEvent, PruneWorldEvent, RefreshMetadata, RegenerateWorldEvent, RemoveObjectEvent,
+  RerollObjectsEvent, UpdateWorldEvent,
+};
+use crate::generation::lib::{ObjectComponent, TerrainType};
+use crate::generation::object::lib::ObjectName;
+use crate::generation::object::ObjectModificationSet;
+use crate::generation::resources::WorldModifications;
+use crate::resources::{
+  CurrentChunk, GeneralGenerationSettings, GenerationMetadataSettings, ObjectGenerationSettings, Settings, SpawnPoint,
+  WorldGenerationSettings,
+};
+use crate::states::AppState;
+use bevy::app::{App, Plugin, Update};
+use bevy::core::FrameCount;
+use bevy::ecs::system::SystemParam;
+use bevy::log::*;
+use bevy::prelude::{
+  in_state, Camera, DetectChanges, EventReader, EventWriter, GlobalTransform, IntoSystemConfigs, OrthographicProjection,
+  Query, Ref, Res, ResMut, Resource, Transform, With,
+};
+
+pub struct RecordingPlugin;
+
+impl Plugin for RecordingPlugin {
+  fn build(&self, app: &mut App) {
+    app.init_resource::<SessionRecorder>().add_systems(
+      Update,
+      (
+        // Runs in the same frame as the removal of an object, so that its entity can still be resolved
+        record_session_system.after_ignore_deferred(ObjectModificationSet),
+        replay_session_system,
+      )
+        .run_if(in_state(AppState::Running)),
+    );
+  }
+}
+
+/// An event that is relevant to the outcome of the generation process. Coordinates are stored as tuples to keep the
+/// recording file readable.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+enum RecordedEvent {
+  Start {
+    camera: (f32, f32),
+    zoom: f32,
+    current_chunk_w: (i32, i32),
+    settings: Settings,
+    world_modifications: WorldModifications,
+  },
+  Settings(Settings),
+  Zoom {
+    scale: f32,
+  },
+  UpdateWorld {
+    w: (i32, i32),
+  },
+  RefreshMetadata {
+    regenerate_world_after: bool,
+    prune_then_update_world_after: bool,
+  },
+  RegenerateWorld,
+  PruneWorld {
+    despawn_all_chunks: bool,
+    update_world_after: bool,
+  },
+  RerollObjects,
+  RemoveObject {
+    cg: (i32, i32),
+    ig: (i32, i32),
+  },
+  PlaceObject {
+    name: ObjectName,
+    cg: (i32, i32),
+    ig: (i32, i32),
+    is_relaxed: bool,
+  },
+  PaintTerrain {
+    tg: (i32, i32),
+    terrain: TerrainType,
+    radius: i32,
+  },
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+struct Recording {
+  entries: Vec<(u32, RecordedEvent)>,
+}
+
+#[derive(Default, PartialEq, Debug)]
+enum RecorderMode {
+  #[default]
+  Idle,
+  Recording,
+  Replaying,
+}
+
+/// Records every generation-relevant event, together with the frame (relative to the start of the recording) in which
+/// it occurred, and writes the result to `SESSION_RECORDING_PATH` once the recording is stopped. A recording can be
+/// replayed which restores the settings, camera, current chunk and world modifications at the start of the recording,
+/// regenerates the world, and then feeds the recorded events back in at the same relative frames. Camera movement does
+/// not trigger any world updates while replaying.
+#[derive(Resource, Default)]
+pub struct SessionRecorder {
+  mode: RecorderMode,
+  recording: Recording,
+  start_frame: u32,
+  next_index: usize,
+  is_toggle_recording_requested: bool,
+  is_replay_requested: bool,
+}
+
+impl SessionRecorder {
+  pub fn toggle_recording(&mut self) {
+    self.is_toggle_recording_requested = true;
+  }
+
+  pub fn request_replay(&mut self) {
+    self.is_replay_requested = true;
+  }
+
+  pub fn is_replaying(&self) -> bool {
+    self.mode == RecorderMode::Replaying
+  }
+
+  fn record(&mut self, frame: u32, event: RecordedEvent) {
+    let relative_frame = frame.wrapping_sub(self.start_frame);
+    trace!("Recorded event in frame {}: {:?}", relative_frame, event);
+    self.recording.entries.push((relative_frame, event));
+  }
+}
+
+/// The readers of all events that are recorded, as well as the objects that `RemoveObjectEvent::ByEntity` may refer to,
+/// which are recorded by their coordinates instead.
+#[derive(SystemParam)]
+struct RecordedEventReaders<'w, 's> {
+  update_world: EventReader<'w, 's, UpdateWorldEvent>,
+  refresh_metadata: EventReader<'w, 's, RefreshMetadata>,
+  regenerate_world: EventReader<'w, 's, RegenerateWorldEvent>,
+  prune_world: EventReader<'w, 's, PruneWorldEvent>,
+  reroll_objects: EventReader<'w, 's, RerollObjectsEvent>,
+  remove_object: EventReader<'w, 's, RemoveObjectEvent>,
+  place_object: EventReader<'w, 's, PlaceObjectEvent>,
+  paint_terrain: EventReader<'w, 's, PaintTerrainEvent>,
+  objects: Query<'w, 's, &'static ObjectComponent>,
+}
+
+/// The writers of all events that are replayed.
+#[derive(SystemParam)]
+struct RecordedEventWriters<'w> {
+  update_world: EventWriter<'w, UpdateWorldEvent>,
+  refresh_metadata: EventWriter<'w, RefreshMetadata>,
+  regenerate_world: EventWriter<'w, RegenerateWorldEvent>,
+  prune_world: EventWriter<'w, PruneWorldEvent>,
+  reroll_objects: EventWriter<'w, RerollObjectsEvent>,
+  remove_object: EventWriter<'w, RemoveObjectEvent>,
+  place_object: EventWriter<'w, PlaceObjectEvent>,
+  paint_terrain: EventWriter<'w, PaintTerrainEvent>,
+}
+
+/// The `Settings` as well as the copies of each of its parts that are edited through the UI, all of which are
+/// overwritten when restoring the settings of a recording.
+#[derive(SystemParam)]
+struct ReplayedSettings<'w> {
+  settings: ResMut<'w, Settings>,
+  general: ResMut<'w, GeneralGenerationSettings>,
+  metadata: ResMut<'w, GenerationMetadataSettings>,
+  world: ResMut<'w, WorldGenerationSettings>,
+  object: ResMut<'w, ObjectGenerationSettings>,
+}
+
+impl ReplayedSettings<'_> {
+  fn apply(&mut self, mut settings: Settings) {
+    for issue in settings.validate() {
+      warn!("Repaired invalid setting {} of recording", issue);
+    }
+    *self.settings = settings;
+    *self.general = settings.general;
+    *self.metadata = settings.metadata;
+    *self.world = settings.world;
+    *self.object = settings.object;
+  }
+}
+
+/// The state of the world that is restored at the start of a replay.
+#[derive(SystemParam)]
+struct ReplayedWorld<'w> {
+  current_chunk: ResMut<'w, CurrentChunk>,
+  world_modifications: ResMut<'w, WorldModifications>,
+  spawn_point: Res<'w, SpawnPoint>,
+}
+
+fn record_session_system(
+  mut recorder: ResMut<SessionRecorder>,
+  mut events: RecordedEventReaders,
+  frame_count: Res<FrameCount>,
+  settings: Res<Settings>,
+  current_chunk: Res<CurrentChunk>,
+  world_modifications: Res<WorldModifications>,
+  camera: Query<(&GlobalTransform, Ref<OrthographicProjection>), With<Camera>>,
+) {
+  let frame = frame_count.0;
+  let (camera_transform, projection) = camera.single();
+  if recorder.is_toggle_recording_requested {
+    recorder.is_toggle_recording_requested = false;
+    match recorder.mode {
+      RecorderMode::Idle => {
+        let camera = camera_transform.translation();
+        let current_chunk_w = current_chunk.get_world();
+        recorder.mode = RecorderMode::Recording;
+        recorder.recording = Recording::default();
+        recorder.start_frame = frame;
+        recorder.record(
+          frame,
+          RecordedEvent::Start {
+            camera: (camera.x, camera.y),
+            zoom: projection.scale,
+            current_chunk_w: (current_chunk_w.x, current_chunk_w.y),
+            settings: *settings,
+            world_modifications: world_modifications.clone(),
+          },
+        );
+        info!("Started recording session");
+      }
+      RecorderMode::Recording => {
+        recorder.mode = RecorderMode::Idle;
+        save_recording(&recorder.recording);
+      }
+      RecorderMode::Replaying => warn!("Cannot record a session while replaying one"),
+    }
+  }
+  if recorder.mode != RecorderMode::Recording {
+    return;
+  }
+
+  if settings.is_changed() {
+    recorder.record(frame, RecordedEvent::Settings(*settings));
+  }
+  if projection.is_changed() {
+    recorder.record(frame, RecordedEvent::Zoom { scale: projection.scale });
+  }
+  // Forced updates are only ever sent as a result of other events and will be sent again when replaying those
+  for event in events.update_world.read() {
+    if !event.is_forced_update && !event.is_dry_run {
+      recorder.record(
+        frame,
+        RecordedEvent::UpdateWorld {
+          w: (event.w.x, event.w.y),
+        },
+      );
+    }
+  }
+  for event in events.refresh_metadata.read() {
+    recorder.record(
+      frame,
+      RecordedEvent::RefreshMetadata {
+        regenerate_world_after: event.regenerate_world_after,
+        prune_then_update_world_after: event.prune_then_update_world_after,
+      },
+    );
+  }
+  for event in events.regenerate_world.read() {
+    if !event.is_derived {
+      recorder.record(frame, RecordedEvent::RegenerateWorld);
+    }
+  }
+  for event in events.prune_world.read() {
+    if !event.is_derived {
+      recorder.record(
+        frame,
+        RecordedEvent::PruneWorld {
+          despawn_all_chunks: event.despawn_all_chunks,
+          update_world_after: event.update_world_after,
+        },
+      );
+    }
+  }
+  for _ in events.reroll_objects.read() {
+    recorder.record(frame, RecordedEvent::RerollObjects);
+  }
+  for event in events.remove_object.read() {
+    let coords = match event {
+      RemoveObjectEvent::ByEntity(entity) => events
+        .objects
+        .get(*entity)
+        .ok()
+        .map(|object| (object.coords.chunk_grid, object.coords.internal_grid)),
+      RemoveObjectEvent::ByCoords(cg, ig) => Some((*cg, *ig)),
+    };
+    if let Some((cg, ig)) = coords {
+      recorder.record(
+        frame,
+        RecordedEvent::RemoveObject {
+          cg: (cg.x, cg.y),
+          ig: (ig.x, ig.y),
+        },
+      );
+    }
+  }
+  for event in events.place_object.read() {
+    recorder.record(
+      frame,
+      RecordedEvent::PlaceObject {
+        name: event.name,
+        cg: (event.cg.x, event.cg.y),
+        ig: (event.ig.x, event.ig.y),
+        is_relaxed: event.is_relaxed,
+      },
+    );
+  }
+  for event in events.paint_terrain.read() {
+    recorder.record(
+      frame,
+      RecordedEvent::PaintTerrain {
+        tg: (event.tg.x, event.tg.y),
+        terrain: event.terrain,
+        radius: event.radius,
+      },
+    );
+  }
+}
+
+fn replay_session_system(
+  mut recorder: ResMut<SessionRecorder>,
+  mut events: RecordedEventWriters,
+  mut settings: ReplayedSettings,
+  mut world: ReplayedWorld,
+  mut camera: Query<(&mut Transform, &mut OrthographicProjection), With<Camera>>,
+  frame_count: Res<FrameCount>,
+) {
+  let frame = frame_count.0;
+  if recorder.is_replay_requested {
+    recorder.is_replay_requested = false;
+    if recorder.mode != RecorderMode::Idle {
+      warn!("Cannot replay a session while in [{:?}] mode", recorder.mode);
+      return;
+    }
+    if let Some(recording) = load_recording() {
+      info!("Started replaying session with {} events", recording.entries.len());
+      recorder.recording = recording;
+      recorder.mode = RecorderMode::Replaying;
+      recorder.start_frame = frame;
+      recorder.next_index = 0;
+    }
+  }
+  if recorder.mode != RecorderMode::Replaying {
+    return;
+  }
+
+  let relative_frame = frame.wrapping_sub(recorder.start_frame);
+  while let Some((event_frame, event)) = recorder.recording.entries.get(recorder.next_index).cloned() {
+    if event_frame > relative_frame {
+      return;
+    }
+    recorder.next_index += 1;
+    debug!("Replaying event from frame {}: {:?}", event_frame, event);
+    match event {
+      RecordedEvent::Start {
+        camera: (x, y),
+        zoom,
+        current_chunk_w: (chunk_x, chunk_y),
+        settings: s,
+        world_modifications: modifications,
+      } => {
+        settings.apply(s);
+        *world.world_modifications = modifications;
+        let (mut transform, mut projection) = camera.single_mut();
+        transform.translation.x = x;
+        transform.translation.y = y;
+        projection.scale = zoom;
+        world.current_chunk.update(Point::new_world(chunk_x, chunk_y));
+        let is_at_spawn_point = world.current_chunk.get_tile_grid() == world.spawn_point.tg;
+        events.refresh_metadata.send(RefreshMetadata {
+          regenerate_world_after: is_at_spawn_point,
+          prune_then_update_world_after: !is_at_spawn_point,
+        });
+      }
+      RecordedEvent::Settings(s) => settings.apply(s),
+      RecordedEvent::Zoom { scale } => camera.single_mut().1.scale = scale,
+      RecordedEvent::UpdateWorld { w: (x, y) } => {
+        let w = Point::new_world(x, y);
+        let translation = projection().project(w.to_vec2());
+        let mut transform = camera.single_mut().0;
+        transform.translation.x = translation.x;
+        transform.translation.y = translation.y;
+        events.update_world.send(UpdateWorldEvent::new(w));
+      }
+      RecordedEvent::RefreshMetadata {
+        regenerate_world_after,
+        prune_then_update_world_after,
+      } => {
+        events.refresh_metadata.send(RefreshMetadata {
+          regenerate_world_after,
+          prune_then_update_world_after,
+        });
+      }
+      RecordedEvent::RegenerateWorld => {
+        events.regenerate_world.send(RegenerateWorldEvent { is_derived: false });
+      }
+      RecordedEvent::PruneWorld {
+        despawn_all_chunks,
+        update_world_after,
+      } => {
+        events.prune_world.send(PruneWorldEvent {
+          despawn_all_chunks,
+          update_world_after,
+          is_derived: false,
+        });
+      }
+      RecordedEvent::RerollObjects => {
+        events.reroll_objects.send(RerollObjectsEvent {});
+      }
+      RecordedEvent::RemoveObject { cg, ig } => {
+        events.remove_object.send(RemoveObjectEvent::ByCoords(
+          Point::new_chunk_grid(cg.0, cg.1),
+          Point::new_internal_grid(ig.0, ig.1),
+        ));
+      }
+      RecordedEvent::PlaceObject {
+        name,
+        cg,
+        ig,
+        is_relaxed,
+      } => {
+        events.place_object.send(PlaceObjectEvent {
+          name,
+          cg: Point::new_chunk_grid(cg.0, cg.1),
+          ig: Point::new_internal_grid(ig.0, ig.1),
+          is_relaxed,
+        });
+      }
+      RecordedEvent::PaintTerrain { tg, terrain, radius } => {
+        events.paint_terrain.send(PaintTerrainEvent {
+          tg: Point::new_tile_grid(tg.0, tg.1),
+          terrain,
+          radius,
+        });
+      }
+    }
+  }
+  info!("Finished replaying session");
+  recorder.mode = RecorderMode::Idle;
+}
+
+fn save_recording(recording: &Recording) {
+  match ron::ser::to_string_pretty(recording, ron::ser::PrettyConfig::default()) {
+    Ok(content) => match std::fs::write(SESSION_RECORDING_PATH, content) {
+      Ok(_) => info!(
+        "Saved recording with {} events to [{}]",
+        recording.entries.len(),
+        SESSION_RECORDING_PATH
+      ),
+      Err(e) => error!("Failed to write recording to [{}]: {}", SESSION_RECORDING_PATH, e),
+    },
+    Err(e) => error!("Failed to serialise recording: {}", e),
+  }
+}
+
+fn load_recording() -> Option<Recording> {
+  match std::fs::read_to_string(SESSION_RECORDING_PATH) {
+    Ok(content) => match ron::from_str::<Recording>(&content) {
+      Ok(recording) => Some(recording),
+      Err(e) => {
+        error!("Failed to parse recording at [{}]: {}", SESSION_RECORDING_PATH, e);
+        None
+      }
+    },
+    Err(e) => {
+      error!("Failed to read recording from [{}]: {}", SESSION_RECORDING_PATH, e);
+      None
+    }
+  }
+}
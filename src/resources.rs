@@ -1,18 +1,32 @@
 use crate::constants::*;
 use crate::coords::point::{ChunkGrid, TileGrid, World};
-use crate::coords::{Coords, Point};
+use crate::coords::tile_size::{chunk_height, chunk_width};
+use crate::coords::{Coords, Point, TileSize, WorldProjection};
+use crate::generation::lib::TerrainType;
+use crate::generation::object::lib::{ObjectCategory, ObjectGroup};
 use bevy::app::{App, Plugin};
 use bevy::log::*;
 use bevy::prelude::{Reflect, ReflectResource, Resource};
+use bevy::utils::HashSet;
 use bevy_inspector_egui::inspector_options::std_options::NumberDisplay;
 use bevy_inspector_egui::prelude::ReflectInspectorOptions;
 use bevy_inspector_egui::InspectorOptions;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
 
 pub struct SharedResourcesPlugin;
 
 impl Plugin for SharedResourcesPlugin {
   fn build(&self, app: &mut App) {
+    // Must happen before any of the resources below are created because their defaults depend on the tile size
+    let tile_size = app.world().get_resource::<TileSize>().copied().unwrap_or_default();
+    tile_size.apply();
+    let projection = app.world().get_resource::<WorldProjection>().copied().unwrap_or_default();
+    projection.apply();
     app
+      .insert_resource(tile_size)
+      .insert_resource(projection)
       .init_resource::<Settings>()
       .register_type::<Settings>()
       .insert_resource(Settings::default())
@@ -28,11 +42,13 @@ impl Plugin for SharedResourcesPlugin {
       .init_resource::<GenerationMetadataSettings>()
       .register_type::<GenerationMetadataSettings>()
       .insert_resource(GenerationMetadataSettings::default())
-      .insert_resource(CurrentChunk::default());
+      .insert_resource(CurrentChunk::default())
+      .insert_resource(VisibleChunks::default())
+      .insert_resource(SpawnPoint::default());
   }
 }
 
-#[derive(Resource, Reflect, Clone, Copy)]
+#[derive(Resource, Reflect, Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct Settings {
   pub general: GeneralGenerationSettings,
   pub metadata: GenerationMetadataSettings,
@@ -40,6 +56,106 @@ pub struct Settings {
   pub object: ObjectGenerationSettings,
 }
 
+impl Settings {
+  /// Returns true if generating the world with `other` may result in a different world than generating it with these
+  /// settings. Settings that take effect immediately, such as freezing the generation, are ignored.
+  pub fn is_generation_affected_by(&self, other: &Settings) -> bool {
+    let (general, other_general) = (&self.general, &other.general);
+    self.metadata != other.metadata
+      || self.world != other.world
+      || self.object != other.object
+      || general.generate_neighbour_chunks != other_general.generate_neighbour_chunks
+      || general.draw_terrain_sprites != other_general.draw_terrain_sprites
+      || general.animate_terrain_sprites != other_general.animate_terrain_sprites
+      || general.spawn_from_layer != other_general.spawn_from_layer
+      || general.spawn_up_to_layer != other_general.spawn_up_to_layer
+      || general.enable_climate_blending != other_general.enable_climate_blending
+      || general.generate_beaches != other_general.generate_beaches
+  }
+
+  /// Repairs any values that would break the world generation, e.g. because they were loaded from a recording, and
+  /// returns an issue for each repaired value.
+  pub fn validate(&mut self) -> Vec<SettingsIssue> {
+    let mut issues = self.general.validate();
+    issues.extend(self.metadata.validate());
+    issues.extend(self.world.validate());
+    issues.extend(self.object.validate());
+
+    issues
+  }
+}
+
+/// Describes a settings value that would have broken the world generation and how it was repaired. The control is the
+/// name of the field that caused the issue, as shown in the settings UI.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingsIssue {
+  pub control: &'static str,
+  pub message: String,
+}
+
+impl Display for SettingsIssue {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "[{}] {}", self.control, self.message)
+  }
+}
+
+/// Resets the given value to its default if it is not a number and clamps it to the given range otherwise, recording
+/// an issue if the value was changed.
+fn repair_f64(
+  issues: &mut Vec<SettingsIssue>,
+  control: &'static str,
+  value: &mut f64,
+  range: RangeInclusive<f64>,
+  default: f64,
+) {
+  if !value.is_finite() {
+    *value = default;
+    issues.push(SettingsIssue {
+      control,
+      message: format!("Must be a number, reset to [{}]", default),
+    });
+  } else if !range.contains(value) {
+    *value = value.clamp(*range.start(), *range.end());
+    issues.push(SettingsIssue {
+      control,
+      message: format!(
+        "Must be between [{}] and [{}], clamped to [{}]",
+        range.start(),
+        range.end(),
+        value
+      ),
+    });
+  }
+}
+
+fn repair_f32(
+  issues: &mut Vec<SettingsIssue>,
+  control: &'static str,
+  value: &mut f32,
+  range: RangeInclusive<f32>,
+  default: f32,
+) {
+  let mut repaired = *value as f64;
+  let range = *range.start() as f64..=*range.end() as f64;
+  repair_f64(issues, control, &mut repaired, range, default as f64);
+  *value = repaired as f32;
+}
+
+fn repair_at_least<T: PartialOrd + Copy + Display>(
+  issues: &mut Vec<SettingsIssue>,
+  control: &'static str,
+  value: &mut T,
+  min: T,
+) {
+  if *value < min {
+    *value = min;
+    issues.push(SettingsIssue {
+      control,
+      message: format!("Must be at least [{}], set to [{}]", min, min),
+    });
+  }
+}
+
 impl Default for Settings {
   fn default() -> Self {
     Self {
@@ -51,19 +167,201 @@ impl Default for Settings {
   }
 }
 
-#[derive(Resource, Reflect, InspectorOptions, Clone, Copy)]
+#[derive(Resource, Reflect, InspectorOptions, Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 #[reflect(Resource, InspectorOptions)]
 pub struct GeneralGenerationSettings {
   pub draw_gizmos: bool,
   pub generate_neighbour_chunks: bool,
   pub enable_tile_debugging: bool,
+  /// Paints the terrain type of the terrain brush onto all tiles within the brush radius around the tile that is
+  /// clicked on, regenerating the affected chunks including their objects. Clicks are ignored while the world is being
+  /// generated. Takes effect immediately.
+  pub enable_terrain_brush: bool,
+  pub terrain_brush_type: TerrainType,
+  /// The radius (in tiles) of the terrain brush. Use `0` to paint a single tile.
+  #[inspector(min = 0, max = 5, display = NumberDisplay::Slider)]
+  pub terrain_brush_radius: i32,
+  /// Takes a snapshot of the terrain and objects of all spawned chunks before the world is regenerated or its objects
+  /// are rerolled. Once the generation has completed, every tile whose terrain or object has changed is tinted and
+  /// summary statistics are logged. Takes effect immediately.
+  pub enable_regeneration_diff: bool,
   pub draw_terrain_sprites: bool,
   pub animate_terrain_sprites: bool,
-  #[inspector(min = 0, max = 4, display = NumberDisplay::Slider)]
+  /// Gives every chunk, tile and object entity a descriptive `Name`, which makes them easier to find in the inspector
+  /// but costs a string allocation per entity. Takes effect immediately, but only affects entities spawned afterwards.
+  pub debug_names: bool,
+  #[inspector(min = 0, max = 5, display = NumberDisplay::Slider)]
   pub spawn_from_layer: usize,
-  #[inspector(min = 0, max = 4, display = NumberDisplay::Slider)]
+  #[inspector(min = 0, max = 5, display = NumberDisplay::Slider)]
   pub spawn_up_to_layer: usize,
   pub enable_world_pruning: bool,
+  /// The margin (in chunks) by which a chunk must exceed the despawn distance before it is pruned. Keeps chunks at the
+  /// border of the despawn distance from being despawned and regenerated repeatedly when moving back and forth across a
+  /// chunk boundary. Takes effect immediately.
+  #[inspector(min = 0., max = 2., display = NumberDisplay::Slider)]
+  pub prune_margin_in_chunks: f32,
+  /// The minimum time (in seconds) that must have passed since a chunk was spawned before it can be pruned. Younger
+  /// chunks are kept until the world is pruned again. Takes effect immediately.
+  #[inspector(min = 0., max = 30., display = NumberDisplay::Slider)]
+  pub min_chunk_lifetime_secs: f32,
+  /// The number of rings of chunks that are generated around the current chunk. Chunks closer to the current chunk are
+  /// generated first. Only has an effect if generating neighbour chunks is enabled.
+  #[inspector(min = 1, max = 4, display = NumberDisplay::Slider)]
+  pub generation_radius: i32,
+  /// The number of rings of chunks around the current chunk for which metadata is generated, independent of the
+  /// generation radius and the visible area. Must be above the generation radius, since every chunk requires the
+  /// metadata of its neighbours. Chunks in the visible area beyond it are not generated.
+  #[inspector(min = 2, max = 10, display = NumberDisplay::Slider)]
+  pub metadata_radius: i32,
+  /// Suppresses world updates and halts all in-progress world generation until disabled again. Takes effect
+  /// immediately and doesn't affect camera movement.
+  pub freeze_generation: bool,
+  /// Requires a key press (or clicking the button in the generation debugger) for every stage transition of the world
+  /// generation process. Takes effect immediately.
+  pub step_through_generation: bool,
+  /// Pauses advancing the world generation and spawning the generated tiles and objects for any frame that follows a
+  /// frame which exceeded the frame time budget, to keep the application responsive while many chunks are queued. Takes
+  /// effect immediately.
+  pub enable_frame_time_guardrail: bool,
+  /// The frame time (in milliseconds) above which the frame time guardrail pauses the world generation.
+  #[inspector(min = 8., max = 50., display = NumberDisplay::Slider)]
+  pub frame_time_budget_ms: f32,
+  /// The maximum number of object sprites that are spawned per frame. Objects closest to the camera are spawned first,
+  /// so that the visible part of a chunk fills in before the rest. Use `0` to spawn every object as soon as it is
+  /// ready. Takes effect immediately.
+  #[inspector(min = 0, max = 2000, display = NumberDisplay::Slider)]
+  pub object_spawn_budget: usize,
+  /// Fades in newly spawned terrain and object sprites instead of having them appear instantly.
+  pub enable_fade_in: bool,
+  /// Fades out the terrain and object sprites of pruned chunks before despawning them instead of having them disappear
+  /// instantly.
+  pub enable_fade_out: bool,
+  /// Gradually blends the climates of neighbouring chunks along their borders instead of switching sprite sets abruptly,
+  /// by randomly rendering tiles near the border with the sprite set of the neighbouring climate.
+  pub enable_climate_blending: bool,
+  /// Widens the sand strips along coastlines into beaches, depending on the climate. Only tiles on the beach allow
+  /// beach-only objects to be placed on them.
+  pub generate_beaches: bool,
+  /// Darkens the world and renders a glow around each object that emits light. Takes effect immediately.
+  pub enable_lighting: bool,
+  /// The opacity of the darkness overlay that is rendered when lighting is enabled.
+  #[inspector(min = 0., max = 0.95, display = NumberDisplay::Slider)]
+  pub darkness: f32,
+  /// Renders an animated ocean behind all chunks, so that the area beyond the generated world doesn't look empty. Takes
+  /// effect immediately.
+  pub enable_ocean_backdrop: bool,
+  /// Renders a blob shadow underneath each object that has one according to the object registry, unless it is placed
+  /// on water. Takes effect immediately.
+  pub draw_object_shadows: bool,
+  /// Makes trees and buildings semi-transparent while the cursor is behind them, so that the tiles they hide can be
+  /// inspected. Takes effect immediately.
+  pub enable_occlusion_fade: bool,
+  /// Shows or hides each terrain layer and group of objects of all spawned chunks. Unlike the layer range above, this
+  /// doesn't affect what is spawned and takes effect immediately.
+  pub layer_visibility: LayerVisibility,
+  /// Snaps the camera zoom to integer levels, so that every sprite pixel covers a whole number of screen pixels (or
+  /// vice versa when zoomed out) which keeps pixel art crisp. Takes effect immediately.
+  pub enable_pixel_perfect_zoom: bool,
+  /// The smallest camera scale, i.e. how far the camera can zoom in. Takes effect immediately.
+  #[inspector(min = 0.05, max = 1., display = NumberDisplay::Slider)]
+  pub min_zoom_scale: f32,
+  /// The largest camera scale, i.e. how far the camera can zoom out. Takes effect immediately.
+  #[inspector(min = 1., max = 10., display = NumberDisplay::Slider)]
+  pub max_zoom_scale: f32,
+  /// Regenerates the chunks of any world generation process that was started before the settings were changed, once
+  /// it completes, so that no chunks generated with outdated settings remain. Takes effect immediately.
+  pub requeue_outdated_chunks: bool,
+  /// The time (in seconds) after which a world generation component that has remained in the same stage is flagged as
+  /// stuck. Takes effect immediately.
+  #[inspector(min = 5., max = 120., display = NumberDisplay::Slider)]
+  pub stuck_component_timeout_secs: f32,
+  /// Skips the remaining stages of any world generation component flagged as stuck and cleans it up instead, so that
+  /// the world generation can't remain in progress forever. Takes effect immediately.
+  pub force_advance_stuck_components: bool,
+  /// The criterion used to select the chunk in which the world is generated on start-up. The closest chunk to the
+  /// origin that matches the criterion is selected. Only takes effect when restarting the application.
+  pub spawn_point_criterion: SpawnPointCriterion,
+}
+
+impl GeneralGenerationSettings {
+  /// Repairs any values that would break the world generation or the camera and returns an issue for each of them.
+  pub fn validate(&mut self) -> Vec<SettingsIssue> {
+    let mut issues = vec![];
+    if self.spawn_from_layer > self.spawn_up_to_layer {
+      self.spawn_from_layer = self.spawn_up_to_layer;
+      issues.push(SettingsIssue {
+        control: "spawn_from_layer",
+        message: format!(
+          "Must not be above [spawn_up_to_layer] or no terrain is spawned, set to [{}]",
+          self.spawn_from_layer
+        ),
+      });
+    }
+    repair_at_least(&mut issues, "generation_radius", &mut self.generation_radius, 1);
+    repair_at_least(&mut issues, "terrain_brush_radius", &mut self.terrain_brush_radius, 0);
+    if self.terrain_brush_type == TerrainType::Any {
+      self.terrain_brush_type = TERRAIN_BRUSH_TYPE;
+      issues.push(SettingsIssue {
+        control: "terrain_brush_type",
+        message: format!("Must be an actual terrain type, set to [{:?}]", self.terrain_brush_type),
+      });
+    }
+    if self.metadata_radius <= self.generation_radius {
+      self.metadata_radius = self.generation_radius + 1;
+      issues.push(SettingsIssue {
+        control: "metadata_radius",
+        message: format!(
+          "Must be above [generation_radius] or the outermost chunks lack the metadata of their neighbours, set to [{}]",
+          self.metadata_radius
+        ),
+      });
+    }
+    repair_f32(
+      &mut issues,
+      "prune_margin_in_chunks",
+      &mut self.prune_margin_in_chunks,
+      0.0..=2.,
+      PRUNE_MARGIN_IN_CHUNKS,
+    );
+    repair_f32(
+      &mut issues,
+      "min_chunk_lifetime_secs",
+      &mut self.min_chunk_lifetime_secs,
+      0.0..=30.,
+      MIN_CHUNK_LIFETIME_SECS,
+    );
+    repair_f32(
+      &mut issues,
+      "frame_time_budget_ms",
+      &mut self.frame_time_budget_ms,
+      8.0..=50.,
+      FRAME_TIME_BUDGET_MS,
+    );
+    repair_f32(&mut issues, "darkness", &mut self.darkness, 0.0..=0.95, DARKNESS);
+    repair_f32(
+      &mut issues,
+      "min_zoom_scale",
+      &mut self.min_zoom_scale,
+      0.05..=1.,
+      MIN_ZOOM_SCALE,
+    );
+    repair_f32(
+      &mut issues,
+      "max_zoom_scale",
+      &mut self.max_zoom_scale,
+      1.0..=10.,
+      MAX_ZOOM_SCALE,
+    );
+    repair_f32(
+      &mut issues,
+      "stuck_component_timeout_secs",
+      &mut self.stuck_component_timeout_secs,
+      5.0..=120.,
+      STUCK_COMPONENT_TIMEOUT_SECS,
+    );
+
+    issues
+  }
 }
 
 impl Default for GeneralGenerationSettings {
@@ -72,21 +370,52 @@ impl Default for GeneralGenerationSettings {
       draw_gizmos: DRAW_GIZMOS,
       generate_neighbour_chunks: GENERATE_NEIGHBOUR_CHUNKS,
       enable_tile_debugging: ENABLE_TILE_DEBUGGING,
+      enable_terrain_brush: ENABLE_TERRAIN_BRUSH,
+      terrain_brush_type: TERRAIN_BRUSH_TYPE,
+      terrain_brush_radius: TERRAIN_BRUSH_RADIUS,
+      enable_regeneration_diff: ENABLE_REGENERATION_DIFF,
       draw_terrain_sprites: DRAW_TERRAIN_SPRITES,
       animate_terrain_sprites: ANIMATE_TERRAIN_SPRITES,
+      debug_names: DEBUG_NAMES,
       spawn_from_layer: SPAWN_FROM_LAYER,
       spawn_up_to_layer: SPAWN_UP_TO_LAYER,
       enable_world_pruning: ENABLE_WORLD_PRUNING,
+      prune_margin_in_chunks: PRUNE_MARGIN_IN_CHUNKS,
+      min_chunk_lifetime_secs: MIN_CHUNK_LIFETIME_SECS,
+      generation_radius: GENERATION_RADIUS,
+      metadata_radius: METADATA_RADIUS,
+      freeze_generation: FREEZE_GENERATION,
+      step_through_generation: STEP_THROUGH_GENERATION,
+      enable_frame_time_guardrail: ENABLE_FRAME_TIME_GUARDRAIL,
+      frame_time_budget_ms: FRAME_TIME_BUDGET_MS,
+      object_spawn_budget: OBJECT_SPAWN_BUDGET,
+      enable_fade_in: ENABLE_FADE_IN,
+      enable_fade_out: ENABLE_FADE_OUT,
+      enable_climate_blending: ENABLE_CLIMATE_BLENDING,
+      generate_beaches: GENERATE_BEACHES,
+      enable_lighting: ENABLE_LIGHTING,
+      darkness: DARKNESS,
+      enable_ocean_backdrop: ENABLE_OCEAN_BACKDROP,
+      draw_object_shadows: DRAW_OBJECT_SHADOWS,
+      enable_occlusion_fade: ENABLE_OCCLUSION_FADE,
+      layer_visibility: LayerVisibility::default(),
+      enable_pixel_perfect_zoom: ENABLE_PIXEL_PERFECT_ZOOM,
+      min_zoom_scale: MIN_ZOOM_SCALE,
+      max_zoom_scale: MAX_ZOOM_SCALE,
+      requeue_outdated_chunks: REQUEUE_OUTDATED_CHUNKS,
+      stuck_component_timeout_secs: STUCK_COMPONENT_TIMEOUT_SECS,
+      force_advance_stuck_components: FORCE_ADVANCE_STUCK_COMPONENTS,
+      spawn_point_criterion: SPAWN_POINT_CRITERION,
     }
   }
 }
 
-#[derive(Resource, Reflect, InspectorOptions, Clone, Copy)]
+#[derive(Resource, Reflect, InspectorOptions, Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
 #[reflect(Resource, InspectorOptions)]
 pub struct GenerationMetadataSettings {
   /// The total elevation change within a chunk. The higher the value, the faster (i.e. over a distance of fewer
   /// chunks) the terrain oscillates between the highest and lowest terrain layers.
-  #[inspector(min = 0.0, max = 0.2, display = NumberDisplay::Slider)]
+  #[inspector(min = 0.001, max = 0.2, display = NumberDisplay::Slider)]
   pub elevation_chunk_step_size: f64,
   /// Shifts the ranges generated for the elevation metadata up/down. The higher the value the more the ranges
   /// will shift into negative values which causes lower terrain layers to be generated for chunks with the lowest
@@ -97,6 +426,70 @@ pub struct GenerationMetadataSettings {
   /// features. A parameter of `BasicMulti<Perlin>`.
   #[inspector(min = 0.0, max = 0.25, display = NumberDisplay::Slider)]
   pub biome_noise_frequency: f64,
+  /// The extent to which the rainfall of a chunk is determined by its distance from the origin in the direction of
+  /// the climate gradient rather than by the biome noise. Creates bands of the same climate that are crossed when
+  /// travelling in that direction. Disabled if `0.0`.
+  #[inspector(min = 0.0, max = 1.0, display = NumberDisplay::Slider)]
+  pub climate_gradient_strength: f64,
+  /// The direction in which the rainfall increases if the climate gradient is enabled.
+  pub climate_gradient_direction: ClimateGradientDirection,
+  /// The distance in chunks from the origin at which the climate gradient reaches its driest and most humid value.
+  #[inspector(min = 1, max = 100, display = NumberDisplay::Slider)]
+  pub climate_gradient_distance: i32,
+}
+
+impl GenerationMetadataSettings {
+  /// Repairs any values that would result in corrupted metadata and returns an issue for each of them. In particular,
+  /// an elevation chunk step size of zero results in elevation ranges that are not a number.
+  pub fn validate(&mut self) -> Vec<SettingsIssue> {
+    let mut issues = vec![];
+    if self.elevation_chunk_step_size.is_finite() && self.elevation_chunk_step_size < MIN_ELEVATION_CHUNK_STEP_SIZE {
+      self.elevation_chunk_step_size = MIN_ELEVATION_CHUNK_STEP_SIZE;
+      issues.push(SettingsIssue {
+        control: "elevation_chunk_step_size",
+        message: format!(
+          "Values below [{}] result in invalid elevation ranges, set to [{}]",
+          MIN_ELEVATION_CHUNK_STEP_SIZE, MIN_ELEVATION_CHUNK_STEP_SIZE
+        ),
+      });
+    }
+    repair_f64(
+      &mut issues,
+      "elevation_chunk_step_size",
+      &mut self.elevation_chunk_step_size,
+      MIN_ELEVATION_CHUNK_STEP_SIZE..=0.2,
+      ELEVATION_CHUNK_STEP_SIZE,
+    );
+    repair_f64(
+      &mut issues,
+      "elevation_offset",
+      &mut self.elevation_offset,
+      -1.0..=1.,
+      ELEVATION_OFFSET,
+    );
+    repair_f64(
+      &mut issues,
+      "biome_noise_frequency",
+      &mut self.biome_noise_frequency,
+      0.0..=0.25,
+      BIOME_NOISE_FREQUENCY,
+    );
+    repair_f64(
+      &mut issues,
+      "climate_gradient_strength",
+      &mut self.climate_gradient_strength,
+      0.0..=1.,
+      CLIMATE_GRADIENT_STRENGTH,
+    );
+    repair_at_least(
+      &mut issues,
+      "climate_gradient_distance",
+      &mut self.climate_gradient_distance,
+      1,
+    );
+
+    issues
+  }
 }
 
 impl Default for GenerationMetadataSettings {
@@ -105,17 +498,26 @@ impl Default for GenerationMetadataSettings {
       elevation_chunk_step_size: ELEVATION_CHUNK_STEP_SIZE,
       elevation_offset: ELEVATION_OFFSET,
       biome_noise_frequency: BIOME_NOISE_FREQUENCY,
+      climate_gradient_strength: CLIMATE_GRADIENT_STRENGTH,
+      climate_gradient_direction: CLIMATE_GRADIENT_DIRECTION,
+      climate_gradient_distance: CLIMATE_GRADIENT_DISTANCE,
     }
   }
 }
 
-#[derive(Resource, Reflect, InspectorOptions, Clone, Copy)]
+#[derive(Resource, Reflect, InspectorOptions, Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
 #[reflect(Resource, InspectorOptions)]
 pub struct WorldGenerationSettings {
-  /// The seed for the noise function. A parameter of `BasicMulti`. Allows for the same terrain to be generated i.e.
-  /// the same seed will always generate the exact same terrain.
+  /// The master seed from which the seeds of all layers are derived, unless a layer has its own seed. Allows for the
+  /// same world to be generated i.e. the same seed will always generate the exact same terrain and objects.
   #[inspector(min = 0, max = 100, display = NumberDisplay::Slider)]
   pub noise_seed: u32,
+  /// The seed for the terrain and its metadata, which is a parameter of `BasicMulti`. Uses the master seed if not set.
+  /// Setting it keeps the terrain fixed while changing the master seed.
+  pub terrain_seed: Option<u32>,
+  /// The seed for the object generation to which the object seed offset is added. Uses the master seed if not set.
+  /// Setting it keeps the objects fixed while changing the master seed.
+  pub object_seed: Option<u32>,
   /// The overall impact of the noise function on the terrain generation. A simple multiplier for the final output of
   /// the Perlin noise function. The lower the value, the higher the impact of other parameters such as the elevation
   /// offset from the `ElevationMetadata`.
@@ -137,10 +539,57 @@ pub struct WorldGenerationSettings {
   pub noise_amplitude: f64,
 }
 
+impl WorldGenerationSettings {
+  pub fn get_terrain_seed(&self) -> u32 {
+    self.terrain_seed.unwrap_or(self.noise_seed)
+  }
+
+  pub fn get_object_seed(&self) -> u32 {
+    self.object_seed.unwrap_or(self.noise_seed)
+  }
+
+  /// Repairs any noise parameters that would result in corrupted terrain and returns an issue for each of them.
+  pub fn validate(&mut self) -> Vec<SettingsIssue> {
+    let mut issues = vec![];
+    repair_f64(
+      &mut issues,
+      "noise_strength",
+      &mut self.noise_strength,
+      0.0..=1.,
+      NOISE_STRENGTH,
+    );
+    repair_f64(
+      &mut issues,
+      "noise_frequency",
+      &mut self.noise_frequency,
+      0.0..=0.25,
+      NOISE_FREQUENCY,
+    );
+    repair_f64(
+      &mut issues,
+      "noise_persistence",
+      &mut self.noise_persistence,
+      0.0..=2.,
+      NOISE_PERSISTENCE,
+    );
+    repair_f64(
+      &mut issues,
+      "noise_amplitude",
+      &mut self.noise_amplitude,
+      0.0..=10.,
+      NOISE_AMPLITUDE,
+    );
+
+    issues
+  }
+}
+
 impl Default for WorldGenerationSettings {
   fn default() -> Self {
     Self {
       noise_seed: NOISE_SEED,
+      terrain_seed: TERRAIN_SEED,
+      object_seed: OBJECT_SEED,
       noise_strength: NOISE_STRENGTH,
       noise_octaves: NOISE_OCTAVES,
       noise_frequency: NOISE_FREQUENCY,
@@ -150,11 +599,126 @@ impl Default for WorldGenerationSettings {
   }
 }
 
-#[derive(Resource, Reflect, InspectorOptions, Clone, Copy)]
+#[derive(Resource, Reflect, InspectorOptions, Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
 #[reflect(Resource, InspectorOptions)]
 pub struct ObjectGenerationSettings {
   pub generate_objects: bool,
   pub enable_colour_variations: bool,
+  /// Aligns every object sprite to the grid, ignoring the jitter radius of its placement rule.
+  pub align_objects_to_grid: bool,
+  /// An offset that is added to the object seed when seeding the random number generator used during the object
+  /// generation. Allows generating different objects for the same terrain. Incremented when rerolling objects.
+  #[inspector(min = 0, max = 100, display = NumberDisplay::Slider)]
+  pub object_seed_offset: u32,
+  /// A multiplier for the weights of all objects in the `ObjectCategory::Trees` category. Use `0.0` to disable them.
+  #[inspector(min = 0., max = 2., display = NumberDisplay::Slider)]
+  pub tree_density: f32,
+  /// A multiplier for the weights of all objects in the `ObjectCategory::Rocks` category. Use `0.0` to disable them.
+  #[inspector(min = 0., max = 2., display = NumberDisplay::Slider)]
+  pub rock_density: f32,
+  /// A multiplier for the weights of all objects in the `ObjectCategory::Flora` category. Use `0.0` to disable them.
+  #[inspector(min = 0., max = 2., display = NumberDisplay::Slider)]
+  pub flora_density: f32,
+  /// A multiplier for the weights of all objects in the `ObjectCategory::Debris` category. Use `0.0` to disable them.
+  #[inspector(min = 0., max = 2., display = NumberDisplay::Slider)]
+  pub debris_density: f32,
+  /// A multiplier for the weights of all objects in the `ObjectCategory::Props` category. Use `0.0` to disable them.
+  #[inspector(min = 0., max = 2., display = NumberDisplay::Slider)]
+  pub prop_density: f32,
+  /// The probability of a chunk containing one of the rare features defined in `all.rare-feature.ruleset.ron`, such as
+  /// a meteorite crater. Use `0.0` to disable them.
+  #[inspector(min = 0., max = 1., display = NumberDisplay::Slider)]
+  pub rare_feature_probability: f32,
+  /// A multiplier for the `rock_density` in chunks whose biome is rocky, which makes rocks more common there than
+  /// elsewhere. Use `1.0` to treat rocky chunks like any other.
+  #[inspector(min = 1., max = 5., display = NumberDisplay::Slider)]
+  pub rocky_rock_density_multiplier: f32,
+  /// The number of rocky features defined in `all.rare-feature.ruleset.ron`, such as boulder fields and cliffside
+  /// outcrops, that are stamped onto each rocky chunk, if they fit. Use `0` to disable them.
+  #[inspector(min = 0, max = 5, display = NumberDisplay::Slider)]
+  pub rocky_features_per_chunk: usize,
+  /// The number of successful iterations of the wave function collapse algorithm after which a snapshot of the grid
+  /// is taken. Lower values use more memory but lose less progress when a contradiction is encountered.
+  #[inspector(min = 1, max = 50, display = NumberDisplay::Slider)]
+  pub snapshot_interval: i32,
+  /// The number of consecutive contradictions for which the most recent snapshot is restored before backtracking
+  /// further to an older snapshot.
+  #[inspector(min = 1, max = 10, display = NumberDisplay::Slider)]
+  pub max_retries_per_snapshot: usize,
+  /// The number of iterations (including failed ones) after which the wave function collapse algorithm gives up and
+  /// leaves all remaining cells empty. Trades the completeness of the decoration for a shorter generation time on slow
+  /// machines. Use `0` to never give up.
+  #[inspector(min = 0, max = 5000, display = NumberDisplay::Slider)]
+  pub short_circuit_after_iterations: i32,
+}
+
+impl ObjectGenerationSettings {
+  /// Returns the seed used for the object generation, derived from the object seed of the world and the object seed
+  /// offset.
+  pub fn object_seed(&self, world_object_seed: u32) -> u32 {
+    world_object_seed.wrapping_add(self.object_seed_offset)
+  }
+
+  /// Returns a copy of these settings with the density of rocks adjusted for the biome of a chunk.
+  pub fn for_biome(&self, is_rocky: bool) -> Self {
+    let mut settings = *self;
+    if is_rocky {
+      settings.rock_density *= self.rocky_rock_density_multiplier;
+    }
+
+    settings
+  }
+
+  /// Returns the multiplier that is applied to the weights of all objects in the given category.
+  pub fn density(&self, category: ObjectCategory) -> f32 {
+    match category {
+      ObjectCategory::Trees => self.tree_density,
+      ObjectCategory::Rocks => self.rock_density,
+      ObjectCategory::Flora => self.flora_density,
+      ObjectCategory::Debris => self.debris_density,
+      ObjectCategory::Props => self.prop_density,
+    }
+  }
+
+  /// Repairs any values that would break the object generation and returns an issue for each of them.
+  pub fn validate(&mut self) -> Vec<SettingsIssue> {
+    let mut issues = vec![];
+    repair_f32(&mut issues, "tree_density", &mut self.tree_density, 0.0..=2., TREE_DENSITY);
+    repair_f32(&mut issues, "rock_density", &mut self.rock_density, 0.0..=2., ROCK_DENSITY);
+    repair_f32(&mut issues, "flora_density", &mut self.flora_density, 0.0..=2., FLORA_DENSITY);
+    repair_f32(
+      &mut issues,
+      "debris_density",
+      &mut self.debris_density,
+      0.0..=2.,
+      DEBRIS_DENSITY,
+    );
+    repair_f32(&mut issues, "prop_density", &mut self.prop_density, 0.0..=2., PROP_DENSITY);
+    repair_f32(
+      &mut issues,
+      "rare_feature_probability",
+      &mut self.rare_feature_probability,
+      0.0..=1.,
+      RARE_FEATURE_PROBABILITY,
+    );
+    repair_f32(
+      &mut issues,
+      "rocky_rock_density_multiplier",
+      &mut self.rocky_rock_density_multiplier,
+      1.0..=5.,
+      ROCKY_ROCK_DENSITY_MULTIPLIER,
+    );
+    repair_at_least(&mut issues, "snapshot_interval", &mut self.snapshot_interval, 1);
+    repair_at_least(&mut issues, "max_retries_per_snapshot", &mut self.max_retries_per_snapshot, 1);
+    repair_at_least(
+      &mut issues,
+      "short_circuit_after_iterations",
+      &mut self.short_circuit_after_iterations,
+      0,
+    );
+
+    issues
+  }
 }
 
 impl Default for ObjectGenerationSettings {
@@ -162,6 +726,19 @@ impl Default for ObjectGenerationSettings {
     Self {
       generate_objects: GENERATE_OBJECTS,
       enable_colour_variations: ENABLE_COLOUR_VARIATIONS,
+      align_objects_to_grid: ALIGN_OBJECTS_TO_GRID,
+      object_seed_offset: OBJECT_SEED_OFFSET,
+      tree_density: TREE_DENSITY,
+      rock_density: ROCK_DENSITY,
+      flora_density: FLORA_DENSITY,
+      debris_density: DEBRIS_DENSITY,
+      prop_density: PROP_DENSITY,
+      rare_feature_probability: RARE_FEATURE_PROBABILITY,
+      rocky_rock_density_multiplier: ROCKY_ROCK_DENSITY_MULTIPLIER,
+      rocky_features_per_chunk: ROCKY_FEATURES_PER_CHUNK,
+      snapshot_interval: SNAPSHOT_INTERVAL,
+      max_retries_per_snapshot: MAX_RETRIES_PER_SNAPSHOT,
+      short_circuit_after_iterations: SHORT_CIRCUIT_AFTER_ITERATIONS,
     }
   }
 }
@@ -191,9 +768,9 @@ impl CurrentChunk {
 
   pub fn contains(&self, tg: Point<TileGrid>) -> bool {
     tg.x >= self.coords.tile_grid.x
-      && tg.x < (self.coords.tile_grid.x + CHUNK_SIZE)
+      && tg.x < (self.coords.tile_grid.x + CHUNK_WIDTH)
       && tg.y >= self.coords.tile_grid.y
-      && tg.y < (self.coords.tile_grid.y - CHUNK_SIZE)
+      && tg.y < (self.coords.tile_grid.y - CHUNK_HEIGHT)
   }
 
   pub fn update(&mut self, w: Point<World>) {
@@ -202,26 +779,194 @@ impl CurrentChunk {
     self.coords.world = w;
     self.coords.chunk_grid = cg;
     self.coords.tile_grid = Point::new_tile_grid_from_world(w);
-    self.center_w = Point::new_world(
-      w.x + (CHUNK_SIZE * TILE_SIZE as i32 / 2),
-      w.y - (CHUNK_SIZE * TILE_SIZE as i32 / 2),
-    );
+    self.center_w = Point::new_world(w.x + (chunk_width() / 2), w.y - (chunk_height() / 2));
     debug!("Current chunk updated from {} to {}", old_value, cg);
   }
 }
 
 impl Default for CurrentChunk {
   fn default() -> Self {
+    let origin_w = Point::new_world_from_tile_grid(ORIGIN_TILE_GRID_SPAWN_POINT);
     Self {
-      center_w: Point::new_world(
-        ORIGIN_WORLD_SPAWN_POINT.x + (CHUNK_SIZE * TILE_SIZE as i32 / 2),
-        ORIGIN_WORLD_SPAWN_POINT.y - (CHUNK_SIZE * TILE_SIZE as i32 / 2),
-      ),
-      coords: Coords::new(
-        ORIGIN_WORLD_SPAWN_POINT,
-        ORIGIN_CHUNK_GRID_SPAWN_POINT,
-        ORIGIN_TILE_GRID_SPAWN_POINT,
-      ),
+      center_w: Point::new_world(origin_w.x + (chunk_width() / 2), origin_w.y - (chunk_height() / 2)),
+      coords: Coords::new(origin_w, ORIGIN_CHUNK_GRID_SPAWN_POINT, ORIGIN_TILE_GRID_SPAWN_POINT),
+    }
+  }
+}
+
+/// The chunks that intersect the area visible through the camera, extended by `VISIBLE_AREA_MARGIN_IN_CHUNKS` and keyed
+/// by their world coordinates. These chunks are generated in addition to the chunks within the generation radius around
+/// the `CurrentChunk` and are never pruned, which keeps the screen filled regardless of the zoom level or window size.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct VisibleChunks {
+  chunks: HashSet<Point<World>>,
+  radius: f32,
+}
+
+impl VisibleChunks {
+  pub fn contains(&self, w: &Point<World>) -> bool {
+    self.chunks.contains(w)
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &Point<World>> {
+    self.chunks.iter()
+  }
+
+  /// Returns the distance (in world coordinates) from the center of the visible area to its furthest corner, including
+  /// the margin.
+  pub fn radius(&self) -> f32 {
+    self.radius
+  }
+
+  /// Replaces the visible chunks and the radius of the visible area and returns whether the chunks have changed.
+  pub fn update(&mut self, chunks: HashSet<Point<World>>, radius: f32) -> bool {
+    self.radius = radius;
+    if self.chunks == chunks {
+      return false;
+    }
+    self.chunks = chunks;
+
+    true
+  }
+}
+
+/// Whether the sprites of each terrain layer and group of objects are visible. Paths are the connected objects that
+/// lead across the terrain and buildings are the ruins, everything else is a decoration.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LayerVisibility {
+  pub deep_water: bool,
+  pub shallow_water: bool,
+  pub land_1: bool,
+  pub land_2: bool,
+  pub land_3: bool,
+  pub land_4: bool,
+  pub decorations: bool,
+  pub paths: bool,
+  pub buildings: bool,
+}
+
+impl LayerVisibility {
+  pub fn is_terrain_visible(&self, terrain: TerrainType) -> bool {
+    match terrain {
+      TerrainType::DeepWater => self.deep_water,
+      TerrainType::ShallowWater => self.shallow_water,
+      TerrainType::Land1 => self.land_1,
+      TerrainType::Land2 => self.land_2,
+      TerrainType::Land3 => self.land_3,
+      TerrainType::Land4 => self.land_4,
+      TerrainType::Any => true,
+    }
+  }
+
+  pub fn is_object_visible(&self, group: ObjectGroup) -> bool {
+    match group {
+      ObjectGroup::Decoration => self.decorations,
+      ObjectGroup::Path => self.paths,
+      ObjectGroup::Building => self.buildings,
+    }
+  }
+
+  /// Returns a mutable reference to the flag of the terrain layer with the given index, where `0` is deep water.
+  pub fn terrain_layer_mut(&mut self, layer: usize) -> Option<&mut bool> {
+    match layer {
+      0 => Some(&mut self.deep_water),
+      1 => Some(&mut self.shallow_water),
+      2 => Some(&mut self.land_1),
+      3 => Some(&mut self.land_2),
+      4 => Some(&mut self.land_3),
+      5 => Some(&mut self.land_4),
+      _ => None,
+    }
+  }
+}
+
+impl Default for LayerVisibility {
+  fn default() -> Self {
+    Self {
+      deep_water: LAYER_VISIBILITY,
+      shallow_water: LAYER_VISIBILITY,
+      land_1: LAYER_VISIBILITY,
+      land_2: LAYER_VISIBILITY,
+      land_3: LAYER_VISIBILITY,
+      land_4: LAYER_VISIBILITY,
+      decorations: LAYER_VISIBILITY,
+      paths: LAYER_VISIBILITY,
+      buildings: LAYER_VISIBILITY,
+    }
+  }
+}
+
+/// The criteria that can be used to select the spawn point of the world based on the biome metadata of the chunks
+/// surrounding the origin. All criteria other than `Origin` only match chunks that contain land.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+pub enum SpawnPointCriterion {
+  /// Always spawns at the origin of the world.
+  #[default]
+  Origin,
+  /// A chunk that contains land and borders a chunk that contains water only.
+  Coastal,
+  Dry,
+  Humid,
+  Rocky,
+}
+
+/// The direction in which the rainfall increases if the climate gradient of the `GenerationMetadataSettings` is
+/// enabled, where `North` is towards increasing `ChunkGrid` y-coordinates.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+pub enum ClimateGradientDirection {
+  #[default]
+  North,
+  East,
+  South,
+  West,
+}
+
+impl ClimateGradientDirection {
+  /// Returns the distance in chunks of the given chunk from the origin in this direction.
+  pub fn distance(&self, cg: Point<ChunkGrid>) -> i32 {
+    match self {
+      ClimateGradientDirection::North => cg.y,
+      ClimateGradientDirection::East => cg.x,
+      ClimateGradientDirection::South => -cg.y,
+      ClimateGradientDirection::West => -cg.x,
+    }
+  }
+}
+
+/// The point at which the world is generated on start-up and when regenerating the world. Set to the
+/// `ORIGIN_*_SPAWN_POINT`s by default but offset during initialisation if a `SpawnPointCriterion` other than `Origin`
+/// is used.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct SpawnPoint {
+  pub w: Point<World>,
+  pub cg: Point<ChunkGrid>,
+  pub tg: Point<TileGrid>,
+}
+
+impl SpawnPoint {
+  /// Returns the spawn point for the given chunk, offset in the same way as the `ORIGIN_*_SPAWN_POINT`s are.
+  pub fn from_chunk_grid(cg: Point<ChunkGrid>) -> Self {
+    let cg_w = Point::new_world_from_chunk_grid(cg);
+    let origin_w = Point::new_world_from_tile_grid(ORIGIN_TILE_GRID_SPAWN_POINT);
+    let w = Point::new_world(cg_w.x + origin_w.x, cg_w.y + origin_w.y);
+    Self {
+      w,
+      cg,
+      tg: Point::new_tile_grid_from_world(w),
+    }
+  }
+
+  pub fn get_center_world(&self) -> Point<World> {
+    Point::new_world(self.w.x + (chunk_width() / 2), self.w.y - (chunk_height() / 2))
+  }
+}
+
+impl Default for SpawnPoint {
+  fn default() -> Self {
+    Self {
+      w: Point::new_world_from_tile_grid(ORIGIN_TILE_GRID_SPAWN_POINT),
+      cg: ORIGIN_CHUNK_GRID_SPAWN_POINT,
+      tg: ORIGIN_TILE_GRID_SPAWN_POINT,
     }
   }
 }
@@ -0,0 +1,198 @@
+use crate::constants::*;
+use crate::coords::tile_size::tile_scale;
+use crate::generation::lib::{shared, ChunkComponent, ObjectComponent, TerrainType, TileDataComponent};
+use crate::generation::object::lib::ObjectShadow;
+use crate::generation::resources::GenerationResourcesCollection;
+use crate::resources::{LayerVisibility, Settings};
+use bevy::app::{App, Plugin, Startup, Update};
+use bevy::asset::RenderAssetUsages;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::utils::{HashMap, HashSet};
+use std::f32::consts::TAU;
+
+pub struct ShadowsPlugin;
+
+impl Plugin for ShadowsPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_resource::<ShadowTracker>()
+      .add_systems(Startup, setup_shadows_system)
+      .add_systems(Update, (mark_chunks_for_shadow_update_system, update_shadows_system).chain());
+  }
+}
+
+#[derive(Resource)]
+struct ShadowMaterial(Handle<ColorMaterial>);
+
+/// The mesh that contains the shadows of all objects of a chunk, spawned as a child of the chunk. Batching the shadows
+/// this way adds a single entity per chunk instead of one per object.
+#[derive(Component)]
+struct ChunkShadowsComponent;
+
+/// Keeps track of the chunks whose shadow mesh must be rebuilt, as well as the chunk of each object with a shadow,
+/// which is needed to find the chunk of an object after it has been despawned or recycled by the `SpritePool`.
+#[derive(Resource, Default)]
+struct ShadowTracker {
+  dirty_chunks: HashSet<Entity>,
+  object_chunks: HashMap<Entity, Entity>,
+}
+
+/// The objects that have been spawned or despawned since the last run.
+#[derive(SystemParam)]
+struct ObjectChanges<'w, 's> {
+  added: Query<'w, 's, (Entity, &'static ObjectComponent, &'static Parent), Added<ObjectComponent>>,
+  removed: RemovedComponents<'w, 's, ObjectComponent>,
+}
+
+/// The shadow meshes of the chunks, along with everything needed to create or update them.
+#[derive(SystemParam)]
+struct ShadowMeshes<'w, 's> {
+  meshes: ResMut<'w, Assets<Mesh>>,
+  material: Res<'w, ShadowMaterial>,
+  chunks: Query<'w, 's, Option<&'static Children>, With<ChunkComponent>>,
+  existing: Query<'w, 's, (Entity, &'static Mesh2d), With<ChunkShadowsComponent>>,
+}
+
+fn setup_shadows_system(mut commands: Commands, mut materials: ResMut<Assets<ColorMaterial>>) {
+  commands.insert_resource(ShadowMaterial(materials.add(ColorMaterial::default())));
+}
+
+/// Marks the chunks in which objects with a shadow have been spawned or despawned, or all chunks if any setting that
+/// affects the shadows has changed.
+fn mark_chunks_for_shadow_update_system(
+  mut tracker: ResMut<ShadowTracker>,
+  mut object_changes: ObjectChanges,
+  tiles: Query<&TileDataComponent>,
+  chunks: Query<Entity, With<ChunkComponent>>,
+  resources: Res<GenerationResourcesCollection>,
+  settings: Res<Settings>,
+  mut previous: Local<Option<(bool, LayerVisibility)>>,
+) {
+  // Removals are handled first because a recycled entity may have been removed from one object and added to another
+  for entity in object_changes.removed.read() {
+    if let Some(chunk) = tracker.object_chunks.remove(&entity) {
+      tracker.dirty_chunks.insert(chunk);
+    }
+  }
+  for (entity, object, parent) in object_changes.added.iter() {
+    if resources.objects.get_shadow(object.object_name).is_none() {
+      continue;
+    }
+    if let Ok(tile) = tiles.get(parent.get()) {
+      let chunk = tile.tile_data.chunk_entity;
+      tracker.object_chunks.insert(entity, chunk);
+      tracker.dirty_chunks.insert(chunk);
+    }
+  }
+  let current = (settings.general.draw_object_shadows, settings.general.layer_visibility);
+  if *previous != Some(current) {
+    *previous = Some(current);
+    tracker.dirty_chunks.extend(chunks.iter());
+  }
+}
+
+/// Rebuilds the shadow mesh of every marked chunk so that it contains the shadows of all visible objects of the chunk that
+/// aren't placed on water. The mesh of a chunk that already has one is replaced in place, so that updating the shadows
+/// of a chunk doesn't add a new mesh every time.
+fn update_shadows_system(
+  mut commands: Commands,
+  mut tracker: ResMut<ShadowTracker>,
+  mut shadow_meshes: ShadowMeshes,
+  objects: Query<(&ObjectComponent, &Transform, &Parent, &Visibility)>,
+  tiles: Query<(&TileDataComponent, &Transform)>,
+  resources: Res<GenerationResourcesCollection>,
+  settings: Res<Settings>,
+) {
+  if tracker.dirty_chunks.is_empty() {
+    return;
+  }
+  let dirty_chunks = std::mem::take(&mut tracker.dirty_chunks);
+  let mut shadows_by_chunk: HashMap<Entity, Vec<(Vec2, ObjectShadow)>> = HashMap::new();
+  if settings.general.draw_object_shadows {
+    for (object, transform, parent, visibility) in objects.iter() {
+      if *visibility == Visibility::Hidden {
+        continue;
+      }
+      let Some(shadow) = resources.objects.get_shadow(object.object_name) else {
+        continue;
+      };
+      let Ok((tile, tile_transform)) = tiles.get(parent.get()) else {
+        continue;
+      };
+      let chunk = tile.tile_data.chunk_entity;
+      let terrain = tile.tile_data.flat_tile.terrain;
+      if !dirty_chunks.contains(&chunk) || matches!(terrain, TerrainType::DeepWater | TerrainType::ShallowWater) {
+        continue;
+      }
+      let position = tile_transform.transform_point(transform.translation).truncate();
+      shadows_by_chunk.entry(chunk).or_default().push((position, shadow));
+    }
+  }
+  for chunk in dirty_chunks {
+    let Ok(children) = shadow_meshes.chunks.get(chunk) else {
+      continue;
+    };
+    let existing = children
+      .into_iter()
+      .flatten()
+      .find_map(|child| shadow_meshes.existing.get(*child).ok());
+    let Some(shadows) = shadows_by_chunk.remove(&chunk) else {
+      if let Some((entity, _)) = existing {
+        commands.entity(entity).despawn_recursive();
+      }
+      continue;
+    };
+    let entity = match existing {
+      Some((entity, mesh)) => {
+        shadow_meshes.meshes.insert(&mesh.0, shadow_mesh(&shadows));
+        entity
+      }
+      None => {
+        let entity = commands
+          .spawn((
+            Mesh2d(shadow_meshes.meshes.add(shadow_mesh(&shadows))),
+            MeshMaterial2d(shadow_meshes.material.0.clone()),
+            Transform::from_xyz(0., 0., OBJECT_SHADOW_Z),
+            ChunkShadowsComponent,
+          ))
+          .id();
+        commands.entity(chunk).add_child(entity);
+        entity
+      }
+    };
+    if let Some(name) = shared::debug_name(&settings, || format!("Shadows of {} Objects", shadows.len())) {
+      commands.entity(entity).insert(name);
+    }
+  }
+}
+
+/// Returns a single mesh that contains an ellipse for each shadow at the given position, whose vertex colours fade from
+/// the opacity of the shadow at its center to fully transparent at its edge.
+fn shadow_mesh(shadows: &[(Vec2, ObjectShadow)]) -> Mesh {
+  let segments = OBJECT_SHADOW_SEGMENTS;
+  let vertex_count = shadows.len() * (segments as usize + 1);
+  let mut positions = Vec::with_capacity(vertex_count);
+  let mut colours = Vec::with_capacity(vertex_count);
+  let mut indices = Vec::with_capacity(shadows.len() * segments as usize * 3);
+  for (position, shadow) in shadows {
+    let center = *position + Vec2::new(shadow.offset.0, shadow.offset.1) * tile_scale();
+    let radii = Vec2::new(shadow.size.0, shadow.size.1) * tile_scale() / 2.;
+    let center_index = positions.len() as u32;
+    positions.push([center.x, center.y, 0.]);
+    colours.push([0., 0., 0., shadow.opacity.clamp(0., 1.)]);
+    for i in 0..segments {
+      let angle = i as f32 / segments as f32 * TAU;
+      let point = center + Vec2::new(angle.cos(), angle.sin()) * radii;
+      positions.push([point.x, point.y, 0.]);
+      colours.push([0., 0., 0., 0.]);
+      indices.extend([center_index, center_index + 1 + i, center_index + 1 + (i + 1) % segments]);
+    }
+  }
+
+  Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::RENDER_WORLD)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colours)
+    .with_inserted_indices(Indices::U32(indices))
+}
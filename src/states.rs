@@ -1,6 +1,6 @@
 use bevy::app::{App, Plugin, Update};
 use bevy::log::*;
-use bevy::prelude::{AppExtStates, EventReader, State, StateTransitionEvent, States};
+use bevy::prelude::{AppExtStates, EventReader, State, StateSet, StateTransitionEvent, States, SubStates};
 use bevy::reflect::Reflect;
 use std::fmt::Display;
 
@@ -13,9 +13,15 @@ impl Plugin for AppStatePlugin {
       .register_type::<State<AppState>>()
       .init_state::<GenerationState>()
       .register_type::<State<GenerationState>>()
+      .add_sub_state::<GenerationPhase>()
+      .register_type::<State<GenerationPhase>>()
       .add_systems(
         Update,
-        (log_app_state_transitions_system, log_generation_state_transitions_system),
+        (
+          log_app_state_transitions_system,
+          log_generation_state_transitions_system,
+          log_generation_phase_transitions_system,
+        ),
       );
   }
 }
@@ -42,6 +48,17 @@ fn log_generation_state_transitions_system(mut generation_state_events: EventRea
   }
 }
 
+fn log_generation_phase_transitions_system(mut generation_phase_events: EventReader<StateTransitionEvent<GenerationPhase>>) {
+  for event in generation_phase_events.read() {
+    debug!(
+      "Transitioning [{}] from [{}] to [{}]",
+      GenerationPhase::name(),
+      name_from(event.exited),
+      name_from(event.entered)
+    );
+  }
+}
+
 fn name_from<T: ToString>(state: Option<T>) -> String {
   match state {
     Some(state_name) => state_name.to_string(),
@@ -88,3 +105,29 @@ impl Display for GenerationState {
     write!(f, "{}", format!("{:?}", self))
   }
 }
+
+/// A sub-state of `GenerationState::Generating` that describes what the world generation process is currently busy
+/// with. Driven by the stages of the `WorldGenerationComponent`s; if multiple components exist, the phase of the
+/// least advanced one is used. Only exists while generating, so systems that must only run while no generation is in
+/// progress should use `GenerationState::Idling` instead.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, PartialOrd, Ord, Hash, SubStates, Reflect)]
+#[source(GenerationState = GenerationState::Generating)]
+pub enum GenerationPhase {
+  #[default]
+  GeneratingTerrain,
+  Spawning,
+  GeneratingObjects,
+  Pruning,
+}
+
+impl GenerationPhase {
+  pub fn name() -> &'static str {
+    "GenerationPhase"
+  }
+}
+
+impl Display for GenerationPhase {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
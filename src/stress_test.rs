@@ -0,0 +1,218 @@
+use crate::constants::*;
+use crate::coords::tile_size::chunk_width;
+use crate::events::PruneWorldEvent;
+use crate::resources::{GeneralGenerationSettings, Settings};
+use crate::states::{AppState, GenerationState};
+use bevy::app::{App, Plugin, Update};
+use bevy::ecs::system::SystemParam;
+use bevy::log::*;
+use bevy::prelude::{
+  in_state, Camera, Entity, EventWriter, IntoSystemConfigs, Query, Res, ResMut, Resource, State, Time, Transform, With,
+};
+
+pub struct StressTestPlugin;
+
+impl Plugin for StressTestPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_resource::<StressTest>()
+      .add_systems(Update, stress_test_system.run_if(in_state(AppState::Running)));
+  }
+}
+
+#[derive(Default, PartialEq, Debug, Clone, Copy)]
+enum StressTestPhase {
+  #[default]
+  Idle,
+  MeasuringBaseline,
+  Generating,
+  Settling,
+  MeasuringSteadyState,
+}
+
+/// Reproduces the drop in frame rate that used to occur after pruning a large world. Measures the baseline frame time,
+/// disables world pruning and moves the camera one chunk at a time until `STRESS_TEST_ENTITY_TARGET` entities exist,
+/// then re-enables pruning, waits for the world to settle and measures the frame time again. The result is logged and
+/// written to `STRESS_TEST_REPORT_PATH`. The test fails if the steady state frame time exceeds the baseline by more
+/// than `STRESS_TEST_TOLERANCE`.
+#[derive(Resource, Default)]
+pub struct StressTest {
+  phase: StressTestPhase,
+  is_start_requested: bool,
+  phase_started_at: f32,
+  last_step_at: f32,
+  frame_times: Vec<f32>,
+  report: StressTestReport,
+  was_pruning_enabled: bool,
+}
+
+impl StressTest {
+  pub fn request_start(&mut self) {
+    self.is_start_requested = true;
+  }
+
+  pub fn is_running(&self) -> bool {
+    self.phase != StressTestPhase::Idle
+  }
+
+  fn set_phase(&mut self, phase: StressTestPhase, now: f32) {
+    debug!("Stress test moved from phase [{:?}] to [{:?}]", self.phase, phase);
+    self.phase = phase;
+    self.phase_started_at = now;
+    self.frame_times.clear();
+  }
+
+  /// Records the frame time and returns the average frame time in milliseconds once `STRESS_TEST_SAMPLE_FRAMES`
+  /// frames have been recorded.
+  fn sample(&mut self, frame_time_ms: f32) -> Option<f32> {
+    self.frame_times.push(frame_time_ms);
+    if self.frame_times.len() < STRESS_TEST_SAMPLE_FRAMES {
+      return None;
+    }
+
+    Some(self.frame_times.iter().sum::<f32>() / self.frame_times.len() as f32)
+  }
+}
+
+#[derive(serde::Serialize, Default, Debug, Clone)]
+struct StressTestReport {
+  baseline_frame_time_ms: f32,
+  steady_state_frame_time_ms: f32,
+  tolerance: f32,
+  peak_entity_count: usize,
+  entity_count_after_pruning: usize,
+  steps: u32,
+  /// The time it took to generate the entities, which allows comparing the spawn time of different settings.
+  generation_secs: f32,
+  debug_names: bool,
+  passed: bool,
+}
+
+impl StressTestReport {
+  fn export(&self) {
+    match serde_json::to_string_pretty(self) {
+      Ok(content) => match std::fs::write(STRESS_TEST_REPORT_PATH, content) {
+        Ok(_) => info!("Exported stress test report to [{}]", STRESS_TEST_REPORT_PATH),
+        Err(e) => error!("Failed to write stress test report to [{}]: {}", STRESS_TEST_REPORT_PATH, e),
+      },
+      Err(e) => error!("Failed to serialise stress test report: {}", e),
+    }
+  }
+}
+
+/// The `Settings` along with the `GeneralGenerationSettings`, which mirror them in the UI and must be kept in sync.
+#[derive(SystemParam)]
+struct SyncedSettings<'w> {
+  settings: ResMut<'w, Settings>,
+  general_settings: ResMut<'w, GeneralGenerationSettings>,
+}
+
+impl SyncedSettings<'_> {
+  fn set_world_pruning(&mut self, is_enabled: bool) {
+    self.settings.general.enable_world_pruning = is_enabled;
+    self.general_settings.enable_world_pruning = is_enabled;
+  }
+}
+
+fn stress_test_system(
+  mut stress_test: ResMut<StressTest>,
+  mut synced: SyncedSettings,
+  mut prune_world_event: EventWriter<PruneWorldEvent>,
+  mut camera: Query<&mut Transform, With<Camera>>,
+  entities: Query<Entity>,
+  generation_state: Res<State<GenerationState>>,
+  time: Res<Time>,
+) {
+  let now = time.elapsed_secs();
+  let frame_time_ms = time.delta_secs() * 1000.;
+  let is_idling = *generation_state.get() == GenerationState::Idling;
+  match stress_test.phase {
+    StressTestPhase::Idle => {
+      if !stress_test.is_start_requested {
+        return;
+      }
+      stress_test.is_start_requested = false;
+      stress_test.was_pruning_enabled = synced.settings.general.enable_world_pruning;
+      stress_test.report = StressTestReport {
+        tolerance: STRESS_TEST_TOLERANCE,
+        debug_names: synced.settings.general.debug_names,
+        ..Default::default()
+      };
+      stress_test.set_phase(StressTestPhase::MeasuringBaseline, now);
+      info!(
+        "Started stress test, measuring baseline frame time over {} frames",
+        STRESS_TEST_SAMPLE_FRAMES
+      );
+    }
+    StressTestPhase::MeasuringBaseline => {
+      if let Some(average) = stress_test.sample(frame_time_ms) {
+        stress_test.report.baseline_frame_time_ms = average;
+        synced.set_world_pruning(false);
+        stress_test.set_phase(StressTestPhase::Generating, now);
+        info!(
+          "Stress test measured a baseline frame time of {:.2} ms, now generating {} entities with pruning disabled",
+          average, STRESS_TEST_ENTITY_TARGET
+        );
+      }
+    }
+    StressTestPhase::Generating => {
+      let entity_count = entities.iter().count();
+      stress_test.report.peak_entity_count = stress_test.report.peak_entity_count.max(entity_count);
+      if entity_count >= STRESS_TEST_ENTITY_TARGET || stress_test.report.steps >= STRESS_TEST_MAX_STEPS {
+        stress_test.report.generation_secs = now - stress_test.phase_started_at;
+        synced.set_world_pruning(true);
+        prune_world_event.send(PruneWorldEvent {
+          despawn_all_chunks: false,
+          update_world_after: false,
+          is_derived: false,
+        });
+        stress_test.set_phase(StressTestPhase::Settling, now);
+        info!(
+          "Stress test re-enabled pruning with {} entities after moving {} chunk(s) in {:.1} s",
+          entity_count, stress_test.report.steps, stress_test.report.generation_secs
+        );
+        return;
+      }
+      if is_idling && now - stress_test.last_step_at >= STRESS_TEST_STEP_INTERVAL_SECS {
+        if let Ok(mut transform) = camera.get_single_mut() {
+          transform.translation.x += chunk_width() as f32;
+          stress_test.last_step_at = now;
+          stress_test.report.steps += 1;
+        }
+      }
+    }
+    StressTestPhase::Settling => {
+      if is_idling && now - stress_test.phase_started_at >= STRESS_TEST_SETTLE_SECS {
+        stress_test.report.entity_count_after_pruning = entities.iter().count();
+        stress_test.set_phase(StressTestPhase::MeasuringSteadyState, now);
+      }
+    }
+    StressTestPhase::MeasuringSteadyState => {
+      if let Some(average) = stress_test.sample(frame_time_ms) {
+        let was_pruning_enabled = stress_test.was_pruning_enabled;
+        synced.set_world_pruning(was_pruning_enabled);
+        let report = &mut stress_test.report;
+        report.steady_state_frame_time_ms = average;
+        report.passed = average <= report.baseline_frame_time_ms * STRESS_TEST_TOLERANCE;
+        if report.passed {
+          info!(
+            "✅  Stress test passed: steady state frame time of {:.2} ms is within {}x of the baseline of {:.2} ms",
+            average, STRESS_TEST_TOLERANCE, report.baseline_frame_time_ms
+          );
+        } else {
+          error!(
+            "❌  Stress test failed: steady state frame time of {:.2} ms exceeds {}x the baseline of {:.2} ms ({} entities \
+            remained after pruning {} at peak)",
+            average,
+            STRESS_TEST_TOLERANCE,
+            report.baseline_frame_time_ms,
+            report.entity_count_after_pruning,
+            report.peak_entity_count
+          );
+        }
+        report.export();
+        stress_test.set_phase(StressTestPhase::Idle, now);
+      }
+    }
+  }
+}
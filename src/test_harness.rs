@@ -0,0 +1,300 @@
+use crate::animations::AnimationsPlugin;
+use crate::components::PendingDespawnComponent;
+use crate::coords::point::ChunkGrid;
+use crate::coords::Point;
+use crate::events::SharedEventsPlugin;
+use crate::generation::lib::{ChunkComponent, WorldGenerationComponent};
+use crate::generation::GenerationPlugin;
+use crate::resources::SharedResourcesPlugin;
+use crate::states::{AppState, AppStatePlugin, GenerationState};
+use bevy::app::App;
+use bevy::asset::{AssetApp, AssetMetaCheck, AssetPlugin};
+use bevy::gizmos::GizmoPlugin;
+use bevy::prelude::{
+  default, Event, Events, ImagePlugin, MinimalPlugins, Shader, State, TextureAtlasLayout, With, Without, World,
+};
+use bevy::state::app::StatesPlugin;
+use std::thread;
+use std::time::Duration;
+
+/// The maximum number of frames `HeadlessApp::run_until` steps the app for before giving up.
+const MAX_FRAMES: usize = 10_000;
+
+/// How long to sleep after each frame, so that the tasks on the async compute task pool can make progress.
+const FRAME_SLEEP: Duration = Duration::from_millis(1);
+
+/// Boots the application with `MinimalPlugins`, the generation plugins and the `AnimationsPlugin`, which despawns pruned
+/// chunks, without rendering, windowing or audio. The assets are loaded from the `assets` folder through the real
+/// `AssetServer`, so the app goes through the same states as the application does. Allows stepping the schedule frame
+/// by frame and inspecting the ECS world in between, which makes it possible to write end-to-end tests for problems
+/// that only occur when all systems run together, such as the order in which events are processed.
+pub(crate) struct HeadlessApp {
+  pub(crate) app: App,
+}
+
+impl HeadlessApp {
+  pub(crate) fn new() -> Self {
+    let mut app = App::new();
+    app
+      .add_plugins((
+        MinimalPlugins,
+        StatesPlugin,
+        AssetPlugin {
+          file_path: format!("{}/assets", env!("CARGO_MANIFEST_DIR")),
+          meta_check: AssetMetaCheck::Never,
+          ..default()
+        },
+        ImagePlugin::default_nearest(),
+      ))
+      // Registered by the `SpritePlugin` and the `RenderPlugin` respectively, neither of which can run headless
+      .init_asset::<TextureAtlasLayout>()
+      .init_asset::<Shader>()
+      .add_plugins(GizmoPlugin)
+      .add_plugins((
+        AppStatePlugin,
+        SharedEventsPlugin,
+        SharedResourcesPlugin,
+        GenerationPlugin,
+        AnimationsPlugin,
+      ));
+
+    Self { app }
+  }
+
+  pub(crate) fn world(&self) -> &World {
+    self.app.world()
+  }
+
+  pub(crate) fn step(&mut self, frames: usize) {
+    for _ in 0..frames {
+      self.app.update();
+      thread::sleep(FRAME_SLEEP);
+    }
+  }
+
+  /// Steps the app until `condition` holds, returning the number of frames it took. Panics if the condition doesn't
+  /// hold within `MAX_FRAMES` frames.
+  pub(crate) fn run_until(&mut self, description: &str, condition: impl Fn(&mut World) -> bool) -> usize {
+    for frame in 1..=MAX_FRAMES {
+      self.step(1);
+      if condition(self.app.world_mut()) {
+        return frame;
+      }
+    }
+    panic!(
+      "Failed to reach [{}] within {} frames (app state: [{}], generation state: [{}])",
+      description,
+      MAX_FRAMES,
+      self.app_state(),
+      self.generation_state()
+    );
+  }
+
+  /// Steps the app until it is running and no world generation is in progress anymore.
+  pub(crate) fn run_until_idle(&mut self) -> usize {
+    self.run_until("idle", |world| {
+      *world.resource::<State<AppState>>().get() == AppState::Running
+        && *world.resource::<State<GenerationState>>().get() == GenerationState::Idling
+        && world
+          .query_filtered::<(), With<WorldGenerationComponent>>()
+          .iter(world)
+          .next()
+          .is_none()
+    })
+  }
+
+  pub(crate) fn send<E: Event>(&mut self, event: E) {
+    self.app.world_mut().resource_mut::<Events<E>>().send(event);
+  }
+
+  pub(crate) fn app_state(&self) -> AppState {
+    *self.world().resource::<State<AppState>>().get()
+  }
+
+  pub(crate) fn generation_state(&self) -> GenerationState {
+    *self.world().resource::<State<GenerationState>>().get()
+  }
+
+  /// Returns the `Point<ChunkGrid>` of every chunk that is spawned and not about to be despawned, sorted so that
+  /// duplicates are adjacent.
+  pub(crate) fn chunks(&mut self) -> Vec<Point<ChunkGrid>> {
+    chunks(self.app.world_mut())
+  }
+}
+
+/// See `HeadlessApp::chunks`.
+pub(crate) fn chunks(world: &mut World) -> Vec<Point<ChunkGrid>> {
+  let mut chunks = world
+    .query_filtered::<&ChunkComponent, Without<PendingDespawnComponent>>()
+    .iter(world)
+    .map(|chunk| chunk.coords.chunk_grid)
+    .collect::<Vec<_>>();
+  chunks.sort_by_key(|cg| (cg.x, cg.y));
+
+  chunks
+}
+
+mod tests {
+  use super::*;
+  use crate::components::LightSourceComponent;
+  use crate::events::{PruneWorldEvent, UpdateWorldEvent};
+  use crate::generation::lib::ObjectComponent;
+  use crate::generation::resources::DryRunReport;
+  use crate::lighting::{GlowMaterial, LightingPlugin};
+  use crate::resources::{CurrentChunk, Settings, SpawnPoint, SpawnPointCriterion};
+  use crate::shadows::ShadowsPlugin;
+  use bevy::prelude::{Asset, Assets, ColorMaterial, Image, Mesh};
+
+  #[test]
+  fn generates_the_world_around_the_spawn_point() {
+    let mut app = HeadlessApp::new();
+    assert_eq!(app.app_state(), AppState::Loading);
+    app.run_until_idle();
+
+    let spawn_cg = app.world().resource::<SpawnPoint>().cg;
+    let chunks = app.chunks();
+    assert!(
+      chunks.contains(&spawn_cg),
+      "Expected the chunk at the spawn point {} to exist",
+      spawn_cg
+    );
+    let mut deduplicated = chunks.clone();
+    deduplicated.dedup();
+    assert_eq!(chunks, deduplicated, "Expected every chunk to be spawned only once");
+  }
+
+  #[test]
+  fn pruning_all_chunks_and_updating_the_world_after_restores_the_same_chunks() {
+    let mut app = HeadlessApp::new();
+    app.run_until_idle();
+    let initial_chunks = app.chunks();
+
+    app.send(PruneWorldEvent {
+      despawn_all_chunks: true,
+      update_world_after: true,
+      is_derived: false,
+    });
+    app.run_until("all chunks are pruned", |world| chunks(world).is_empty());
+    app.run_until("the world is updated", |world| !chunks(world).is_empty());
+    app.run_until_idle();
+
+    assert_eq!(app.chunks(), initial_chunks);
+  }
+
+  #[test]
+  fn dry_run_reports_the_update_without_changing_the_world() {
+    let mut app = HeadlessApp::new();
+    app.run_until_idle();
+    let initial_chunks = app.chunks();
+    let current_cg = app.world().resource::<CurrentChunk>().get_chunk_grid();
+    let target_cg = Point::new_chunk_grid(current_cg.x + 1, current_cg.y);
+
+    app.send(UpdateWorldEvent::dry_run(
+      SpawnPoint::from_chunk_grid(target_cg).get_center_world(),
+    ));
+    app.step(10);
+
+    let report = app.world().resource::<DryRunReport>();
+    assert_eq!(report.current_cg, current_cg);
+    assert_eq!(report.new_current_cg, target_cg);
+    assert!(
+      !report.chunks_to_spawn.is_empty(),
+      "Expected the report to contain chunks to spawn"
+    );
+    assert_eq!(app.world().resource::<CurrentChunk>().get_chunk_grid(), current_cg);
+    assert_eq!(app.generation_state(), GenerationState::Idling);
+    assert_eq!(app.chunks(), initial_chunks);
+  }
+
+  /// How many chunks `exploring_the_world_does_not_grow_the_asset_storage` moves away from the spawn point and back.
+  const EXPLORED_CHUNKS: i32 = 6;
+
+  fn count<A: Asset>(world: &World) -> usize {
+    world.resource::<Assets<A>>().len()
+  }
+
+  /// Returns the number of meshes, materials, images and texture atlas layouts that currently exist.
+  fn count_assets(world: &World) -> [usize; 5] {
+    [
+      count::<Mesh>(world),
+      count::<ColorMaterial>(world),
+      count::<GlowMaterial>(world),
+      count::<Image>(world),
+      count::<TextureAtlasLayout>(world),
+    ]
+  }
+
+  /// Moves the `CurrentChunk` to the given `Point<ChunkGrid>`, which must be a neighbour of the `CurrentChunk`, and waits
+  /// until the world around it has been generated and all chunks that were pruned as a result have been despawned.
+  fn explore(app: &mut HeadlessApp, cg: Point<ChunkGrid>) {
+    app.send(UpdateWorldEvent::new(SpawnPoint::from_chunk_grid(cg).get_center_world()));
+    app.run_until("the world is updated", |world| chunks(world).contains(&cg));
+    app.run_until_idle();
+    app.run_until("pruned chunks are despawned", |world| {
+      world
+        .query_filtered::<(), With<PendingDespawnComponent>>()
+        .iter(world)
+        .next()
+        .is_none()
+    });
+  }
+
+  #[test]
+  fn exploring_the_world_does_not_grow_the_asset_storage() {
+    let mut app = HeadlessApp::new();
+    app
+      .app
+      .init_asset::<Mesh>()
+      .init_asset::<ColorMaterial>()
+      .add_plugins((LightingPlugin, ShadowsPlugin));
+    let mut settings = app.app.world_mut().resource_mut::<Settings>();
+    settings.general.min_chunk_lifetime_secs = 0.;
+    settings.general.prune_margin_in_chunks = 0.;
+    settings.general.enable_lighting = true;
+    // A seed for which the explored chunks contain objects with shadows and objects that emit light
+    settings.world.noise_seed = 42;
+    app.run_until_idle();
+    let spawn_cg = app.world().resource::<SpawnPoint>().cg;
+    let initial_chunks = app.chunks();
+    let initial_assets = count_assets(app.world());
+
+    let mut peak_meshes = initial_assets[0];
+    let mut peak_glows = 0;
+    for x in (1..=EXPLORED_CHUNKS).chain((0..EXPLORED_CHUNKS).rev()) {
+      explore(&mut app, Point::new_chunk_grid(spawn_cg.x + x, spawn_cg.y));
+      peak_meshes = peak_meshes.max(count::<Mesh>(app.world()));
+      peak_glows = peak_glows.max(glows(app.app.world_mut()));
+    }
+
+    assert!(
+      peak_meshes > initial_assets[0],
+      "Expected to explore chunks with object shadows"
+    );
+    assert!(peak_glows > 0, "Expected to explore chunks with objects that emit light");
+    assert_eq!(app.chunks(), initial_chunks);
+    assert_eq!(count_assets(app.world()), initial_assets);
+  }
+
+  fn glows(world: &mut World) -> usize {
+    world.query_filtered::<(), With<LightSourceComponent>>().iter(world).count()
+  }
+
+  #[test]
+  fn toggling_lighting_spawns_and_despawns_glows() {
+    let mut app = HeadlessApp::new();
+    app.app.init_asset::<Mesh>().add_plugins(LightingPlugin);
+    // A spawn point around which flowers, which emit light, are generated
+    app.app.world_mut().resource_mut::<Settings>().general.spawn_point_criterion = SpawnPointCriterion::Humid;
+    app.run_until_idle();
+    app.run_until("objects are spawned", |world| {
+      world.query::<&ObjectComponent>().iter(world).next().is_some()
+    });
+    assert_eq!(glows(app.app.world_mut()), 0, "Expected no glows while lighting is disabled");
+
+    app.app.world_mut().resource_mut::<Settings>().general.enable_lighting = true;
+    app.run_until("glows are spawned", |world| glows(world) > 0);
+
+    app.app.world_mut().resource_mut::<Settings>().general.enable_lighting = false;
+    app.run_until("glows are despawned", |world| glows(world) == 0);
+  }
+}
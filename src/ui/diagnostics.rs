@@ -1,5 +1,7 @@
 use crate::constants::*;
 use crate::events::ToggleDebugInfo;
+use crate::generation::resources::SPRITE_POOL_REUSE_RATE;
+use crate::generation::ORPHANED_SPRITES;
 use crate::resources::Settings;
 use bevy::app::{App, Plugin, Update};
 use bevy::diagnostic::DiagnosticsStore;
@@ -13,7 +15,15 @@ impl Plugin for DiagnosticsUiPlugin {
     app
       .add_plugins(FrameTimeDiagnosticsPlugin::default())
       .add_systems(Startup, create_fps_counter_system)
-      .add_systems(Update, (update_fps_system, toggle_fps_counter_event));
+      .add_systems(
+        Update,
+        (
+          update_fps_system,
+          update_orphaned_sprites_system,
+          update_sprite_pool_reuse_rate_system,
+          toggle_fps_counter_event,
+        ),
+      );
   }
 }
 
@@ -23,6 +33,12 @@ struct FpsUiRoot;
 #[derive(Component)]
 struct FpsText;
 
+#[derive(Component)]
+struct OrphanedSpritesText;
+
+#[derive(Component)]
+struct SpritePoolReuseRateText;
+
 fn create_fps_counter_system(mut commands: Commands) {
   commands
     .spawn((
@@ -42,7 +58,13 @@ fn create_fps_counter_system(mut commands: Commands) {
       Text::new("FPS: "),
       TextColor(LIGHT),
     ))
-    .with_child((TextSpan::new("N/A"), FpsText, TextColor(LIGHT)));
+    .with_children(|parent| {
+      parent.spawn((TextSpan::new("N/A"), FpsText, TextColor(LIGHT)));
+      parent.spawn((TextSpan::new("\nOrphans: "), TextColor(LIGHT)));
+      parent.spawn((TextSpan::new("0"), OrphanedSpritesText, TextColor(LIGHT)));
+      parent.spawn((TextSpan::new("\nPool reuse: "), TextColor(LIGHT)));
+      parent.spawn((TextSpan::new("0%"), SpritePoolReuseRateText, TextColor(LIGHT)));
+    });
 }
 
 fn update_fps_system(diagnostics: Res<DiagnosticsStore>, mut query: Query<&mut TextSpan, With<FpsText>>) {
@@ -58,6 +80,32 @@ fn update_fps_system(diagnostics: Res<DiagnosticsStore>, mut query: Query<&mut T
   }
 }
 
+/// Shows the total number of orphaned sprites that have been despawned, which should remain at zero.
+fn update_orphaned_sprites_system(
+  diagnostics: Res<DiagnosticsStore>,
+  mut query: Query<&mut TextSpan, With<OrphanedSpritesText>>,
+) {
+  let Some(value) = diagnostics.get(&ORPHANED_SPRITES).and_then(|d| d.value()) else {
+    return;
+  };
+  for mut span in &mut query {
+    **span = format!("{value:.0}");
+  }
+}
+
+/// Shows the percentage of tile and object sprites that were spawned by reusing a recycled entity from the sprite pool.
+fn update_sprite_pool_reuse_rate_system(
+  diagnostics: Res<DiagnosticsStore>,
+  mut query: Query<&mut TextSpan, With<SpritePoolReuseRateText>>,
+) {
+  let Some(value) = diagnostics.get(&SPRITE_POOL_REUSE_RATE).and_then(|d| d.value()) else {
+    return;
+  };
+  for mut span in &mut query {
+    **span = format!("{value:.0}%");
+  }
+}
+
 fn toggle_fps_counter_event(
   mut events: EventReader<ToggleDebugInfo>,
   mut fps_ui_root: Query<&mut Visibility, With<FpsUiRoot>>,
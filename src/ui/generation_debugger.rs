@@ -0,0 +1,202 @@
+use crate::constants::{GREEN, RED, STAGE_TIMINGS_SAMPLE_SIZE, YELLOW};
+use crate::generation::lib::{shared, WorldGenerationComponent};
+use crate::generation::resources::{GenerationStepper, Metadata, StageTimings};
+use crate::resources::Settings;
+use bevy::app::{App, Plugin, Update};
+use bevy::color::ColorToPacked;
+use bevy::input::ButtonInput;
+use bevy::prelude::{Color, KeyCode, Local, With, World};
+use bevy::window::PrimaryWindow;
+use bevy_inspector_egui::bevy_egui::EguiContext;
+use bevy_inspector_egui::egui::{Align2, CollapsingHeader, Color32, Grid, ProgressBar, RichText, ScrollArea, Window};
+
+pub struct GenerationDebuggerUiPlugin;
+
+impl Plugin for GenerationDebuggerUiPlugin {
+  fn build(&self, app: &mut App) {
+    app.add_systems(Update, render_generation_debugger_ui_system);
+  }
+}
+
+/// Renders every active `WorldGenerationComponent` with its current stage, the size of each stage's payload and the
+/// time spent per stage. The time spent in the current stage is colour-coded relative to the timeout after which the
+/// component is considered stuck, so that slow stages stand out before the watchdog flags them. Allows stepping through the generation process if `step_through_generation` is enabled.
+/// Also shows the distribution of the duration of each stage across the most recently completed components, with the
+/// bar of each stage scaled relative to the slowest one, and lists the rare features that were stamped onto the chunks
+/// in the metadata window. Hidden by default and toggled by pressing F4.
+fn render_generation_debugger_ui_system(world: &mut World, mut enabled: Local<bool>) {
+  let is_toggled = world.resource::<ButtonInput<KeyCode>>().just_pressed(KeyCode::F4);
+  if is_toggled {
+    *enabled = !*enabled;
+  }
+  if !*enabled {
+    return;
+  }
+
+  let mut egui_context = world
+    .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+    .single(world)
+    .clone();
+
+  let now = shared::get_time();
+  let is_stepping_through = world.resource::<Settings>().general.step_through_generation;
+  let timeout_ms = (world.resource::<Settings>().general.stuck_component_timeout_secs * 1000.) as u128;
+  let (is_step_requested, step_count) = {
+    let stepper = world.resource::<GenerationStepper>();
+    (stepper.is_step_requested(), stepper.step_count())
+  };
+  let rows = world
+    .query::<&WorldGenerationComponent>()
+    .iter(world)
+    .map(|component| {
+      (
+        component.cg.to_string(),
+        format!("{:?}", component.stage),
+        now.saturating_sub(component.stage_started_at),
+        now.saturating_sub(component.created_at),
+        format!(
+          "{}/{}/{}/{}/{}",
+          if component.stage_1_gen_task.is_some() { 1 } else { 0 },
+          component.stage_2_chunks.len(),
+          component.stage_3_spawn_data.len(),
+          component.stage_4_spawn_data.len(),
+          component.stage_5_object_data.len()
+        ),
+        component
+          .stage_durations
+          .iter()
+          .map(|(stage, duration)| format!("{:?}: {} ms", stage, duration))
+          .collect::<Vec<String>>()
+          .join(", "),
+      )
+    })
+    .collect::<Vec<_>>();
+  let mut rare_features = world
+    .resource::<Metadata>()
+    .rare_features
+    .values()
+    .map(|feature| (feature.name.clone(), feature.cg.to_string(), format!("{:?}", feature.ig)))
+    .collect::<Vec<_>>();
+  rare_features.sort_by(|a, b| a.1.cmp(&b.1));
+  let stage_timings = world.resource::<StageTimings>().summaries();
+  let slowest_p95 = stage_timings
+    .iter()
+    .map(|summary| summary.p95)
+    .max()
+    .unwrap_or_default()
+    .max(1);
+
+  let mut should_step = false;
+  let mut should_clear_stage_timings = false;
+  Window::new("Generation Debugger")
+    .default_size([700.0, 300.0])
+    .pivot(Align2::RIGHT_TOP)
+    .anchor(Align2::RIGHT_TOP, [-10.0, 10.0])
+    .show(egui_context.get_mut(), |ui| {
+      ui.horizontal(|ui| {
+        ui.label(format!(
+          "Stepping through: {} | Steps taken: {} | Step pending: {}",
+          is_stepping_through, step_count, is_step_requested
+        ));
+        if ui.button("Step").clicked() {
+          should_step = true;
+        }
+      });
+      ui.separator();
+      CollapsingHeader::new(format!("Active components ({})", rows.len()))
+        .default_open(true)
+        .show(ui, |ui| {
+          ScrollArea::both().show(ui, |ui| {
+            Grid::new("generation_debugger_grid").striped(true).show(ui, |ui| {
+              ui.strong("Chunk");
+              ui.strong("Stage");
+              ui.strong("In stage");
+              ui.strong("Age");
+              ui.strong("Payloads (1/2/3/4/5)");
+              ui.strong("Completed stages");
+              ui.end_row();
+              for (cg, stage, elapsed, age, payloads, durations) in rows.iter() {
+                ui.label(cg);
+                ui.label(stage);
+                ui.label(RichText::new(format!("{} ms", elapsed)).color(duration_colour(*elapsed, timeout_ms)));
+                ui.label(format!("{} ms", age));
+                ui.label(payloads);
+                ui.label(durations);
+                ui.end_row();
+              }
+            });
+          });
+        });
+      CollapsingHeader::new(format!("Stage timings (last {} components)", STAGE_TIMINGS_SAMPLE_SIZE))
+        .default_open(false)
+        .show(ui, |ui| {
+          Grid::new("generation_debugger_stage_timings_grid")
+            .striped(true)
+            .show(ui, |ui| {
+              ui.strong("Stage");
+              ui.strong("Samples");
+              ui.strong("p50");
+              ui.strong("p95");
+              ui.strong("Max");
+              ui.strong("p95 relative to slowest stage");
+              ui.end_row();
+              for summary in stage_timings.iter() {
+                ui.label(format!("{:?}", summary.stage));
+                ui.label(summary.sample_count.to_string());
+                ui.label(format!("{} ms", summary.p50));
+                ui.label(format!("{} ms", summary.p95));
+                ui.label(format!("{} ms", summary.max));
+                ui.add(ProgressBar::new(summary.p95 as f32 / slowest_p95 as f32).desired_width(200.));
+                ui.end_row();
+              }
+            });
+          if ui.button("Clear").clicked() {
+            should_clear_stage_timings = true;
+          }
+        });
+      CollapsingHeader::new(format!("Rare features ({})", rare_features.len()))
+        .default_open(false)
+        .show(ui, |ui| {
+          Grid::new("generation_debugger_rare_features_grid")
+            .striped(true)
+            .show(ui, |ui| {
+              ui.strong("Feature");
+              ui.strong("Chunk");
+              ui.strong("Position");
+              ui.end_row();
+              for (name, cg, ig) in rare_features.iter() {
+                ui.label(name);
+                ui.label(cg);
+                ui.label(ig);
+                ui.end_row();
+              }
+            });
+        });
+      ui.separator();
+      ui.label("Press F4 to toggle this window and N to request the next step");
+    });
+
+  if should_step {
+    world.resource_mut::<GenerationStepper>().request_step();
+  }
+  if should_clear_stage_timings {
+    world.resource_mut::<StageTimings>().clear();
+  }
+}
+
+/// Returns green for durations below a quarter of the timeout, yellow for durations below the timeout and red otherwise.
+fn duration_colour(elapsed_ms: u128, timeout_ms: u128) -> Color32 {
+  let colour = match elapsed_ms {
+    ms if ms < timeout_ms / 4 => GREEN,
+    ms if ms < timeout_ms => YELLOW,
+    _ => RED,
+  };
+
+  to_colour32(colour)
+}
+
+fn to_colour32(colour: Color) -> Color32 {
+  let [r, g, b, a] = colour.to_srgba().to_u8_array();
+
+  Color32::from_rgba_unmultiplied(r, g, b, a)
+}
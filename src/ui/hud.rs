@@ -0,0 +1,88 @@
+use crate::constants::*;
+use crate::coords::TilePicker;
+use crate::events::ToggleDebugInfo;
+use crate::generation::resources::Metadata;
+use crate::resources::{CurrentChunk, Settings};
+use bevy::app::{App, Plugin, Update};
+use bevy::prelude::*;
+
+pub struct HudUiPlugin;
+
+impl Plugin for HudUiPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .add_systems(Startup, create_hud_system)
+      .add_systems(Update, (update_hud_system, toggle_hud_event));
+  }
+}
+
+#[derive(Component)]
+struct HudUiRoot;
+
+fn create_hud_system(mut commands: Commands) {
+  commands.spawn((
+    Name::new("Coordinates HUD"),
+    HudUiRoot,
+    Node {
+      position_type: PositionType::Absolute,
+      right: Val::Percent(1.),
+      top: Val::Px(32.),
+      bottom: Val::Auto,
+      left: Val::Auto,
+      padding: UiRect::all(Val::Px(4.0)),
+      margin: UiRect::all(Val::Px(1.0)),
+      ..Default::default()
+    },
+    BackgroundColor(VERY_DARK.with_alpha(0.5)),
+    Text::new(""),
+    TextFont::from_font_size(14.),
+    TextColor(LIGHT),
+    TextLayout::new_with_justify(JustifyText::Right),
+  ));
+}
+
+/// Shows the position of the camera, the current chunk and its biome, and the coordinates of the tile under the cursor.
+fn update_hud_system(
+  picker: TilePicker,
+  current_chunk: Res<CurrentChunk>,
+  metadata: Res<Metadata>,
+  mut hud: Query<&mut Text, With<HudUiRoot>>,
+) {
+  let Ok(mut text) = hud.get_single_mut() else {
+    return;
+  };
+  let Some(camera) = picker.camera() else {
+    return;
+  };
+  let cg = current_chunk.get_chunk_grid();
+  let biome = metadata.biome.get(&cg).map_or("Biome: -".to_string(), |bm| {
+    format!(
+      "Biome: {:?}, rainfall {:.2}, max. layer {}{}",
+      bm.climate,
+      bm.rainfall,
+      bm.max_layer,
+      if bm.is_rocky { ", rocky" } else { "" }
+    )
+  });
+  let cursor = picker.cursor().map_or("Cursor: -".to_string(), |tile| {
+    format!("Cursor: {} {} {}", tile.tg, tile.cg, tile.ig)
+  });
+
+  **text = format!("Camera: {}\nChunk: {}\n{}\n{}", camera.w, cg, biome, cursor);
+}
+
+fn toggle_hud_event(
+  mut events: EventReader<ToggleDebugInfo>,
+  mut hud: Query<&mut Visibility, With<HudUiRoot>>,
+  settings: Res<Settings>,
+) {
+  let event_count = events.read().count();
+  if event_count > 0 {
+    for mut visibility in hud.iter_mut() {
+      *visibility = match settings.general.enable_tile_debugging {
+        true => Visibility::Visible,
+        false => Visibility::Hidden,
+      };
+    }
+  }
+}
@@ -1,14 +1,35 @@
 mod diagnostics;
+mod generation_debugger;
+mod hud;
+mod object_selection;
 mod settings;
+mod terrain_statistics;
+mod wfc_failures;
+mod wfc_visualiser;
 
 use crate::ui::diagnostics::DiagnosticsUiPlugin;
+use crate::ui::generation_debugger::GenerationDebuggerUiPlugin;
+use crate::ui::hud::HudUiPlugin;
+use crate::ui::object_selection::ObjectSelectionUiPlugin;
 use bevy::app::{App, Plugin};
 use settings::SettingsUiPlugin;
+use terrain_statistics::TerrainStatisticsUiPlugin;
+use wfc_failures::WfcFailuresUiPlugin;
+use wfc_visualiser::WfcVisualiserUiPlugin;
 
 pub struct UiPlugin;
 
 impl Plugin for UiPlugin {
   fn build(&self, app: &mut App) {
-    app.add_plugins((SettingsUiPlugin, DiagnosticsUiPlugin));
+    app.add_plugins((
+      SettingsUiPlugin,
+      DiagnosticsUiPlugin,
+      HudUiPlugin,
+      WfcFailuresUiPlugin,
+      GenerationDebuggerUiPlugin,
+      WfcVisualiserUiPlugin,
+      ObjectSelectionUiPlugin,
+      TerrainStatisticsUiPlugin,
+    ));
   }
 }
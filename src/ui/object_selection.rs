@@ -0,0 +1,213 @@
+use crate::constants::*;
+use crate::coords::point::{ChunkGrid, InternalGrid};
+use crate::coords::{Point, TilePicker};
+use crate::events::RemoveObjectEvent;
+use crate::generation::lib::{ObjectComponent, TileDataComponent};
+use bevy::app::{App, Plugin, Update};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_inspector_egui::bevy_egui::{EguiContext, EguiContexts};
+use bevy_inspector_egui::egui;
+use bevy_inspector_egui::egui::{Align2, Grid};
+
+pub struct ObjectSelectionUiPlugin;
+
+impl Plugin for ObjectSelectionUiPlugin {
+  fn build(&self, app: &mut App) {
+    app.init_resource::<ObjectSelection>().add_systems(
+      Update,
+      (
+        hover_object_system,
+        select_object_system,
+        highlight_object_system,
+        render_object_details_ui_system,
+      )
+        .chain(),
+    );
+  }
+}
+
+/// Keeps track of the object sprite under the cursor and the object sprite that was last clicked on. The entities of
+/// object sprites are reused by the `SpritePool` once their chunk has been despawned, so the coordinates of the selected
+/// object are kept as well, which tell whether its entity still belongs to the same object.
+#[derive(Resource, Default)]
+struct ObjectSelection {
+  hovered: Option<Entity>,
+  selected: Option<(Entity, Point<ChunkGrid>, Point<InternalGrid>)>,
+}
+
+impl ObjectSelection {
+  fn selected_entity(&self) -> Option<Entity> {
+    self.selected.map(|(entity, _, _)| entity)
+  }
+}
+
+/// Stores the colour of an object sprite prior to it being highlighted, so that it can be restored afterwards.
+#[derive(Component)]
+struct HighlightComponent {
+  original_colour: Color,
+}
+
+/// Finds the topmost object sprite whose bounds contain the cursor. Sprites are anchored at their bottom center and
+/// their bounds are derived from the size of their texture atlas entry, which avoids the need for mesh picking.
+fn hover_object_system(
+  picker: TilePicker,
+  objects: Query<(Entity, &GlobalTransform, &Sprite), With<ObjectComponent>>,
+  layouts: Res<Assets<TextureAtlasLayout>>,
+  mut egui_contexts: EguiContexts,
+  mut selection: ResMut<ObjectSelection>,
+) {
+  selection.hovered = None;
+  if egui_contexts.ctx_mut().wants_pointer_input() {
+    return;
+  }
+  let Some(cursor) = picker.cursor_translation() else {
+    return;
+  };
+  let mut topmost: Option<(Entity, f32)> = None;
+  for (entity, transform, sprite) in objects.iter() {
+    let size = sprite.custom_size.or_else(|| {
+      let atlas = sprite.texture_atlas.as_ref()?;
+      layouts
+        .get(&atlas.layout)
+        .and_then(|layout| layout.textures.get(atlas.index))
+        .map(|rect| rect.size().as_vec2())
+    });
+    let Some(size) = size else {
+      continue;
+    };
+    let (scale, _, translation) = transform.to_scale_rotation_translation();
+    let size = size * scale.truncate();
+    let min = Vec2::new(translation.x - size.x / 2., translation.y);
+    let max = Vec2::new(translation.x + size.x / 2., translation.y + size.y);
+    let is_hovered = cursor.x >= min.x && cursor.x <= max.x && cursor.y >= min.y && cursor.y <= max.y;
+    if is_hovered && topmost.is_none_or(|(_, z)| translation.z > z) {
+      topmost = Some((entity, translation.z));
+    }
+  }
+  selection.hovered = topmost.map(|(entity, _)| entity);
+}
+
+/// Selects the hovered object when clicking the left mouse button and despawns the selected object when pressing
+/// delete. Clears the selection if the selected object no longer exists e.g. because its chunk was pruned, even if its
+/// entity has since been reused for another object.
+fn select_object_system(
+  mouse_button_input: Res<ButtonInput<MouseButton>>,
+  keyboard_input: Res<ButtonInput<KeyCode>>,
+  objects: Query<&ObjectComponent>,
+  mut egui_contexts: EguiContexts,
+  mut selection: ResMut<ObjectSelection>,
+  mut remove_object_event: EventWriter<RemoveObjectEvent>,
+) {
+  if mouse_button_input.just_pressed(MouseButton::Left) && !egui_contexts.ctx_mut().wants_pointer_input() {
+    selection.selected = selection.hovered.and_then(|entity| {
+      let object = objects.get(entity).ok()?;
+      Some((entity, object.coords.chunk_grid, object.coords.internal_grid))
+    });
+  }
+  if let Some((entity, cg, ig)) = selection.selected {
+    let is_same_object = objects
+      .get(entity)
+      .is_ok_and(|object| object.coords.chunk_grid == cg && object.coords.internal_grid == ig);
+    if !is_same_object {
+      selection.selected = None;
+    } else if keyboard_input.just_pressed(KeyCode::Delete) {
+      remove_object_event.send(RemoveObjectEvent::ByCoords(cg, ig));
+      selection.selected = None;
+    }
+  }
+}
+
+/// Tints the hovered and selected object sprites and restores the original colour of all other previously highlighted
+/// sprites. Only the colour is restored, so that the alpha of sprites that are still fading in is not affected.
+fn highlight_object_system(
+  mut commands: Commands,
+  selection: Res<ObjectSelection>,
+  mut highlighted: Query<(Entity, &HighlightComponent, &mut Sprite)>,
+  mut objects: Query<&mut Sprite, (With<ObjectComponent>, Without<HighlightComponent>)>,
+) {
+  for (entity, highlight, mut sprite) in highlighted.iter_mut() {
+    let tint = if selection.selected_entity() == Some(entity) {
+      SELECTED_OBJECT_TINT
+    } else if selection.hovered == Some(entity) {
+      HOVERED_OBJECT_TINT
+    } else {
+      let alpha = sprite.color.alpha();
+      sprite.color = highlight.original_colour.with_alpha(alpha);
+      commands.entity(entity).remove::<HighlightComponent>();
+      continue;
+    };
+    sprite.color = tint.with_alpha(sprite.color.alpha());
+  }
+  // The selected object takes precedence, so that an object that is both hovered and selected is only highlighted once
+  let selected = selection.selected_entity();
+  let hovered = selection.hovered.filter(|entity| selected != Some(*entity));
+  for (entity, tint) in [(selected, SELECTED_OBJECT_TINT), (hovered, HOVERED_OBJECT_TINT)] {
+    let Some(entity) = entity else {
+      continue;
+    };
+    if let Ok(mut sprite) = objects.get_mut(entity) {
+      commands.entity(entity).insert(HighlightComponent {
+        original_colour: sprite.color,
+      });
+      sprite.color = tint.with_alpha(sprite.color.alpha());
+    }
+  }
+}
+
+/// Renders the details of the selected object and the tile it was spawned on.
+fn render_object_details_ui_system(world: &mut World) {
+  let Some(entity) = world.resource::<ObjectSelection>().selected_entity() else {
+    return;
+  };
+  let Some(object) = world.get::<ObjectComponent>(entity).cloned() else {
+    return;
+  };
+  let tile_data = world
+    .get::<Parent>(entity)
+    .and_then(|parent| world.get::<TileDataComponent>(parent.get()))
+    .map(|component| component.tile_data);
+
+  let mut egui_context = world
+    .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+    .single(world)
+    .clone();
+
+  egui::Window::new("Selected Object")
+    .default_size([280.0, 200.0])
+    .pivot(Align2::RIGHT_TOP)
+    .anchor(Align2::RIGHT_TOP, [-10.0, 120.0])
+    .show(egui_context.get_mut(), |ui| {
+      Grid::new("selected_object_grid").striped(true).show(ui, |ui| {
+        ui.label("Name");
+        ui.label(format!("{:?}", object.object_name));
+        ui.end_row();
+        ui.label("Coordinates");
+        ui.label(format!(
+          "{} {} {}",
+          object.coords.chunk_grid, object.coords.internal_grid, object.coords.tile_grid
+        ));
+        ui.end_row();
+        ui.label("WFC state");
+        ui.label(format!("Collapsed to sprite index {}", object.sprite_index));
+        ui.end_row();
+        ui.label("Layer");
+        ui.label(object.layer.to_string());
+        ui.end_row();
+        if let Some(tile_data) = tile_data {
+          let tile = tile_data.flat_tile;
+          ui.label("Terrain below");
+          ui.label(format!(
+            "{:?} {:?} ({:?}{})",
+            tile.terrain,
+            tile.tile_type,
+            tile.climate,
+            if tile.is_beach { ", beach" } else { "" }
+          ));
+          ui.end_row();
+        }
+      });
+      ui.separator();
+      ui.label("Press [Delete] to despawn this object");
+    });
+}
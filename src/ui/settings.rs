@@ -1,16 +1,17 @@
-use crate::constants::ORIGIN_TILE_GRID_SPAWN_POINT;
-use crate::events::RefreshMetadata;
+use crate::events::{RefreshMetadata, RerollObjectsEvent};
+use crate::generation::resources::ArtPacks;
 use crate::resources::{
-  CurrentChunk, GeneralGenerationSettings, GenerationMetadataSettings, ObjectGenerationSettings, Settings,
-  WorldGenerationSettings,
+  CurrentChunk, GeneralGenerationSettings, GenerationMetadataSettings, ObjectGenerationSettings, Settings, SettingsIssue,
+  SpawnPoint, WorldGenerationSettings,
 };
-use crate::states::{AppState, GenerationState};
+use crate::states::{AppState, GenerationPhase, GenerationState};
 use bevy::app::{App, Plugin, Update};
 use bevy::input::ButtonInput;
-use bevy::prelude::{EventWriter, KeyCode, Local, Res, ResMut, Resource, With, World};
+use bevy::log::{info, warn};
+use bevy::prelude::{DetectChanges, EventWriter, KeyCode, Local, Res, ResMut, Resource, State, With, World};
 use bevy::window::PrimaryWindow;
 use bevy_inspector_egui::bevy_egui::EguiContext;
-use bevy_inspector_egui::egui::{Align, Align2, FontId, Layout, RichText, ScrollArea, Window};
+use bevy_inspector_egui::egui::{Align, Align2, Color32, ComboBox, FontId, Layout, RichText, ScrollArea, Ui, Window};
 
 pub struct SettingsUiPlugin;
 
@@ -18,6 +19,7 @@ impl Plugin for SettingsUiPlugin {
   fn build(&self, app: &mut App) {
     app
       .insert_resource(UiState::default())
+      .init_resource::<SettingsIssues>()
       .add_systems(Update, (render_settings_ui_system, handle_ui_events_system));
   }
 }
@@ -29,6 +31,17 @@ struct UiState {
   has_changed: bool,
   regenerate: bool,
   generate_next: bool,
+  reroll_objects: bool,
+}
+
+/// The issues found during the most recent validation of each settings section. Replaced whenever the settings of the
+/// respective section change.
+#[derive(Default, Resource)]
+struct SettingsIssues {
+  general: Vec<SettingsIssue>,
+  metadata: Vec<SettingsIssue>,
+  world: Vec<SettingsIssue>,
+  object: Vec<SettingsIssue>,
 }
 
 impl UiState {
@@ -41,6 +54,11 @@ impl UiState {
     self.generate_next = true;
     self.has_changed = true;
   }
+
+  pub fn trigger_object_reroll(&mut self) {
+    self.reroll_objects = true;
+    self.has_changed = true;
+  }
 }
 
 fn render_settings_ui_system(world: &mut World, mut disabled: Local<bool>) {
@@ -77,26 +95,42 @@ fn render_settings_ui_system(world: &mut World, mut disabled: Local<bool>) {
               bevy_inspector_egui::bevy_inspector::ui_for_state::<GenerationState>(world, ui)
             });
           });
+          ui.columns(2, |columns| {
+            columns[0].label("generation_phase");
+            let phase = world
+              .get_resource::<State<GenerationPhase>>()
+              .map_or("-".to_string(), |phase| phase.get().to_string());
+            columns[1].label(phase);
+          });
         });
         ui.add_space(20.0);
         ui.push_id("general_generation", |ui| {
           ui.label(RichText::new("General Generation").font(HEADING));
           bevy_inspector_egui::bevy_inspector::ui_for_resource::<GeneralGenerationSettings>(world, ui);
+          render_issues(ui, &world.resource::<SettingsIssues>().general);
         });
         ui.add_space(20.0);
         ui.push_id("generation_metadata", |ui| {
           ui.label(RichText::new("Generation Metadata").font(HEADING));
           bevy_inspector_egui::bevy_inspector::ui_for_resource::<GenerationMetadataSettings>(world, ui);
+          render_issues(ui, &world.resource::<SettingsIssues>().metadata);
         });
         ui.add_space(20.0);
         ui.push_id("world_generation", |ui| {
           ui.label(RichText::new("World Generation").font(HEADING));
           bevy_inspector_egui::bevy_inspector::ui_for_resource::<WorldGenerationSettings>(world, ui);
+          render_issues(ui, &world.resource::<SettingsIssues>().world);
         });
         ui.add_space(20.0);
         ui.push_id("object_generation", |ui| {
           ui.label(RichText::new("Object Generation").font(HEADING));
           bevy_inspector_egui::bevy_inspector::ui_for_resource::<ObjectGenerationSettings>(world, ui);
+          render_issues(ui, &world.resource::<SettingsIssues>().object);
+        });
+        ui.add_space(20.0);
+        ui.push_id("art_pack", |ui| {
+          ui.label(RichText::new("Art Pack").font(HEADING));
+          render_art_pack_selection(world, ui);
         });
         ui.separator();
         ui.horizontal(|ui| {
@@ -111,22 +145,196 @@ fn render_settings_ui_system(world: &mut World, mut disabled: Local<bool>) {
             }
           });
         });
+        ui.horizontal(|ui| {
+          if ui.button("Reroll Objects").clicked() {
+            let mut event_writer = world.resource_mut::<UiState>();
+            event_writer.trigger_object_reroll();
+          }
+        });
         ui.separator();
         ui.label("Press F2 to toggle the inspector window");
       });
     });
 }
 
+/// Renders a warning for each value of a settings section that was repaired during the last validation, naming the
+/// control that caused it.
+fn render_issues(ui: &mut Ui, issues: &[SettingsIssue]) {
+  for issue in issues.iter() {
+    ui.colored_label(Color32::ORANGE, format!("⚠ {}", issue));
+  }
+}
+
+/// Renders a drop-down with all available art packs. Selecting a different art pack re-spawns all chunks using it.
+fn render_art_pack_selection(world: &mut World, ui: &mut Ui) {
+  let art_packs = world.resource::<ArtPacks>();
+  let names = art_packs.names();
+  let current = art_packs.selected();
+  let mut selected = current;
+  ComboBox::from_id_salt("art_pack_selection")
+    .selected_text(names.get(current).cloned().unwrap_or_default())
+    .show_ui(ui, |ui| {
+      for (index, name) in names.iter().enumerate() {
+        ui.selectable_value(&mut selected, index, name);
+      }
+    });
+  if selected != current {
+    world.resource_mut::<ArtPacks>().request(selected);
+  }
+}
+
 fn handle_ui_events_system(
   mut refresh_metadata_event: EventWriter<RefreshMetadata>,
+  mut reroll_objects_event: EventWriter<RerollObjectsEvent>,
   mut state: ResMut<UiState>,
+  mut issues: ResMut<SettingsIssues>,
   mut settings: ResMut<Settings>,
-  general: Res<GeneralGenerationSettings>,
-  metadata_settings: Res<GenerationMetadataSettings>,
-  object: Res<ObjectGenerationSettings>,
+  mut general: ResMut<GeneralGenerationSettings>,
+  mut metadata_settings: ResMut<GenerationMetadataSettings>,
+  mut object: ResMut<ObjectGenerationSettings>,
   mut world_gen: ResMut<WorldGenerationSettings>,
   current_chunk: Res<CurrentChunk>,
+  spawn_point: Res<SpawnPoint>,
 ) {
+  validate(&mut general, &mut issues.general, GeneralGenerationSettings::validate);
+  validate(
+    &mut metadata_settings,
+    &mut issues.metadata,
+    GenerationMetadataSettings::validate,
+  );
+  validate(&mut world_gen, &mut issues.world, WorldGenerationSettings::validate);
+  validate(&mut object, &mut issues.object, ObjectGenerationSettings::validate);
+
+  if general.is_changed() && settings.general.freeze_generation != general.freeze_generation {
+    settings.general.freeze_generation = general.freeze_generation;
+    info!("Set freezing generation to [{}]", settings.general.freeze_generation);
+  }
+  if general.is_changed() && settings.general.step_through_generation != general.step_through_generation {
+    settings.general.step_through_generation = general.step_through_generation;
+    info!(
+      "Set stepping through generation to [{}]",
+      settings.general.step_through_generation
+    );
+  }
+
+  if general.is_changed()
+    && (settings.general.enable_frame_time_guardrail != general.enable_frame_time_guardrail
+      || settings.general.frame_time_budget_ms != general.frame_time_budget_ms)
+  {
+    settings.general.enable_frame_time_guardrail = general.enable_frame_time_guardrail;
+    settings.general.frame_time_budget_ms = general.frame_time_budget_ms;
+    info!(
+      "Set frame time guardrail to [{}] with a budget of [{}] ms",
+      settings.general.enable_frame_time_guardrail, settings.general.frame_time_budget_ms
+    );
+  }
+
+  if general.is_changed()
+    && (settings.general.prune_margin_in_chunks != general.prune_margin_in_chunks
+      || settings.general.min_chunk_lifetime_secs != general.min_chunk_lifetime_secs)
+  {
+    settings.general.prune_margin_in_chunks = general.prune_margin_in_chunks;
+    settings.general.min_chunk_lifetime_secs = general.min_chunk_lifetime_secs;
+    info!(
+      "Set prune margin to [{}] chunk(s) and minimum chunk lifetime to [{}] s",
+      settings.general.prune_margin_in_chunks, settings.general.min_chunk_lifetime_secs
+    );
+  }
+
+  if general.is_changed() && settings.general.object_spawn_budget != general.object_spawn_budget {
+    settings.general.object_spawn_budget = general.object_spawn_budget;
+    info!("Set object spawn budget to [{}]", settings.general.object_spawn_budget);
+  }
+
+  if general.is_changed()
+    && (settings.general.enable_lighting != general.enable_lighting || settings.general.darkness != general.darkness)
+  {
+    settings.general.enable_lighting = general.enable_lighting;
+    settings.general.darkness = general.darkness;
+    info!(
+      "Set lighting to [{}] with a darkness of [{}]",
+      settings.general.enable_lighting, settings.general.darkness
+    );
+  }
+
+  if general.is_changed()
+    && (settings.general.enable_terrain_brush != general.enable_terrain_brush
+      || settings.general.terrain_brush_type != general.terrain_brush_type
+      || settings.general.terrain_brush_radius != general.terrain_brush_radius)
+  {
+    settings.general.enable_terrain_brush = general.enable_terrain_brush;
+    settings.general.terrain_brush_type = general.terrain_brush_type;
+    settings.general.terrain_brush_radius = general.terrain_brush_radius;
+    info!(
+      "Set terrain brush to [{}] with [{:?}] terrain and a radius of [{}]",
+      settings.general.enable_terrain_brush, settings.general.terrain_brush_type, settings.general.terrain_brush_radius
+    );
+  }
+
+  if general.is_changed() && settings.general.enable_regeneration_diff != general.enable_regeneration_diff {
+    settings.general.enable_regeneration_diff = general.enable_regeneration_diff;
+    info!("Set regeneration diff to [{}]", settings.general.enable_regeneration_diff);
+  }
+
+  if general.is_changed() && settings.general.debug_names != general.debug_names {
+    settings.general.debug_names = general.debug_names;
+    info!("Set debug names to [{}]", settings.general.debug_names);
+  }
+
+  if general.is_changed() && settings.general.layer_visibility != general.layer_visibility {
+    settings.general.layer_visibility = general.layer_visibility;
+    info!("Set layer visibility to [{:?}]", settings.general.layer_visibility);
+  }
+
+  if general.is_changed() && settings.general.enable_ocean_backdrop != general.enable_ocean_backdrop {
+    settings.general.enable_ocean_backdrop = general.enable_ocean_backdrop;
+    info!("Set ocean backdrop to [{}]", settings.general.enable_ocean_backdrop);
+  }
+
+  if general.is_changed() && settings.general.draw_object_shadows != general.draw_object_shadows {
+    settings.general.draw_object_shadows = general.draw_object_shadows;
+    info!("Set drawing object shadows to [{}]", settings.general.draw_object_shadows);
+  }
+
+  if general.is_changed() && settings.general.enable_occlusion_fade != general.enable_occlusion_fade {
+    settings.general.enable_occlusion_fade = general.enable_occlusion_fade;
+    info!("Set occlusion fade to [{}]", settings.general.enable_occlusion_fade);
+  }
+
+  if general.is_changed()
+    && (settings.general.enable_pixel_perfect_zoom != general.enable_pixel_perfect_zoom
+      || settings.general.min_zoom_scale != general.min_zoom_scale
+      || settings.general.max_zoom_scale != general.max_zoom_scale)
+  {
+    settings.general.enable_pixel_perfect_zoom = general.enable_pixel_perfect_zoom;
+    settings.general.min_zoom_scale = general.min_zoom_scale;
+    settings.general.max_zoom_scale = general.max_zoom_scale;
+    info!(
+      "Set pixel perfect zoom to [{}] with a scale between [{}] and [{}]",
+      settings.general.enable_pixel_perfect_zoom, settings.general.min_zoom_scale, settings.general.max_zoom_scale
+    );
+  }
+
+  if general.is_changed() && settings.general.requeue_outdated_chunks != general.requeue_outdated_chunks {
+    settings.general.requeue_outdated_chunks = general.requeue_outdated_chunks;
+    info!(
+      "Set requeuing outdated chunks to [{}]",
+      settings.general.requeue_outdated_chunks
+    );
+  }
+
+  if general.is_changed()
+    && (settings.general.stuck_component_timeout_secs != general.stuck_component_timeout_secs
+      || settings.general.force_advance_stuck_components != general.force_advance_stuck_components)
+  {
+    settings.general.stuck_component_timeout_secs = general.stuck_component_timeout_secs;
+    settings.general.force_advance_stuck_components = general.force_advance_stuck_components;
+    info!(
+      "Set stuck component timeout to [{}] s with force-advancing set to [{}]",
+      settings.general.stuck_component_timeout_secs, settings.general.force_advance_stuck_components
+    );
+  }
+
   if state.has_changed {
     state.has_changed = false;
     settings.general = general.clone();
@@ -135,26 +343,54 @@ fn handle_ui_events_system(
     settings.object = object.clone();
 
     if state.regenerate {
-      send_regenerate_or_prune_event(&current_chunk, &mut refresh_metadata_event);
+      send_regenerate_or_prune_event(&current_chunk, &spawn_point, &mut refresh_metadata_event);
       state.regenerate = false;
     }
 
     if state.generate_next {
       settings.world.noise_seed = settings.world.noise_seed.saturating_add(1);
       world_gen.noise_seed = settings.world.noise_seed;
-      send_regenerate_or_prune_event(&current_chunk, &mut refresh_metadata_event);
+      send_regenerate_or_prune_event(&current_chunk, &spawn_point, &mut refresh_metadata_event);
       state.generate_next = false;
     }
+
+    if state.reroll_objects {
+      settings.object.object_seed_offset = settings.object.object_seed_offset.wrapping_add(1);
+      object.object_seed_offset = settings.object.object_seed_offset;
+      reroll_objects_event.send(RerollObjectsEvent {});
+      state.reroll_objects = false;
+    }
+  }
+}
+
+/// Validates the given settings if they have changed, writing back any repaired values and replacing the issues found
+/// during the previous validation.
+fn validate<T: Resource + Copy>(
+  settings: &mut ResMut<T>,
+  issues: &mut Vec<SettingsIssue>,
+  validate: fn(&mut T) -> Vec<SettingsIssue>,
+) {
+  if !settings.is_changed() {
+    return;
+  }
+  let mut repaired = **settings;
+  *issues = validate(&mut repaired);
+  if !issues.is_empty() {
+    for issue in issues.iter() {
+      warn!("Repaired invalid setting {}", issue);
+    }
+    **settings = repaired;
   }
 }
 
 fn send_regenerate_or_prune_event(
   current_chunk: &Res<CurrentChunk>,
+  spawn_point: &Res<SpawnPoint>,
   refresh_metadata_event: &mut EventWriter<RefreshMetadata>,
 ) {
-  let is_at_origin_spawn_point = current_chunk.get_tile_grid() == ORIGIN_TILE_GRID_SPAWN_POINT;
+  let is_at_spawn_point = current_chunk.get_tile_grid() == spawn_point.tg;
   refresh_metadata_event.send(RefreshMetadata {
-    regenerate_world_after: is_at_origin_spawn_point,
-    prune_then_update_world_after: !is_at_origin_spawn_point,
+    regenerate_world_after: is_at_spawn_point,
+    prune_then_update_world_after: !is_at_spawn_point,
   });
 }
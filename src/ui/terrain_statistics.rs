@@ -0,0 +1,116 @@
+use crate::generation::lib::{ChunkComponent, ObjectComponent};
+use crate::generation::resources::TerrainStatistics;
+use bevy::app::{App, Plugin, Update};
+use bevy::input::ButtonInput;
+use bevy::prelude::{KeyCode, Local, With, World};
+use bevy::window::PrimaryWindow;
+use bevy_inspector_egui::bevy_egui::EguiContext;
+use bevy_inspector_egui::egui::{Align2, Grid, ScrollArea, Ui, Window};
+use std::collections::BTreeMap;
+
+pub struct TerrainStatisticsUiPlugin;
+
+impl Plugin for TerrainStatisticsUiPlugin {
+  fn build(&self, app: &mut App) {
+    app.add_systems(Update, render_terrain_statistics_ui_system);
+  }
+}
+
+/// Renders the latest `TerrainStatistics` report and allows analysing the world again or exporting the report as JSON.
+/// The world is analysed every time the window is opened. Hidden by default and toggled by pressing F9.
+fn render_terrain_statistics_ui_system(world: &mut World, mut enabled: Local<bool>) {
+  let is_toggled = world.resource::<ButtonInput<KeyCode>>().just_pressed(KeyCode::F9);
+  if is_toggled {
+    *enabled = !*enabled;
+    if *enabled {
+      analyse(world);
+    }
+  }
+  if !*enabled {
+    return;
+  }
+
+  let mut egui_context = world
+    .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+    .single(world)
+    .clone();
+
+  let (mut should_analyse, mut should_export) = (false, false);
+  {
+    let statistics = world.resource::<TerrainStatistics>();
+    Window::new("Terrain Statistics")
+      .default_size([300.0, 500.0])
+      .pivot(Align2::CENTER_TOP)
+      .anchor(Align2::CENTER_TOP, [0.0, 10.0])
+      .show(egui_context.get_mut(), |ui| {
+        ui.horizontal(|ui| {
+          ui.label(format!(
+            "{} chunk(s), {} tile(s), {} object(s)",
+            statistics.chunk_count, statistics.tile_count, statistics.object_count
+          ));
+        });
+        ui.horizontal(|ui| {
+          if ui.button("Analyse").clicked() {
+            should_analyse = true;
+          }
+          if ui.button("Export JSON").clicked() {
+            should_export = true;
+          }
+        });
+        ui.separator();
+        ScrollArea::vertical().show(ui, |ui| {
+          render_percentages(ui, "Terrain", &statistics.terrain);
+          render_percentages(ui, "Climate", &statistics.climates);
+          render_percentages(ui, "Tile type", &statistics.tile_types);
+          Grid::new("terrain_statistics_beach_grid").striped(true).show(ui, |ui| {
+            ui.strong("Beach");
+            ui.label(format!("{:.1}%", statistics.beach));
+            ui.end_row();
+          });
+          ui.add_space(10.0);
+          Grid::new("terrain_statistics_objects_grid").striped(true).show(ui, |ui| {
+            ui.strong("Object");
+            ui.strong("Count");
+            ui.end_row();
+            for (name, count) in statistics.objects.iter() {
+              ui.label(name);
+              ui.label(count.to_string());
+              ui.end_row();
+            }
+          });
+        });
+        ui.separator();
+        ui.label("Press F9 to toggle this window");
+      });
+  }
+
+  if should_analyse {
+    analyse(world);
+  }
+  if should_export {
+    world.resource::<TerrainStatistics>().export();
+  }
+}
+
+fn render_percentages(ui: &mut Ui, heading: &str, percentages: &BTreeMap<String, f32>) {
+  Grid::new(format!("terrain_statistics_{}_grid", heading))
+    .striped(true)
+    .show(ui, |ui| {
+      ui.strong(heading);
+      ui.strong("Share");
+      ui.end_row();
+      for (key, percentage) in percentages.iter() {
+        ui.label(key);
+        ui.label(format!("{:.1}%", percentage));
+        ui.end_row();
+      }
+    });
+  ui.add_space(10.0);
+}
+
+fn analyse(world: &mut World) {
+  let mut chunks = world.query::<&ChunkComponent>();
+  let mut objects = world.query::<&ObjectComponent>();
+  let statistics = TerrainStatistics::analyse(chunks.iter(world), objects.iter(world));
+  world.insert_resource(statistics);
+}
@@ -0,0 +1,83 @@
+use crate::generation::resources::WfcStatistics;
+use bevy::app::{App, Plugin, Update};
+use bevy::input::ButtonInput;
+use bevy::prelude::{KeyCode, Local, With, World};
+use bevy::window::PrimaryWindow;
+use bevy_inspector_egui::bevy_egui::EguiContext;
+use bevy_inspector_egui::egui::{Align2, Grid, ScrollArea, Window};
+
+pub struct WfcFailuresUiPlugin;
+
+impl Plugin for WfcFailuresUiPlugin {
+  fn build(&self, app: &mut App) {
+    app.add_systems(Update, render_wfc_failures_ui_system);
+  }
+}
+
+const MAX_ROWS: usize = 50;
+
+/// Renders a table of all failures recorded by the `WfcStatistics` resource, sorted by the number of times they
+/// occurred. Hidden by default and toggled by pressing F3.
+fn render_wfc_failures_ui_system(world: &mut World, mut enabled: Local<bool>) {
+  let is_toggled = world.resource::<ButtonInput<KeyCode>>().just_pressed(KeyCode::F3);
+  if is_toggled {
+    *enabled = !*enabled;
+  }
+  if !*enabled {
+    return;
+  }
+
+  let mut egui_context = world
+    .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+    .single(world)
+    .clone();
+
+  let mut should_clear = false;
+  {
+    let statistics = world.resource::<WfcStatistics>();
+    let failures = statistics.get_sorted();
+    Window::new("WFC Failures")
+      .default_size([700.0, 400.0])
+      .pivot(Align2::RIGHT_BOTTOM)
+      .anchor(Align2::RIGHT_BOTTOM, [-10.0, -10.0])
+      .show(egui_context.get_mut(), |ui| {
+        ui.horizontal(|ui| {
+          ui.label(format!(
+            "Recorded {} failure(s) across {} unique combination(s)",
+            statistics.total_count(),
+            failures.len()
+          ));
+          if ui.button("Reset").clicked() {
+            should_clear = true;
+          }
+        });
+        ui.separator();
+        ScrollArea::both().show(ui, |ui| {
+          Grid::new("wfc_failures_grid").striped(true).show(ui, |ui| {
+            ui.strong("Count");
+            ui.strong("Reference terrain");
+            ui.strong("Reference tile type");
+            ui.strong("Reference at");
+            ui.strong("Attempted by reference");
+            ui.strong("States of failed cell");
+            ui.end_row();
+            for (failure, count) in failures.iter().take(MAX_ROWS) {
+              ui.label(count.to_string());
+              ui.label(format!("{:?}", failure.terrain));
+              ui.label(format!("{:?}", failure.tile_type));
+              ui.label(format!("{:?}", failure.connection));
+              ui.label(format!("{:?}", failure.attempted));
+              ui.label(format!("{:?}", failure.neighbours));
+              ui.end_row();
+            }
+          });
+        });
+        ui.separator();
+        ui.label("Press F3 to toggle this window");
+      });
+  }
+
+  if should_clear {
+    world.resource_mut::<WfcStatistics>().clear();
+  }
+}
@@ -0,0 +1,84 @@
+use crate::generation::resources::WfcVisualiser;
+use bevy::app::{App, Plugin, Update};
+use bevy::input::ButtonInput;
+use bevy::prelude::{KeyCode, Local, With, World};
+use bevy::window::PrimaryWindow;
+use bevy_inspector_egui::bevy_egui::EguiContext;
+use bevy_inspector_egui::egui::{Align2, Slider, Window};
+
+pub struct WfcVisualiserUiPlugin;
+
+impl Plugin for WfcVisualiserUiPlugin {
+  fn build(&self, app: &mut App) {
+    app.add_systems(Update, render_wfc_visualiser_ui_system);
+  }
+}
+
+/// Renders the controls for the `WfcVisualiser`. Hidden by default and toggled by pressing F6.
+fn render_wfc_visualiser_ui_system(world: &mut World, mut enabled: Local<bool>) {
+  let is_toggled = world.resource::<ButtonInput<KeyCode>>().just_pressed(KeyCode::F6);
+  if is_toggled {
+    *enabled = !*enabled;
+  }
+  if !*enabled {
+    return;
+  }
+
+  let mut egui_context = world
+    .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+    .single(world)
+    .clone();
+
+  let status = {
+    let visualiser = world.resource::<WfcVisualiser>();
+    match (&visualiser.cg, &visualiser.stepper) {
+      (Some(cg), Some(stepper)) => format!(
+        "Chunk {} | Iteration {} | Failures {} | {}",
+        cg,
+        stepper.iteration_count(),
+        stepper.failure_count(),
+        if stepper.is_complete() {
+          "Complete"
+        } else if visualiser.is_running {
+          "Running"
+        } else {
+          "Paused"
+        }
+      ),
+      _ => "Inactive".to_string(),
+    }
+  };
+
+  let mut visualiser = world.resource_mut::<WfcVisualiser>();
+  let mut steps_per_second = visualiser.steps_per_second;
+  Window::new("WFC Visualiser")
+    .default_size([400.0, 100.0])
+    .pivot(Align2::CENTER_BOTTOM)
+    .anchor(Align2::CENTER_BOTTOM, [0.0, -10.0])
+    .show(egui_context.get_mut(), |ui| {
+      ui.label(status);
+      ui.horizontal(|ui| {
+        if ui.button("Start at camera").clicked() {
+          visualiser.is_start_requested = true;
+        }
+        if ui.button("Run").clicked() {
+          visualiser.is_running = true;
+        }
+        if ui.button("Pause").clicked() {
+          visualiser.is_running = false;
+        }
+        if ui.button("Step").clicked() {
+          visualiser.is_step_requested = true;
+        }
+        if ui.button("Stop").clicked() {
+          visualiser.is_stop_requested = true;
+        }
+      });
+      ui.add(Slider::new(&mut steps_per_second, 1.0..=60.0).text("steps per second"));
+      ui.separator();
+      ui.label("Press F6 to toggle this window");
+    });
+  if steps_per_second != visualiser.steps_per_second {
+    visualiser.steps_per_second = steps_per_second;
+  }
+}